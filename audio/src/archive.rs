@@ -0,0 +1,162 @@
+use std::{
+    fs,
+    io::{self, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Error;
+use percent_encoding::percent_decode_str;
+use symphonia::core::io::MediaSource;
+use zip::{CompressionMethod, ZipArchive};
+
+use crate::remote::encode_uri;
+
+const SCHEME: &str = "zip://";
+
+/// An audio file inside a ZIP archive, addressed as
+/// `zip:///path/album.zip#01.flac`. The name of the entry is percent-encoded
+/// so that the uri splits on its last `#`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveEntry {
+    pub archive: PathBuf,
+    pub name: String,
+}
+
+impl ArchiveEntry {
+    pub fn new(archive: &Path, name: &str) -> Self {
+        Self {
+            archive: archive.to_path_buf(),
+            name: name.to_string(),
+        }
+    }
+
+    pub fn is_archive_entry(uri: &str) -> bool {
+        uri.starts_with(SCHEME)
+    }
+
+    pub fn parse(uri: &str) -> Result<Self, Error> {
+        let (archive, name) = uri
+            .strip_prefix(SCHEME)
+            .and_then(|uri| uri.rsplit_once('#'))
+            .ok_or_else(|| Error::msg(format!("Invalid archive entry: {}", uri)))?;
+        Ok(Self {
+            archive: PathBuf::from(archive),
+            name: percent_decode_str(name).decode_utf8_lossy().to_string(),
+        })
+    }
+
+    pub fn uri(&self) -> String {
+        format!(
+            "{}{}#{}",
+            SCHEME,
+            self.archive.display(),
+            encode_uri(&self.name, false)
+        )
+    }
+
+    /// The path the entry would have once the archive is unpacked in its
+    /// folder, `/music/J. Cole/album.zip#01.flac` giving
+    /// `/music/J. Cole/album/01.flac`.
+    pub fn path(&self) -> PathBuf {
+        self.archive.with_extension("").join(&self.name)
+    }
+}
+
+/// Lists the files of an archive, folders left out.
+pub fn list_entries(archive: &Path) -> Result<Vec<ArchiveEntry>, Error> {
+    let zip = ZipArchive::new(fs::File::open(archive)?)?;
+    Ok(zip
+        .file_names()
+        .filter(|name| !name.ends_with('/'))
+        .map(|name| ArchiveEntry::new(archive, name))
+        .collect())
+}
+
+/// The content of an entry of a ZIP archive. Audio is mostly stored without
+/// compression, the entry is then read in place in the archive. Compressed
+/// entries can't be seeked and are inflated to a temporary file first.
+pub struct ArchiveFile {
+    file: fs::File,
+    start: u64,
+    length: u64,
+    position: u64,
+}
+
+impl ArchiveFile {
+    pub fn open(entry: &ArchiveEntry) -> Result<Self, Error> {
+        let mut zip = ZipArchive::new(fs::File::open(&entry.archive)?)?;
+        let mut zip_file = zip.by_name(&entry.name)?;
+        let length = zip_file.size();
+
+        if zip_file.compression() == CompressionMethod::Stored {
+            let start = zip_file.data_start();
+            drop(zip_file);
+            let mut file = zip.into_inner();
+            file.seek(SeekFrom::Start(start))?;
+            return Ok(Self {
+                file,
+                start,
+                length,
+                position: 0,
+            });
+        }
+
+        let mut file = tempfile::tempfile()?;
+        io::copy(&mut zip_file, &mut file)?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(Self {
+            file,
+            start: 0,
+            length,
+            position: 0,
+        })
+    }
+
+    pub fn len(&self) -> u64 {
+        self.length
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.length == 0
+    }
+}
+
+impl Read for ArchiveFile {
+    fn read(&mut self, output: &mut [u8]) -> io::Result<usize> {
+        // The data of the next entries follows in the archive
+        let remaining = self.length.saturating_sub(self.position) as usize;
+        let length = output.len().min(remaining);
+        let read = self.file.read(&mut output[..length])?;
+        self.position += read as u64;
+        Ok(read)
+    }
+}
+
+impl Seek for ArchiveFile {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let position = match pos {
+            SeekFrom::Start(offset) => Some(offset),
+            SeekFrom::End(offset) => self.length.checked_add_signed(offset),
+            SeekFrom::Current(offset) => self.position.checked_add_signed(offset),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "invalid seek to a negative position",
+            )
+        })?;
+        self.file.seek(SeekFrom::Start(self.start + position))?;
+        self.position = position;
+        Ok(position)
+    }
+}
+
+impl MediaSource for ArchiveFile {
+    fn is_seekable(&self) -> bool {
+        true
+    }
+
+    fn byte_len(&self) -> Option<u64> {
+        Some(self.length)
+    }
+}
@@ -2,8 +2,8 @@ use anyhow::Error;
 use futures_util::{future::IntoStream, FutureExt};
 use hyper::{
     client::{self, HttpConnector, ResponseFuture},
-    header::RANGE,
-    Request,
+    header::{AUTHORIZATION, RANGE},
+    Body, Request, Response,
 };
 use hyper_rustls::HttpsConnector;
 use rustls::{OwnedTrustAnchor, RootCertStore};
@@ -25,6 +25,7 @@ impl rustls::client::ServerCertVerifier for NoCertificateVerification {
 }
 pub struct Client {
     client: hyper::Client<HttpsConnector<HttpConnector>>,
+    authorization: Option<String>,
 }
 
 impl Client {
@@ -50,7 +51,17 @@ impl Client {
             .enable_http1()
             .build();
         let client = client::Client::builder().build(https);
-        Self { client }
+        Self {
+            client,
+            authorization: None,
+        }
+    }
+
+    /// Sends the given `Authorization` header with every request, to read
+    /// the files of a remote library root.
+    pub fn with_authorization(mut self, authorization: Option<String>) -> Self {
+        self.authorization = authorization;
+        self
     }
 
     pub fn stream_from_url(
@@ -59,11 +70,22 @@ impl Client {
         offset: usize,
         length: usize,
     ) -> Result<IntoStream<ResponseFuture>, Error> {
-        let req = Request::builder()
+        let mut req = Request::builder()
             .method("GET")
             .uri(url)
-            .header(RANGE, format!("bytes={}-{}", offset, offset + length - 1))
-            .body(hyper::Body::empty())?;
+            .header(RANGE, format!("bytes={}-{}", offset, offset + length - 1));
+        if let Some(authorization) = &self.authorization {
+            req = req.header(AUTHORIZATION, authorization);
+        }
+        let req = req.body(hyper::Body::empty())?;
         Ok(self.client.request(req).into_stream())
     }
+
+    pub async fn send(&self, mut req: Request<Body>) -> Result<Response<Body>, Error> {
+        if let Some(authorization) = &self.authorization {
+            req.headers_mut()
+                .insert(AUTHORIZATION, authorization.parse()?);
+        }
+        Ok(self.client.request(req).await?)
+    }
 }
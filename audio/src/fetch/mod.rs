@@ -29,8 +29,10 @@ use url::Url;
 use self::{client::Client, receive::audio_file_fetch};
 
 use crate::{
+    archive::{ArchiveEntry, ArchiveFile},
     fetch::cache::Cache,
     range_set::{Range, RangeSet},
+    remote::{self, RemoteRequest, RemoteRoot},
 };
 
 pub mod client;
@@ -81,6 +83,7 @@ pub enum AudioFile {
     Cached(fs::File),
     Streaming(AudioFileStreaming),
     Local(fs::File),
+    Archive(ArchiveFile),
 }
 
 #[derive(Debug)]
@@ -260,10 +263,31 @@ struct AudioFileDownloadStatus {
 
 impl AudioFile {
     pub async fn open(url: &str, bytes_per_second: usize) -> Result<AudioFile, Error> {
+        if ArchiveEntry::is_archive_entry(url) {
+            let entry = ArchiveEntry::parse(url)?;
+            return Ok(AudioFile::Archive(ArchiveFile::open(&entry)?));
+        }
         if Url::parse(url).is_err() {
             return Ok(AudioFile::Local(fs::File::open(url)?));
         }
 
+        let request = match RemoteRoot::is_remote(url) {
+            true => remote::resolve(url)?,
+            false => RemoteRequest {
+                url: url.to_owned(),
+                authorization: None,
+            },
+        };
+        Self::open_request(url, request, bytes_per_second).await
+    }
+
+    /// Streams a file with the given request, the file being cached under
+    /// its `url` once it is completely downloaded.
+    pub async fn open_request(
+        url: &str,
+        request: RemoteRequest,
+        bytes_per_second: usize,
+    ) -> Result<AudioFile, Error> {
         let cache = Cache::new();
         let file_id = format!("{:x}", md5::compute(url.to_owned()));
         if cache.is_file_cached(file_id.as_str()) {
@@ -274,7 +298,12 @@ impl AudioFile {
 
         let (complete_tx, complete_rx) = oneshot::channel();
 
-        let streaming = AudioFileStreaming::open(url.to_owned(), complete_tx, bytes_per_second);
+        let streaming = AudioFileStreaming::open(
+            request.url,
+            request.authorization,
+            complete_tx,
+            bytes_per_second,
+        );
 
         let file_id = format!("{:x}", md5::compute(url.to_owned()));
 
@@ -315,6 +344,11 @@ impl AudioFile {
                 stream_shared: None,
                 file_size: file.metadata()?.len() as usize,
             },
+            AudioFile::Archive(ref file) => StreamLoaderController {
+                channel_tx: None,
+                stream_shared: None,
+                file_size: file.len() as usize,
+            },
         };
 
         Ok(controller)
@@ -329,6 +363,13 @@ impl AudioFile {
     }
 
     pub async fn get_mime_type(url: &str) -> Result<String, Error> {
+        if ArchiveEntry::is_archive_entry(url) {
+            let entry = ArchiveEntry::parse(url)?;
+            return mime_guess::from_path(&entry.name)
+                .first()
+                .map(|mime| mime.to_string())
+                .ok_or_else(|| Error::msg("No mime type found"));
+        }
         if Url::parse(url).is_err() {
             if !Path::new(url).exists() {
                 return Err(Error::msg("File does not exist"));
@@ -338,23 +379,26 @@ impl AudioFile {
                 None => return Err(Error::msg("No mime type found")),
             }
         }
-        let mut streamer = Client::new().stream_from_url(url, 0, 512)?;
-        let response = streamer.next().await.ok_or(AudioFileError::NoData)??;
-
-        let content_type = match response.headers().get(header::CONTENT_TYPE) {
-            Some(content_type) => content_type,
-            None => return Err(Error::msg("No Content-Type header")),
+        let request = match RemoteRoot::is_remote(url) {
+            true => remote::resolve(url)?,
+            false => RemoteRequest {
+                url: url.to_owned(),
+                authorization: None,
+            },
         };
+        let mut streamer = Client::new()
+            .with_authorization(request.authorization)
+            .stream_from_url(&request.url, 0, 512)?;
+        let response = streamer.next().await.ok_or(AudioFileError::NoData)??;
 
-        let mime = content_type.to_str()?;
-
-        Ok(mime.to_owned())
+        mime_type(&response, &request.url)
     }
 }
 
 impl AudioFileStreaming {
     pub async fn open(
         url: String,
+        authorization: Option<String>,
         complete_tx: oneshot::Sender<NamedTempFile>,
         bytes_per_second: usize,
     ) -> Result<AudioFileStreaming, Error> {
@@ -364,7 +408,9 @@ impl AudioFileStreaming {
         // 206 Partial Content.
 
         debug!(">> Downloading file: {}", url);
-        let mut streamer = Client::new().stream_from_url(url.as_str(), 0, MINIMUM_DOWNLOAD_SIZE)?;
+        let mut streamer = Client::new()
+            .with_authorization(authorization.clone())
+            .stream_from_url(url.as_str(), 0, MINIMUM_DOWNLOAD_SIZE)?;
 
         // Get the first chunk with the headers to get the file size.
         // The remainder of that chunk with possibly also a response body is then
@@ -388,13 +434,7 @@ impl AudioFileStreaming {
         let upper_bound: usize = str_value[hyphen_index + 1..slash_index].parse()?;
         let file_size = str_value[slash_index + 1..].parse()?;
 
-        let content_type = match response.headers().get(header::CONTENT_TYPE) {
-            Some(content_type) => content_type,
-            None => return Err(Error::msg("No Content-Type header")),
-        };
-
-        let mime = content_type.to_str()?;
-        let mime = mime.to_owned();
+        let mime = mime_type(&response, &url)?;
 
         let initial_request = StreamingRequest {
             streamer,
@@ -405,6 +445,7 @@ impl AudioFileStreaming {
 
         let shared = Arc::new(AudioFileShared {
             url,
+            authorization,
             file_size,
             bytes_per_second,
             cond: Condvar::new(),
@@ -453,6 +494,28 @@ impl AudioFileStreaming {
     }
 }
 
+/// Reads the mime type from the `Content-Type` header. Object storage and
+/// WebDAV servers often answer with a generic type, the type is guessed from
+/// the extension of the file then.
+fn mime_type(response: &Response<Body>, url: &str) -> Result<String, Error> {
+    let content_type = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|content_type| content_type.to_str())
+        .transpose()?
+        .filter(|content_type| !content_type.ends_with("octet-stream"));
+    if let Some(content_type) = content_type {
+        return Ok(content_type.to_owned());
+    }
+    let path = Url::parse(url)
+        .map(|url| url.path().to_string())
+        .unwrap_or_default();
+    match mime_guess::from_path(path).first() {
+        Some(mime) => Ok(mime.to_string()),
+        None => Err(Error::msg("No Content-Type header")),
+    }
+}
+
 impl Read for AudioFileStreaming {
     fn read(&mut self, output: &mut [u8]) -> io::Result<usize> {
         let offset = self.position as usize;
@@ -571,6 +634,7 @@ impl Read for AudioFile {
             AudioFile::Cached(ref mut file) => file.read(output),
             AudioFile::Streaming(ref mut file) => file.read(output),
             AudioFile::Local(ref mut file) => file.read(output),
+            AudioFile::Archive(ref mut file) => file.read(output),
         }
     }
 }
@@ -581,6 +645,7 @@ impl Seek for AudioFile {
             AudioFile::Cached(ref mut file) => file.seek(pos),
             AudioFile::Streaming(ref mut file) => file.seek(pos),
             AudioFile::Local(ref mut file) => file.seek(pos),
+            AudioFile::Archive(ref mut file) => file.seek(pos),
         }
     }
 }
@@ -593,6 +658,7 @@ pub enum StreamLoaderCommand {
 
 struct AudioFileShared {
     url: String,
+    authorization: Option<String>,
     file_size: usize,
     bytes_per_second: usize,
     cond: Condvar,
@@ -87,8 +87,9 @@ impl AudioFileFetch {
         ranges_to_request.subtract_range_set(&download_status.requested);
 
         for range in ranges_to_request.iter() {
-            let streamer =
-                Client::new().stream_from_url(&self.shared.url, range.start, range.length)?;
+            let streamer = Client::new()
+                .with_authorization(self.shared.authorization.clone())
+                .stream_from_url(&self.shared.url, range.start, range.length)?;
             download_status.requested.add_range(range);
 
             let streaming_request = StreamingRequest {
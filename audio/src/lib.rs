@@ -1,3 +1,10 @@
+#[cfg(test)]
+mod tests;
+
+pub mod archive;
+
 pub mod fetch;
 
 pub mod range_set;
+
+pub mod remote;
@@ -1,6 +1,8 @@
+pub mod archive;
 pub mod decoder;
 pub mod fetch;
 pub mod range_set;
+pub mod remote;
 
 use decoder::{symphonia_decoder::SymphoniaDecoder, AudioDecoder};
 use fetch::{AudioFile, Subfile};
@@ -0,0 +1,247 @@
+use std::env;
+
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use hyper::{Body, Request, StatusCode};
+use quick_xml::{events::Event, Reader};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::fetch::client::Client;
+
+use super::{authority, credentials, encode_uri, RemoteFile, RemoteRequest};
+
+/// Presigned URLs stay valid long enough to stream a whole album.
+const URL_EXPIRATION_SECONDS: u32 = 6 * 60 * 60;
+
+/// A library root in an S3-compatible bucket, addressed path-style so that
+/// MinIO and other self-hosted servers work without DNS setup. The requests
+/// are authenticated with presigned URLs (AWS Signature Version 4), which
+/// the streaming code fetches like any other URL.
+pub struct S3 {
+    /// `http` or `https`, set with the `scheme` query parameter
+    pub scheme: String,
+    /// `host[:port]`
+    pub host: String,
+    pub bucket: String,
+    pub prefix: String,
+    pub region: String,
+    pub access_key: String,
+    pub secret_key: String,
+}
+
+/// A page of a `ListObjectsV2` response.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ObjectList {
+    pub objects: Vec<(String, u64)>,
+    pub next_continuation_token: Option<String>,
+}
+
+impl S3 {
+    /// The credentials fall back to the `AWS_ACCESS_KEY_ID` and
+    /// `AWS_SECRET_ACCESS_KEY` environment variables.
+    pub fn parse(url: &Url) -> Result<Self, Error> {
+        let mut segments = url.path().trim_matches('/').splitn(2, '/');
+        let bucket = segments.next().unwrap_or_default().to_string();
+        if url.host_str().is_none() || bucket.is_empty() {
+            return Err(Error::msg("S3 library root must be s3://host/bucket"));
+        }
+        let prefix = segments.next().unwrap_or_default().trim_matches('/');
+        let query = |name: &str| {
+            url.query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        let (access_key, secret_key) = match credentials(url) {
+            Some(credentials) => credentials,
+            None => (
+                env::var("AWS_ACCESS_KEY_ID")?,
+                env::var("AWS_SECRET_ACCESS_KEY")?,
+            ),
+        };
+        Ok(Self {
+            scheme: query("scheme").unwrap_or_else(|| "https".to_string()),
+            host: authority(url),
+            bucket,
+            prefix: percent_encoding::percent_decode_str(prefix)
+                .decode_utf8_lossy()
+                .to_string(),
+            region: query("region").unwrap_or_else(|| "us-east-1".to_string()),
+            access_key,
+            secret_key,
+        })
+    }
+
+    pub fn uri(&self) -> String {
+        let mut uri = format!("s3://{}/{}", self.host, self.bucket);
+        if !self.prefix.is_empty() {
+            uri.push('/');
+            uri.push_str(&encode_uri(&self.prefix, false));
+        }
+        uri
+    }
+
+    pub fn request(&self, uri: &str) -> RemoteRequest {
+        let path = uri
+            .strip_prefix(&format!("s3://{}", self.host))
+            .unwrap_or_default();
+        RemoteRequest {
+            url: self.presign("GET", path, &[], Utc::now(), URL_EXPIRATION_SECONDS),
+            authorization: None,
+        }
+    }
+
+    pub async fn list(&self) -> Result<Vec<RemoteFile>, Error> {
+        let client = Client::new();
+        let prefix = match self.prefix.is_empty() {
+            true => String::new(),
+            false => format!("{}/", self.prefix),
+        };
+        let mut files = vec![];
+        let mut continuation_token: Option<String> = None;
+        loop {
+            let mut query = vec![("list-type", "2"), ("prefix", prefix.as_str())];
+            if let Some(token) = &continuation_token {
+                query.push(("continuation-token", token.as_str()));
+            }
+            let path = format!("/{}", self.bucket);
+            let url = self.presign("GET", &path, &query, Utc::now(), URL_EXPIRATION_SECONDS);
+            let response = client.send(Request::get(url).body(Body::empty())?).await?;
+            let status = response.status();
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            if status != StatusCode::OK {
+                return Err(Error::msg(format!(
+                    "Listing bucket {} failed: {} {}",
+                    self.bucket,
+                    status,
+                    String::from_utf8_lossy(&body)
+                )));
+            }
+
+            let page = parse_object_list(&String::from_utf8_lossy(&body))?;
+            files.extend(
+                page.objects
+                    .into_iter()
+                    .filter(|(key, _)| !key.ends_with('/'))
+                    .map(|(key, size)| RemoteFile {
+                        uri: format!(
+                            "s3://{}/{}/{}",
+                            self.host,
+                            self.bucket,
+                            encode_uri(&key, false)
+                        ),
+                        size,
+                    }),
+            );
+            continuation_token = page.next_continuation_token;
+            if continuation_token.is_none() {
+                return Ok(files);
+            }
+        }
+    }
+
+    /// Signs a request into a URL carrying its credentials in the query
+    /// string. `path` must already be encoded with [`encode_uri`].
+    pub fn presign(
+        &self,
+        method: &str,
+        path: &str,
+        query: &[(&str, &str)],
+        now: DateTime<Utc>,
+        expires: u32,
+    ) -> String {
+        let date = now.format("%Y%m%d").to_string();
+        let timestamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let credential = format!("{}/{}", self.access_key, scope);
+        let expires = expires.to_string();
+
+        let mut parameters: Vec<(String, String)> = query
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .chain([
+                (
+                    "X-Amz-Algorithm".to_string(),
+                    "AWS4-HMAC-SHA256".to_string(),
+                ),
+                ("X-Amz-Credential".to_string(), credential),
+                ("X-Amz-Date".to_string(), timestamp.clone()),
+                ("X-Amz-Expires".to_string(), expires),
+                ("X-Amz-SignedHeaders".to_string(), "host".to_string()),
+            ])
+            .map(|(key, value)| (encode_uri(&key, true), encode_uri(&value, true)))
+            .collect();
+        parameters.sort();
+        let query = parameters
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join("&");
+
+        let canonical_request = format!(
+            "{}\n{}\n{}\nhost:{}\n\nhost\nUNSIGNED-PAYLOAD",
+            method, path, query, self.host
+        );
+        let string_to_sign = format!(
+            "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+            timestamp,
+            scope,
+            hex::encode(Sha256::digest(canonical_request.as_bytes()))
+        );
+        let key = [date.as_str(), &self.region, "s3", "aws4_request"]
+            .iter()
+            .fold(
+                format!("AWS4{}", self.secret_key).into_bytes(),
+                |key, part| hmac_sha256(&key, part),
+            );
+        let signature = hex::encode(hmac_sha256(&key, &string_to_sign));
+
+        format!(
+            "{}://{}{}?{}&X-Amz-Signature={}",
+            self.scheme, self.host, path, query, signature
+        )
+    }
+}
+
+fn hmac_sha256(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+pub fn parse_object_list(xml: &str) -> Result<ObjectList, Error> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut list = ObjectList::default();
+    let mut key = String::new();
+    let mut size = 0;
+    let mut element = vec![];
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => element = start.local_name().as_ref().to_vec(),
+            Event::Text(text) => {
+                let text = text.unescape()?;
+                match element.as_slice() {
+                    b"Key" => key = text.to_string(),
+                    b"Size" => size = text.parse().unwrap_or_default(),
+                    b"NextContinuationToken" => {
+                        list.next_continuation_token = Some(text.to_string())
+                    }
+                    _ => {}
+                }
+            }
+            Event::End(end) => {
+                if end.local_name().as_ref() == b"Contents" {
+                    list.objects.push((std::mem::take(&mut key), size));
+                    size = 0;
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(list)
+}
@@ -0,0 +1,157 @@
+use anyhow::Error;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use hyper::{header::CONTENT_TYPE, Body, Method, Request, StatusCode};
+use quick_xml::{events::Event, Reader};
+use url::Url;
+
+use crate::fetch::client::Client;
+
+use super::{authority, credentials, RemoteFile, RemoteRequest};
+
+const PROPFIND_BODY: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<propfind xmlns="DAV:"><prop><resourcetype/><getcontentlength/></prop></propfind>"#;
+
+/// A library root on a WebDAV server, authenticated with HTTP basic auth.
+pub struct WebDav {
+    /// `webdav` or `webdavs`
+    pub scheme: String,
+    /// `host[:port]`
+    pub host: String,
+    pub path: String,
+    pub authorization: Option<String>,
+}
+
+/// An entry of a `PROPFIND` response.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct WebDavEntry {
+    pub href: String,
+    pub collection: bool,
+    pub size: u64,
+}
+
+impl WebDav {
+    pub fn parse(url: &Url) -> Result<Self, Error> {
+        if url.host_str().is_none() {
+            return Err(Error::msg("WebDAV library root without host"));
+        }
+        Ok(Self {
+            scheme: url.scheme().to_string(),
+            host: authority(url),
+            path: url.path().trim_end_matches('/').to_string(),
+            authorization: credentials(url).map(|(username, password)| {
+                format!(
+                    "Basic {}",
+                    STANDARD.encode(format!("{}:{}", username, password))
+                )
+            }),
+        })
+    }
+
+    pub fn uri(&self) -> String {
+        format!("{}://{}{}", self.scheme, self.host, self.path)
+    }
+
+    fn http_url(&self, path: &str) -> String {
+        let scheme = match self.scheme.as_str() {
+            "webdavs" => "https",
+            _ => "http",
+        };
+        format!("{}://{}{}", scheme, self.host, path)
+    }
+
+    pub fn request(&self, uri: &str) -> RemoteRequest {
+        let path = uri
+            .strip_prefix(&format!("{}://{}", self.scheme, self.host))
+            .unwrap_or_default();
+        RemoteRequest {
+            url: self.http_url(path),
+            authorization: self.authorization.clone(),
+        }
+    }
+
+    /// Walks the folders of the root with `Depth: 1` requests, servers often
+    /// refusing `Depth: infinity`.
+    pub async fn list(&self) -> Result<Vec<RemoteFile>, Error> {
+        let client = Client::new().with_authorization(self.authorization.clone());
+        let mut files = vec![];
+        let mut folders = vec![format!("{}/", self.path)];
+        while let Some(folder) = folders.pop() {
+            let request = Request::builder()
+                .method(Method::from_bytes(b"PROPFIND")?)
+                .uri(self.http_url(&folder))
+                .header("Depth", "1")
+                .header(CONTENT_TYPE, "application/xml")
+                .body(Body::from(PROPFIND_BODY))?;
+            let response = client.send(request).await?;
+            if response.status() != StatusCode::MULTI_STATUS {
+                return Err(Error::msg(format!(
+                    "PROPFIND {} failed: {}",
+                    folder,
+                    response.status()
+                )));
+            }
+            let body = hyper::body::to_bytes(response.into_body()).await?;
+            for entry in parse_multistatus(&String::from_utf8_lossy(&body))? {
+                // The folder itself is part of the response
+                if entry.href.trim_end_matches('/') == folder.trim_end_matches('/') {
+                    continue;
+                }
+                match entry.collection {
+                    true => folders.push(entry.href),
+                    false => files.push(RemoteFile {
+                        uri: format!("{}://{}{}", self.scheme, self.host, entry.href),
+                        size: entry.size,
+                    }),
+                }
+            }
+        }
+        Ok(files)
+    }
+}
+
+/// Reads the entries of a `207 Multi-Status` response. The hrefs are
+/// returned as absolute paths, some servers sending full URLs.
+pub fn parse_multistatus(xml: &str) -> Result<Vec<WebDavEntry>, Error> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut entries = vec![];
+    let mut entry = WebDavEntry::default();
+    let mut element = vec![];
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => {
+                let name = start.local_name().as_ref().to_vec();
+                if name == b"collection" {
+                    entry.collection = true;
+                }
+                element = name;
+            }
+            Event::Empty(empty) if empty.local_name().as_ref() == b"collection" => {
+                entry.collection = true;
+            }
+            Event::Text(text) => {
+                let text = text.unescape()?;
+                match element.as_slice() {
+                    b"href" => {
+                        entry.href = match Url::parse(&text) {
+                            Ok(url) => url.path().to_string(),
+                            Err(_) => text.to_string(),
+                        }
+                    }
+                    b"getcontentlength" => entry.size = text.trim().parse().unwrap_or_default(),
+                    _ => {}
+                }
+            }
+            Event::End(end) => {
+                if end.local_name().as_ref() == b"response" {
+                    entries.push(std::mem::take(&mut entry));
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(entries)
+}
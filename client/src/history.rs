@@ -0,0 +1,53 @@
+use anyhow::Error;
+use music_player_server::api::music::v1alpha1::{
+    history_service_client::HistoryServiceClient, GetHistoryRequest, GetMostPlayedRequest,
+    GetPlayStatsRequest,
+};
+use music_player_types::types::{HistoryEntry, PlayStats};
+use tonic::transport::Channel;
+
+pub struct HistoryClient {
+    client: HistoryServiceClient<Channel>,
+}
+
+impl HistoryClient {
+    pub async fn new(host: String, port: u16) -> Result<Self, Error> {
+        let url = format!("tcp://{}:{}", host, port);
+        let client = HistoryServiceClient::connect(url).await?;
+        Ok(Self { client })
+    }
+
+    pub async fn history(&mut self, offset: i32, limit: i32) -> Result<Vec<HistoryEntry>, Error> {
+        let request = tonic::Request::new(GetHistoryRequest { offset, limit });
+        let response = self.client.get_history(request).await?;
+        Ok(response
+            .into_inner()
+            .entries
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+
+    pub async fn stats(&mut self, track_id: &str) -> Result<PlayStats, Error> {
+        let request = tonic::Request::new(GetPlayStatsRequest {
+            track_id: track_id.to_string(),
+        });
+        let response = self.client.get_play_stats(request).await?;
+        Ok(response
+            .into_inner()
+            .stats
+            .map(Into::into)
+            .unwrap_or_default())
+    }
+
+    pub async fn most_played(&mut self, offset: i32, limit: i32) -> Result<Vec<PlayStats>, Error> {
+        let request = tonic::Request::new(GetMostPlayedRequest { offset, limit });
+        let response = self.client.get_most_played(request).await?;
+        Ok(response
+            .into_inner()
+            .tracks
+            .into_iter()
+            .map(Into::into)
+            .collect())
+    }
+}
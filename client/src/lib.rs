@@ -1,6 +1,7 @@
 #[cfg(test)]
 mod tests;
 
+pub mod history;
 pub mod library;
 pub mod playback;
 pub mod playlist;
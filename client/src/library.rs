@@ -1,9 +1,12 @@
 use anyhow::Error;
 use music_player_server::api::{
-    metadata::v1alpha1::{Album, Artist, Track},
+    metadata::v1alpha1::{Album, Artist, Genre, Track},
     music::v1alpha1::{
         library_service_client::LibraryServiceClient, GetAlbumDetailsRequest, GetAlbumsRequest,
-        GetArtistDetailsRequest, GetArtistsRequest, GetTrackDetailsRequest, GetTracksRequest,
+        GetArtistDetailsRequest, GetArtistsRequest, GetFavouriteAlbumsRequest,
+        GetFavouriteArtistsRequest, GetGenreDetailsRequest, GetGenresRequest,
+        GetLikedTracksRequest, GetTrackDetailsRequest, GetTracksRequest, RateTrackRequest,
+        SearchRequest, SearchResponse, SetFavouriteRequest, SuggestRequest, Suggestion,
     },
 };
 use tonic::transport::Channel;
@@ -39,6 +42,7 @@ impl LibraryClient {
             offset,
             limit,
             filter,
+            ..Default::default()
         });
         let response = self.client.get_albums(request).await?;
         Ok(response.into_inner().albums.into_iter().collect())
@@ -69,6 +73,27 @@ impl LibraryClient {
         Ok(response.into_inner().artists.into_iter().collect())
     }
 
+    pub async fn genre(&mut self, id: &str) -> Result<Option<Genre>, Error> {
+        let request = tonic::Request::new(GetGenreDetailsRequest { id: id.to_string() });
+        let response = self.client.get_genre_details(request).await?;
+        Ok(response.into_inner().genre)
+    }
+
+    pub async fn genres(
+        &mut self,
+        filter: Option<String>,
+        offset: i32,
+        limit: i32,
+    ) -> Result<Vec<Genre>, Error> {
+        let request = tonic::Request::new(GetGenresRequest {
+            offset,
+            limit,
+            filter: filter.unwrap_or_default(),
+        });
+        let response = self.client.get_genres(request).await?;
+        Ok(response.into_inner().genres)
+    }
+
     pub async fn songs(
         &mut self,
         filter: Option<String>,
@@ -83,6 +108,7 @@ impl LibraryClient {
             offset,
             limit,
             filter,
+            ..Default::default()
         });
         let response = self.client.get_tracks(request).await?;
         Ok(response.into_inner().tracks.into_iter().collect())
@@ -94,7 +120,75 @@ impl LibraryClient {
         Ok(response.into_inner().track)
     }
 
-    pub async fn search(&mut self, query: &str) -> Result<(), Error> {
-        todo!()
+    /// From 1 to 5 stars, 0 removes the rating.
+    pub async fn rate(&mut self, id: &str, rating: u8) -> Result<(), Error> {
+        let request = tonic::Request::new(RateTrackRequest {
+            id: id.to_string(),
+            rating: rating as i32,
+        });
+        self.client.rate_track(request).await?;
+        Ok(())
+    }
+
+    /// `kind` is one of `track`, `album` or `artist`.
+    pub async fn set_favourite(
+        &mut self,
+        kind: &str,
+        id: &str,
+        favourite: bool,
+    ) -> Result<(), Error> {
+        let request = tonic::Request::new(SetFavouriteRequest {
+            kind: kind.to_string(),
+            id: id.to_string(),
+            favourite,
+        });
+        self.client.set_favourite(request).await?;
+        Ok(())
+    }
+
+    pub async fn liked_songs(&mut self, offset: i32, limit: i32) -> Result<Vec<Track>, Error> {
+        let request = tonic::Request::new(GetLikedTracksRequest { offset, limit });
+        let response = self.client.get_liked_tracks(request).await?;
+        Ok(response.into_inner().tracks)
+    }
+
+    pub async fn favourite_albums(&mut self, offset: i32, limit: i32) -> Result<Vec<Album>, Error> {
+        let request = tonic::Request::new(GetFavouriteAlbumsRequest { offset, limit });
+        let response = self.client.get_favourite_albums(request).await?;
+        Ok(response.into_inner().albums)
+    }
+
+    pub async fn favourite_artists(
+        &mut self,
+        offset: i32,
+        limit: i32,
+    ) -> Result<Vec<Artist>, Error> {
+        let request = tonic::Request::new(GetFavouriteArtistsRequest { offset, limit });
+        let response = self.client.get_favourite_artists(request).await?;
+        Ok(response.into_inner().artists)
+    }
+
+    pub async fn search(
+        &mut self,
+        query: &str,
+        offset: i32,
+        limit: i32,
+    ) -> Result<SearchResponse, Error> {
+        let request = tonic::Request::new(SearchRequest {
+            query: query.to_string(),
+            offset,
+            limit,
+        });
+        let response = self.client.search(request).await?;
+        Ok(response.into_inner())
+    }
+
+    pub async fn suggest(&mut self, input: &str, limit: i32) -> Result<Vec<Suggestion>, Error> {
+        let request = tonic::Request::new(SuggestRequest {
+            input: input.to_string(),
+            limit,
+        });
+        let response = self.client.suggest(request).await?;
+        Ok(response.into_inner().suggestions)
     }
 }
@@ -1,6 +1,8 @@
 use anyhow::{Error, Ok};
 use music_player_server::api::music::v1alpha1::{
-    playlist_service_client::PlaylistServiceClient, FindAllRequest, GetPlaylistDetailsRequest,
+    playlist_service_client::PlaylistServiceClient, CreateSmartRequest, ExportRequest,
+    FindAllRequest, GetPlaylistDetailsRequest, ImportRequest, InsertItemRequest, MoveItemRequest,
+    UpdateSmartRequest,
 };
 use music_player_settings::{read_settings, Settings};
 use music_player_types::types::Playlist;
@@ -41,6 +43,86 @@ impl PlaylistClient {
         Ok(playlists.into_iter().map(Into::into).collect())
     }
 
+    pub async fn create_smart(&mut self, name: &str, rules: &str) -> Result<Playlist, Error> {
+        let request = tonic::Request::new(CreateSmartRequest {
+            name: name.to_string(),
+            rules: rules.to_string(),
+            ..Default::default()
+        });
+        let response = self.client.create_smart(request).await?;
+        Ok(response.into_inner().into())
+    }
+
+    pub async fn update_smart(&mut self, id: &str, rules: &str) -> Result<Playlist, Error> {
+        let request = tonic::Request::new(UpdateSmartRequest {
+            id: id.to_string(),
+            rules: rules.to_string(),
+        });
+        let response = self.client.update_smart(request).await?;
+        Ok(response.into_inner().into())
+    }
+
+    pub async fn insert_track(
+        &mut self,
+        id: &str,
+        track_id: &str,
+        position: u32,
+    ) -> Result<Playlist, Error> {
+        let request = tonic::Request::new(InsertItemRequest {
+            id: id.to_string(),
+            track_id: track_id.to_string(),
+            position,
+        });
+        let response = self.client.insert_item(request).await?;
+        Ok(response.into_inner().into())
+    }
+
+    pub async fn move_track(&mut self, id: &str, from: u32, to: u32) -> Result<Playlist, Error> {
+        let request = tonic::Request::new(MoveItemRequest {
+            id: id.to_string(),
+            from,
+            to,
+        });
+        let response = self.client.move_item(request).await?;
+        Ok(response.into_inner().into())
+    }
+
+    /// Creates a playlist from the content of a playlist file of `format`,
+    /// the relative paths being resolved from `base_path`. Returns the
+    /// playlist and the entries matching no track of the library.
+    pub async fn import(
+        &mut self,
+        name: &str,
+        content: &str,
+        format: &str,
+        base_path: &str,
+    ) -> Result<(Playlist, Vec<String>), Error> {
+        let request = tonic::Request::new(ImportRequest {
+            name: name.to_string(),
+            content: content.to_string(),
+            format: format.to_string(),
+            base_path: base_path.to_string(),
+        });
+        let response = self.client.import(request).await?.into_inner();
+        let playlist = Playlist {
+            id: response.id,
+            name: response.name,
+            tracks: response.tracks.into_iter().map(Into::into).collect(),
+            ..Default::default()
+        };
+        Ok((playlist, response.missing))
+    }
+
+    /// The playlist as a file of `format`, with the file name to save it as.
+    pub async fn export(&mut self, id: &str, format: &str) -> Result<(String, String), Error> {
+        let request = tonic::Request::new(ExportRequest {
+            id: id.to_string(),
+            format: format.to_string(),
+        });
+        let response = self.client.export(request).await?.into_inner();
+        Ok((response.content, response.file_name))
+    }
+
     pub async fn play(&mut self, id: &str) {
         todo!()
     }
@@ -3,8 +3,8 @@ use music_player_server::api::{
     metadata::v1alpha1::Track,
     music::v1alpha1::{
         tracklist_service_client::TracklistServiceClient, AddTrackRequest, AddTracksRequest,
-        ClearTracklistRequest, GetTracklistTracksRequest, LoadTracksRequest, PlayNextRequest,
-        PlayTrackAtRequest, RemoveTrackAtRequest,
+        ClearTracklistRequest, ExportTracklistRequest, GetTracklistTracksRequest,
+        LoadTracksRequest, PlayNextRequest, PlayTrackAtRequest, RemoveTrackAtRequest,
     },
 };
 use music_player_types::types;
@@ -63,6 +63,16 @@ impl TracklistClient {
         Ok((response.previous_tracks, response.next_tracks))
     }
 
+    /// The previous and next tracks as a playlist file of `format`, "m3u8",
+    /// "pls" or "xspf".
+    pub async fn export(&mut self, format: &str) -> Result<String, Error> {
+        let request = tonic::Request::new(ExportTracklistRequest {
+            format: format.to_string(),
+        });
+        let response = self.client.export_tracklist(request).await?;
+        Ok(response.into_inner().content)
+    }
+
     pub async fn remove(&mut self, position: u32) -> Result<(), Error> {
         let request = tonic::Request::new(RemoveTrackAtRequest {
             position,
@@ -14,6 +14,21 @@ pub struct Model {
     pub artist_id: Option<String>,
     pub year: Option<u32>,
     pub cover: Option<String>,
+    pub cover_small: Option<String>,
+    pub cover_medium: Option<String>,
+    pub cover_large: Option<String>,
+    /// The thumbnails in WebP, unset when the artwork couldn't be decoded
+    pub cover_small_webp: Option<String>,
+    pub cover_medium_webp: Option<String>,
+    pub cover_large_webp: Option<String>,
+    pub compilation: bool,
+    pub label: Option<String>,
+    pub title_sort: Option<String>,
+    pub artist_sort: Option<String>,
+    pub musicbrainz_release_id: Option<String>,
+    /// When the album was first scanned, unknown for the albums scanned
+    /// before it was recorded
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[sea_orm(ignore)]
     pub tracks: Vec<super::track::Model>,
 }
@@ -42,7 +57,14 @@ impl Related<super::artist::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+impl ActiveModelBehavior for ActiveModel {
+    fn before_save(mut self, insert: bool) -> Result<Self, DbErr> {
+        if insert && self.created_at.is_not_set() {
+            self.created_at = ActiveValue::Set(Some(chrono::Utc::now()));
+        }
+        Ok(self)
+    }
+}
 
 impl From<&Song> for ActiveModel {
     fn from(song: &Song) -> Self {
@@ -57,6 +79,18 @@ impl From<&Song> for ActiveModel {
             ))),
             year: ActiveValue::Set(song.year),
             cover: ActiveValue::Set(song.cover.clone()),
+            cover_small: ActiveValue::NotSet,
+            cover_medium: ActiveValue::NotSet,
+            cover_large: ActiveValue::NotSet,
+            cover_small_webp: ActiveValue::NotSet,
+            cover_medium_webp: ActiveValue::NotSet,
+            cover_large_webp: ActiveValue::NotSet,
+            compilation: ActiveValue::Set(song.compilation),
+            label: ActiveValue::Set(song.label.clone()),
+            title_sort: ActiveValue::Set(song.album_sort.clone()),
+            artist_sort: ActiveValue::Set(song.album_artist_sort.clone()),
+            musicbrainz_release_id: ActiveValue::Set(song.musicbrainz_release_id.clone()),
+            created_at: ActiveValue::NotSet,
         }
     }
 }
@@ -76,9 +110,21 @@ impl From<AlbumType> for Model {
             id: album.id.clone(),
             title: album.title,
             cover: album.cover,
+            cover_small: album.cover_small,
+            cover_medium: album.cover_medium,
+            cover_large: album.cover_large,
+            cover_small_webp: album.cover_small_webp,
+            cover_medium_webp: album.cover_medium_webp,
+            cover_large_webp: album.cover_large_webp,
+            compilation: album.compilation,
+            label: album.label,
+            title_sort: album.title_sort,
+            artist_sort: album.artist_sort,
+            musicbrainz_release_id: album.musicbrainz_release_id,
             artist: album.artist,
             artist_id: album.artist_id,
             year: album.year,
+            created_at: None,
             tracks: tracks.into_iter().map(Into::into).collect(),
         }
     }
@@ -90,6 +136,17 @@ impl Into<AlbumType> for Model {
             id: self.id,
             title: self.title,
             cover: self.cover,
+            cover_small: self.cover_small,
+            cover_medium: self.cover_medium,
+            cover_large: self.cover_large,
+            cover_small_webp: self.cover_small_webp,
+            cover_medium_webp: self.cover_medium_webp,
+            cover_large_webp: self.cover_large_webp,
+            compilation: self.compilation,
+            label: self.label,
+            title_sort: self.title_sort,
+            artist_sort: self.artist_sort,
+            musicbrainz_release_id: self.musicbrainz_release_id,
             artist: self.artist,
             artist_id: self.artist_id,
             year: self.year,
@@ -105,6 +162,30 @@ impl RemoteCoverUrl for Model {
                 .cover
                 .clone()
                 .map(|cover| format!("{}/covers/{}", base_url, cover)),
+            cover_small: self
+                .cover_small
+                .clone()
+                .map(|cover| format!("{}/covers/{}", base_url, cover)),
+            cover_medium: self
+                .cover_medium
+                .clone()
+                .map(|cover| format!("{}/covers/{}", base_url, cover)),
+            cover_large: self
+                .cover_large
+                .clone()
+                .map(|cover| format!("{}/covers/{}", base_url, cover)),
+            cover_small_webp: self
+                .cover_small_webp
+                .clone()
+                .map(|cover| format!("{}/covers/{}", base_url, cover)),
+            cover_medium_webp: self
+                .cover_medium_webp
+                .clone()
+                .map(|cover| format!("{}/covers/{}", base_url, cover)),
+            cover_large_webp: self
+                .cover_large_webp
+                .clone()
+                .map(|cover| format!("{}/covers/{}", base_url, cover)),
             ..self.clone()
         }
     }
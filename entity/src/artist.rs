@@ -36,6 +36,18 @@ impl Related<super::track::Entity> for Entity {
 
 impl ActiveModelBehavior for ActiveModel {}
 
+impl ActiveModel {
+    pub fn from_artists(song: &Song) -> Vec<Self> {
+        song.linked_artists()
+            .into_iter()
+            .map(|name| Self {
+                id: ActiveValue::set(format!("{:x}", md5::compute(&name))),
+                name: ActiveValue::Set(name),
+            })
+            .collect()
+    }
+}
+
 impl From<&Song> for ActiveModel {
     fn from(song: &Song) -> Self {
         let id = format!("{:x}", md5::compute(song.album_artist.to_owned()));
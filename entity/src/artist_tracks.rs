@@ -26,20 +26,41 @@ pub enum Relation {
     Track,
 }
 
+impl Related<super::artist::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Artist.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
 
+impl ActiveModel {
+    pub fn from_artists(song: &Song) -> Vec<Self> {
+        let uri = song.location();
+        let track_id = song.track_id();
+        song.linked_artists()
+            .into_iter()
+            .map(|name| Self {
+                id: ActiveValue::set(format!("{:x}", md5::compute(format!("{}{}", name, uri)))),
+                artist_id: ActiveValue::Set(format!("{:x}", md5::compute(&name))),
+                track_id: ActiveValue::Set(track_id.clone()),
+            })
+            .collect()
+    }
+}
+
 impl From<&Song> for ActiveModel {
     fn from(song: &Song) -> Self {
         Self {
             id: ActiveValue::set(format!(
                 "{:x}",
-                md5::compute(format!("{}{}", song.artist, song.uri.as_ref().unwrap()))
+                md5::compute(format!("{}{}", song.artist, song.location()))
             )),
             artist_id: ActiveValue::Set(format!(
                 "{:x}",
                 md5::compute(song.album_artist.to_owned())
             )),
-            track_id: ActiveValue::Set(format!("{:x}", md5::compute(song.uri.as_ref().unwrap()))),
+            track_id: ActiveValue::Set(song.track_id()),
         }
     }
 }
@@ -0,0 +1,36 @@
+use music_player_types::rating::FavouriteKind;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "favourite")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    /// `track`, `album` or `artist`
+    pub kind: String,
+    pub item_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    pub fn new(kind: FavouriteKind, item_id: &str) -> Self {
+        Self {
+            id: ActiveValue::Set(favourite_id(kind, item_id)),
+            kind: ActiveValue::Set(kind.as_str().to_string()),
+            item_id: ActiveValue::Set(item_id.to_string()),
+            created_at: ActiveValue::Set(chrono::Utc::now()),
+        }
+    }
+}
+
+/// The kind is part of the id: an album and an artist sharing a name have
+/// the same id, the md5 of the name.
+pub fn favourite_id(kind: FavouriteKind, item_id: &str) -> String {
+    format!("{}:{}", kind.as_str(), item_id)
+}
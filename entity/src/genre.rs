@@ -0,0 +1,43 @@
+use music_player_types::genre::{genre_id, split_genres};
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "genre")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub name: String,
+    #[sea_orm(ignore)]
+    pub albums: Vec<super::album::Model>,
+    #[sea_orm(ignore)]
+    pub tracks: Vec<super::track::Model>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl Related<super::track::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::genre_tracks::Relation::Track.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::genre_tracks::Relation::Genre.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// The genres of a genre tag.
+    pub fn from_genres(genre: &str) -> Vec<Self> {
+        split_genres(genre)
+            .into_iter()
+            .map(|name| Self {
+                id: ActiveValue::set(genre_id(&name)),
+                name: ActiveValue::Set(name),
+            })
+            .collect()
+    }
+}
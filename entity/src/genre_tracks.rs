@@ -0,0 +1,49 @@
+use music_player_types::genre::{genre_id, split_genres};
+use sea_orm::{entity::prelude::*, ActiveValue};
+
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "genre_track")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub genre_id: String,
+    pub track_id: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::genre::Entity",
+        from = "Column::GenreId",
+        to = "super::genre::Column::Id"
+    )]
+    Genre,
+    #[sea_orm(
+        belongs_to = "super::track::Entity",
+        from = "Column::TrackId",
+        to = "super::track::Column::Id"
+    )]
+    Track,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Links a track to each genre of its genre tag.
+    pub fn from_genres(track_id: &str, genre: &str) -> Vec<Self> {
+        split_genres(genre)
+            .into_iter()
+            .map(|name| {
+                let genre_id = genre_id(&name);
+                Self {
+                    id: ActiveValue::set(format!(
+                        "{:x}",
+                        md5::compute(format!("{}{}", genre_id, track_id))
+                    )),
+                    genre_id: ActiveValue::Set(genre_id),
+                    track_id: ActiveValue::Set(track_id.to_string()),
+                }
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,69 @@
+use music_player_types::types::{HistoryEntry, Track};
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "history")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub track_id: String,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    pub listened_ms: u32,
+    pub completed: bool,
+    pub device: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Track,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Track => Entity::belongs_to(super::track::Entity)
+                .from(Column::TrackId)
+                .to(super::track::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::track::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Track.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<&HistoryEntry> for ActiveModel {
+    fn from(entry: &HistoryEntry) -> Self {
+        Self {
+            id: ActiveValue::set(entry.id.clone()),
+            track_id: ActiveValue::Set(entry.track.id.clone()),
+            started_at: ActiveValue::Set(entry.started_at),
+            listened_ms: ActiveValue::Set(entry.listened_ms),
+            completed: ActiveValue::Set(entry.completed),
+            device: ActiveValue::Set(entry.device.clone()),
+        }
+    }
+}
+
+impl Model {
+    /// The track is left with its id only when it was removed from the library.
+    pub fn into_entry(self, track: Option<super::track::Model>) -> HistoryEntry {
+        HistoryEntry {
+            id: self.id,
+            track: track.map(Into::into).unwrap_or_else(|| Track {
+                id: self.track_id,
+                ..Default::default()
+            }),
+            started_at: self.started_at,
+            listened_ms: self.listened_ms,
+            completed: self.completed,
+            device: self.device,
+        }
+    }
+}
@@ -1,14 +1,24 @@
 #[cfg(test)]
 mod tests;
 
+pub mod acoustic_fingerprint;
 pub mod addon;
 pub mod album;
 pub mod artist;
 pub mod artist_tracks;
+pub mod favourite;
 pub mod folder;
+pub mod genre;
+pub mod genre_tracks;
+pub mod history;
+pub mod lyrics;
 pub mod playlist;
 pub mod playlist_tracks;
+pub mod scan_error;
+pub mod smart_playlist;
+pub mod smart_playlist_rule;
 pub mod track;
+pub mod track_rating;
 
 pub mod select_result {
     use music_player_types::types::{Album, Artist, Track};
@@ -0,0 +1,42 @@
+use music_player_types::types::{Lyrics, LyricsSource};
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "lyrics")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub track_id: String,
+    pub content: String,
+    pub synced: bool,
+    pub source: String,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    /// Lyrics are stored once per track, so the track id is used as primary key.
+    pub fn from_lyrics(track_id: &str, lyrics: &Lyrics) -> Self {
+        Self {
+            id: ActiveValue::set(track_id.to_string()),
+            track_id: ActiveValue::Set(track_id.to_string()),
+            content: ActiveValue::Set(lyrics.content.clone()),
+            synced: ActiveValue::Set(lyrics.synced),
+            source: ActiveValue::Set(lyrics.source.as_str().to_string()),
+        }
+    }
+}
+
+impl From<Model> for Lyrics {
+    fn from(model: Model) -> Self {
+        Self {
+            content: model.content,
+            synced: model.synced,
+            source: LyricsSource::from(model.source.as_str()),
+        }
+    }
+}
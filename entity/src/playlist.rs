@@ -13,6 +13,9 @@ pub struct Model {
     pub tracks: Vec<super::track::Model>,
     pub folder_id: Option<String>,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Hash of the content of the playlist file of the library it was
+    /// imported from.
+    pub file_hash: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter)]
@@ -56,6 +59,7 @@ impl Into<PlaylistType> for Model {
             name: self.name,
             description: self.description,
             tracks: self.tracks.into_iter().map(Into::into).collect(),
+            rules: None,
         }
     }
 }
@@ -7,6 +7,8 @@ pub struct Model {
     pub id: String,
     pub playlist_id: String,
     pub track_id: String,
+    /// Index of the track in the playlist, from 0
+    pub position: i32,
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
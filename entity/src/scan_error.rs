@@ -0,0 +1,34 @@
+use music_player_types::types::ScanError;
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "scan_error")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub path: String,
+    pub kind: String,
+    pub message: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl From<&ScanError> for ActiveModel {
+    /// A file has at most one error of each kind, the id is the same when the
+    /// error is reported again.
+    fn from(error: &ScanError) -> Self {
+        let key = format!("{}:{}", error.kind.as_str(), error.path);
+        Self {
+            id: ActiveValue::set(format!("{:x}", md5::compute(key.as_bytes()))),
+            path: ActiveValue::Set(error.path.clone()),
+            kind: ActiveValue::Set(error.kind.as_str().to_string()),
+            message: ActiveValue::Set(error.message.clone()),
+            created_at: ActiveValue::Set(chrono::Utc::now()),
+        }
+    }
+}
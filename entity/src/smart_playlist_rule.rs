@@ -0,0 +1,23 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A node of the rule tree of a smart playlist: `all`, `any` or `not` nodes
+/// group the nodes pointing to them, `condition` nodes are the leaves.
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "smart_playlist_rule")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: String,
+    pub playlist_id: String,
+    pub parent_id: Option<String>,
+    pub position: u32,
+    pub kind: String,
+    pub field: Option<String>,
+    pub operator: Option<String>,
+    pub value: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -186,3 +186,40 @@ fn song_to_artist() {
     );
     assert_eq!(artist.name, ActiveValue::Set(song.album_artist.clone()));
 }
+
+#[test]
+fn song_with_rich_tags_to_track() {
+    let song = Song {
+        artist: "Berliner Philharmoniker".to_owned(),
+        title: "Symphony No. 5 in C minor, Op. 67: I. Allegro con brio".to_owned(),
+        uri: Some("/Music/Beethoven/Symphonies/2-01 Allegro con brio.flac".to_owned()),
+        album: "Symphonies".to_owned(),
+        album_artist: "Berliner Philharmoniker".to_owned(),
+        track: Some(1),
+        disc_number: Some(2),
+        disc_total: Some(5),
+        track_total: Some(4),
+        composer: Some("Ludwig van Beethoven".to_owned()),
+        conductor: Some("Herbert von Karajan".to_owned()),
+        label: Some("Deutsche Grammophon".to_owned()),
+        isrc: Some("DEF056330000".to_owned()),
+        bpm: Some(108),
+        musicbrainz_release_id: Some("5cfa0f49-0a97-4e2b-b3a6-b5b7b8f2e1d4".to_owned()),
+        ..Default::default()
+    };
+    let track = Track::from(&song);
+    assert_eq!(track.disc_number, ActiveValue::Set(Some(2)));
+    assert_eq!(track.disc_total, ActiveValue::Set(Some(5)));
+    assert_eq!(track.track_total, ActiveValue::Set(Some(4)));
+    assert_eq!(track.composer, ActiveValue::Set(song.composer.clone()));
+    assert_eq!(track.conductor, ActiveValue::Set(song.conductor.clone()));
+    assert_eq!(track.isrc, ActiveValue::Set(song.isrc.clone()));
+    assert_eq!(track.bpm, ActiveValue::Set(Some(108)));
+
+    let album = album_entity::ActiveModel::from(&song);
+    assert_eq!(album.label, ActiveValue::Set(song.label.clone()));
+    assert_eq!(
+        album.musicbrainz_release_id,
+        ActiveValue::Set(song.musicbrainz_release_id.clone())
+    );
+}
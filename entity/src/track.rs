@@ -23,6 +23,31 @@ pub struct Model {
     pub uri: String,
     pub album_id: Option<String>,
     pub artist_id: Option<String>,
+    pub disc_number: Option<u32>,
+    pub disc_total: Option<u32>,
+    pub track_total: Option<u32>,
+    pub composer: Option<String>,
+    pub conductor: Option<String>,
+    pub isrc: Option<String>,
+    pub comment: Option<String>,
+    pub bpm: Option<u32>,
+    pub title_sort: Option<String>,
+    pub artist_sort: Option<String>,
+    pub musicbrainz_recording_id: Option<String>,
+    pub content_hash: Option<String>,
+    pub loudness: Option<f32>,
+    pub true_peak: Option<f32>,
+    pub replaygain_track_gain: Option<f32>,
+    pub replaygain_track_peak: Option<f32>,
+    pub replaygain_album_gain: Option<f32>,
+    pub replaygain_album_peak: Option<f32>,
+    pub tempo: Option<f32>,
+    pub musical_key: Option<String>,
+    pub start_offset: Option<u32>,
+    pub end_offset: Option<u32>,
+    /// When the track was first scanned, unknown for the tracks scanned
+    /// before it was recorded
+    pub created_at: Option<chrono::DateTime<chrono::Utc>>,
     #[sea_orm(ignore)]
     pub artists: Vec<artist::Model>,
     #[sea_orm(ignore)]
@@ -71,7 +96,24 @@ impl Related<super::artist::Entity> for Entity {
     }
 }
 
-impl ActiveModelBehavior for ActiveModel {}
+impl Related<super::genre::Entity> for Entity {
+    fn to() -> RelationDef {
+        super::genre_tracks::Relation::Genre.def()
+    }
+
+    fn via() -> Option<RelationDef> {
+        Some(super::genre_tracks::Relation::Track.def().rev())
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {
+    fn before_save(mut self, insert: bool) -> Result<Self, DbErr> {
+        if insert && self.created_at.is_not_set() {
+            self.created_at = ActiveValue::Set(Some(chrono::Utc::now()));
+        }
+        Ok(self)
+    }
+}
 
 #[derive(Debug)]
 pub struct TrackToAlbum;
@@ -120,7 +162,7 @@ impl Linked for TrackToPlaylist {
 
 impl From<&Song> for ActiveModel {
     fn from(song: &Song) -> Self {
-        let id = format!("{:x}", md5::compute(song.uri.as_ref().unwrap()));
+        let id = song.track_id();
         Self {
             id: ActiveValue::set(id),
             artist: ActiveValue::Set(song.artist.clone()),
@@ -142,6 +184,94 @@ impl From<&Song> for ActiveModel {
                 "{:x}",
                 md5::compute(song.album_artist.to_owned())
             ))),
+            disc_number: ActiveValue::Set(song.disc_number),
+            disc_total: ActiveValue::Set(song.disc_total),
+            track_total: ActiveValue::Set(song.track_total),
+            composer: ActiveValue::Set(song.composer.clone()),
+            conductor: ActiveValue::Set(song.conductor.clone()),
+            isrc: ActiveValue::Set(song.isrc.clone()),
+            comment: ActiveValue::Set(song.comment.clone()),
+            bpm: ActiveValue::Set(song.bpm),
+            title_sort: ActiveValue::Set(song.title_sort.clone()),
+            artist_sort: ActiveValue::Set(song.artist_sort.clone()),
+            musicbrainz_recording_id: ActiveValue::Set(song.musicbrainz_recording_id.clone()),
+            content_hash: ActiveValue::Set(song.content_hash.clone()),
+            // Filled by the analysis pass, left untouched by scans and tag edits
+            loudness: ActiveValue::NotSet,
+            true_peak: ActiveValue::NotSet,
+            replaygain_track_gain: ActiveValue::NotSet,
+            replaygain_track_peak: ActiveValue::NotSet,
+            replaygain_album_gain: ActiveValue::NotSet,
+            replaygain_album_peak: ActiveValue::NotSet,
+            tempo: ActiveValue::NotSet,
+            musical_key: ActiveValue::NotSet,
+            start_offset: ActiveValue::Set(song.start_offset),
+            end_offset: ActiveValue::Set(song.end_offset),
+            created_at: ActiveValue::NotSet,
+        }
+    }
+}
+
+impl From<&Song> for Model {
+    fn from(song: &Song) -> Self {
+        let album_id = format!("{:x}", md5::compute(&song.album));
+        let artist_id = format!("{:x}", md5::compute(&song.album_artist));
+        Self {
+            id: song.track_id(),
+            title: song.title.clone(),
+            artist: song.artist.clone(),
+            genre: song.genre.clone(),
+            year: song.year,
+            track: song.track,
+            bitrate: song.bitrate,
+            sample_rate: song.sample_rate,
+            bit_depth: song.bit_depth,
+            channels: song.channels,
+            duration: Some(song.duration.as_secs_f32()),
+            uri: song.uri.clone().unwrap_or_default(),
+            album_id: Some(album_id.clone()),
+            artist_id: Some(artist_id.clone()),
+            disc_number: song.disc_number,
+            disc_total: song.disc_total,
+            track_total: song.track_total,
+            composer: song.composer.clone(),
+            conductor: song.conductor.clone(),
+            isrc: song.isrc.clone(),
+            comment: song.comment.clone(),
+            bpm: song.bpm,
+            title_sort: song.title_sort.clone(),
+            artist_sort: song.artist_sort.clone(),
+            musicbrainz_recording_id: song.musicbrainz_recording_id.clone(),
+            content_hash: song.content_hash.clone(),
+            loudness: None,
+            true_peak: None,
+            replaygain_track_gain: None,
+            replaygain_track_peak: None,
+            replaygain_album_gain: None,
+            replaygain_album_peak: None,
+            tempo: None,
+            musical_key: None,
+            start_offset: song.start_offset,
+            end_offset: song.end_offset,
+            created_at: None,
+            artists: song
+                .linked_artists()
+                .into_iter()
+                .map(|name| artist::Model {
+                    id: format!("{:x}", md5::compute(&name)),
+                    name,
+                    ..Default::default()
+                })
+                .collect(),
+            album: album::Model {
+                id: album_id,
+                title: song.album.clone(),
+                artist: song.album_artist.clone(),
+                artist_id: Some(artist_id),
+                year: song.year,
+                cover: song.cover.clone(),
+                ..Default::default()
+            },
         }
     }
 }
@@ -187,6 +317,20 @@ impl From<TrackType> for Model {
                 Some(track.artists[0].id.clone())
             },
             duration: track.duration,
+            track: track.track_number,
+            disc_number: Some(track.disc_number).filter(|disc| *disc > 0),
+            disc_total: track.disc_total,
+            track_total: track.track_total,
+            composer: track.composer,
+            conductor: track.conductor,
+            isrc: track.isrc,
+            comment: track.comment,
+            bpm: track.bpm,
+            title_sort: track.title_sort,
+            artist_sort: track.artist_sort,
+            musicbrainz_recording_id: track.musicbrainz_recording_id,
+            start_offset: track.start_offset,
+            end_offset: track.end_offset,
             album: album::Model {
                 id: track_album.id,
                 title: track_album.title,
@@ -208,9 +352,22 @@ impl Into<TrackType> for Model {
             artist: self.artist,
             uri: self.uri,
             duration: self.duration,
+            track_number: self.track,
+            disc_number: self.disc_number.unwrap_or_default(),
+            disc_total: self.disc_total,
+            track_total: self.track_total,
+            composer: self.composer,
+            conductor: self.conductor,
+            isrc: self.isrc,
+            comment: self.comment,
+            bpm: self.bpm,
+            title_sort: self.title_sort,
+            artist_sort: self.artist_sort,
+            musicbrainz_recording_id: self.musicbrainz_recording_id,
+            start_offset: self.start_offset,
+            end_offset: self.end_offset,
             album: Some(self.album.into()),
             artists: self.artists.into_iter().map(Into::into).collect(),
-            ..Default::default()
         }
     }
 }
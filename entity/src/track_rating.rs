@@ -0,0 +1,47 @@
+use sea_orm::{entity::prelude::*, ActiveValue};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "track_rating")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub track_id: String,
+    /// From 1 to 5 stars, 0 once the rating was removed, tracks never rated
+    /// have no row
+    pub rating: u8,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter)]
+pub enum Relation {
+    Track,
+}
+
+impl RelationTrait for Relation {
+    fn def(&self) -> RelationDef {
+        match self {
+            Self::Track => Entity::belongs_to(super::track::Entity)
+                .from(Column::TrackId)
+                .to(super::track::Column::Id)
+                .into(),
+        }
+    }
+}
+
+impl Related<super::track::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Track.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl ActiveModel {
+    pub fn new(track_id: &str, rating: u8) -> Self {
+        Self {
+            track_id: ActiveValue::Set(track_id.to_string()),
+            rating: ActiveValue::Set(rating),
+            updated_at: ActiveValue::Set(chrono::Utc::now()),
+        }
+    }
+}
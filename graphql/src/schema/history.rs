@@ -1 +1,81 @@
+use async_graphql::*;
+use futures_util::Stream;
+use music_player_storage::{repo::history::HistoryRepository, Database};
+
+use crate::simple_broker::SimpleBroker;
+
+use super::objects::{
+    connection::{paginate, LibraryConnection},
+    history::{HistoryEntry, PlayStats},
+};
+
+#[derive(Default)]
 pub struct HistoryQuery;
+
+#[Object]
+impl HistoryQuery {
+    /// The tracks played, from the most recent one.
+    async fn history(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<HistoryEntry>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let entries = HistoryRepository::new(db.get_connection())
+            .find_all(offset.unwrap_or(0) as u64, limit.unwrap_or(100) as u64)
+            .await?;
+        Ok(entries.into_iter().map(Into::into).collect())
+    }
+
+    /// The tracks played from the most recent one, in pages following keyset
+    /// cursors, which stay put while new plays are recorded.
+    async fn history_connection(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<LibraryConnection<HistoryEntry>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        paginate(after, before, first, last, |request| async move {
+            HistoryRepository::new(db.get_connection())
+                .find_page(&request)
+                .await
+        })
+        .await
+    }
+
+    async fn play_stats(&self, ctx: &Context<'_>, track_id: ID) -> Result<PlayStats, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let stats = HistoryRepository::new(db.get_connection())
+            .stats(&track_id)
+            .await?;
+        Ok(stats.into())
+    }
+
+    async fn most_played(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<PlayStats>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let stats = HistoryRepository::new(db.get_connection())
+            .most_played(offset.unwrap_or(0) as u64, limit.unwrap_or(100) as u64)
+            .await?;
+        Ok(stats.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Default)]
+pub struct HistorySubscription;
+
+#[Subscription]
+impl HistorySubscription {
+    /// Emits each play once it is recorded.
+    async fn history(&self) -> impl Stream<Item = HistoryEntry> {
+        SimpleBroker::<HistoryEntry>::subscribe()
+    }
+}
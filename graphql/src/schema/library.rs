@@ -2,18 +2,37 @@ use std::sync::Arc;
 
 use async_graphql::{futures_util::FutureExt, *};
 use music_player_addons::CurrentSourceDevice;
-use music_player_entity::{album as album_entity, artist as artist_entity, track as track_entity};
-use music_player_scanner::scan_directory;
+use music_player_scanner::{
+    duplicates::find_duplicates,
+    editor::{edit_album, edit_tracks},
+    save_song, scan_directory,
+};
 use music_player_storage::{
-    repo::{album::AlbumRepository, artist::ArtistRepository, track::TrackRepository},
+    repo::{
+        album::AlbumRepository, artist::ArtistRepository, genre::GenreRepository,
+        lyrics::LyricsRepository, scan_error::ScanErrorRepository, track::TrackRepository,
+    },
     searcher::Searcher,
     Database,
 };
-use music_player_types::types::{RemoteCoverUrl, RemoteTrackUrl};
-use sea_orm::{ActiveModelTrait, ActiveValue};
+use music_player_types::types::{RemoteCoverUrl, RemoteTrackUrl, TagChanges};
 use tokio::sync::Mutex;
 
-use super::objects::{album::Album, artist::Artist, search_result::SearchResult, track::Track};
+use super::objects::{
+    album::{Album, AlbumSortInput},
+    artist::Artist,
+    connection::{paginate, LibraryConnection},
+    duplicate_group::DuplicateGroup,
+    genre::Genre,
+    lyrics::Lyrics,
+    scan_error::ScanError,
+    search_result::SearchResult,
+    suggestion::Suggestion,
+    track::{
+        AudioFilterInput, LibraryFilterInput, TagChangesInput, Track, TrackBoundsInput,
+        TrackFilterInput, TrackSortInput,
+    },
+};
 
 #[derive(Default)]
 pub struct LibraryQuery;
@@ -26,6 +45,8 @@ impl LibraryQuery {
         filter: Option<String>,
         offset: Option<i32>,
         limit: Option<i32>,
+        bounds: Option<TrackBoundsInput>,
+        sort: Option<TrackSortInput>,
     ) -> Result<Vec<Track>, Error> {
         let current_device = ctx.data::<Arc<Mutex<CurrentSourceDevice>>>().unwrap();
         let mut device = current_device.lock().await;
@@ -57,9 +78,13 @@ impl LibraryQuery {
         }
 
         let db = ctx.data::<Database>().unwrap();
+        let TrackBoundsInput { tags, audio } = bounds.unwrap_or_default();
         let results = TrackRepository::new(db.get_connection())
-            .find_all(
+            .find_all_matching(
                 filter,
+                &tags.map(Into::into).unwrap_or_default(),
+                &audio.map(Into::into).unwrap_or_default(),
+                sort.map(Into::into).unwrap_or_default(),
                 Some(offset.unwrap_or(0) as u64),
                 limit.unwrap_or(100) as u64,
             )
@@ -68,6 +93,34 @@ impl LibraryQuery {
         Ok(results.into_iter().map(Into::into).collect())
     }
 
+    /// The tracks of the local library sorted like `tracks`, by title by
+    /// default, in pages following keyset cursors, which stay put while a scan
+    /// adds tracks.
+    #[allow(clippy::too_many_arguments)]
+    async fn tracks_connection(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<LibraryFilterInput>,
+        audio: Option<AudioFilterInput>,
+        sort: Option<TrackSortInput>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<LibraryConnection<Track>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let LibraryFilterInput { text, tags } = filter.unwrap_or_default();
+        let tags = tags.map(Into::into).unwrap_or_default();
+        let audio = audio.map(Into::into).unwrap_or_default();
+        let order = sort.map(Into::into).unwrap_or_default();
+        paginate(after, before, first, last, |request| async move {
+            TrackRepository::new(db.get_connection())
+                .find_page(text, &tags, &audio, order, &request)
+                .await
+        })
+        .await
+    }
+
     async fn artists(
         &self,
         ctx: &Context<'_>,
@@ -112,12 +165,51 @@ impl LibraryQuery {
         Ok(results.into_iter().map(Into::into).collect())
     }
 
+    /// The artists of the local library sorted by name, in pages following
+    /// keyset cursors.
+    async fn artists_connection(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<String>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<LibraryConnection<Artist>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        paginate(after, before, first, last, |request| async move {
+            ArtistRepository::new(db.get_connection())
+                .find_page(filter, &request)
+                .await
+        })
+        .await
+    }
+
+    /// The genres split from the genre tags of the tracks, "Hip-Hop" and
+    /// "Hip Hop" being the same genre.
+    async fn genres(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<String>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<Genre>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let results = GenreRepository::new(db.get_connection())
+            .find_all(filter, offset.map(|x| x as u64), limit.map(|x| x as u64))
+            .await?;
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    /// The albums having at least one track matching `tags`.
     async fn albums(
         &self,
         ctx: &Context<'_>,
         filter: Option<String>,
         offset: Option<i32>,
         limit: Option<i32>,
+        tags: Option<TrackFilterInput>,
+        sort: Option<AlbumSortInput>,
     ) -> Result<Vec<Album>, Error> {
         let current_device = ctx.data::<Arc<Mutex<CurrentSourceDevice>>>().unwrap();
         let mut device = current_device.lock().await;
@@ -151,12 +243,40 @@ impl LibraryQuery {
         let db = ctx.data::<Database>().unwrap();
 
         let results = AlbumRepository::new(db.get_connection())
-            .find_all(filter, offset.map(|x| x as u64), limit.map(|x| x as u64))
+            .find_all_matching(
+                filter,
+                &tags.map(Into::into).unwrap_or_default(),
+                sort.map(Into::into).unwrap_or_default(),
+                offset.map(|x| x as u64),
+                limit.map(|x| x as u64),
+            )
             .await?;
 
         Ok(results.into_iter().map(Into::into).collect())
     }
 
+    /// The albums of the local library having at least one track matching
+    /// the `tags` of `filter`, sorted by title, in pages following keyset cursors.
+    async fn albums_connection(
+        &self,
+        ctx: &Context<'_>,
+        filter: Option<LibraryFilterInput>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<LibraryConnection<Album>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let LibraryFilterInput { text, tags } = filter.unwrap_or_default();
+        let tags = tags.map(Into::into).unwrap_or_default();
+        paginate(after, before, first, last, |request| async move {
+            AlbumRepository::new(db.get_connection())
+                .find_page(text, &tags, &request)
+                .await
+        })
+        .await
+    }
+
     async fn track(&self, ctx: &Context<'_>, id: ID) -> Result<Track, Error> {
         let current_device = ctx.data::<Arc<Mutex<CurrentSourceDevice>>>().unwrap();
         let mut device = current_device.lock().await;
@@ -216,6 +336,14 @@ impl LibraryQuery {
         Ok(artist.into())
     }
 
+    async fn genre(&self, ctx: &Context<'_>, id: ID) -> Result<Genre, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let genre = GenreRepository::new(db.get_connection())
+            .find(&id)
+            .await?;
+        Ok(genre.into())
+    }
+
     async fn album(&self, ctx: &Context<'_>, id: ID) -> Result<Album, Error> {
         let current_device = ctx.data::<Arc<Mutex<CurrentSourceDevice>>>().unwrap();
 
@@ -246,18 +374,71 @@ impl LibraryQuery {
         Ok(album.into())
     }
 
-    async fn search(&self, ctx: &Context<'_>, keyword: String) -> Result<SearchResult, Error> {
+    /// Searches the tracks, albums and artists ranked by relevance. The
+    /// keyword can target fields and exclude words, e.g.
+    /// `artist:"miles davis" year:1955..1960 genre:jazz -live`.
+    async fn search(
+        &self,
+        ctx: &Context<'_>,
+        keyword: String,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<SearchResult, Error> {
+        // The searcher rejects the pages out of bounds
         let searcher = ctx.data::<Arc<Mutex<Searcher>>>().unwrap();
         let indexer = searcher.lock().await;
-        let artists = indexer.artist.search(&keyword)?;
-        let albums = indexer.album.search(&keyword)?;
-        let tracks = indexer.track.search(&keyword)?;
+        let results = indexer.search(
+            &keyword,
+            offset.unwrap_or(0).max(0) as usize,
+            limit.unwrap_or(10).max(0) as usize,
+        )?;
         Ok(SearchResult {
-            artists: artists.into_iter().map(|x| Into::into(x)).collect(),
-            tracks: tracks.into_iter().map(|x| Into::into(x)).collect(),
-            albums: albums.into_iter().map(|x| Into::into(x)).collect(),
+            artists: results.artists.items.into_iter().map(Into::into).collect(),
+            tracks: results.tracks.items.into_iter().map(Into::into).collect(),
+            albums: results.albums.items.into_iter().map(Into::into).collect(),
+            total_artists: results.artists.total,
+            total_tracks: results.tracks.total,
+            total_albums: results.albums.total,
         })
     }
+
+    /// Completes the beginning of the words of the titles, artists, albums
+    /// and genres while typing.
+    async fn suggestions(
+        &self,
+        ctx: &Context<'_>,
+        input: String,
+        limit: Option<i32>,
+    ) -> Result<Vec<Suggestion>, Error> {
+        let searcher = ctx.data::<Arc<Mutex<Searcher>>>().unwrap();
+        let suggestions = searcher
+            .lock()
+            .await
+            .suggest(&input, limit.unwrap_or(10).max(0) as usize)?;
+        Ok(suggestions.into_iter().map(Into::into).collect())
+    }
+
+    async fn scan_errors(&self, ctx: &Context<'_>) -> Result<Vec<ScanError>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let results = ScanErrorRepository::new(db.get_connection())
+            .find_all()
+            .await?;
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    async fn duplicates(&self, ctx: &Context<'_>) -> Result<Vec<DuplicateGroup>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let results = find_duplicates(db).await?;
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    async fn lyrics(&self, ctx: &Context<'_>, track_id: ID) -> Result<Option<Lyrics>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let lyrics = LyricsRepository::new(db.get_connection())
+            .find_by_track(&track_id)
+            .await?;
+        Ok(lyrics.map(Into::into))
+    }
 }
 
 #[derive(Default)]
@@ -267,68 +448,7 @@ pub struct LibraryMutation;
 impl LibraryMutation {
     async fn scan(&self, _ctx: &Context<'_>) -> Result<bool, Error> {
         scan_directory(
-            move |song, db| {
-                async move {
-                    let id = format!("{:x}", md5::compute(song.artist.to_string()));
-                    let item = artist_entity::ActiveModel {
-                        id: ActiveValue::set(id),
-                        name: ActiveValue::Set(song.artist.clone()),
-                    };
-                    match item.insert(db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
-
-                    let id = format!(
-                        "{:x}",
-                        md5::compute(format!("{}{}", song.album, song.artist))
-                    );
-                    let item = album_entity::ActiveModel {
-                        id: ActiveValue::set(id),
-                        artist: ActiveValue::set(song.artist.clone()),
-                        title: ActiveValue::Set(song.album.clone()),
-                        artist_id: ActiveValue::Set(Some(format!(
-                            "{:x}",
-                            md5::compute(song.artist.to_string())
-                        ))),
-                        year: ActiveValue::Set(song.year),
-                        cover: ActiveValue::Set(song.cover.clone()),
-                    };
-                    match item.insert(db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
-                    let id = format!("{:x}", md5::compute(song.uri.as_ref().unwrap()));
-                    let item = track_entity::ActiveModel {
-                        id: ActiveValue::set(id),
-                        title: ActiveValue::Set(song.title.clone()),
-                        artist: ActiveValue::set(song.artist.clone()),
-                        genre: ActiveValue::Set(song.genre.clone()),
-                        year: ActiveValue::Set(song.year),
-                        track: ActiveValue::Set(song.track),
-                        bitrate: ActiveValue::Set(song.bitrate),
-                        sample_rate: ActiveValue::Set(song.sample_rate),
-                        bit_depth: ActiveValue::Set(song.bit_depth),
-                        channels: ActiveValue::Set(song.channels),
-                        duration: ActiveValue::Set(Some(song.duration.as_secs_f32())),
-                        uri: ActiveValue::Set(song.uri.clone().unwrap_or_default()),
-                        album_id: ActiveValue::Set(Some(format!(
-                            "{:x}",
-                            md5::compute(format!("{}{}", song.album, song.artist))
-                        ))),
-                        artist_id: ActiveValue::Set(Some(format!(
-                            "{:x}",
-                            md5::compute(song.artist.to_string())
-                        ))),
-                    };
-
-                    match item.insert(db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
-                }
-                .boxed()
-            },
+            move |song, db| save_song(song, db).boxed(),
             &Database::new().await,
             &Searcher::new(),
         )
@@ -336,4 +456,34 @@ impl LibraryMutation {
 
         Ok(false)
     }
+
+    async fn update_track_tags(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        changes: TagChangesInput,
+        #[graphql(default)] dry_run: bool,
+    ) -> Result<Track, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let searcher = ctx.data::<Arc<Mutex<Searcher>>>().unwrap();
+        let searcher = searcher.lock().await;
+        let changes = TagChanges::try_from(changes)?;
+        let mut tracks = edit_tracks(db, &searcher, &[id.to_string()], &changes, dry_run).await?;
+        Ok(tracks.remove(0).into())
+    }
+
+    async fn update_album_tags(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        changes: TagChangesInput,
+        #[graphql(default)] dry_run: bool,
+    ) -> Result<Vec<Track>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let searcher = ctx.data::<Arc<Mutex<Searcher>>>().unwrap();
+        let searcher = searcher.lock().await;
+        let changes = TagChanges::try_from(changes)?;
+        let tracks = edit_album(db, &searcher, &id, &changes, dry_run).await?;
+        Ok(tracks.into_iter().map(Into::into).collect())
+    }
 }
@@ -0,0 +1,196 @@
+use std::collections::HashMap;
+
+use async_graphql::{
+    async_trait,
+    dataloader::{DataLoader, Loader},
+    Error, SchemaBuilder,
+};
+use music_player_entity::{album as album_entity, artist as artist_entity, track as track_entity};
+use music_player_storage::{
+    repo::{album::AlbumRepository, rating::RatingRepository, track::TrackRepository},
+    Database,
+};
+use music_player_types::rating::FavouriteKind;
+
+/// Registers the loaders resolving the relations of the tracks, albums and
+/// artists, and their ratings: the relations of a page of results are fetched
+/// with one query per relation instead of one query per row.
+pub fn with_loaders<Query, Mutation, Subscription>(
+    builder: SchemaBuilder<Query, Mutation, Subscription>,
+    db: &Database,
+) -> SchemaBuilder<Query, Mutation, Subscription> {
+    builder
+        .data(DataLoader::new(AlbumLoader::new(db), tokio::spawn))
+        .data(DataLoader::new(TrackArtistsLoader::new(db), tokio::spawn))
+        .data(DataLoader::new(AlbumTracksLoader::new(db), tokio::spawn))
+        .data(DataLoader::new(ArtistAlbumsLoader::new(db), tokio::spawn))
+        .data(DataLoader::new(RatingLoader::new(db), tokio::spawn))
+        .data(DataLoader::new(FavouriteLoader::new(db), tokio::spawn))
+}
+
+/// The album of a track, by album id.
+pub struct AlbumLoader {
+    db: Database,
+}
+
+impl AlbumLoader {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for AlbumLoader {
+    type Value = album_entity::Model;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let albums = AlbumRepository::new(self.db.get_connection())
+            .find_by_ids(keys)
+            .await?;
+        Ok(albums
+            .into_iter()
+            .map(|album| (album.id.clone(), album))
+            .collect())
+    }
+}
+
+/// The artists of a track, by track id.
+pub struct TrackArtistsLoader {
+    db: Database,
+}
+
+impl TrackArtistsLoader {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for TrackArtistsLoader {
+    type Value = Vec<artist_entity::Model>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let artists = TrackRepository::new(self.db.get_connection())
+            .find_artists(keys)
+            .await?;
+        Ok(artists)
+    }
+}
+
+/// The tracks of an album, by album id.
+pub struct AlbumTracksLoader {
+    db: Database,
+}
+
+impl AlbumTracksLoader {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for AlbumTracksLoader {
+    type Value = Vec<track_entity::Model>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let tracks = TrackRepository::new(self.db.get_connection())
+            .find_by_albums(keys)
+            .await?;
+        Ok(tracks)
+    }
+}
+
+/// The albums of an artist, by artist id.
+pub struct ArtistAlbumsLoader {
+    db: Database,
+}
+
+impl ArtistAlbumsLoader {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for ArtistAlbumsLoader {
+    type Value = Vec<album_entity::Model>;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let albums = AlbumRepository::new(self.db.get_connection())
+            .find_by_artists(keys)
+            .await?;
+        Ok(albums)
+    }
+}
+
+/// The rating of a track, by track id. Unrated tracks have no value.
+pub struct RatingLoader {
+    db: Database,
+}
+
+impl RatingLoader {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<String> for RatingLoader {
+    type Value = u8;
+    type Error = Error;
+
+    async fn load(&self, keys: &[String]) -> Result<HashMap<String, Self::Value>, Self::Error> {
+        let ratings = RatingRepository::new(self.db.get_connection())
+            .ratings(keys)
+            .await?;
+        Ok(ratings)
+    }
+}
+
+/// Whether a track, an album or an artist is a favourite, by kind and id.
+/// Only the favourites have a value.
+pub struct FavouriteLoader {
+    db: Database,
+}
+
+impl FavouriteLoader {
+    pub fn new(db: &Database) -> Self {
+        Self { db: db.clone() }
+    }
+}
+
+#[async_trait::async_trait]
+impl Loader<(FavouriteKind, String)> for FavouriteLoader {
+    type Value = bool;
+    type Error = Error;
+
+    async fn load(
+        &self,
+        keys: &[(FavouriteKind, String)],
+    ) -> Result<HashMap<(FavouriteKind, String), Self::Value>, Self::Error> {
+        let repo = RatingRepository::new(self.db.get_connection());
+        let mut favourites = HashMap::new();
+        for kind in [
+            FavouriteKind::Track,
+            FavouriteKind::Album,
+            FavouriteKind::Artist,
+        ] {
+            let ids: Vec<String> = keys
+                .iter()
+                .filter(|(key_kind, _)| *key_kind == kind)
+                .map(|(_, id)| id.clone())
+                .collect();
+            if ids.is_empty() {
+                continue;
+            }
+            for id in repo.favourites(kind, &ids).await? {
+                favourites.insert((kind, id), true);
+            }
+        }
+        Ok(favourites)
+    }
+}
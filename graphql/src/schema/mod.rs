@@ -10,10 +10,12 @@ use music_player_types::types::Device;
 
 use self::{
     devices::{DevicesMutation, DevicesQuery, DevicesSubscription},
+    history::{HistoryQuery, HistorySubscription},
     library::{LibraryMutation, LibraryQuery},
     mixer::{MixerMutation, MixerQuery},
     playback::{PlaybackMutation, PlaybackQuery, PlaybackSubscription},
     playlist::{PlaylistMutation, PlaylistQuery, PlaylistSubscription},
+    rating::{RatingMutation, RatingQuery},
     tracklist::{TracklistMutation, TracklistQuery, TracklistSubscription},
 };
 
@@ -22,19 +24,23 @@ pub mod core;
 pub mod devices;
 pub mod history;
 pub mod library;
+pub mod loaders;
 pub mod mixer;
 pub mod objects;
 pub mod playback;
 pub mod playlist;
+pub mod rating;
 pub mod tracklist;
 
 #[derive(MergedObject, Default)]
 pub struct Query(
     DevicesQuery,
+    HistoryQuery,
     LibraryQuery,
     MixerQuery,
     PlaybackQuery,
     PlaylistQuery,
+    RatingQuery,
     TracklistQuery,
 );
 
@@ -45,6 +51,7 @@ pub struct Mutation(
     MixerMutation,
     PlaybackMutation,
     PlaylistMutation,
+    RatingMutation,
     TracklistMutation,
 );
 
@@ -54,6 +61,7 @@ pub struct Subscription(
     PlaylistSubscription,
     TracklistSubscription,
     DevicesSubscription,
+    HistorySubscription,
 );
 
 #[derive(Enum, Eq, PartialEq, Copy, Clone)]
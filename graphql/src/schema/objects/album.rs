@@ -1,20 +1,80 @@
-use async_graphql::*;
+use async_graphql::{dataloader::DataLoader, *};
 use music_player_entity::album::Model;
+use music_player_scanner::cover::THUMBNAIL_SIZES;
+use music_player_storage::repo::album::{AlbumField, AlbumOrder};
+use music_player_types::rating::FavouriteKind;
 use music_player_types::types::{Album as AlbumType, RemoteCoverUrl, RemoteTrackUrl};
 use serde::Serialize;
 
 use super::track::Track;
+use crate::schema::loaders::{AlbumTracksLoader, FavouriteLoader};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum CoverFormat {
+    #[default]
+    Jpeg,
+    Webp,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum AlbumSortField {
+    #[default]
+    Title,
+    Year,
+    /// When the album was first scanned, descending for the recently added
+    AddedAt,
+    Duration,
+    Artist,
+    PlayCount,
+    Rating,
+}
+
+#[derive(InputObject, Default, Clone)]
+pub struct AlbumSortInput {
+    pub field: AlbumSortField,
+    #[graphql(default)]
+    pub descending: bool,
+}
+
+impl From<AlbumSortInput> for AlbumOrder {
+    fn from(input: AlbumSortInput) -> Self {
+        Self {
+            field: match input.field {
+                AlbumSortField::Title => AlbumField::Title,
+                AlbumSortField::Year => AlbumField::Year,
+                AlbumSortField::AddedAt => AlbumField::AddedAt,
+                AlbumSortField::Duration => AlbumField::Duration,
+                AlbumSortField::Artist => AlbumField::Artist,
+                AlbumSortField::PlayCount => AlbumField::PlayCount,
+                AlbumSortField::Rating => AlbumField::Rating,
+            },
+            descending: input.descending,
+        }
+    }
+}
 
 #[derive(Default, Clone, Serialize)]
 pub struct Album {
     pub id: ID,
     pub title: String,
     pub cover: Option<String>,
+    pub cover_small: Option<String>,
+    pub cover_medium: Option<String>,
+    pub cover_large: Option<String>,
+    pub cover_small_webp: Option<String>,
+    pub cover_medium_webp: Option<String>,
+    pub cover_large_webp: Option<String>,
+    pub compilation: bool,
+    pub label: Option<String>,
+    pub title_sort: Option<String>,
+    pub artist_sort: Option<String>,
+    pub musicbrainz_release_id: Option<String>,
     pub release_date: String,
     pub artist: String,
     pub year: Option<u32>,
     pub genres: Vec<String>,
-    pub tracks: Vec<Track>,
+    /// Loaded on demand when `None`, see `loaders`
+    pub tracks: Option<Vec<Track>>,
 }
 
 #[Object]
@@ -31,6 +91,52 @@ impl Album {
         &self.cover
     }
 
+    /// The smallest thumbnail at least `size` pixels wide, or the largest one available.
+    async fn thumbnail(
+        &self,
+        size: u32,
+        #[graphql(default)] format: CoverFormat,
+    ) -> Option<String> {
+        let covers = match format {
+            CoverFormat::Jpeg => [&self.cover_small, &self.cover_medium, &self.cover_large],
+            CoverFormat::Webp => [
+                &self.cover_small_webp,
+                &self.cover_medium_webp,
+                &self.cover_large_webp,
+            ],
+        };
+        let thumbnails = THUMBNAIL_SIZES
+            .into_iter()
+            .zip(covers)
+            .filter_map(|(width, cover)| cover.as_ref().map(|cover| (width, cover)))
+            .collect::<Vec<_>>();
+        let (_, thumbnail) = thumbnails
+            .iter()
+            .find(|(width, _)| *width >= size)
+            .or(thumbnails.last())?;
+        Some(thumbnail.to_string())
+    }
+
+    async fn compilation(&self) -> bool {
+        self.compilation
+    }
+
+    async fn label(&self) -> &Option<String> {
+        &self.label
+    }
+
+    async fn title_sort(&self) -> &Option<String> {
+        &self.title_sort
+    }
+
+    async fn artist_sort(&self) -> &Option<String> {
+        &self.artist_sort
+    }
+
+    async fn musicbrainz_release_id(&self) -> &Option<String> {
+        &self.musicbrainz_release_id
+    }
+
     async fn release_date(&self) -> &str {
         &self.release_date
     }
@@ -47,18 +153,42 @@ impl Album {
         self.genres.clone()
     }
 
-    async fn tracks(&self) -> Vec<Track> {
-        self.tracks.clone()
+    async fn tracks(&self, ctx: &Context<'_>) -> Result<Vec<Track>, Error> {
+        if let Some(tracks) = &self.tracks {
+            return Ok(tracks.clone());
+        }
+        let tracks = ctx
+            .data::<DataLoader<AlbumTracksLoader>>()?
+            .load_one(self.id.to_string())
+            .await?
+            .unwrap_or_default();
+        Ok(tracks.into_iter().map(Into::into).collect())
+    }
+
+    async fn favourite(&self, ctx: &Context<'_>) -> Result<bool, Error> {
+        let favourite = ctx
+            .data::<DataLoader<FavouriteLoader>>()?
+            .load_one((FavouriteKind::Album, self.id.to_string()))
+            .await?;
+        Ok(favourite.unwrap_or_default())
     }
 }
 
 impl RemoteCoverUrl for Album {
     fn with_remote_cover_url(&self, base_url: &str) -> Self {
-        Self {
-            cover: self
-                .cover
+        let cover_url = |cover: &Option<String>| {
+            cover
                 .clone()
-                .map(|cover| format!("{}/covers/{}", base_url, cover)),
+                .map(|cover| format!("{}/covers/{}", base_url, cover))
+        };
+        Self {
+            cover: cover_url(&self.cover),
+            cover_small: cover_url(&self.cover_small),
+            cover_medium: cover_url(&self.cover_medium),
+            cover_large: cover_url(&self.cover_large),
+            cover_small_webp: cover_url(&self.cover_small_webp),
+            cover_medium_webp: cover_url(&self.cover_medium_webp),
+            cover_large_webp: cover_url(&self.cover_large_webp),
             ..self.clone()
         }
     }
@@ -67,11 +197,12 @@ impl RemoteCoverUrl for Album {
 impl RemoteTrackUrl for Album {
     fn with_remote_track_url(&self, base_url: &str) -> Self {
         Self {
-            tracks: self
-                .tracks
-                .iter()
-                .map(|track| track.with_remote_track_url(base_url))
-                .collect(),
+            tracks: self.tracks.as_ref().map(|tracks| {
+                tracks
+                    .iter()
+                    .map(|track| track.with_remote_track_url(base_url))
+                    .collect()
+            }),
             ..self.clone()
         }
     }
@@ -83,9 +214,24 @@ impl From<Model> for Album {
             id: ID(model.id),
             title: model.title,
             cover: model.cover,
+            cover_small: model.cover_small,
+            cover_medium: model.cover_medium,
+            cover_large: model.cover_large,
+            cover_small_webp: model.cover_small_webp,
+            cover_medium_webp: model.cover_medium_webp,
+            cover_large_webp: model.cover_large_webp,
+            compilation: model.compilation,
+            label: model.label,
+            title_sort: model.title_sort,
+            artist_sort: model.artist_sort,
+            musicbrainz_release_id: model.musicbrainz_release_id,
             artist: model.artist,
             year: model.year,
-            tracks: model.tracks.into_iter().map(Into::into).collect(),
+            // Empty when the repository didn't load them
+            tracks: match model.tracks.is_empty() {
+                true => None,
+                false => Some(model.tracks.into_iter().map(Into::into).collect()),
+            },
             ..Default::default()
         }
     }
@@ -97,9 +243,20 @@ impl From<AlbumType> for Album {
             id: ID(album.id),
             title: album.title,
             cover: album.cover,
+            cover_small: album.cover_small,
+            cover_medium: album.cover_medium,
+            cover_large: album.cover_large,
+            cover_small_webp: album.cover_small_webp,
+            cover_medium_webp: album.cover_medium_webp,
+            cover_large_webp: album.cover_large_webp,
+            compilation: album.compilation,
+            label: album.label,
+            title_sort: album.title_sort,
+            artist_sort: album.artist_sort,
+            musicbrainz_release_id: album.musicbrainz_release_id,
             artist: album.artist,
             year: album.year,
-            tracks: album.tracks.into_iter().map(Into::into).collect(),
+            tracks: Some(album.tracks.into_iter().map(Into::into).collect()),
             ..Default::default()
         }
     }
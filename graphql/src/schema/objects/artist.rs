@@ -1,6 +1,8 @@
 use super::{album::Album, track::Track};
-use async_graphql::*;
+use crate::schema::loaders::{ArtistAlbumsLoader, FavouriteLoader};
+use async_graphql::{dataloader::DataLoader, *};
 use music_player_entity::artist::Model;
+use music_player_types::rating::FavouriteKind;
 use music_player_types::types::{Artist as ArtistType, RemoteTrackUrl};
 use serde::Serialize;
 
@@ -13,7 +15,8 @@ pub struct Artist {
     pub website: String,
     pub genres: Vec<String>,
     pub images: Vec<String>,
-    pub albums: Vec<Album>,
+    /// Loaded on demand when `None`, see `loaders`
+    pub albums: Option<Vec<Album>>,
     pub songs: Vec<Track>,
 }
 
@@ -47,13 +50,29 @@ impl Artist {
         self.images.clone()
     }
 
-    async fn albums(&self) -> Vec<Album> {
-        self.albums.clone()
+    async fn albums(&self, ctx: &Context<'_>) -> Result<Vec<Album>, Error> {
+        if let Some(albums) = &self.albums {
+            return Ok(albums.clone());
+        }
+        let albums = ctx
+            .data::<DataLoader<ArtistAlbumsLoader>>()?
+            .load_one(self.id.to_string())
+            .await?
+            .unwrap_or_default();
+        Ok(albums.into_iter().map(Into::into).collect())
     }
 
     async fn songs(&self) -> Vec<Track> {
         self.songs.clone()
     }
+
+    async fn favourite(&self, ctx: &Context<'_>) -> Result<bool, Error> {
+        let favourite = ctx
+            .data::<DataLoader<FavouriteLoader>>()?
+            .load_one((FavouriteKind::Artist, self.id.to_string()))
+            .await?;
+        Ok(favourite.unwrap_or_default())
+    }
 }
 
 impl From<Model> for Artist {
@@ -61,7 +80,11 @@ impl From<Model> for Artist {
         Self {
             id: ID(model.id),
             name: model.name,
-            albums: model.albums.into_iter().map(Into::into).collect(),
+            // Empty when the repository didn't load them
+            albums: match model.albums.is_empty() {
+                true => None,
+                false => Some(model.albums.into_iter().map(Into::into).collect()),
+            },
             songs: model.tracks.into_iter().map(Into::into).collect(),
             ..Default::default()
         }
@@ -74,7 +97,7 @@ impl From<ArtistType> for Artist {
             id: ID(artist.id),
             name: artist.name,
             picture: artist.picture.unwrap_or_default(),
-            albums: artist.albums.into_iter().map(Into::into).collect(),
+            albums: Some(artist.albums.into_iter().map(Into::into).collect()),
             songs: artist.songs.into_iter().map(Into::into).collect(),
             ..Default::default()
         }
@@ -0,0 +1,89 @@
+use std::future::Future;
+
+use async_graphql::{
+    connection::{query, Connection, CursorType, Edge},
+    *,
+};
+use music_player_storage::repo::page::{Cursor, Page, PageRequest};
+
+/// A cursor of a connection, the key and id of a row encoded in base64 so
+/// that clients treat it as opaque. The cursors of the rows missing the key
+/// only hold the id.
+pub struct LibraryCursor(pub Cursor);
+
+impl CursorType for LibraryCursor {
+    type Error = String;
+
+    fn decode_cursor(s: &str) -> Result<Self, Self::Error> {
+        let bytes = base64::decode(s).map_err(|_| "Invalid cursor".to_string())?;
+        let value = String::from_utf8(bytes).map_err(|_| "Invalid cursor".to_string())?;
+        Ok(Self(match value.rsplit_once('\0') {
+            Some((key, id)) => Cursor::new(key, id),
+            None => Cursor {
+                key: None,
+                id: value,
+            },
+        }))
+    }
+
+    fn encode_cursor(&self) -> String {
+        match &self.0.key {
+            Some(key) => base64::encode(format!("{}\0{}", key, self.0.id)),
+            None => base64::encode(&self.0.id),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ConnectionFields {
+    /// Number of nodes of the whole list, all pages included
+    pub total_count: usize,
+}
+
+pub type LibraryConnection<Node> = Connection<LibraryCursor, Node, ConnectionFields>;
+
+/// Resolves a connection from the page fetched by `fetch`, the nodes being
+/// converted from the rows of the page.
+pub async fn paginate<Row, Node, F, R, E>(
+    after: Option<String>,
+    before: Option<String>,
+    first: Option<i32>,
+    last: Option<i32>,
+    fetch: F,
+) -> Result<LibraryConnection<Node>>
+where
+    Node: From<Row> + OutputType,
+    F: FnOnce(PageRequest) -> R,
+    R: Future<Output = std::result::Result<Page<Row>, E>>,
+    E: Into<Error>,
+{
+    query(
+        after,
+        before,
+        first,
+        last,
+        |after: Option<LibraryCursor>, before: Option<LibraryCursor>, first, last| async move {
+            let request = PageRequest {
+                after: after.map(|cursor| cursor.0),
+                before: before.map(|cursor| cursor.0),
+                first: first.map(|first| first as u64),
+                last: last.map(|last| last as u64),
+            };
+            let page = fetch(request).await.map_err(Into::into)?;
+            let mut connection = Connection::with_additional_fields(
+                page.has_previous_page,
+                page.has_next_page,
+                ConnectionFields {
+                    total_count: page.total_count,
+                },
+            );
+            connection.edges.extend(
+                page.items
+                    .into_iter()
+                    .map(|(cursor, row)| Edge::new(LibraryCursor(cursor), Node::from(row))),
+            );
+            Ok::<_, Error>(connection)
+        },
+    )
+    .await
+}
@@ -0,0 +1,68 @@
+use async_graphql::*;
+use music_player_entity::track::Model;
+use music_player_scanner::duplicates::DuplicateGroup as DuplicateGroupType;
+
+use super::track::Track;
+
+#[derive(Default, Clone)]
+pub struct DuplicateCopy {
+    pub track: Track,
+    pub bitrate: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub bit_depth: Option<u8>,
+}
+
+#[Object]
+impl DuplicateCopy {
+    async fn track(&self) -> &Track {
+        &self.track
+    }
+
+    async fn bitrate(&self) -> Option<u32> {
+        self.bitrate
+    }
+
+    async fn sample_rate(&self) -> Option<u32> {
+        self.sample_rate
+    }
+
+    async fn bit_depth(&self) -> Option<u8> {
+        self.bit_depth
+    }
+}
+
+impl From<Model> for DuplicateCopy {
+    fn from(model: Model) -> Self {
+        Self {
+            bitrate: model.bitrate,
+            sample_rate: model.sample_rate,
+            bit_depth: model.bit_depth,
+            track: model.into(),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct DuplicateGroup {
+    pub copies: Vec<DuplicateCopy>,
+}
+
+#[Object]
+impl DuplicateGroup {
+    async fn copies(&self) -> Vec<DuplicateCopy> {
+        self.copies.clone()
+    }
+
+    /// The copy with the highest bit depth and bitrate.
+    async fn best_copy(&self) -> DuplicateCopy {
+        self.copies[0].clone()
+    }
+}
+
+impl From<DuplicateGroupType> for DuplicateGroup {
+    fn from(group: DuplicateGroupType) -> Self {
+        Self {
+            copies: group.tracks.into_iter().map(Into::into).collect(),
+        }
+    }
+}
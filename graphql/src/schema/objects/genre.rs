@@ -0,0 +1,42 @@
+use super::{album::Album, track::Track};
+use async_graphql::*;
+use music_player_entity::genre::Model;
+use serde::Serialize;
+
+#[derive(Default, Clone, Serialize)]
+pub struct Genre {
+    pub id: ID,
+    pub name: String,
+    pub albums: Vec<Album>,
+    pub tracks: Vec<Track>,
+}
+
+#[Object]
+impl Genre {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn albums(&self) -> Vec<Album> {
+        self.albums.clone()
+    }
+
+    async fn tracks(&self) -> Vec<Track> {
+        self.tracks.clone()
+    }
+}
+
+impl From<Model> for Genre {
+    fn from(model: Model) -> Self {
+        Self {
+            id: ID(model.id),
+            name: model.name,
+            albums: model.albums.into_iter().map(Into::into).collect(),
+            tracks: model.tracks.into_iter().map(Into::into).collect(),
+        }
+    }
+}
@@ -0,0 +1,96 @@
+use async_graphql::*;
+use music_player_types::types::{HistoryEntry as HistoryEntryType, PlayStats as PlayStatsType};
+
+use super::track::Track;
+
+#[derive(Default, Clone)]
+pub struct HistoryEntry {
+    pub id: ID,
+    pub track: Track,
+    pub started_at: String,
+    pub listened_ms: u32,
+    pub completed: bool,
+    pub device: Option<String>,
+}
+
+#[Object]
+impl HistoryEntry {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn track(&self) -> &Track {
+        &self.track
+    }
+
+    async fn started_at(&self) -> &str {
+        &self.started_at
+    }
+
+    async fn listened_ms(&self) -> u32 {
+        self.listened_ms
+    }
+
+    async fn completed(&self) -> bool {
+        self.completed
+    }
+
+    async fn skipped(&self) -> bool {
+        !self.completed
+    }
+
+    async fn device(&self) -> &Option<String> {
+        &self.device
+    }
+}
+
+impl From<HistoryEntryType> for HistoryEntry {
+    fn from(entry: HistoryEntryType) -> Self {
+        Self {
+            id: ID(entry.id),
+            track: entry.track.into(),
+            started_at: entry.started_at.to_rfc3339(),
+            listened_ms: entry.listened_ms,
+            completed: entry.completed,
+            device: entry.device,
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct PlayStats {
+    pub track_id: ID,
+    pub play_count: u32,
+    pub skip_count: u32,
+    pub last_played: Option<String>,
+}
+
+#[Object]
+impl PlayStats {
+    async fn track_id(&self) -> &str {
+        &self.track_id
+    }
+
+    async fn play_count(&self) -> u32 {
+        self.play_count
+    }
+
+    async fn skip_count(&self) -> u32 {
+        self.skip_count
+    }
+
+    async fn last_played(&self) -> &Option<String> {
+        &self.last_played
+    }
+}
+
+impl From<PlayStatsType> for PlayStats {
+    fn from(stats: PlayStatsType) -> Self {
+        Self {
+            track_id: ID(stats.track_id),
+            play_count: stats.play_count,
+            skip_count: stats.skip_count,
+            last_played: stats.last_played.map(|date| date.to_rfc3339()),
+        }
+    }
+}
@@ -1,9 +1,14 @@
 use async_graphql::*;
+use music_player_entity::lyrics::Model;
+use music_player_types::types::{self, LyricLine as LyricLineType};
 
 #[derive(Default, Clone)]
 pub struct Lyrics {
     pub id: ID,
     pub content: String,
+    pub synced: bool,
+    pub source: String,
+    pub lines: Vec<LyricLine>,
 }
 
 #[Object]
@@ -15,4 +20,56 @@ impl Lyrics {
     async fn content(&self) -> &str {
         &self.content
     }
+
+    async fn synced(&self) -> bool {
+        self.synced
+    }
+
+    async fn source(&self) -> &str {
+        &self.source
+    }
+
+    async fn lines(&self) -> Vec<LyricLine> {
+        self.lines.clone()
+    }
+}
+
+#[derive(Default, Clone)]
+pub struct LyricLine {
+    pub time_ms: Option<u32>,
+    pub text: String,
+}
+
+#[Object]
+impl LyricLine {
+    async fn time_ms(&self) -> Option<u32> {
+        self.time_ms
+    }
+
+    async fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<LyricLineType> for LyricLine {
+    fn from(line: LyricLineType) -> Self {
+        Self {
+            time_ms: line.time_ms,
+            text: line.text,
+        }
+    }
+}
+
+impl From<Model> for Lyrics {
+    fn from(model: Model) -> Self {
+        let id = ID(model.track_id.clone());
+        let lyrics = types::Lyrics::from(model);
+        Self {
+            id,
+            lines: lyrics.lines().into_iter().map(Into::into).collect(),
+            source: lyrics.source.as_str().to_string(),
+            content: lyrics.content,
+            synced: lyrics.synced,
+        }
+    }
 }
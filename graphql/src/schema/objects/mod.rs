@@ -1,11 +1,17 @@
 pub mod album;
 pub mod artist;
+pub mod connection;
 pub mod current_track;
+pub mod duplicate_group;
 pub mod folder;
+pub mod genre;
+pub mod history;
 pub mod lyrics;
 pub mod player_state;
+pub mod scan_error;
 pub mod playlist;
 pub mod search_result;
+pub mod suggestion;
 pub mod track;
 pub mod tracklist;
 pub mod device;
\ No newline at end of file
@@ -1,15 +1,42 @@
 use async_graphql::*;
 use music_player_entity::{playlist::Model, select_result};
+use music_player_scanner::playlist_file::PlaylistFormat;
 use music_player_types::types::{Playlist as PlaylistType, RemoteTrackUrl};
 
 use super::track::Track;
 
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum PlaylistFileFormat {
+    #[graphql(name = "M3U8")]
+    M3u8,
+    Pls,
+    Xspf,
+}
+
+impl From<PlaylistFileFormat> for PlaylistFormat {
+    fn from(format: PlaylistFileFormat) -> Self {
+        match format {
+            PlaylistFileFormat::M3u8 => PlaylistFormat::M3u8,
+            PlaylistFileFormat::Pls => PlaylistFormat::Pls,
+            PlaylistFileFormat::Xspf => PlaylistFormat::Xspf,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct ImportedPlaylist {
+    pub playlist: Playlist,
+    /// The entries of the file matching no track of the library
+    pub missing: Vec<String>,
+}
+
 #[derive(Default, Clone)]
 pub struct Playlist {
     pub id: ID,
     pub name: String,
     pub description: Option<String>,
     pub tracks: Vec<Track>,
+    pub rules: Option<String>,
 }
 
 #[Object]
@@ -29,6 +56,10 @@ impl Playlist {
     async fn tracks(&self) -> &Vec<Track> {
         &self.tracks
     }
+
+    async fn rules(&self) -> &Option<String> {
+        &self.rules
+    }
 }
 
 impl From<Model> for Playlist {
@@ -38,6 +69,7 @@ impl From<Model> for Playlist {
             name: model.name,
             description: model.description,
             tracks: model.tracks.into_iter().map(Track::from).collect(),
+            ..Default::default()
         }
     }
 }
@@ -52,6 +84,7 @@ impl From<Vec<select_result::PlaylistTrack>> for Playlist {
             name: result[0].name.clone(),
             description: result[0].description.clone(),
             tracks: result.into_iter().map(Track::from).collect(),
+            ..Default::default()
         }
     }
 }
@@ -63,6 +96,7 @@ impl From<PlaylistType> for Playlist {
             name: playlist.name,
             description: playlist.description,
             tracks: playlist.tracks.into_iter().map(Into::into).collect(),
+            rules: playlist.rules,
         }
     }
 }
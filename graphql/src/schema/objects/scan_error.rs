@@ -0,0 +1,46 @@
+use async_graphql::*;
+use music_player_entity::scan_error::Model;
+
+#[derive(Default, Clone)]
+pub struct ScanError {
+    pub id: ID,
+    pub path: String,
+    pub kind: String,
+    pub message: String,
+    pub created_at: String,
+}
+
+#[Object]
+impl ScanError {
+    async fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn path(&self) -> &str {
+        &self.path
+    }
+
+    async fn kind(&self) -> &str {
+        &self.kind
+    }
+
+    async fn message(&self) -> &str {
+        &self.message
+    }
+
+    async fn created_at(&self) -> &str {
+        &self.created_at
+    }
+}
+
+impl From<Model> for ScanError {
+    fn from(model: Model) -> Self {
+        Self {
+            id: ID(model.id),
+            path: model.path,
+            kind: model.kind,
+            message: model.message,
+            created_at: model.created_at.to_rfc3339(),
+        }
+    }
+}
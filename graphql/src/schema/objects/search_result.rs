@@ -7,6 +7,9 @@ pub struct SearchResult {
     pub artists: Vec<Artist>,
     pub tracks: Vec<Track>,
     pub albums: Vec<Album>,
+    pub total_artists: usize,
+    pub total_tracks: usize,
+    pub total_albums: usize,
 }
 
 #[Object]
@@ -22,4 +25,17 @@ impl SearchResult {
     async fn albums(&self) -> Vec<Album> {
         self.albums.clone()
     }
+
+    /// Number of artists matching the query, across all the pages.
+    async fn total_artists(&self) -> usize {
+        self.total_artists
+    }
+
+    async fn total_tracks(&self) -> usize {
+        self.total_tracks
+    }
+
+    async fn total_albums(&self) -> usize {
+        self.total_albums
+    }
 }
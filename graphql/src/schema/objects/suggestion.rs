@@ -0,0 +1,56 @@
+use async_graphql::*;
+use music_player_storage::searcher::suggestion::{
+    Suggestion as SuggestionType, SuggestionKind as SuggestionKindType,
+};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum SuggestionKind {
+    Track,
+    Album,
+    Artist,
+    Genre,
+}
+
+impl From<SuggestionKindType> for SuggestionKind {
+    fn from(kind: SuggestionKindType) -> Self {
+        match kind {
+            SuggestionKindType::Track => SuggestionKind::Track,
+            SuggestionKindType::Album => SuggestionKind::Album,
+            SuggestionKindType::Artist => SuggestionKind::Artist,
+            SuggestionKindType::Genre => SuggestionKind::Genre,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    pub id: ID,
+    pub text: String,
+}
+
+#[Object]
+impl Suggestion {
+    async fn kind(&self) -> SuggestionKind {
+        self.kind
+    }
+
+    /// The id of the track, album, artist or genre.
+    async fn id(&self) -> &str {
+        &self.id
+    }
+
+    async fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+impl From<SuggestionType> for Suggestion {
+    fn from(suggestion: SuggestionType) -> Self {
+        Self {
+            kind: suggestion.kind.into(),
+            id: ID(suggestion.id),
+            text: suggestion.text,
+        }
+    }
+}
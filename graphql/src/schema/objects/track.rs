@@ -1,10 +1,13 @@
-use async_graphql::*;
+use async_graphql::{dataloader::DataLoader, *};
 use music_player_entity::{select_result, track::Model};
+use music_player_storage::repo::track::{AudioFilter, TrackField, TrackFilter, TrackOrder};
+use music_player_types::rating::FavouriteKind;
 use music_player_types::types::{self, RemoteTrackUrl};
 use music_player_types::types::{RemoteCoverUrl, SimplifiedSong as TrackType};
 use serde::Serialize;
 
 use super::{album::Album, artist::Artist};
+use crate::schema::loaders::{AlbumLoader, FavouriteLoader, RatingLoader, TrackArtistsLoader};
 
 #[derive(InputObject, Default, Clone)]
 pub struct TrackInput {
@@ -16,6 +19,152 @@ pub struct TrackInput {
     pub uri: String,
 }
 
+/// Bounds on the tags and properties of the tracks.
+#[derive(InputObject, Default, Clone)]
+pub struct TrackFilterInput {
+    pub min_year: Option<u32>,
+    pub max_year: Option<u32>,
+    /// Any spelling of a genre
+    pub genre: Option<String>,
+    /// The extension of the files, e.g. "flac"
+    pub format: Option<String>,
+    /// In kbps
+    pub min_bitrate: Option<u32>,
+    pub max_bitrate: Option<u32>,
+}
+
+impl From<TrackFilterInput> for TrackFilter {
+    fn from(input: TrackFilterInput) -> Self {
+        Self {
+            min_year: input.min_year,
+            max_year: input.max_year,
+            genre: input.genre,
+            format: input.format,
+            min_bitrate: input.min_bitrate,
+            max_bitrate: input.max_bitrate,
+        }
+    }
+}
+
+/// The bounds on the tags and the analysis of the tracks.
+#[derive(InputObject, Default, Clone)]
+pub struct TrackBoundsInput {
+    pub tags: Option<TrackFilterInput>,
+    pub audio: Option<AudioFilterInput>,
+}
+
+/// The filters of the library connections, grouped to keep the paging
+/// arguments apart.
+#[derive(InputObject, Default, Clone)]
+pub struct LibraryFilterInput {
+    /// Part of the titles
+    pub text: Option<String>,
+    pub tags: Option<TrackFilterInput>,
+}
+
+/// Bounds on the measures of the analysis pass.
+#[derive(InputObject, Default, Clone)]
+pub struct AudioFilterInput {
+    pub min_loudness: Option<f32>,
+    pub max_loudness: Option<f32>,
+    pub min_tempo: Option<f32>,
+    pub max_tempo: Option<f32>,
+    pub musical_key: Option<String>,
+}
+
+impl From<AudioFilterInput> for AudioFilter {
+    fn from(input: AudioFilterInput) -> Self {
+        Self {
+            min_loudness: input.min_loudness,
+            max_loudness: input.max_loudness,
+            min_tempo: input.min_tempo,
+            max_tempo: input.max_tempo,
+            musical_key: input.musical_key,
+        }
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Default)]
+pub enum TrackSortField {
+    #[default]
+    Title,
+    Loudness,
+    Tempo,
+    MusicalKey,
+    Year,
+    /// When the track was first scanned, descending for the recently added
+    AddedAt,
+    Duration,
+    Artist,
+    PlayCount,
+    Rating,
+}
+
+#[derive(InputObject, Default, Clone)]
+pub struct TrackSortInput {
+    pub field: TrackSortField,
+    #[graphql(default)]
+    pub descending: bool,
+}
+
+impl From<TrackSortInput> for TrackOrder {
+    fn from(input: TrackSortInput) -> Self {
+        Self {
+            field: match input.field {
+                TrackSortField::Title => TrackField::Title,
+                TrackSortField::Loudness => TrackField::Loudness,
+                TrackSortField::Tempo => TrackField::Tempo,
+                TrackSortField::MusicalKey => TrackField::MusicalKey,
+                TrackSortField::Year => TrackField::Year,
+                TrackSortField::AddedAt => TrackField::AddedAt,
+                TrackSortField::Duration => TrackField::Duration,
+                TrackSortField::Artist => TrackField::Artist,
+                TrackSortField::PlayCount => TrackField::PlayCount,
+                TrackSortField::Rating => TrackField::Rating,
+            },
+            descending: input.descending,
+        }
+    }
+}
+
+#[derive(InputObject, Default, Clone)]
+pub struct TagChangesInput {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub year: Option<u32>,
+    pub genre: Option<String>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    /// Base64 encoded JPEG or PNG image
+    pub cover: Option<String>,
+}
+
+impl TryFrom<TagChangesInput> for types::TagChanges {
+    type Error = Error;
+
+    fn try_from(input: TagChangesInput) -> Result<Self, Self::Error> {
+        let cover = match input.cover {
+            Some(cover) => Some(
+                base64::decode(cover).map_err(|e| Error::new(format!("Invalid cover: {}", e)))?,
+            ),
+            None => None,
+        };
+        Ok(Self {
+            title: input.title,
+            artist: input.artist,
+            album: input.album,
+            album_artist: input.album_artist,
+            year: input.year,
+            genre: input.genre,
+            track_number: input.track_number,
+            disc_number: input.disc_number,
+            cover,
+        })
+    }
+}
+
 #[derive(Default, Clone, Serialize)]
 pub struct Track {
     pub id: ID,
@@ -24,13 +173,33 @@ pub struct Track {
     pub disc_number: u32,
     pub track_number: Option<u32>,
     pub uri: String,
-    pub artists: Vec<Artist>,
-    pub album: Album,
+    /// Loaded on demand when `None`, see `loaders`
+    pub artists: Option<Vec<Artist>>,
+    /// Loaded on demand when `None`, see `loaders`
+    pub album: Option<Album>,
     pub artist: String,
     pub cover: Option<String>,
     pub artist_id: String,
     pub album_id: String,
     pub album_title: String,
+    pub disc_total: Option<u32>,
+    pub track_total: Option<u32>,
+    pub composer: Option<String>,
+    pub conductor: Option<String>,
+    pub isrc: Option<String>,
+    pub comment: Option<String>,
+    pub bpm: Option<u32>,
+    pub title_sort: Option<String>,
+    pub artist_sort: Option<String>,
+    pub musicbrainz_recording_id: Option<String>,
+    pub loudness: Option<f32>,
+    pub true_peak: Option<f32>,
+    pub replaygain_track_gain: Option<f32>,
+    pub replaygain_track_peak: Option<f32>,
+    pub replaygain_album_gain: Option<f32>,
+    pub replaygain_album_peak: Option<f32>,
+    pub tempo: Option<f32>,
+    pub musical_key: Option<String>,
 }
 
 #[Object]
@@ -59,12 +228,30 @@ impl Track {
         &self.uri
     }
 
-    async fn artists(&self) -> Vec<Artist> {
-        self.artists.clone()
+    async fn artists(&self, ctx: &Context<'_>) -> Result<Vec<Artist>, Error> {
+        if let Some(artists) = &self.artists {
+            return Ok(artists.clone());
+        }
+        let artists = ctx
+            .data::<DataLoader<TrackArtistsLoader>>()?
+            .load_one(self.id.to_string())
+            .await?
+            .unwrap_or_default();
+        Ok(artists.into_iter().map(Into::into).collect())
     }
 
-    async fn album(&self) -> Album {
-        self.album.clone()
+    async fn album(&self, ctx: &Context<'_>) -> Result<Album, Error> {
+        if let Some(album) = &self.album {
+            return Ok(album.clone());
+        }
+        if self.album_id.is_empty() {
+            return Ok(Album::default());
+        }
+        let album = ctx
+            .data::<DataLoader<AlbumLoader>>()?
+            .load_one(self.album_id.clone())
+            .await?;
+        Ok(album.map(Into::into).unwrap_or_default())
     }
 
     async fn artist(&self) -> &str {
@@ -86,6 +273,98 @@ impl Track {
     async fn album_title(&self) -> &str {
         &self.album_title
     }
+
+    async fn disc_total(&self) -> Option<u32> {
+        self.disc_total
+    }
+
+    async fn track_total(&self) -> Option<u32> {
+        self.track_total
+    }
+
+    async fn composer(&self) -> &Option<String> {
+        &self.composer
+    }
+
+    async fn conductor(&self) -> &Option<String> {
+        &self.conductor
+    }
+
+    async fn isrc(&self) -> &Option<String> {
+        &self.isrc
+    }
+
+    async fn comment(&self) -> &Option<String> {
+        &self.comment
+    }
+
+    async fn bpm(&self) -> Option<u32> {
+        self.bpm
+    }
+
+    async fn title_sort(&self) -> &Option<String> {
+        &self.title_sort
+    }
+
+    async fn artist_sort(&self) -> &Option<String> {
+        &self.artist_sort
+    }
+
+    async fn musicbrainz_recording_id(&self) -> &Option<String> {
+        &self.musicbrainz_recording_id
+    }
+
+    /// EBU R128 integrated loudness, in LUFS
+    async fn loudness(&self) -> Option<f32> {
+        self.loudness
+    }
+
+    /// In dBTP
+    async fn true_peak(&self) -> Option<f32> {
+        self.true_peak
+    }
+
+    async fn replaygain_track_gain(&self) -> Option<f32> {
+        self.replaygain_track_gain
+    }
+
+    async fn replaygain_track_peak(&self) -> Option<f32> {
+        self.replaygain_track_peak
+    }
+
+    async fn replaygain_album_gain(&self) -> Option<f32> {
+        self.replaygain_album_gain
+    }
+
+    async fn replaygain_album_peak(&self) -> Option<f32> {
+        self.replaygain_album_peak
+    }
+
+    /// Detected tempo, in BPM
+    async fn tempo(&self) -> Option<f32> {
+        self.tempo
+    }
+
+    async fn musical_key(&self) -> &Option<String> {
+        &self.musical_key
+    }
+
+    /// From 1 to 5 stars, `null` when the track isn't rated
+    async fn rating(&self, ctx: &Context<'_>) -> Result<Option<u8>, Error> {
+        let rating = ctx
+            .data::<DataLoader<RatingLoader>>()?
+            .load_one(self.id.to_string())
+            .await?;
+        Ok(rating)
+    }
+
+    async fn favourite(&self, ctx: &Context<'_>) -> Result<bool, Error> {
+        let favourite = ctx
+            .data::<DataLoader<FavouriteLoader>>()?
+            .load_one((FavouriteKind::Track, self.id.to_string()))
+            .await?;
+        Ok(favourite.unwrap_or_default())
+    }
 }
 
 impl RemoteTrackUrl for Track {
@@ -100,13 +379,13 @@ impl RemoteTrackUrl for Track {
 impl RemoteCoverUrl for Track {
     fn with_remote_cover_url(&self, base_url: &str) -> Self {
         Self {
-            album: Album {
-                cover: match self.album.cover {
-                    Some(ref cover) => Some(format!("{}/covers/{}", base_url, cover)),
-                    None => None,
-                },
-                ..self.album.clone()
-            },
+            album: self.album.as_ref().map(|album| Album {
+                cover: album
+                    .cover
+                    .as_ref()
+                    .map(|cover| format!("{}/covers/{}", base_url, cover)),
+                ..album.clone()
+            }),
             ..self.clone()
         }
     }
@@ -120,8 +399,36 @@ impl From<Model> for Track {
             uri: model.uri,
             duration: model.duration,
             track_number: model.track,
-            artists: model.artists.into_iter().map(Into::into).collect(),
-            album: model.album.into(),
+            disc_number: model.disc_number.unwrap_or_default(),
+            disc_total: model.disc_total,
+            track_total: model.track_total,
+            composer: model.composer,
+            conductor: model.conductor,
+            isrc: model.isrc,
+            comment: model.comment,
+            bpm: model.bpm,
+            title_sort: model.title_sort,
+            artist_sort: model.artist_sort,
+            musicbrainz_recording_id: model.musicbrainz_recording_id,
+            loudness: model.loudness,
+            true_peak: model.true_peak,
+            replaygain_track_gain: model.replaygain_track_gain,
+            replaygain_track_peak: model.replaygain_track_peak,
+            replaygain_album_gain: model.replaygain_album_gain,
+            replaygain_album_peak: model.replaygain_album_peak,
+            tempo: model.tempo,
+            musical_key: model.musical_key,
+            // Empty when the repository didn't load them
+            artists: match model.artists.is_empty() {
+                true => None,
+                false => Some(model.artists.into_iter().map(Into::into).collect()),
+            },
+            album: match model.album.id.is_empty() {
+                true => None,
+                false => Some(model.album.into()),
+            },
+            album_id: model.album_id.unwrap_or_default(),
+            artist_id: model.artist_id.unwrap_or_default(),
             artist: model.artist,
             ..Default::default()
         }
@@ -165,17 +472,30 @@ impl From<types::Track> for Track {
             uri: track.uri,
             duration: track.duration,
             track_number: track.track_number,
+            disc_number: track.disc_number,
+            disc_total: track.disc_total,
+            track_total: track.track_total,
+            composer: track.composer,
+            conductor: track.conductor,
+            isrc: track.isrc,
+            comment: track.comment,
+            bpm: track.bpm,
+            title_sort: track.title_sort,
+            artist_sort: track.artist_sort,
+            musicbrainz_recording_id: track.musicbrainz_recording_id,
             artist: track.artist,
             album: match track.album.clone() {
-                Some(album) => album.into(),
-                None => Default::default(),
+                Some(album) => Some(album.into()),
+                None => Some(Default::default()),
             },
-            artists: track
-                .artists
-                .clone()
-                .into_iter()
-                .map(|artist| artist.into())
-                .collect(),
+            artists: Some(
+                track
+                    .artists
+                    .clone()
+                    .into_iter()
+                    .map(|artist| artist.into())
+                    .collect(),
+            ),
             album_title: match track.album.clone() {
                 Some(album) => album.title,
                 None => String::new(),
@@ -209,13 +529,13 @@ impl From<select_result::PlaylistTrack> for Track {
             album_id: result.album_id.clone(),
             artist_id: result.artist_id.clone(),
             cover: result.album_cover.clone(),
-            album: Album {
+            album: Some(Album {
                 id: ID(result.album_id),
                 title: result.album_title,
                 cover: result.album_cover,
                 year: result.album_year,
                 ..Default::default()
-            },
+            }),
             ..Default::default()
         }
     }
@@ -2,15 +2,17 @@ use std::sync::{Arc, Mutex};
 
 use crate::simple_broker::SimpleBroker;
 use async_graphql::*;
-use futures_util::Stream;
+use futures_util::{stream, Stream, StreamExt};
 use music_player_addons::CurrentReceiverDevice;
 use music_player_playback::player::PlayerCommand;
+use music_player_storage::{repo::lyrics::LyricsRepository, Database};
 use music_player_tracklist::{PlaybackState, Tracklist};
+use music_player_types::types::{current_lyric_line, LyricLine as LyricLineType, Lyrics};
 use tokio::sync::mpsc::UnboundedSender;
 use tokio::sync::Mutex as TokioMutex;
 
 use super::objects::{
-    current_track::CurrentlyPlayingSong, player_state::PlayerState, track::Track,
+    current_track::CurrentlyPlayingSong, lyrics::LyricLine, player_state::PlayerState, track::Track,
 };
 
 #[derive(Default)]
@@ -235,4 +237,58 @@ impl PlaybackSubscription {
     async fn track_time_position(&self) -> impl Stream<Item = PositionMilliseconds> {
         SimpleBroker::<PositionMilliseconds>::subscribe()
     }
+
+    /// Emits the synced lyric line matching the track time position, each
+    /// time the line changes.
+    async fn current_lyric_line(&self, ctx: &Context<'_>) -> impl Stream<Item = LyricLine> {
+        let db = ctx.data::<Database>().unwrap().clone();
+        let tracklist = ctx.data::<Arc<Mutex<Tracklist>>>().unwrap();
+        let (track, _) = tracklist.lock().unwrap().current_track();
+        let lines = match track {
+            Some(track) => synced_lyrics(&db, &track.id).await,
+            None => vec![],
+        };
+        let state = Arc::new(TokioMutex::new((lines, None::<u32>)));
+
+        let tracks = SimpleBroker::<Track>::subscribe().map(|track| LyricsEvent::Track(track.id));
+        let positions =
+            SimpleBroker::<PositionMilliseconds>::subscribe().map(LyricsEvent::Position);
+        stream::select(tracks, positions).filter_map(move |event| {
+            let db = db.clone();
+            let state = Arc::clone(&state);
+            async move {
+                let mut state = state.lock().await;
+                match event {
+                    LyricsEvent::Track(id) => {
+                        *state = (synced_lyrics(&db, &id).await, None);
+                        None
+                    }
+                    LyricsEvent::Position(position) => {
+                        let (lines, current) = &mut *state;
+                        let line = current_lyric_line(lines, position.position_ms)?;
+                        if *current == line.time_ms {
+                            return None;
+                        }
+                        *current = line.time_ms;
+                        Some(line.clone().into())
+                    }
+                }
+            }
+        })
+    }
+}
+
+enum LyricsEvent {
+    Track(ID),
+    Position(PositionMilliseconds),
+}
+
+async fn synced_lyrics(db: &Database, track_id: &str) -> Vec<LyricLineType> {
+    match LyricsRepository::new(db.get_connection())
+        .find_by_track(track_id)
+        .await
+    {
+        Ok(Some(lyrics)) if lyrics.synced => Lyrics::from(lyrics).lines(),
+        _ => vec![],
+    }
 }
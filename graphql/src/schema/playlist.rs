@@ -5,20 +5,28 @@ use cuid::cuid;
 use futures_util::Stream;
 use music_player_addons::CurrentSourceDevice;
 use music_player_entity::{
-    folder as folder_entity, playlist as playlist_entity,
-    playlist_tracks as playlist_tracks_entity, select_result, track as track_entity,
+    folder as folder_entity, playlist as playlist_entity, track as track_entity,
 };
-use music_player_storage::{repo::playlist::PlaylistRepository, Database};
-use sea_orm::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, JoinType, ModelTrait, QueryFilter,
-    QueryOrder, QuerySelect, RelationTrait,
+use music_player_scanner::playlist_file::{
+    import_playlist, parse_playlist, write_playlist, PlaylistFile,
 };
+use music_player_storage::{
+    repo::{playlist::PlaylistRepository, smart_playlist::SmartPlaylistRepository},
+    Database,
+};
+use music_player_types::smart_playlist::SmartQuery;
+use sea_orm::{ActiveModelTrait, ActiveValue, EntityTrait, ModelTrait, QueryOrder};
 use tokio::sync::Mutex;
 
 use crate::simple_broker::SimpleBroker;
 
 use super::{
-    objects::{folder::Folder, playlist::Playlist, track::Track},
+    objects::{
+        connection::{paginate, LibraryConnection},
+        folder::Folder,
+        playlist::{ImportedPlaylist, Playlist, PlaylistFileFormat},
+        track::Track,
+    },
     MutationType,
 };
 
@@ -65,6 +73,41 @@ impl PlaylistQuery {
             .map_err(|e| Error::new(e.to_string()))
     }
 
+    /// The local playlists sorted by name, in pages following keyset cursors.
+    async fn playlists_connection(
+        &self,
+        ctx: &Context<'_>,
+        after: Option<String>,
+        before: Option<String>,
+        first: Option<i32>,
+        last: Option<i32>,
+    ) -> Result<LibraryConnection<Playlist>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        paginate(after, before, first, last, |request| async move {
+            PlaylistRepository::new(db.get_connection())
+                .find_page(&request)
+                .await
+        })
+        .await
+    }
+
+    /// A local playlist as a M3U8, PLS or XSPF file.
+    async fn export_playlist(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        format: PlaylistFileFormat,
+    ) -> Result<String, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let playlist = PlaylistRepository::new(db.get_connection())
+            .find(id.as_str())
+            .await?;
+        Ok(write_playlist(
+            format.into(),
+            &PlaylistFile::from(&playlist),
+        ))
+    }
+
     async fn main_playlists(&self, ctx: &Context<'_>) -> Result<Vec<Playlist>, Error> {
         let db = ctx.data::<Database>().unwrap();
         PlaylistRepository::new(db.get_connection())
@@ -120,46 +163,47 @@ impl PlaylistMutation {
         name: String,
         description: Option<String>,
         folder_id: Option<ID>,
+    ) -> Result<Playlist, Error> {
+        let playlist = self
+            .insert_playlist(ctx, name, description, folder_id, None)
+            .await?;
+        Ok(playlist.into())
+    }
+
+    async fn create_smart_playlist(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        rules: String,
+        description: Option<String>,
+        folder_id: Option<ID>,
     ) -> Result<Playlist, Error> {
         let db = ctx.data::<Database>().unwrap();
-        let mut folder: Option<folder_entity::Model> = None;
-        let folder_id = match folder_id {
-            Some(folder_id) => {
-                folder = folder_entity::Entity::find_by_id(folder_id.to_string())
-                    .one(db.get_connection())
-                    .await?;
-                if folder.is_none() {
-                    return Err(Error::new("Folder not found"));
-                }
-                Some(folder_id.to_string())
-            }
-            None => None,
-        };
-        let playlist = playlist_entity::ActiveModel {
-            id: ActiveValue::set(cuid().unwrap()),
-            name: ActiveValue::Set(name),
-            description: ActiveValue::Set(description),
-            folder_id: ActiveValue::Set(folder_id.clone()),
-            created_at: ActiveValue::set(chrono::Utc::now()),
-        };
-        match playlist.insert(db.get_connection()).await {
-            Ok(playlist) => {
-                if let Some(folder) = folder {
-                    SimpleBroker::publish(FolderChanged {
-                        folder: folder.into(),
-                        mutation_type: MutationType::Updated,
-                        playlist: Some(playlist.clone().into()),
-                    });
-                }
-                SimpleBroker::publish(PlaylistChanged {
-                    playlist: playlist.clone().into(),
-                    mutation_type: MutationType::Created,
-                    track: None,
-                });
-                Ok(playlist.into())
-            }
-            Err(err) => Err(Error::new(err.to_string())),
+        let query = SmartQuery::parse(&rules).map_err(Error::new)?;
+        let playlist = self
+            .insert_playlist(ctx, name, description, folder_id, Some(&query))
+            .await?;
+        Ok(PlaylistRepository::new(db.get_connection())
+            .find(&playlist.id)
+            .await?
+            .into())
+    }
+
+    async fn update_smart_playlist(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        rules: String,
+    ) -> Result<Playlist, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let query = SmartQuery::parse(&rules).map_err(Error::new)?;
+        if !SmartPlaylistRepository::new(db.get_connection())
+            .is_smart(&id)
+            .await?
+        {
+            return Err(Error::new("Playlist is not a smart playlist"));
         }
+        self.save_rules(ctx, id, &query).await
     }
 
     async fn delete_playlist(&self, ctx: &Context<'_>, id: ID) -> Result<Playlist, Error> {
@@ -170,11 +214,16 @@ impl PlaylistMutation {
 
         match playlist {
             Some(playlist) => {
+                SmartPlaylistRepository::new(db.get_connection())
+                    .delete(&playlist.id)
+                    .await?;
                 playlist.clone().delete(db.get_connection()).await?;
                 SimpleBroker::publish(PlaylistChanged {
                     playlist: playlist.clone().into(),
                     mutation_type: MutationType::Deleted,
                     track: None,
+                    position: None,
+                    previous_position: None,
                 });
                 Ok(playlist.into())
             }
@@ -182,13 +231,17 @@ impl PlaylistMutation {
         }
     }
 
+    /// Adds a track at `position`, after the last track when unset. A track
+    /// may be added several times.
     async fn add_track_to_playlist(
         &self,
         ctx: &Context<'_>,
         id: ID,
         track_id: ID,
+        position: Option<usize>,
     ) -> Result<Playlist, Error> {
         let db = ctx.data::<Database>().unwrap();
+        reject_smart_playlist(db, &id).await?;
         let track = track_entity::Entity::find_by_id(track_id.to_string())
             .one(db.get_connection())
             .await?;
@@ -199,20 +252,20 @@ impl PlaylistMutation {
                     .await?;
                 match playlist {
                     Some(playlist) => {
-                        let playlist_track = playlist_tracks_entity::ActiveModel {
-                            id: ActiveValue::set(cuid().unwrap()),
-                            playlist_id: ActiveValue::Set(playlist.id.clone()),
-                            track_id: ActiveValue::Set(track.id.clone()),
-                            created_at: ActiveValue::set(chrono::Utc::now()),
-                        };
-                        playlist_track
-                            .insert(db.get_connection())
+                        let items = PlaylistRepository::new(db.get_connection())
+                            .insert_tracks(
+                                &playlist.id,
+                                std::slice::from_ref(&track.id),
+                                position.map(|position| position as u32),
+                            )
                             .await
                             .map_err(|err| Error::new(err.to_string()))?;
                         SimpleBroker::publish(PlaylistChanged {
                             playlist: playlist.clone().into(),
                             mutation_type: MutationType::Updated,
                             track: Some(track.clone().into()),
+                            position: items.first().map(|item| item.position as usize),
+                            previous_position: None,
                         });
                         Ok(playlist.into())
                     }
@@ -230,28 +283,85 @@ impl PlaylistMutation {
         position: usize,
     ) -> Result<Playlist, Error> {
         let db = ctx.data::<Database>().unwrap();
-        let playlist_track = playlist_tracks_entity::Entity::find()
-            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id.to_string()))
-            .all(db.get_connection())
+        reject_smart_playlist(db, &id).await?;
+        let playlist = playlist_entity::Entity::find_by_id(id.to_string())
+            .one(db.get_connection())
+            .await?
+            .ok_or_else(|| Error::new("Playlist not found"))?;
+        let track_id = PlaylistRepository::new(db.get_connection())
+            .remove_track(&playlist.id, position as u32)
+            .await?;
+        let track = track_entity::Entity::find_by_id(track_id)
+            .one(db.get_connection())
             .await?;
-        if playlist_track.len() <= position {
-            return Err(Error::new("Track not found"));
-        }
-        playlist_track[position]
-            .clone()
-            .delete(db.get_connection())
-            .await
-            .map_err(|err| Error::new(err.to_string()))?;
-        let playlist =
-            playlist_entity::Entity::find_by_id(playlist_track[position].clone().playlist_id)
-                .one(db.get_connection())
-                .await?;
         SimpleBroker::publish(PlaylistChanged {
-            playlist: playlist.clone().unwrap().into(),
+            playlist: playlist.clone().into(),
             mutation_type: MutationType::Updated,
+            track: track.map(Into::into),
+            position: None,
+            previous_position: Some(position),
+        });
+        Ok(playlist.into())
+    }
+
+    /// Creates a playlist from the content of a M3U8, PLS or XSPF file, the
+    /// entries being matched with the tracks of the library by path or by
+    /// artist and title. Named after the title of the file when `name` is
+    /// not set.
+    async fn import_playlist(
+        &self,
+        ctx: &Context<'_>,
+        name: Option<String>,
+        content: String,
+        format: PlaylistFileFormat,
+    ) -> Result<ImportedPlaylist, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let file = parse_playlist(format.into(), &content)?;
+        let name = name
+            .or_else(|| file.title.clone())
+            .unwrap_or_else(|| "Imported playlist".into());
+        let imported = import_playlist(db, &cuid().unwrap(), &name, &file, None).await?;
+        let playlist: Playlist = PlaylistRepository::new(db.get_connection())
+            .find(&imported.id)
+            .await?
+            .into();
+        SimpleBroker::publish(PlaylistChanged {
+            playlist: playlist.clone(),
+            mutation_type: MutationType::Created,
             track: None,
+            position: None,
+            previous_position: None,
+        });
+        Ok(ImportedPlaylist {
+            playlist,
+            missing: imported.missing.iter().map(ToString::to_string).collect(),
+        })
+    }
+
+    /// Moves the track at `from` to `to`, the tracks in between shifting by
+    /// one.
+    async fn move_track_in_playlist(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        from: usize,
+        to: usize,
+    ) -> Result<Playlist, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        reject_smart_playlist(db, &id).await?;
+        let repo = PlaylistRepository::new(db.get_connection());
+        let position = repo.move_track(&id, from as u32, to as u32).await?;
+        let playlist = repo.find(&id).await?;
+        let track = playlist.tracks.get(position as usize).cloned();
+        let playlist: Playlist = playlist.into();
+        SimpleBroker::publish(PlaylistChanged {
+            playlist: playlist.clone(),
+            mutation_type: MutationType::Moved,
+            track: track.map(Into::into),
+            position: Some(position as usize),
+            previous_position: Some(from),
         });
-        Ok(playlist.unwrap().into())
+        Ok(playlist)
     }
 
     async fn rename_playlist(
@@ -275,6 +385,8 @@ impl PlaylistMutation {
                     playlist: playlist.clone().into(),
                     mutation_type: MutationType::Renamed,
                     track: None,
+                    position: None,
+                    previous_position: None,
                 });
                 Ok(playlist.into())
             }
@@ -375,6 +487,8 @@ impl PlaylistMutation {
                                     playlist: moved_playlist.into(),
                                     mutation_type: MutationType::Moved,
                                     track: None,
+                                    position: None,
+                                    previous_position: None,
                                 });
                                 SimpleBroker::publish(FolderChanged {
                                     folder: folder.clone().into(),
@@ -420,6 +534,8 @@ impl PlaylistMutation {
                                 playlist: moved_playlist.into(),
                                 mutation_type: MutationType::Moved,
                                 track: None,
+                                position: None,
+                                previous_position: None,
                             });
                             Ok(p)
                         }
@@ -433,6 +549,102 @@ impl PlaylistMutation {
     }
 }
 
+impl PlaylistMutation {
+    /// Inserts a playlist, along with its rules when it is a smart one.
+    async fn insert_playlist(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+        description: Option<String>,
+        folder_id: Option<ID>,
+        query: Option<&SmartQuery>,
+    ) -> Result<playlist_entity::Model, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let mut folder: Option<folder_entity::Model> = None;
+        let folder_id = match folder_id {
+            Some(folder_id) => {
+                folder = folder_entity::Entity::find_by_id(folder_id.to_string())
+                    .one(db.get_connection())
+                    .await?;
+                if folder.is_none() {
+                    return Err(Error::new("Folder not found"));
+                }
+                Some(folder_id.to_string())
+            }
+            None => None,
+        };
+        let playlist = playlist_entity::ActiveModel {
+            id: ActiveValue::set(cuid().unwrap()),
+            name: ActiveValue::Set(name),
+            description: ActiveValue::Set(description),
+            folder_id: ActiveValue::Set(folder_id.clone()),
+            created_at: ActiveValue::set(chrono::Utc::now()),
+            file_hash: ActiveValue::Set(None),
+        };
+        let playlist = match query {
+            Some(query) => SmartPlaylistRepository::new(db.get_connection())
+                .create(playlist, query)
+                .await
+                .map_err(|err| Error::new(err.to_string()))?,
+            None => playlist
+                .insert(db.get_connection())
+                .await
+                .map_err(|err| Error::new(err.to_string()))?,
+        };
+        if let Some(folder) = folder {
+            SimpleBroker::publish(FolderChanged {
+                folder: folder.into(),
+                mutation_type: MutationType::Updated,
+                playlist: Some(playlist.clone().into()),
+            });
+        }
+        SimpleBroker::publish(PlaylistChanged {
+            playlist: playlist.clone().into(),
+            mutation_type: MutationType::Created,
+            track: None,
+            position: None,
+            previous_position: None,
+        });
+        Ok(playlist)
+    }
+
+    async fn save_rules(
+        &self,
+        ctx: &Context<'_>,
+        id: ID,
+        query: &SmartQuery,
+    ) -> Result<Playlist, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        SmartPlaylistRepository::new(db.get_connection())
+            .save(&id, query)
+            .await?;
+        let playlist: Playlist = PlaylistRepository::new(db.get_connection())
+            .find(&id)
+            .await?
+            .into();
+        SimpleBroker::publish(PlaylistChanged {
+            playlist: playlist.clone(),
+            mutation_type: MutationType::Updated,
+            track: None,
+            position: None,
+            previous_position: None,
+        });
+        Ok(playlist)
+    }
+}
+
+/// The tracks of a smart playlist come from its rules, they can't be
+/// added or removed by hand.
+async fn reject_smart_playlist(db: &Database, id: &ID) -> Result<(), Error> {
+    match SmartPlaylistRepository::new(db.get_connection())
+        .is_smart(id)
+        .await?
+    {
+        true => Err(Error::new("Tracks of a smart playlist can't be edited")),
+        false => Ok(()),
+    }
+}
+
 #[derive(Clone)]
 struct FolderChanged {
     folder: Folder,
@@ -460,6 +672,8 @@ struct PlaylistChanged {
     playlist: Playlist,
     mutation_type: MutationType,
     track: Option<Track>,
+    position: Option<usize>,
+    previous_position: Option<usize>,
 }
 
 #[Object]
@@ -475,6 +689,16 @@ impl PlaylistChanged {
     async fn track(&self) -> Option<&Track> {
         self.track.as_ref()
     }
+
+    /// Position of the track added or moved
+    async fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// Position of the track removed or moved, before the change
+    async fn previous_position(&self) -> Option<usize> {
+        self.previous_position
+    }
 }
 
 #[derive(Default)]
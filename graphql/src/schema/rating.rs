@@ -0,0 +1,105 @@
+use async_graphql::*;
+use music_player_scanner::rating::export_rating;
+use music_player_settings::{read_settings, Settings};
+use music_player_storage::{repo::rating::RatingRepository, Database};
+use music_player_types::rating;
+
+use super::objects::{album::Album, artist::Artist, track::Track};
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum FavouriteKind {
+    Track,
+    Album,
+    Artist,
+}
+
+impl From<FavouriteKind> for rating::FavouriteKind {
+    fn from(kind: FavouriteKind) -> Self {
+        match kind {
+            FavouriteKind::Track => rating::FavouriteKind::Track,
+            FavouriteKind::Album => rating::FavouriteKind::Album,
+            FavouriteKind::Artist => rating::FavouriteKind::Artist,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RatingQuery;
+
+#[Object]
+impl RatingQuery {
+    /// The favourite tracks, from the most recently liked one.
+    async fn liked_songs(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<Track>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let tracks = RatingRepository::new(db.get_connection())
+            .liked_tracks(offset.unwrap_or(0) as u64, limit.unwrap_or(100) as u64)
+            .await?;
+        Ok(tracks.into_iter().map(Into::into).collect())
+    }
+
+    async fn favourite_albums(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<Album>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let albums = RatingRepository::new(db.get_connection())
+            .favourite_albums(offset.unwrap_or(0) as u64, limit.unwrap_or(100) as u64)
+            .await?;
+        Ok(albums.into_iter().map(Into::into).collect())
+    }
+
+    async fn favourite_artists(
+        &self,
+        ctx: &Context<'_>,
+        offset: Option<i32>,
+        limit: Option<i32>,
+    ) -> Result<Vec<Artist>, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        let artists = RatingRepository::new(db.get_connection())
+            .favourite_artists(offset.unwrap_or(0) as u64, limit.unwrap_or(100) as u64)
+            .await?;
+        Ok(artists.into_iter().map(Into::into).collect())
+    }
+}
+
+#[derive(Default)]
+pub struct RatingMutation;
+
+#[Object]
+impl RatingMutation {
+    /// Rates a track from 1 to 5 stars, 0 removes its rating. The rating is
+    /// also written into the file when `write_rating_tags` is enabled.
+    async fn rate_track(&self, ctx: &Context<'_>, id: ID, rating: u8) -> Result<bool, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        RatingRepository::new(db.get_connection())
+            .set_rating(&id, rating)
+            .await?;
+
+        let settings = read_settings()?.try_deserialize::<Settings>()?;
+        if settings.write_rating_tags {
+            export_rating(db, &id).await?;
+        }
+        Ok(true)
+    }
+
+    async fn set_favourite(
+        &self,
+        ctx: &Context<'_>,
+        kind: FavouriteKind,
+        id: ID,
+        favourite: bool,
+    ) -> Result<bool, Error> {
+        let db = ctx.data::<Database>().unwrap();
+        RatingRepository::new(db.get_connection())
+            .set_favourite(kind.into(), &id, favourite)
+            .await?;
+        Ok(favourite)
+    }
+}
@@ -3,6 +3,7 @@ use futures_util::Stream;
 use music_player_addons::{CurrentReceiverDevice, CurrentSourceDevice};
 use music_player_entity::{album as album_entity, artist as artist_entity, track as track_entity};
 use music_player_playback::player::PlayerCommand;
+use music_player_scanner::playlist_file::{write_playlist, PlaylistFile};
 use music_player_storage::repo::album::AlbumRepository;
 use music_player_storage::repo::artist::ArtistRepository;
 use music_player_storage::repo::playlist::PlaylistRepository;
@@ -30,6 +31,7 @@ use crate::update_tracks_url;
 use super::objects::album::Album;
 use super::{
     objects::{
+        playlist::PlaylistFileFormat,
         track::{Track, TrackInput},
         tracklist::Tracklist,
     },
@@ -67,6 +69,27 @@ impl TracklistQuery {
 
         Ok(response)
     }
+
+    /// The previous and next tracks of the tracklist as a M3U8, PLS or XSPF
+    /// file.
+    async fn export_tracklist(
+        &self,
+        ctx: &Context<'_>,
+        format: PlaylistFileFormat,
+    ) -> Result<String, Error> {
+        let state = ctx.data::<Arc<StdMutex<TracklistState>>>().unwrap();
+        let (previous_tracks, next_tracks) = state.lock().unwrap().tracks();
+        let file = PlaylistFile {
+            title: None,
+            entries: previous_tracks
+                .iter()
+                .chain(next_tracks.iter())
+                .map(Into::into)
+                .collect(),
+        };
+        Ok(write_playlist(format.into(), &file))
+    }
+
     async fn get_repeat(&self, ctx: &Context<'_>) -> Result<bool, Error> {
         todo!()
     }
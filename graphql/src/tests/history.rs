@@ -0,0 +1,114 @@
+use async_graphql::value;
+use music_player_playback::player::Player;
+use music_player_storage::{repo::history::HistoryRepository, Database};
+use music_player_types::types::{HistoryEntry, Track};
+use std::sync::Arc;
+
+use super::setup_schema;
+
+#[tokio::test]
+async fn play_stats() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    let db = Database::new().await;
+    let repo = HistoryRepository::new(db.get_connection());
+    let track_id = format!("history-{}", chrono::Utc::now().timestamp_nanos());
+    let skipped_id = format!("skipped-{}", chrono::Utc::now().timestamp_nanos());
+    for (id, listened_ms, completed) in [
+        (&track_id, 180000, true),
+        (&track_id, 2000, false),
+        (&skipped_id, 3000, false),
+        (&track_id, 175000, true),
+    ] {
+        repo.record(&HistoryEntry {
+            track: Track {
+                id: id.clone(),
+                ..Default::default()
+            },
+            started_at: chrono::Utc::now(),
+            listened_ms,
+            completed,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    }
+
+    let resp = schema
+        .execute(format!(
+            r#"
+              query PlayStats {{
+                playStats(trackId: "{}") {{
+                  playCount
+                  skipCount
+                }}
+              }}
+            "#,
+            track_id
+        ))
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "playStats": {
+            "playCount": 2,
+            "skipCount": 1
+          }
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              query History {
+                history(limit: 3) {
+                  listenedMs
+                  skipped
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "history": [
+            { "listenedMs": 175000, "skipped": false },
+            { "listenedMs": 3000, "skipped": true },
+            { "listenedMs": 2000, "skipped": true }
+          ]
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              query MostPlayed {
+                mostPlayed(limit: 1000) {
+                  trackId
+                  playCount
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    let data = resp.data.into_json().unwrap();
+    let most_played = data["mostPlayed"].as_array().unwrap();
+    // A track which was only skipped wasn't played
+    assert!(most_played
+        .iter()
+        .any(|stats| stats["trackId"] == track_id.as_str() && stats["playCount"] == 2));
+    assert!(!most_played
+        .iter()
+        .any(|stats| stats["trackId"] == skipped_id.as_str()));
+}
@@ -4,7 +4,7 @@ use music_player_entity::{album, artist, artist_tracks, track};
 use music_player_playback::player::Player;
 use music_player_scanner::scan_directory;
 use music_player_storage::{searcher::Searcher, Database};
-use sea_orm::ActiveModelTrait;
+use sea_orm::{sea_query::Expr, ActiveModelTrait, ColumnTrait, EntityTrait, QueryFilter};
 use std::{sync::Arc, thread, time::Duration};
 
 use super::setup_schema;
@@ -55,6 +55,98 @@ async fn tracks() {
     );
 }
 
+#[tokio::test]
+async fn tracks_sorted_and_filtered() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    // Only Wet Dreamz was analyzed
+    let db = Database::new().await;
+    let analyze = |loudness: Option<f32>, tempo: Option<f32>, key: Option<&str>| {
+        track::Entity::update_many()
+            .col_expr(track::Column::Loudness, Expr::value(loudness))
+            .col_expr(track::Column::Tempo, Expr::value(tempo))
+            .col_expr(track::Column::MusicalKey, Expr::value(key))
+            .filter(track::Column::Id.eq("3ac1f1651b6ef6d5f3f55b711e3bfcd1"))
+            .exec(db.get_connection())
+    };
+    analyze(Some(-8.5), Some(92.0), Some("F minor")).await.unwrap();
+
+    let resp = schema
+        .execute(
+            r#"
+              query Tracks {
+                tracks(sort: { field: TITLE, descending: true }) {
+                    title
+                    loudness
+                    tempo
+                    musicalKey
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "tracks": [
+            {
+              "title": "Wet Dreamz",
+              "loudness": -8.5,
+              "tempo": 92.0,
+              "musicalKey": "F minor"
+            },
+            {
+              "title": "Fire Squad",
+              "loudness": null,
+              "tempo": null,
+              "musicalKey": null
+            },
+          ]
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              query Tracks {
+                matching: tracks(bounds: { tags: { format: "m4a" }, audio: { minTempo: 60, maxLoudness: -5, musicalKey: "F minor" } }) {
+                    title
+                }
+                tooSlow: tracks(bounds: { audio: { minTempo: 100 } }) {
+                    title
+                }
+                ascending: tracks(sort: { field: TEMPO }) {
+                    title
+                }
+                descending: tracks(sort: { field: MUSICAL_KEY, descending: true }) {
+                    title
+                }
+              }
+            "#,
+        )
+        .await;
+    analyze(None, None, None).await.unwrap();
+    assert_eq!(resp.errors.len(), 0);
+    // The tracks which weren't analyzed come last in both directions
+    assert_eq!(
+        resp.data,
+        value!({
+          "matching": [{ "title": "Wet Dreamz" }],
+          "tooSlow": [],
+          "ascending": [{ "title": "Wet Dreamz" }, { "title": "Fire Squad" }],
+          "descending": [{ "title": "Wet Dreamz" }, { "title": "Fire Squad" }]
+        })
+    );
+}
+
 #[tokio::test]
 async fn artists() {
     let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
@@ -91,6 +183,54 @@ async fn artists() {
     );
 }
 
+#[tokio::test]
+async fn genres() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+    // The tracks of the fixtures have no genre tag
+    let resp = schema
+        .execute(
+            r#"
+              query Genres {
+                genres {
+                  id
+                  name
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "genres": []
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              query Genre {
+                genre(id: "6c8349cc7260ae62e3b1396831a8398f") {
+                  name
+                  tracks {
+                    title
+                  }
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 1);
+}
+
 #[tokio::test]
 async fn albums() {
     let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
@@ -131,6 +271,239 @@ async fn albums() {
     );
 }
 
+#[tokio::test]
+async fn nested_relations() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+    let resp = schema
+        .execute(
+            r#"
+              query Library {
+                albums {
+                  title
+                  tracks {
+                    title
+                    album {
+                      title
+                    }
+                    artists {
+                      name
+                    }
+                  }
+                }
+                artists {
+                  name
+                  albums {
+                    title
+                  }
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "albums": [
+            {
+              "title": "2014 Forest Hills Drive",
+              "tracks": [
+                {
+                  "title": "Wet Dreamz",
+                  "album": {
+                    "title": "2014 Forest Hills Drive"
+                  },
+                  "artists": [
+                    {
+                      "name": "J. Cole"
+                    }
+                  ]
+                },
+                {
+                  "title": "Fire Squad",
+                  "album": {
+                    "title": "2014 Forest Hills Drive"
+                  },
+                  "artists": [
+                    {
+                      "name": "J. Cole"
+                    }
+                  ]
+                }
+              ]
+            }
+          ],
+          "artists": [
+            {
+              "name": "J. Cole",
+              "albums": [
+                {
+                  "title": "2014 Forest Hills Drive"
+                }
+              ]
+            }
+          ]
+        })
+    );
+}
+
+#[tokio::test]
+async fn tracks_connection() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+    let resp = schema
+        .execute(
+            r#"
+              query Tracks {
+                tracksConnection(first: 1) {
+                  totalCount
+                  edges {
+                    node {
+                      title
+                    }
+                  }
+                  pageInfo {
+                    hasPreviousPage
+                    hasNextPage
+                    endCursor
+                  }
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    let data = resp.data.into_json().unwrap();
+    let connection = &data["tracksConnection"];
+    assert_eq!(connection["totalCount"], 2);
+    assert_eq!(connection["edges"][0]["node"]["title"], "Fire Squad");
+    assert_eq!(connection["pageInfo"]["hasPreviousPage"], false);
+    assert_eq!(connection["pageInfo"]["hasNextPage"], true);
+    let cursor = connection["pageInfo"]["endCursor"].as_str().unwrap();
+
+    let resp = schema
+        .execute(format!(
+            r#"
+              query Tracks {{
+                tracksConnection(first: 1, after: "{}") {{
+                  edges {{
+                    node {{
+                      title
+                    }}
+                  }}
+                  pageInfo {{
+                    hasPreviousPage
+                    hasNextPage
+                  }}
+                }}
+              }}
+            "#,
+            cursor
+        ))
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "tracksConnection": {
+            "edges": [
+              {
+                "node": {
+                  "title": "Wet Dreamz"
+                }
+              }
+            ],
+            "pageInfo": {
+              "hasPreviousPage": true,
+              "hasNextPage": false
+            }
+          }
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              query Tracks {
+                tracksConnection(first: 10, filter: { text: "Dreamz", tags: { format: "m4a" } }) {
+                  totalCount
+                  edges {
+                    node {
+                      title
+                    }
+                  }
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "tracksConnection": {
+            "totalCount": 1,
+            "edges": [
+              {
+                "node": {
+                  "title": "Wet Dreamz"
+                }
+              }
+            ]
+          }
+        })
+    );
+
+    let query = |after: Option<&str>| {
+        let after = after
+            .map(|cursor| format!(r#", after: "{}""#, cursor))
+            .unwrap_or_default();
+        format!(
+            r#"
+              query Tracks {{
+                tracksConnection(first: 1{}, sort: {{ field: TITLE, descending: true }}) {{
+                  edges {{
+                    node {{
+                      title
+                    }}
+                  }}
+                  pageInfo {{
+                    endCursor
+                  }}
+                }}
+              }}
+            "#,
+            after
+        )
+    };
+    let resp = schema.execute(query(None)).await;
+    assert_eq!(resp.errors.len(), 0);
+    let data = resp.data.into_json().unwrap();
+    let connection = &data["tracksConnection"];
+    assert_eq!(connection["edges"][0]["node"]["title"], "Wet Dreamz");
+    let cursor = connection["pageInfo"]["endCursor"].as_str().unwrap();
+    let resp = schema.execute(query(Some(cursor))).await;
+    assert_eq!(resp.errors.len(), 0);
+    let data = resp.data.into_json().unwrap();
+    assert_eq!(
+        data["tracksConnection"]["edges"][0]["node"]["title"],
+        "Fire Squad"
+    );
+}
+
 #[tokio::test]
 async fn track() {
     let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
@@ -299,10 +672,11 @@ async fn search() {
     scan_directory(
         move |song, db| {
             async move {
-                let item: artist::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
+                for item in artist::ActiveModel::from_artists(song) {
+                    match item.insert(db.get_connection()).await {
+                        Ok(_) => (),
+                        Err(_) => (),
+                    }
                 }
 
                 let item: album::ActiveModel = song.try_into().unwrap();
@@ -318,10 +692,11 @@ async fn search() {
                     Err(_) => (),
                 }
 
-                let item: artist_tracks::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
+                for item in artist_tracks::ActiveModel::from_artists(song) {
+                    match item.insert(db.get_connection()).await {
+                        Ok(_) => (),
+                        Err(_) => (),
+                    }
                 }
             }
             .boxed()
@@ -376,4 +751,264 @@ async fn search() {
               }
         })
     );
+
+    let resp = schema
+        .execute(
+            r#"
+              query Search {
+                search(keyword: "artist:\"j. cole\" fire -live", limit: 5) {
+                    tracks {
+                        id
+                        title
+                      }
+                    totalTracks
+                    totalAlbums
+                    totalArtists
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+            "search": {
+                "tracks": [
+                  {
+                    "id": "dd77dd0ea2de5208e4987001a59ba8e4",
+                    "title": "Fire Squad",
+                  }
+                ],
+                "totalTracks": 1,
+                "totalAlbums": 0,
+                "totalArtists": 0
+              }
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              query Suggestions {
+                suggestions(input: "fire sq") {
+                    kind
+                    id
+                    text
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+            "suggestions": [
+              {
+                "kind": "TRACK",
+                "id": "dd77dd0ea2de5208e4987001a59ba8e4",
+                "text": "Fire Squad",
+              }
+            ]
+        })
+    );
+
+    // The pages are capped, tantivy allocates room for `offset + limit`
+    // results
+    for query in [
+        r#"{ search(keyword: "fire", limit: 2147483647) { totalTracks } }"#,
+        r#"{ search(keyword: "fire", offset: 2147483647) { totalTracks } }"#,
+        r#"{ suggestions(input: "fire", limit: 2147483647) { text } }"#,
+    ] {
+        let resp = schema.execute(query).await;
+        assert_eq!(resp.errors.len(), 1);
+    }
+}
+
+#[tokio::test]
+async fn scan_errors() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+    let resp = schema
+        .execute(
+            r#"
+              query ScanErrors {
+                scanErrors {
+                    id
+                    path
+                    kind
+                    message
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "scanErrors": []
+        })
+    );
+}
+
+#[tokio::test]
+async fn duplicates() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+    let resp = schema
+        .execute(
+            r#"
+              query Duplicates {
+                duplicates {
+                    copies {
+                        track {
+                            id
+                        }
+                        bitrate
+                    }
+                    bestCopy {
+                        track {
+                            uri
+                        }
+                    }
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "duplicates": []
+        })
+    );
+}
+
+#[tokio::test]
+async fn album_thumbnail() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+    let resp = schema
+        .execute(
+            r#"
+              query Album {
+                album(id: "216ccc791352fbbffc11268b984db19a") {
+                    small: thumbnail(size: 64)
+                    medium: thumbnail(size: 100)
+                    large: thumbnail(size: 1024, format: WEBP)
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "album": {
+            "small": "216ccc791352fbbffc11268b984db19a_64.jpg",
+            "medium": "216ccc791352fbbffc11268b984db19a_256.jpg",
+            "large": "216ccc791352fbbffc11268b984db19a_512.webp"
+          }
+        })
+    );
+}
+
+#[tokio::test]
+async fn update_track_tags_dry_run() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+    let resp = schema
+        .execute(
+            r#"
+              mutation UpdateTrackTags {
+                updateTrackTags(
+                  id: "3ac1f1651b6ef6d5f3f55b711e3bfcd1",
+                  changes: { title: "Wet Dreamz (Live)", year: 2015 },
+                  dryRun: true
+                ) {
+                    id
+                    title
+                    artist
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "updateTrackTags": {
+            "id": "3ac1f1651b6ef6d5f3f55b711e3bfcd1",
+            "title": "Wet Dreamz (Live)",
+            "artist": "J. Cole",
+          }
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              query Track {
+                track(id: "3ac1f1651b6ef6d5f3f55b711e3bfcd1") {
+                    title
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "track": {
+            "title": "Wet Dreamz",
+          }
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              mutation UpdateAlbumTags {
+                updateAlbumTags(
+                  id: "216ccc791352fbbffc11268b984db19a",
+                  changes: { title: "Intro" },
+                  dryRun: true
+                ) {
+                    id
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 1);
 }
@@ -17,14 +17,16 @@ use tokio::sync::{
 
 use crate::{
     scan_devices,
-    schema::{Mutation, Query, Subscription},
+    schema::{loaders::with_loaders, Mutation, Query, Subscription},
     MusicPlayerSchema,
 };
 
+pub mod history;
 pub mod library;
 pub mod objects;
 pub mod playback;
 pub mod playlist;
+pub mod rating;
 pub mod tracklist;
 
 pub async fn setup_schema() -> (
@@ -56,10 +58,13 @@ pub async fn setup_schema() -> (
 
     let db = Database::new().await;
     (
-        Schema::build(
-            Query::default(),
-            Mutation::default(),
-            Subscription::default(),
+        with_loaders(
+            Schema::build(
+                Query::default(),
+                Mutation::default(),
+                Subscription::default(),
+            ),
+            &db,
         )
         .data(db)
         .data(Arc::clone(&cmd_tx))
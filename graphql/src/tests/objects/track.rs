@@ -78,7 +78,7 @@ fn playlist_track_to_track() {
         "df2ac64a1a2c4c4b604d02b6d2d0477a".to_owned()
     );
     assert_eq!(track.album_title, "HEROES & VILLAINS".to_owned());
-    assert_eq!(track.album.year, Some(2022));
+    assert_eq!(track.album.unwrap().year, Some(2022));
     assert_eq!(track.duration, Some(174.8209991455078));
     assert_eq!(track.track_number, Some(13));
 }
@@ -1,4 +1,4 @@
-use async_graphql::value;
+use async_graphql::{value, Value};
 use music_player_playback::player::Player;
 
 use super::{new_folder, new_playlist, setup_schema};
@@ -213,6 +213,89 @@ async fn remove_track_from_playlist() {
     );
 }
 
+#[tokio::test]
+async fn move_track_in_playlist() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    let id = new_playlist(schema.clone()).await;
+
+    // Wet Dreamz, then Fire Squad twice, the second one inserted first
+    for (track_id, position) in [
+        ("3ac1f1651b6ef6d5f3f55b711e3bfcd1", "null"),
+        ("dd77dd0ea2de5208e4987001a59ba8e4", "null"),
+        ("dd77dd0ea2de5208e4987001a59ba8e4", "0"),
+    ] {
+        let resp = schema
+            .execute(format!(
+                r#"
+                mutation AddTrackToPlaylist {{
+                  addTrackToPlaylist(id: {}, trackId: "{}", position: {}) {{
+                    id
+                  }}
+                }}
+                "#,
+                id, track_id, position
+            ))
+            .await;
+        assert_eq!(resp.errors.len(), 0);
+    }
+
+    let resp = schema
+        .execute(format!(
+            r#"
+            mutation MoveTrackInPlaylist {{
+              moveTrackInPlaylist(id: {}, from: 2, to: 1) {{
+                tracks {{
+                  title
+                }}
+              }}
+            }}
+            "#,
+            id
+        ))
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "moveTrackInPlaylist": {
+            "tracks": [
+              {
+                "title": "Fire Squad"
+              },
+              {
+                "title": "Fire Squad"
+              },
+              {
+                "title": "Wet Dreamz"
+              }
+            ]
+          }
+        })
+    );
+
+    let resp = schema
+        .execute(format!(
+            r#"
+            mutation MoveTrackInPlaylist {{
+              moveTrackInPlaylist(id: {}, from: 3, to: 0) {{
+                id
+              }}
+            }}
+            "#,
+            id
+        ))
+        .await;
+    assert_eq!(resp.errors.len(), 1);
+}
+
 #[tokio::test]
 async fn rename_playlist() {
     let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
@@ -477,3 +560,117 @@ async fn move_playlists_to_folder() {
         })
     );
 }
+
+#[tokio::test]
+async fn create_smart_playlist() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              mutation CreateSmartPlaylist {
+                createSmartPlaylist(name: "Dreams", rules: "title contains dream, limit 10") {
+                  name
+                  rules
+                  tracks {
+                    title
+                  }
+                }
+            }"#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "createSmartPlaylist":
+            {
+              "name": "Dreams",
+              "rules": "title contains \"dream\", limit 10",
+              "tracks": [
+                {
+                  "title": "Wet Dreamz"
+                }
+              ]
+            }
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"mutation { createSmartPlaylist(name: "Broken", rules: "year is soon") { id } }"#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 1);
+}
+
+#[tokio::test]
+async fn import_and_export_playlist() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    // By path, by artist and title, and a track missing from the library
+    let resp = schema
+        .execute(
+            r##"
+            mutation ImportPlaylist {
+              importPlaylist(
+                content: "#EXTM3U\n#PLAYLIST:Forest Hills\n/tmp/audio/03 - J. Cole - Wet Dreamz(Explicit).m4a\n#EXTINF:288,J. Cole - Fire Squad\nMusic/Fire Squad.m4a\n#EXTINF:200,Nobody - Unknown Song\nMusic/Unknown Song.mp3\n",
+                format: M3U8
+              ) {
+                playlist {
+                  id
+                  name
+                  tracks {
+                    title
+                  }
+                }
+                missing
+              }
+            }
+            "##,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    let data = resp.data.into_json().unwrap();
+    let imported = &data["importPlaylist"];
+    assert_eq!(imported["playlist"]["name"], "Forest Hills");
+    assert_eq!(
+        Value::from_json(imported["playlist"]["tracks"].clone()).unwrap(),
+        value!([{ "title": "Wet Dreamz" }, { "title": "Fire Squad" }])
+    );
+    assert_eq!(
+        Value::from_json(imported["missing"].clone()).unwrap(),
+        value!(["Nobody - Unknown Song"])
+    );
+
+    let resp = schema
+        .execute(format!(
+            r#"
+            query ExportPlaylist {{
+              exportPlaylist(id: {}, format: M3U8)
+            }}
+            "#,
+            imported["playlist"]["id"]
+        ))
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    let data = resp.data.into_json().unwrap();
+    let content = data["exportPlaylist"].as_str().unwrap();
+    assert!(content.starts_with("#EXTM3U\n#PLAYLIST:Forest Hills\n"));
+    assert!(content.contains("\n/tmp/audio/03 - J. Cole - Wet Dreamz(Explicit).m4a\n"));
+    assert!(content.contains("\n/tmp/audio/06 - J. Cole - Fire Squad(Explicit).m4a\n"));
+}
@@ -0,0 +1,163 @@
+use async_graphql::{value, Schema};
+use music_player_addons::CurrentSourceDevice;
+use music_player_playback::player::Player;
+use music_player_storage::Database;
+use std::{
+    env,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+};
+use tokio::sync::Mutex;
+
+use super::setup_schema;
+use crate::{
+    schema::{loaders::with_loaders, Mutation, Query, Subscription},
+    MusicPlayerSchema,
+};
+
+#[tokio::test]
+async fn rate_and_like_track() {
+    let (schema, cmd_tx, cmd_rx, tracklist, backend, audio_format) = setup_schema().await;
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        |_| {},
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              mutation RateAndLike {
+                rateTrack(id: "dd77dd0ea2de5208e4987001a59ba8e4", rating: 4)
+                setFavourite(kind: TRACK, id: "dd77dd0ea2de5208e4987001a59ba8e4", favourite: true)
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+
+    let resp = schema
+        .execute(
+            r#"
+              query LikedSongs {
+                likedSongs {
+                  id
+                  title
+                  rating
+                  favourite
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "likedSongs": [
+            {
+              "id": "dd77dd0ea2de5208e4987001a59ba8e4",
+              "title": "Fire Squad",
+              "rating": 4,
+              "favourite": true
+            }
+          ]
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              mutation Reset {
+                rateTrack(id: "dd77dd0ea2de5208e4987001a59ba8e4", rating: 0)
+                setFavourite(kind: TRACK, id: "dd77dd0ea2de5208e4987001a59ba8e4", favourite: false)
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+
+    let resp = schema
+        .execute(
+            r#"
+              query Track {
+                track(id: "dd77dd0ea2de5208e4987001a59ba8e4") {
+                  rating
+                  favourite
+                }
+                likedSongs {
+                  id
+                }
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 0);
+    assert_eq!(
+        resp.data,
+        value!({
+          "track": {
+            "rating": null,
+            "favourite": false
+          },
+          "likedSongs": []
+        })
+    );
+
+    let resp = schema
+        .execute(
+            r#"
+              mutation Rate {
+                rateTrack(id: "dd77dd0ea2de5208e4987001a59ba8e4", rating: 6)
+              }
+            "#,
+        )
+        .await;
+    assert_eq!(resp.errors.len(), 1);
+}
+
+async fn count_queries(schema: &MusicPlayerSchema, queries: &AtomicUsize, query: &str) -> usize {
+    let before = queries.load(Ordering::SeqCst);
+    let resp = schema.execute(query).await;
+    assert_eq!(resp.errors.len(), 0);
+    queries.load(Ordering::SeqCst) - before
+}
+
+#[tokio::test]
+async fn batch_ratings_and_favourites() {
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", "/tmp");
+    env::set_var("MUSIC_PLAYER_MUSIC_DIRECTORY", "/tmp/audio");
+    env::set_var(
+        "MUSIC_PLAYER_DATABASE_URL",
+        "sqlite:///tmp/music-player.sqlite3",
+    );
+    let queries = Arc::new(AtomicUsize::new(0));
+    let mut db = Database::new().await;
+    let counter = Arc::clone(&queries);
+    db.connection.set_metric_callback(move |_| {
+        counter.fetch_add(1, Ordering::SeqCst);
+    });
+    let schema = with_loaders(
+        Schema::build(
+            Query::default(),
+            Mutation::default(),
+            Subscription::default(),
+        ),
+        &db,
+    )
+    .data(db)
+    .data(Arc::new(Mutex::new(CurrentSourceDevice::new())))
+    .finish();
+
+    let plain = count_queries(&schema, &queries, "{ tracks { id } }").await;
+    let rated = count_queries(&schema, &queries, "{ tracks { id rating favourite } }").await;
+    assert_eq!(rated, plain + 2);
+
+    let plain = count_queries(&schema, &queries, "{ albums { id } }").await;
+    let liked = count_queries(&schema, &queries, "{ albums { id favourite } }").await;
+    assert_eq!(liked, plain + 1);
+}
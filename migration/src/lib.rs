@@ -9,6 +9,23 @@ use std::{env, fs::File, path::Path};
 mod m20220101_000001_create_table;
 mod m20221115_220318_add_folder_table;
 mod m20221117_211308_add_created_at_column;
+mod m20261018_090000_create_scan_error_table;
+mod m20261018_100000_add_album_thumbnail_columns;
+mod m20261018_110000_add_album_compilation_column;
+mod m20261018_120000_add_rich_tag_columns;
+mod m20261018_130000_create_lyrics_table;
+mod m20261018_140000_add_track_content_hash_column;
+mod m20261018_150000_create_acoustic_fingerprint_table;
+mod m20261018_160000_add_track_analysis_columns;
+mod m20261018_170000_add_track_offset_columns;
+mod m20261018_180000_create_smart_playlist_tables;
+mod m20261018_190000_create_history_table;
+mod m20261018_200000_create_rating_tables;
+mod m20261018_210000_create_genre_tables;
+mod m20261018_220000_add_created_at_to_library;
+mod m20261018_230000_add_playlist_track_position_column;
+mod m20261019_000000_add_playlist_file_hash_column;
+mod m20261019_100000_add_album_webp_thumbnail_columns;
 
 pub struct Migrator;
 
@@ -19,6 +36,23 @@ impl MigratorTrait for Migrator {
             Box::new(m20220101_000001_create_table::Migration),
             Box::new(m20221115_220318_add_folder_table::Migration),
             Box::new(m20221117_211308_add_created_at_column::Migration),
+            Box::new(m20261018_090000_create_scan_error_table::Migration),
+            Box::new(m20261018_100000_add_album_thumbnail_columns::Migration),
+            Box::new(m20261018_110000_add_album_compilation_column::Migration),
+            Box::new(m20261018_120000_add_rich_tag_columns::Migration),
+            Box::new(m20261018_130000_create_lyrics_table::Migration),
+            Box::new(m20261018_140000_add_track_content_hash_column::Migration),
+            Box::new(m20261018_150000_create_acoustic_fingerprint_table::Migration),
+            Box::new(m20261018_160000_add_track_analysis_columns::Migration),
+            Box::new(m20261018_170000_add_track_offset_columns::Migration),
+            Box::new(m20261018_180000_create_smart_playlist_tables::Migration),
+            Box::new(m20261018_190000_create_history_table::Migration),
+            Box::new(m20261018_200000_create_rating_tables::Migration),
+            Box::new(m20261018_210000_create_genre_tables::Migration),
+            Box::new(m20261018_220000_add_created_at_to_library::Migration),
+            Box::new(m20261018_230000_add_playlist_track_position_column::Migration),
+            Box::new(m20261019_000000_add_playlist_file_hash_column::Migration),
+            Box::new(m20261019_100000_add_album_webp_thumbnail_columns::Migration),
         ]
     }
 }
@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(ScanError::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ScanError::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(ScanError::Path).string().not_null())
+                    .col(ColumnDef::new(ScanError::Kind).string().not_null())
+                    .col(ColumnDef::new(ScanError::Message).string().not_null())
+                    .col(ColumnDef::new(ScanError::CreatedAt).date_time().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ScanError::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum ScanError {
+    Table,
+    Id,
+    Path,
+    Kind,
+    Message,
+    CreatedAt,
+}
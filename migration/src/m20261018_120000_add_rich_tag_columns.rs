@@ -0,0 +1,123 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let track_columns = [
+            ColumnDef::new(Tracks::DiscNumber).integer().to_owned(),
+            ColumnDef::new(Tracks::DiscTotal).integer().to_owned(),
+            ColumnDef::new(Tracks::TrackTotal).integer().to_owned(),
+            ColumnDef::new(Tracks::Composer).string().to_owned(),
+            ColumnDef::new(Tracks::Conductor).string().to_owned(),
+            ColumnDef::new(Tracks::Isrc).string().to_owned(),
+            ColumnDef::new(Tracks::Comment).string().to_owned(),
+            ColumnDef::new(Tracks::Bpm).integer().to_owned(),
+            ColumnDef::new(Tracks::TitleSort).string().to_owned(),
+            ColumnDef::new(Tracks::ArtistSort).string().to_owned(),
+            ColumnDef::new(Tracks::MusicbrainzRecordingId)
+                .string()
+                .to_owned(),
+        ];
+        // SQLite only supports adding one column per statement
+        for mut column in track_columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Tracks::Table)
+                        .add_column_if_not_exists(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        let album_columns = [
+            ColumnDef::new(Album::Label).string().to_owned(),
+            ColumnDef::new(Album::TitleSort).string().to_owned(),
+            ColumnDef::new(Album::ArtistSort).string().to_owned(),
+            ColumnDef::new(Album::MusicbrainzReleaseId)
+                .string()
+                .to_owned(),
+        ];
+        for mut column in album_columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Album::Table)
+                        .add_column_if_not_exists(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Tracks::DiscNumber,
+            Tracks::DiscTotal,
+            Tracks::TrackTotal,
+            Tracks::Composer,
+            Tracks::Conductor,
+            Tracks::Isrc,
+            Tracks::Comment,
+            Tracks::Bpm,
+            Tracks::TitleSort,
+            Tracks::ArtistSort,
+            Tracks::MusicbrainzRecordingId,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Tracks::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        for column in [
+            Album::Label,
+            Album::TitleSort,
+            Album::ArtistSort,
+            Album::MusicbrainzReleaseId,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Album::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+#[iden = "track"]
+enum Tracks {
+    Table,
+    DiscNumber,
+    DiscTotal,
+    TrackTotal,
+    Composer,
+    Conductor,
+    Isrc,
+    Comment,
+    Bpm,
+    TitleSort,
+    ArtistSort,
+    MusicbrainzRecordingId,
+}
+
+#[derive(Iden)]
+enum Album {
+    Table,
+    Label,
+    TitleSort,
+    ArtistSort,
+    MusicbrainzReleaseId,
+}
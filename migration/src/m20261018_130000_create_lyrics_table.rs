@@ -0,0 +1,44 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Lyrics::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Lyrics::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(Lyrics::TrackId).string().not_null())
+                    .col(ColumnDef::new(Lyrics::Content).text().not_null())
+                    .col(
+                        ColumnDef::new(Lyrics::Synced)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(Lyrics::Source).string().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Lyrics::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Lyrics {
+    Table,
+    Id,
+    TrackId,
+    Content,
+    Synced,
+    Source,
+}
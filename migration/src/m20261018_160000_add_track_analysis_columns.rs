@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            ColumnDef::new(Track::Loudness).float().to_owned(),
+            ColumnDef::new(Track::TruePeak).float().to_owned(),
+            ColumnDef::new(Track::ReplaygainTrackGain)
+                .float()
+                .to_owned(),
+            ColumnDef::new(Track::ReplaygainTrackPeak)
+                .float()
+                .to_owned(),
+            ColumnDef::new(Track::ReplaygainAlbumGain)
+                .float()
+                .to_owned(),
+            ColumnDef::new(Track::ReplaygainAlbumPeak)
+                .float()
+                .to_owned(),
+            ColumnDef::new(Track::Tempo).float().to_owned(),
+            ColumnDef::new(Track::MusicalKey).string().to_owned(),
+        ];
+        // SQLite only supports adding one column per statement
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Track::Table)
+                        .add_column_if_not_exists(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Track::Loudness,
+            Track::TruePeak,
+            Track::ReplaygainTrackGain,
+            Track::ReplaygainTrackPeak,
+            Track::ReplaygainAlbumGain,
+            Track::ReplaygainAlbumPeak,
+            Track::Tempo,
+            Track::MusicalKey,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Track::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Track {
+    Table,
+    Loudness,
+    TruePeak,
+    ReplaygainTrackGain,
+    ReplaygainTrackPeak,
+    ReplaygainAlbumGain,
+    ReplaygainAlbumPeak,
+    Tempo,
+    MusicalKey,
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let columns = [
+            ColumnDef::new(Track::StartOffset).integer().to_owned(),
+            ColumnDef::new(Track::EndOffset).integer().to_owned(),
+        ];
+        // SQLite only supports adding one column per statement
+        for mut column in columns {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Track::Table)
+                        .add_column_if_not_exists(&mut column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [Track::StartOffset, Track::EndOffset] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Track::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Track {
+    Table,
+    StartOffset,
+    EndOffset,
+}
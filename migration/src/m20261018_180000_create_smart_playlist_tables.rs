@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(SmartPlaylist::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SmartPlaylist::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(SmartPlaylist::Sort).string())
+                    .col(
+                        ColumnDef::new(SmartPlaylist::Descending)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .col(ColumnDef::new(SmartPlaylist::TrackLimit).integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        // One row per node of the rule tree
+        manager
+            .create_table(
+                Table::create()
+                    .table(SmartPlaylistRule::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SmartPlaylistRule::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(SmartPlaylistRule::PlaylistId)
+                            .string()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SmartPlaylistRule::ParentId).string())
+                    .col(
+                        ColumnDef::new(SmartPlaylistRule::Position)
+                            .integer()
+                            .not_null(),
+                    )
+                    .col(ColumnDef::new(SmartPlaylistRule::Kind).string().not_null())
+                    .col(ColumnDef::new(SmartPlaylistRule::Field).string())
+                    .col(ColumnDef::new(SmartPlaylistRule::Operator).string())
+                    .col(ColumnDef::new(SmartPlaylistRule::Value).string())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SmartPlaylistRule::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(SmartPlaylist::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum SmartPlaylist {
+    Table,
+    Id,
+    Sort,
+    Descending,
+    TrackLimit,
+}
+
+#[derive(Iden)]
+enum SmartPlaylistRule {
+    Table,
+    Id,
+    PlaylistId,
+    ParentId,
+    Position,
+    Kind,
+    Field,
+    Operator,
+    Value,
+}
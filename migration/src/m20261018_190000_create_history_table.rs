@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(History::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(History::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(History::TrackId).string().not_null())
+                    .col(ColumnDef::new(History::StartedAt).timestamp().not_null())
+                    .col(ColumnDef::new(History::ListenedMs).integer().not_null())
+                    .col(ColumnDef::new(History::Completed).boolean().not_null())
+                    .col(ColumnDef::new(History::Device).string())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("history_track_id_index")
+                    .table(History::Table)
+                    .col(History::TrackId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("history_started_at_index")
+                    .table(History::Table)
+                    .col(History::StartedAt)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(History::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum History {
+    Table,
+    Id,
+    TrackId,
+    StartedAt,
+    ListenedMs,
+    Completed,
+    Device,
+}
@@ -0,0 +1,88 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(TrackRating::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TrackRating::TrackId)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(TrackRating::Rating).integer().not_null())
+                    .col(
+                        ColumnDef::new(TrackRating::UpdatedAt)
+                            .timestamp()
+                            .not_null(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(Favourite::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Favourite::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Favourite::Kind).string().not_null())
+                    .col(ColumnDef::new(Favourite::ItemId).string().not_null())
+                    .col(ColumnDef::new(Favourite::CreatedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("favourite_kind_index")
+                    .table(Favourite::Table)
+                    .col(Favourite::Kind)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Favourite::Table).if_exists().to_owned())
+            .await?;
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(TrackRating::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum TrackRating {
+    Table,
+    TrackId,
+    Rating,
+    UpdatedAt,
+}
+
+#[derive(Iden)]
+enum Favourite {
+    Table,
+    Id,
+    Kind,
+    ItemId,
+    CreatedAt,
+}
@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Genre::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(Genre::Id).string().not_null().primary_key())
+                    .col(ColumnDef::new(Genre::Name).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_table(
+                Table::create()
+                    .table(GenreTrack::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(GenreTrack::Id)
+                            .string()
+                            .not_null()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(GenreTrack::GenreId).string().not_null())
+                    .col(ColumnDef::new(GenreTrack::TrackId).string().not_null())
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("genre_track_genre_id_index")
+                    .table(GenreTrack::Table)
+                    .col(GenreTrack::GenreId)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("genre_track_track_id_index")
+                    .table(GenreTrack::Table)
+                    .col(GenreTrack::TrackId)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(
+                Table::drop()
+                    .table(GenreTrack::Table)
+                    .if_exists()
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .drop_table(Table::drop().table(Genre::Table).if_exists().to_owned())
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum Genre {
+    Table,
+    Id,
+    Name,
+}
+
+#[derive(Iden)]
+enum GenreTrack {
+    Table,
+    Id,
+    GenreId,
+    TrackId,
+}
@@ -0,0 +1,78 @@
+use sea_orm_migration::{
+    prelude::*,
+    sea_orm::{ConnectionTrait, Statement},
+};
+
+/// Numbers the tracks of the existing playlists in the order they were added,
+/// from 0.
+const NUMBER_TRACKS: &str = "UPDATE playlist_track SET position = (\
+    SELECT COUNT(*) FROM playlist_track AS previous \
+    WHERE previous.playlist_id = playlist_track.playlist_id \
+    AND (previous.created_at < playlist_track.created_at \
+    OR (previous.created_at = playlist_track.created_at AND previous.id < playlist_track.id)))";
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlaylistTrack::Table)
+                    .add_column_if_not_exists(
+                        ColumnDef::new(PlaylistTrack::Position)
+                            .integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .get_connection()
+            .execute(Statement::from_string(
+                manager.get_database_backend(),
+                NUMBER_TRACKS.to_owned(),
+            ))
+            .await?;
+        manager
+            .create_index(
+                Index::create()
+                    .if_not_exists()
+                    .name("playlist_track_position_index")
+                    .table(PlaylistTrack::Table)
+                    .col(PlaylistTrack::PlaylistId)
+                    .col(PlaylistTrack::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("playlist_track_position_index")
+                    .table(PlaylistTrack::Table)
+                    .to_owned(),
+            )
+            .await?;
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(PlaylistTrack::Table)
+                    .drop_column(PlaylistTrack::Position)
+                    .to_owned(),
+            )
+            .await
+    }
+}
+
+#[derive(Iden)]
+enum PlaylistTrack {
+    Table,
+    PlaylistId,
+    Position,
+}
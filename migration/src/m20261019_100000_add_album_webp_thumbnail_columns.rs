@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    // Only set when the artwork could be decoded, the albums covered by the
+    // original file have no WebP thumbnail.
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Album::CoverSmallWebp,
+            Album::CoverMediumWebp,
+            Album::CoverLargeWebp,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Album::Table)
+                        .add_column_if_not_exists(ColumnDef::new(column).string())
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Album::CoverSmallWebp,
+            Album::CoverMediumWebp,
+            Album::CoverLargeWebp,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Album::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Iden)]
+enum Album {
+    Table,
+    CoverSmallWebp,
+    CoverMediumWebp,
+    CoverLargeWebp,
+}
@@ -31,5 +31,5 @@ async fn test_cli() {
         .await
         .unwrap();
 
-    assert_eq!(result.len(), 9);
+    assert_eq!(result.len(), 19);
 }
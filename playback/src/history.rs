@@ -0,0 +1,88 @@
+use chrono::{DateTime, Utc};
+use music_player_entity::track::Model as Track;
+use music_player_types::types::HistoryEntry;
+
+use crate::player::PlayerEvent;
+
+/// A play counts as completed once this share of the track was listened to.
+const COMPLETED_RATIO: f32 = 0.9;
+
+/// Position updates further apart than this come from a seek.
+const MAX_POSITION_STEP_MS: u32 = 1000;
+
+struct Listening {
+    track: Track,
+    position: usize,
+    started_at: DateTime<Utc>,
+    listened_ms: u32,
+    position_ms: u32,
+}
+
+/// Turns the events of the player into history entries. A play starts with
+/// the `CurrentTrack` event of a new track and ends when it reaches its end,
+/// or is skipped when another track replaces it before.
+pub struct ListeningTracker {
+    device: Option<String>,
+    current: Option<Listening>,
+}
+
+impl ListeningTracker {
+    pub fn new(device: Option<String>) -> Self {
+        Self {
+            device,
+            current: None,
+        }
+    }
+
+    pub fn handle(&mut self, event: &PlayerEvent) -> Option<HistoryEntry> {
+        match event {
+            PlayerEvent::CurrentTrack {
+                track, position, ..
+            } => {
+                // Pausing and resuming send the current track again
+                if let (Some(current), Some(track)) = (&self.current, track) {
+                    if current.track.id == track.id && current.position == *position {
+                        return None;
+                    }
+                }
+                let finished = self.finish(false);
+                self.current = track.clone().map(|track| Listening {
+                    track,
+                    position: *position,
+                    started_at: Utc::now(),
+                    listened_ms: 0,
+                    position_ms: 0,
+                });
+                finished
+            }
+            PlayerEvent::TrackTimePosition { position_ms } => {
+                if let Some(current) = self.current.as_mut() {
+                    let step = position_ms.saturating_sub(current.position_ms);
+                    if step <= MAX_POSITION_STEP_MS {
+                        current.listened_ms += step;
+                    }
+                    current.position_ms = *position_ms;
+                }
+                None
+            }
+            PlayerEvent::EndOfTrack { .. } => self.finish(true),
+            PlayerEvent::Stopped => self.finish(false),
+            _ => None,
+        }
+    }
+
+    fn finish(&mut self, ended: bool) -> Option<HistoryEntry> {
+        let listening = self.current.take()?;
+        let duration_ms = listening.track.duration.unwrap_or_default() * 1000.0;
+        let completed = ended
+            || (duration_ms > 0.0 && listening.listened_ms as f32 >= duration_ms * COMPLETED_RATIO);
+        Some(HistoryEntry {
+            track: listening.track.into(),
+            started_at: listening.started_at,
+            listened_ms: listening.listened_ms,
+            completed,
+            device: self.device.clone(),
+            ..Default::default()
+        })
+    }
+}
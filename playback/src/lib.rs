@@ -7,6 +7,7 @@ pub mod convert;
 pub mod decoder;
 pub mod dither;
 pub mod formatter;
+pub mod history;
 pub mod metadata;
 pub mod player;
 
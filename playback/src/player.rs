@@ -94,6 +94,8 @@ impl Player {
                 tracklist,
                 event_broadcaster: Box::new(event_broadcaster),
                 position_ms: 0,
+                start_offset: 0,
+                end_offset: None,
             };
             let runtime = tokio::runtime::Runtime::new().expect("Failed to create Tokio runtime");
             runtime.block_on(internal);
@@ -149,6 +151,8 @@ impl PlayerEngine for Player {
     fn load(&mut self, track_id: &str, _start_playing: bool, _position_ms: u32) {
         self.command(PlayerCommand::Load {
             track_id: track_id.to_string(),
+            start_offset: 0,
+            end_offset: None,
         });
     }
 
@@ -257,6 +261,9 @@ struct PlayerInternal {
     event_senders: Vec<mpsc::UnboundedSender<PlayerEvent>>,
     tracklist: Arc<std::sync::Mutex<Tracklist>>,
     position_ms: u32,
+    /// Slice of the file played for the tracks of a cue sheet
+    start_offset: u32,
+    end_offset: Option<u32>,
     event_broadcaster: Box<dyn Fn(PlayerEvent) + Send + 'static>,
 }
 
@@ -281,6 +288,11 @@ impl Future for PlayerInternal {
             if let PlayerState::Playing { ref mut decoder } = self.state {
                 match decoder.next_packet() {
                     Ok(result) => {
+                        // The track of a cue sheet ends where the next one starts
+                        let end_offset = self.end_offset;
+                        let result = result.filter(|(packet_position, ..)| {
+                            end_offset.is_none_or(|end| packet_position.position_ms < end)
+                        });
                         if let Some((ref packet_position, packet, channels, sample_rate)) = result {
                             match packet.samples() {
                                 Ok(_) => {
@@ -300,7 +312,9 @@ impl Future for PlayerInternal {
                                     error!("Failed to decode packet: {}", e);
                                 }
                             }
-                            self.position_ms = packet_position.position_ms;
+                            self.position_ms = packet_position
+                                .position_ms
+                                .saturating_sub(self.start_offset);
                             let playback_state = self.tracklist.lock().unwrap().playback_state();
                             self.tracklist
                                 .lock()
@@ -310,7 +324,7 @@ impl Future for PlayerInternal {
                                     ..playback_state
                                 });
                             (self.event_broadcaster)(PlayerEvent::TrackTimePosition {
-                                position_ms: packet_position.position_ms,
+                                position_ms: self.position_ms,
                             });
                         } else {
                             // end of track
@@ -324,9 +338,11 @@ impl Future for PlayerInternal {
                                     is_playing: false,
                                     ..playback_state
                                 });
-                            self.send_event(PlayerEvent::EndOfTrack {
+                            let event = PlayerEvent::EndOfTrack {
                                 is_last_track: tracklist.lock().unwrap().is_empty(),
-                            });
+                            };
+                            (self.event_broadcaster)(event.clone());
+                            self.send_event(event);
                             self.handle_next();
                         }
                     }
@@ -392,7 +408,11 @@ impl PlayerInternal {
 
     fn handle_command(&mut self, cmd: PlayerCommand) -> PlayerResult {
         match cmd {
-            PlayerCommand::Load { track_id } => self.handle_command_load(&track_id),
+            PlayerCommand::Load {
+                track_id,
+                start_offset,
+                end_offset,
+            } => self.handle_command_load(&track_id, start_offset, end_offset),
             PlayerCommand::LoadTracklist { tracks } => self.handle_command_load_tracklist(tracks),
             PlayerCommand::Preload => self.handle_command_preload(),
             PlayerCommand::Play => self.handle_play(),
@@ -453,11 +473,20 @@ impl PlayerInternal {
             .retain(|sender| sender.send(event.clone()).is_ok());
     }
 
-    fn handle_command_load(&mut self, track_id: &str) {
+    /// Loads a file and plays it from `start_offset` to `end_offset`, the
+    /// slice of the file of a track of a cue sheet.
+    fn handle_command_load(&mut self, track_id: &str, start_offset: u32, end_offset: Option<u32>) {
+        self.start_offset = start_offset;
+        self.end_offset = end_offset;
         formatter::print_format(track_id);
         let loaded_track = self.load_track(track_id);
         match loaded_track {
-            Some(loaded_track) => {
+            Some(mut loaded_track) => {
+                if start_offset > 0 {
+                    if let Err(e) = loaded_track.decoder.seek(start_offset) {
+                        error!("Failed to seek to the start of the track: {}", e);
+                    }
+                }
                 self.start_playback(track_id, loaded_track);
             }
             None => {
@@ -510,6 +539,7 @@ impl PlayerInternal {
         self.ensure_sink_stopped(false);
         self.state = PlayerState::Stopped;
         self.tracklist.lock().unwrap().stop();
+        (self.event_broadcaster)(PlayerEvent::Stopped);
     }
 
     fn handle_pause(&mut self) {
@@ -540,24 +570,35 @@ impl PlayerInternal {
         todo!()
     }
 
+    /// Loads the current track of the tracklist, seeking to its start in the
+    /// file for the tracks of a cue sheet.
+    fn load_current_track(&mut self) {
+        let (current_track, _) = self.tracklist.lock().unwrap().current_track();
+        if let Some(track) = current_track {
+            self.handle_command_load(
+                &track.uri,
+                track.start_offset.unwrap_or_default(),
+                track.end_offset,
+            );
+        }
+    }
+
     fn handle_next(&mut self) {
         if self.tracklist.lock().unwrap().next_track().is_some() {
-            let (current_track, _) = self.tracklist.lock().unwrap().current_track();
-            self.handle_command_load(&current_track.unwrap().uri);
+            self.load_current_track();
         }
     }
 
     fn handle_previous(&mut self) {
         if self.tracklist.lock().unwrap().previous_track().is_some() {
-            let (current_track, _) = self.tracklist.lock().unwrap().current_track();
-            self.handle_command_load(&current_track.unwrap().uri);
+            self.load_current_track();
         }
     }
 
     fn handle_play_track_at(&mut self, index: usize) {
         let (current_track, _) = self.tracklist.lock().unwrap().play_track_at(index);
         if current_track.is_some() {
-            self.handle_command_load(&current_track.unwrap().uri);
+            self.load_current_track();
         }
     }
 
@@ -756,8 +797,15 @@ impl PlayerTrackLoader {
 
 #[derive(Debug)]
 pub enum PlayerCommand {
-    Load { track_id: String },
-    LoadTracklist { tracks: Vec<Track> },
+    Load {
+        track_id: String,
+        /// Slice of the file played, in milliseconds
+        start_offset: u32,
+        end_offset: Option<u32>,
+    },
+    LoadTracklist {
+        tracks: Vec<Track>,
+    },
     Preload,
     Play,
     Pause,
@@ -689,6 +689,123 @@ fn current_track() {
     assert_eq!(index, 1);
 }
 
+type Positions = Arc<Mutex<Vec<u32>>>;
+
+/// Records the positions broadcast by the player until the end of a track.
+fn record_positions() -> (
+    Positions,
+    Arc<Mutex<bool>>,
+    impl Fn(player::PlayerEvent) + Send + 'static,
+) {
+    let positions = Arc::new(Mutex::new(vec![]));
+    let ended = Arc::new(Mutex::new(false));
+    let broadcaster = {
+        let positions = Arc::clone(&positions);
+        let ended = Arc::clone(&ended);
+        move |event| match event {
+            player::PlayerEvent::TrackTimePosition { position_ms } if !*ended.lock().unwrap() => {
+                positions.lock().unwrap().push(position_ms)
+            }
+            player::PlayerEvent::EndOfTrack { .. } => *ended.lock().unwrap() = true,
+            _ => {}
+        }
+    };
+    (positions, ended, broadcaster)
+}
+
+fn wait_for_end_of_track(ended: &Mutex<bool>) {
+    for _ in 0..150 {
+        if *ended.lock().unwrap() {
+            return;
+        }
+        sleep(Duration::from_millis(100));
+    }
+    panic!("The track didn't end");
+}
+
+#[test]
+fn load_slice_of_file() {
+    let (backend, audio_format, cmd_tx, cmd_rx, tracklist) = setup_new_params();
+    let (positions, ended, broadcaster) = record_positions();
+
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        broadcaster,
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    cmd_tx
+        .lock()
+        .unwrap()
+        .send(PlayerCommand::Load {
+            track_id: "/tmp/audio/03 - J. Cole - Wet Dreamz(Explicit).m4a".to_owned(),
+            start_offset: 100_000,
+            end_offset: Some(101_000),
+        })
+        .unwrap();
+
+    // Only a second of the file is played, from its 100th second
+    wait_for_end_of_track(&ended);
+    let positions = positions.lock().unwrap();
+    assert!(!positions.is_empty());
+    // A few dozen packets, not the 100 seconds before the slice
+    assert!(positions.len() < 100, "{}", positions.len());
+    assert!(positions[0] < 500, "{:?}", positions);
+    assert!(positions.iter().all(|position| *position < 1000));
+}
+
+#[test]
+fn play_cue_sheet_tracks() {
+    let (backend, audio_format, cmd_tx, cmd_rx, tracklist) = setup_new_params();
+    let (positions, ended, broadcaster) = record_positions();
+
+    let (_, _) = Player::new(
+        move || backend(None, audio_format),
+        broadcaster,
+        Arc::clone(&cmd_tx),
+        Arc::clone(&cmd_rx),
+        Arc::clone(&tracklist),
+    );
+
+    // Two tracks of a sheet sharing the same file
+    let track = |id: &str, start_offset: u32, end_offset: Option<u32>| Track {
+        id: id.to_owned(),
+        uri: "/tmp/audio/03 - J. Cole - Wet Dreamz(Explicit).m4a".to_owned(),
+        start_offset: Some(start_offset),
+        end_offset,
+        ..Default::default()
+    };
+    let tracks = vec![
+        track("first", 100_000, Some(101_000)),
+        track("second", 101_000, None),
+    ];
+    cmd_tx
+        .lock()
+        .unwrap()
+        .send(PlayerCommand::LoadTracklist { tracks })
+        .unwrap();
+
+    wait_for_end_of_track(&ended);
+    {
+        let positions = positions.lock().unwrap();
+        assert!(!positions.is_empty());
+        assert!(positions.len() < 100, "{}", positions.len());
+        assert!(positions[0] < 500, "{:?}", positions);
+        assert!(positions.iter().all(|position| *position < 1000));
+    }
+
+    // The next track starts where the first one ended
+    sleep(Duration::from_millis(500));
+    let (current, index) = tracklist.lock().unwrap().current_track();
+    assert_eq!(current.unwrap().id, "second");
+    assert_eq!(index, 2);
+    assert!(tracklist.lock().unwrap().playback_state().is_playing);
+
+    cmd_tx.lock().unwrap().send(PlayerCommand::Stop).unwrap();
+}
+
 #[test]
 fn playback_state() {
     let (backend, audio_format, cmd_tx, cmd_rx, tracklist) = setup_new_params();
@@ -705,6 +822,52 @@ fn playback_state() {
     assert_eq!(state.is_playing, false);
 }
 
+#[test]
+fn track_listening_history() {
+    let mut tracker = history::ListeningTracker::new(Some("Music Player".to_string()));
+    let track = |id: &str| Track {
+        id: id.to_string(),
+        duration: Some(10.0),
+        ..Default::default()
+    };
+    let current = |track: Track, position: usize| player::PlayerEvent::CurrentTrack {
+        track: Some(track),
+        position,
+        position_ms: 0,
+        is_playing: true,
+    };
+    let play = |tracker: &mut history::ListeningTracker, to_ms: u32| {
+        for position_ms in (0..=to_ms).step_by(500) {
+            tracker.handle(&player::PlayerEvent::TrackTimePosition { position_ms });
+        }
+    };
+
+    assert!(tracker.handle(&current(track("a"), 0)).is_none());
+    play(&mut tracker, 3000);
+    // Pausing and resuming doesn't end the play
+    assert!(tracker.handle(&current(track("a"), 0)).is_none());
+
+    let skipped = tracker.handle(&current(track("b"), 1)).unwrap();
+    assert_eq!(skipped.track.id, "a");
+    assert_eq!(skipped.listened_ms, 3000);
+    assert!(skipped.skipped());
+    assert_eq!(skipped.device, Some("Music Player".to_string()));
+
+    play(&mut tracker, 1000);
+    // A seek isn't counted as listened
+    play(&mut tracker, 0);
+    tracker.handle(&player::PlayerEvent::TrackTimePosition { position_ms: 9500 });
+    let completed = tracker
+        .handle(&player::PlayerEvent::EndOfTrack {
+            is_last_track: true,
+        })
+        .unwrap();
+    assert_eq!(completed.track.id, "b");
+    assert_eq!(completed.listened_ms, 1000);
+    assert!(completed.completed);
+    assert!(tracker.handle(&player::PlayerEvent::Stopped).is_none());
+}
+
 fn setup_new_params() -> (
     fn(Option<String>, AudioFormat) -> Box<dyn Sink>,
     AudioFormat,
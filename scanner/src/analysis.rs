@@ -0,0 +1,599 @@
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    f64::consts::PI,
+    path::Path,
+};
+
+use anyhow::Error;
+use lofty::{ItemKey, Probe, TagExt};
+use music_player_audio::archive::ArchiveEntry;
+use music_player_entity::track as track_entity;
+use music_player_storage::Database;
+use sea_orm::{ActiveModelTrait, ActiveValue, ColumnTrait, Condition, EntityTrait, QueryFilter};
+
+use crate::decoder::AudioStream;
+
+/// ReplayGain 2.0 normalises every track to -18 LUFS.
+pub const REFERENCE_LOUDNESS: f32 = -18.0;
+const ABSOLUTE_GATE: f64 = -70.0;
+const RELATIVE_GATE: f64 = -10.0;
+/// Taps of the interpolation filter used to find the peaks between samples.
+const TRUE_PEAK_TAPS: usize = 16;
+const TRUE_PEAK_OVERSAMPLING: usize = 4;
+/// Rate of the onset envelope the tempo is detected from.
+const ENVELOPE_RATE: usize = 200;
+const MIN_TEMPO: f64 = 60.0;
+const MAX_TEMPO: f64 = 200.0;
+const KEY_FRAME_LENGTH: usize = 4096;
+const KEY_SAMPLE_RATE: u32 = 11025;
+const NOTE_NAMES: [&str; 12] = [
+    "C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B",
+];
+/// Krumhansl-Schmuckler key profiles.
+const MAJOR_PROFILE: [f64; 12] = [
+    6.35, 2.23, 3.48, 2.33, 4.38, 4.09, 2.52, 5.19, 2.39, 3.66, 2.29, 2.88,
+];
+const MINOR_PROFILE: [f64; 12] = [
+    6.33, 2.68, 3.52, 5.38, 2.60, 3.53, 2.54, 4.75, 3.98, 2.69, 3.34, 3.17,
+];
+
+/// Measures taken from the decoded audio of a track.
+#[derive(Debug, Clone, Default)]
+pub struct Analysis {
+    /// EBU R128 integrated loudness in LUFS, `None` for a silent track
+    pub loudness: Option<f32>,
+    /// Highest inter-sample peak in dBTP
+    pub true_peak: f32,
+    pub tempo: Option<f32>,
+    pub key: Option<String>,
+    blocks: Vec<f64>,
+    peak: f64,
+}
+
+impl Analysis {
+    pub fn replaygain_gain(&self) -> Option<f32> {
+        self.loudness.map(|loudness| REFERENCE_LOUDNESS - loudness)
+    }
+
+    /// Linear peak amplitude, as stored in ReplayGain tags.
+    pub fn replaygain_peak(&self) -> f32 {
+        self.peak as f32
+    }
+}
+
+/// Decodes a whole track to measure its loudness, true peak, tempo and key.
+pub fn analyze(path: &Path) -> Result<Analysis, Error> {
+    analyze_stream(AudioStream::open(path)?)
+}
+
+/// Analyzes a track of the library, only its slice of the file for the
+/// tracks of a cue sheet.
+pub fn analyze_track(track: &track_entity::Model) -> Result<Analysis, Error> {
+    let stream = AudioStream::open_uri(&track.uri)?.slice(track.start_offset, track.end_offset)?;
+    analyze_stream(stream)
+}
+
+fn analyze_stream(mut stream: AudioStream) -> Result<Analysis, Error> {
+    let mut loudness = LoudnessMeter::new(stream.sample_rate);
+    let mut tempo = TempoDetector::new(stream.sample_rate);
+    let mut key = KeyDetector::new(stream.sample_rate);
+    stream.for_each_frame(None, |frame| {
+        loudness.push(frame);
+        let mono = frame.iter().sum::<f32>() / frame.len() as f32;
+        tempo.push(mono);
+        key.push(mono);
+    });
+
+    let peak = loudness.peak;
+    Ok(Analysis {
+        loudness: integrated_loudness(&loudness.blocks),
+        true_peak: (20.0 * peak.max(1e-10).log10()) as f32,
+        tempo: tempo.finish(),
+        key: key.finish(),
+        blocks: loudness.blocks,
+        peak,
+    })
+}
+
+/// Loudness of an album, gated over the blocks of all its tracks as if they
+/// were played in a row.
+pub fn album_loudness(analyses: &[&Analysis]) -> Option<f32> {
+    let blocks: Vec<f64> = analyses
+        .iter()
+        .flat_map(|analysis| analysis.blocks.iter().copied())
+        .collect();
+    integrated_loudness(&blocks)
+}
+
+/// Gates the mean square of the 400ms blocks as described by ITU-R BS.1770.
+pub fn integrated_loudness(blocks: &[f64]) -> Option<f32> {
+    let loudness = |power: f64| -0.691 + 10.0 * power.log10();
+    let mean = |blocks: &[f64]| blocks.iter().sum::<f64>() / blocks.len() as f64;
+
+    let audible: Vec<f64> = blocks
+        .iter()
+        .copied()
+        .filter(|power| loudness(*power) > ABSOLUTE_GATE)
+        .collect();
+    if audible.is_empty() {
+        return None;
+    }
+    let threshold = loudness(mean(&audible)) + RELATIVE_GATE;
+    let gated: Vec<f64> = audible
+        .into_iter()
+        .filter(|power| loudness(*power) > threshold)
+        .collect();
+    if gated.is_empty() {
+        return None;
+    }
+    Some(loudness(mean(&gated)) as f32)
+}
+
+/// Analyzes the tracks which were never analyzed, along with the other
+/// tracks of their albums so that the album gain covers the whole album.
+/// Returns the number of analyzed tracks.
+pub async fn analyze_library(db: &Database, write_tags: bool) -> Result<usize, Error> {
+    let pending = track_entity::Entity::find()
+        .filter(track_entity::Column::TruePeak.is_null())
+        .all(db.get_connection())
+        .await?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+    let album_ids: HashSet<String> = pending
+        .iter()
+        .filter_map(|track| track.album_id.clone())
+        .collect();
+    let tracks = track_entity::Entity::find()
+        .filter(
+            Condition::any()
+                .add(track_entity::Column::TruePeak.is_null())
+                .add(track_entity::Column::AlbumId.is_in(album_ids)),
+        )
+        .all(db.get_connection())
+        .await?;
+
+    let mut albums: HashMap<String, Vec<track_entity::Model>> = HashMap::new();
+    for track in tracks {
+        // Tracks without album are their own album
+        let key = track.album_id.clone().unwrap_or_else(|| track.id.clone());
+        albums.entry(key).or_default().push(track);
+    }
+
+    let mut count = 0;
+    for tracks in albums.into_values() {
+        let mut results = Vec::new();
+        for track in tracks {
+            let result = tokio::task::spawn_blocking({
+                let track = track.clone();
+                move || analyze_track(&track)
+            })
+            .await
+            .map_err(Error::from)
+            .and_then(|result| result);
+            match result {
+                Ok(analysis) => results.push((track, analysis)),
+                Err(e) => println!("Error analyzing {}: {}", track.uri, e),
+            }
+        }
+
+        let analyses: Vec<&Analysis> = results.iter().map(|(_, analysis)| analysis).collect();
+        let album_gain = album_loudness(&analyses).map(|loudness| REFERENCE_LOUDNESS - loudness);
+        let album_peak = analyses
+            .iter()
+            .map(|analysis| analysis.replaygain_peak())
+            .fold(0.0f32, f32::max);
+
+        for (track, analysis) in &results {
+            let item = track_entity::ActiveModel {
+                id: ActiveValue::set(track.id.clone()),
+                loudness: ActiveValue::Set(analysis.loudness),
+                true_peak: ActiveValue::Set(Some(analysis.true_peak)),
+                replaygain_track_gain: ActiveValue::Set(analysis.replaygain_gain()),
+                replaygain_track_peak: ActiveValue::Set(Some(analysis.replaygain_peak())),
+                replaygain_album_gain: ActiveValue::Set(album_gain),
+                replaygain_album_peak: ActiveValue::Set(Some(album_peak)),
+                tempo: ActiveValue::Set(analysis.tempo),
+                musical_key: ActiveValue::Set(analysis.key.clone()),
+                ..Default::default()
+            };
+            if let Err(e) = item.update(db.get_connection()).await {
+                println!("Error saving analysis of {}: {}", track.uri, e);
+                continue;
+            }
+            count += 1;
+
+            // The file of a cue sheet holds several tracks, a single tag
+            // can't carry their gains. Archives are left untouched.
+            if write_tags
+                && track.start_offset.is_none()
+                && !ArchiveEntry::is_archive_entry(&track.uri)
+            {
+                let gains = ReplayGain {
+                    track_gain: analysis.replaygain_gain(),
+                    track_peak: analysis.replaygain_peak(),
+                    album_gain,
+                    album_peak,
+                };
+                if let Err(e) = write_replaygain_tags(Path::new(&track.uri), &gains) {
+                    println!("Error writing ReplayGain tags to {}: {}", track.uri, e);
+                }
+            }
+        }
+    }
+    Ok(count)
+}
+
+pub struct ReplayGain {
+    pub track_gain: Option<f32>,
+    pub track_peak: f32,
+    pub album_gain: Option<f32>,
+    pub album_peak: f32,
+}
+
+pub fn write_replaygain_tags(path: &Path, gains: &ReplayGain) -> Result<(), Error> {
+    let mut tagged_file = Probe::open(path)?.read()?;
+    let tag = tagged_file
+        .primary_tag_mut()
+        .ok_or_else(|| Error::msg("The file has no tag"))?;
+
+    let mut items = vec![
+        (
+            ItemKey::ReplayGainTrackPeak,
+            format!("{:.6}", gains.track_peak),
+        ),
+        (
+            ItemKey::ReplayGainAlbumPeak,
+            format!("{:.6}", gains.album_peak),
+        ),
+    ];
+    if let Some(gain) = gains.track_gain {
+        items.push((ItemKey::ReplayGainTrackGain, format!("{:.2} dB", gain)));
+    }
+    if let Some(gain) = gains.album_gain {
+        items.push((ItemKey::ReplayGainAlbumGain, format!("{:.2} dB", gain)));
+    }
+    for (key, value) in items {
+        if !tag.insert_text(key, value) {
+            return Err(Error::msg(
+                "ReplayGain tags aren't supported by this format",
+            ));
+        }
+    }
+    tag.save_to_path(path)?;
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+struct Biquad {
+    b: [f64; 3],
+    a: [f64; 2],
+}
+
+impl Biquad {
+    fn process(&self, state: &mut [f64; 4], x: f64) -> f64 {
+        let y = self.b[0] * x + self.b[1] * state[0] + self.b[2] * state[1]
+            - self.a[0] * state[2]
+            - self.a[1] * state[3];
+        *state = [x, state[0], y, state[2]];
+        y
+    }
+}
+
+/// Measures the K-weighted energy of 400ms blocks overlapping by 75%, and
+/// the true peak of the signal oversampled four times.
+struct LoudnessMeter {
+    shelf: Biquad,
+    highpass: Biquad,
+    states: Vec<[[f64; 4]; 2]>,
+    history: Vec<[f64; TRUE_PEAK_TAPS]>,
+    interpolation: Vec<[f64; TRUE_PEAK_TAPS]>,
+    step_length: usize,
+    step_energy: f64,
+    step_samples: usize,
+    steps: VecDeque<f64>,
+    blocks: Vec<f64>,
+    peak: f64,
+}
+
+impl LoudnessMeter {
+    fn new(sample_rate: u32) -> Self {
+        let rate = sample_rate as f64;
+
+        // Pre-filter and RLB filter coefficients, recomputed for the sample rate
+        let (f0, gain, q) = (1681.974450955533, 3.999843853973347, 0.7071752369554196);
+        let k = (PI * f0 / rate).tan();
+        let vh = 10f64.powf(gain / 20.0);
+        let vb = vh.powf(0.4996667741545416);
+        let a0 = 1.0 + k / q + k * k;
+        let shelf = Biquad {
+            b: [
+                (vh + vb * k / q + k * k) / a0,
+                2.0 * (k * k - vh) / a0,
+                (vh - vb * k / q + k * k) / a0,
+            ],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        };
+        let (f0, q) = (38.13547087602444, 0.5003270373238773);
+        let k = (PI * f0 / rate).tan();
+        let a0 = 1.0 + k / q + k * k;
+        let highpass = Biquad {
+            b: [1.0, -2.0, 1.0],
+            a: [2.0 * (k * k - 1.0) / a0, (1.0 - k / q + k * k) / a0],
+        };
+
+        // Windowed sinc interpolating the points between the two samples in
+        // the middle of the history
+        let half = (TRUE_PEAK_TAPS / 2) as f64;
+        let interpolation = (1..TRUE_PEAK_OVERSAMPLING)
+            .map(|phase| {
+                let position = half - 1.0 + phase as f64 / TRUE_PEAK_OVERSAMPLING as f64;
+                let mut taps = [0.0; TRUE_PEAK_TAPS];
+                for (index, tap) in taps.iter_mut().enumerate() {
+                    let distance = position - index as f64;
+                    let sinc = (PI * distance).sin() / (PI * distance);
+                    let window = 0.5 * (1.0 + (PI * distance / half).cos());
+                    *tap = sinc * window;
+                }
+                taps
+            })
+            .collect();
+
+        Self {
+            shelf,
+            highpass,
+            states: Vec::new(),
+            history: Vec::new(),
+            interpolation,
+            step_length: (sample_rate as usize / 10).max(1),
+            step_energy: 0.0,
+            step_samples: 0,
+            steps: VecDeque::new(),
+            blocks: Vec::new(),
+            peak: 0.0,
+        }
+    }
+
+    fn push(&mut self, frame: &[f32]) {
+        if self.states.len() != frame.len() {
+            self.states = vec![[[0.0; 4]; 2]; frame.len()];
+            self.history = vec![[0.0; TRUE_PEAK_TAPS]; frame.len()];
+        }
+
+        let mut energy = 0.0;
+        for (channel, sample) in frame.iter().enumerate() {
+            let x = *sample as f64;
+            let state = &mut self.states[channel];
+            let shelved = self.shelf.process(&mut state[0], x);
+            let y = self.highpass.process(&mut state[1], shelved);
+            energy += channel_weight(channel, frame.len()) * y * y;
+
+            let history = &mut self.history[channel];
+            history.copy_within(1.., 0);
+            history[TRUE_PEAK_TAPS - 1] = x;
+            self.peak = self.peak.max(x.abs());
+            for taps in &self.interpolation {
+                let y: f64 = taps.iter().zip(history.iter()).map(|(t, x)| t * x).sum();
+                self.peak = self.peak.max(y.abs());
+            }
+        }
+
+        self.step_energy += energy;
+        self.step_samples += 1;
+        if self.step_samples == self.step_length {
+            self.steps
+                .push_back(self.step_energy / self.step_length as f64);
+            if self.steps.len() > 4 {
+                self.steps.pop_front();
+            }
+            if self.steps.len() == 4 {
+                self.blocks.push(self.steps.iter().sum::<f64>() / 4.0);
+            }
+            self.step_energy = 0.0;
+            self.step_samples = 0;
+        }
+    }
+}
+
+/// The LFE channel of a 5.1 layout is left out and the surround channels
+/// weigh more.
+fn channel_weight(channel: usize, channels: usize) -> f64 {
+    match (channels > 3, channel) {
+        (true, 3) => 0.0,
+        (true, 4..) => 1.41,
+        _ => 1.0,
+    }
+}
+
+/// Autocorrelates the onset envelope, the rises of the signal energy, to
+/// find the beat period.
+struct TempoDetector {
+    hop: usize,
+    energy: f64,
+    samples: usize,
+    previous: Option<f64>,
+    envelope: Vec<f64>,
+}
+
+impl TempoDetector {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            hop: (sample_rate as usize / ENVELOPE_RATE).max(1),
+            energy: 0.0,
+            samples: 0,
+            previous: None,
+            envelope: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, sample: f32) {
+        self.energy += (sample * sample) as f64;
+        self.samples += 1;
+        if self.samples == self.hop {
+            let level = (self.energy / self.hop as f64 + 1e-10).ln();
+            if let Some(previous) = self.previous {
+                self.envelope.push((level - previous).max(0.0));
+            }
+            self.previous = Some(level);
+            self.energy = 0.0;
+            self.samples = 0;
+        }
+    }
+
+    fn finish(self) -> Option<f32> {
+        let min_lag = (60.0 * ENVELOPE_RATE as f64 / MAX_TEMPO) as usize;
+        let max_lag = (60.0 * ENVELOPE_RATE as f64 / MIN_TEMPO) as usize;
+        if self.envelope.len() < ENVELOPE_RATE * 10 {
+            return None;
+        }
+        let mean = self.envelope.iter().sum::<f64>() / self.envelope.len() as f64;
+        let envelope: Vec<f64> = self.envelope.iter().map(|value| value - mean).collect();
+        let correlation = |lag: usize| {
+            envelope
+                .iter()
+                .zip(&envelope[lag..])
+                .map(|(a, b)| a * b)
+                .sum::<f64>()
+                / (envelope.len() - lag) as f64
+        };
+        let correlations: Vec<f64> = (min_lag - 1..=max_lag + 1).map(correlation).collect();
+
+        // Favours the tempos around 120 BPM over their halves and doubles
+        let preferred_lag = 60.0 * ENVELOPE_RATE as f64 / 120.0;
+        let (index, _) = (1..correlations.len() - 1)
+            .map(|index| {
+                let lag = (min_lag - 1 + index) as f64;
+                let weight = (-0.5 * ((lag / preferred_lag).log2() / 0.9).powi(2)).exp();
+                (index, correlations[index] * weight)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))?;
+        if correlations[index] <= 0.0 {
+            return None;
+        }
+
+        let (a, b, c) = (
+            correlations[index - 1],
+            correlations[index],
+            correlations[index + 1],
+        );
+        let denominator = a - 2.0 * b + c;
+        let offset = if denominator.abs() > f64::EPSILON {
+            (0.5 * (a - c) / denominator).clamp(-0.5, 0.5)
+        } else {
+            0.0
+        };
+        let lag = (min_lag - 1 + index) as f64 + offset;
+        let tempo = 60.0 * ENVELOPE_RATE as f64 / lag;
+        Some(((tempo * 10.0).round() / 10.0) as f32)
+    }
+}
+
+/// Accumulates a chromagram with the Goertzel algorithm, then matches it
+/// against the major and minor key profiles.
+struct KeyDetector {
+    decimation: usize,
+    sum: f32,
+    samples: usize,
+    buffer: Vec<f32>,
+    window: Vec<f32>,
+    notes: Vec<(usize, f32)>,
+    skip: bool,
+    chroma: [f64; 12],
+}
+
+impl KeyDetector {
+    fn new(sample_rate: u32) -> Self {
+        let decimation = (sample_rate / KEY_SAMPLE_RATE).max(1) as usize;
+        let rate = sample_rate as f32 / decimation as f32;
+        // C3 to B6, where the harmony lies
+        let notes = (48..96)
+            .map(|note| {
+                let frequency = 440.0 * 2f32.powf((note as f32 - 69.0) / 12.0);
+                (
+                    note % 12,
+                    2.0 * (2.0 * std::f32::consts::PI * frequency / rate).cos(),
+                )
+            })
+            .collect();
+        let window = (0..KEY_FRAME_LENGTH)
+            .map(|index| {
+                let phase = 2.0 * std::f32::consts::PI * index as f32 / KEY_FRAME_LENGTH as f32;
+                0.5 - 0.5 * phase.cos()
+            })
+            .collect();
+        Self {
+            decimation,
+            sum: 0.0,
+            samples: 0,
+            buffer: Vec::with_capacity(KEY_FRAME_LENGTH),
+            window,
+            notes,
+            skip: false,
+            chroma: [0.0; 12],
+        }
+    }
+
+    fn push(&mut self, sample: f32) {
+        self.sum += sample;
+        self.samples += 1;
+        if self.samples < self.decimation {
+            return;
+        }
+        self.buffer.push(self.sum / self.decimation as f32);
+        self.sum = 0.0;
+        self.samples = 0;
+        if self.buffer.len() < KEY_FRAME_LENGTH {
+            return;
+        }
+
+        // Every other frame is enough to get the harmony of a track
+        if !self.skip {
+            for (pitch_class, coefficient) in &self.notes {
+                let (mut s1, mut s2) = (0.0f32, 0.0f32);
+                for (sample, window) in self.buffer.iter().zip(&self.window) {
+                    let s = sample * window + coefficient * s1 - s2;
+                    s2 = s1;
+                    s1 = s;
+                }
+                let power = s1 * s1 + s2 * s2 - coefficient * s1 * s2;
+                self.chroma[*pitch_class] += power.max(0.0).sqrt() as f64;
+            }
+        }
+        self.skip = !self.skip;
+        self.buffer.clear();
+    }
+
+    fn finish(self) -> Option<String> {
+        if self.chroma.iter().sum::<f64>() <= 0.0 {
+            return None;
+        }
+        let mut best: Option<(f64, String)> = None;
+        for (tonic, note) in NOTE_NAMES.iter().enumerate() {
+            let rotated: Vec<f64> = (0..12)
+                .map(|index| self.chroma[(index + tonic) % 12])
+                .collect();
+            for (profile, mode) in [(MAJOR_PROFILE, "major"), (MINOR_PROFILE, "minor")] {
+                let score = correlation(&rotated, &profile);
+                if best.as_ref().is_none_or(|(best, _)| score > *best) {
+                    best = Some((score, format!("{} {}", note, mode)));
+                }
+            }
+        }
+        best.map(|(_, key)| key)
+    }
+}
+
+fn correlation(a: &[f64], b: &[f64]) -> f64 {
+    let mean_a = a.iter().sum::<f64>() / a.len() as f64;
+    let mean_b = b.iter().sum::<f64>() / b.len() as f64;
+    let (mut covariance, mut variance_a, mut variance_b) = (0.0, 0.0, 0.0);
+    for (a, b) in a.iter().zip(b) {
+        covariance += (a - mean_a) * (b - mean_b);
+        variance_a += (a - mean_a).powi(2);
+        variance_b += (b - mean_b).powi(2);
+    }
+    if variance_a == 0.0 || variance_b == 0.0 {
+        return 0.0;
+    }
+    covariance / (variance_a * variance_b).sqrt()
+}
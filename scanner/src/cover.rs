@@ -0,0 +1,137 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Error;
+use image::{imageops::FilterType, ImageFormat};
+use lofty::{MimeType, Tag};
+use music_player_settings::get_application_directory;
+
+pub const THUMBNAIL_SIZES: [u32; 3] = [64, 256, 512];
+
+const FOLDER_COVER_NAMES: [&str; 3] = ["cover", "folder", "front"];
+
+#[derive(Debug, Clone, Default)]
+pub struct Cover {
+    pub original: String,
+    pub small: String,
+    pub medium: String,
+    pub large: String,
+    /// The WebP thumbnails, none when the image couldn't be decoded
+    pub small_webp: Option<String>,
+    pub medium_webp: Option<String>,
+    pub large_webp: Option<String>,
+}
+
+/// Returns the first picture embedded in the tag with its file extension.
+pub fn embedded_cover(tag: &Tag) -> Option<(Vec<u8>, &'static str)> {
+    let picture = tag.pictures().first()?;
+    let extension = match picture.mime_type() {
+        MimeType::Jpeg => "jpg",
+        MimeType::Png => "png",
+        _ => {
+            println!("Unsupported picture format");
+            return None;
+        }
+    };
+    Some((picture.data().to_vec(), extension))
+}
+
+/// Looks for an artwork file next to the audio file: `cover.*`, `folder.*`,
+/// `front.*` or `albumart*.jpg`, in that order of preference.
+pub fn folder_cover(directory: &Path) -> Option<PathBuf> {
+    let mut candidates: Vec<(usize, PathBuf)> = fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file())
+        .filter_map(|path| {
+            let stem = path.file_stem()?.to_str()?.to_lowercase();
+            let extension = path.extension()?.to_str()?.to_lowercase();
+            let rank = match extension.as_str() {
+                "jpg" | "jpeg" | "png" if FOLDER_COVER_NAMES.contains(&stem.as_str()) => {
+                    FOLDER_COVER_NAMES.iter().position(|name| *name == stem)?
+                }
+                "jpg" | "jpeg" if stem.starts_with("albumart") => FOLDER_COVER_NAMES.len(),
+                _ => return None,
+            };
+            Some((rank, path))
+        })
+        .collect();
+    candidates.sort();
+    candidates.into_iter().next().map(|(_, path)| path)
+}
+
+/// Reads a folder artwork file, returning its content with the extension
+/// used to store it in the covers directory: the one of the format sniffed
+/// from the content, the file's own when the format is unknown.
+pub fn read_folder_cover(path: &Path) -> Result<(Vec<u8>, String), Error> {
+    let data = fs::read(path)?;
+    let extension = match image::guess_format(&data) {
+        Ok(ImageFormat::Jpeg) => "jpg".to_owned(),
+        Ok(format) => format.extensions_str()[0].to_owned(),
+        Err(_) => path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or("jpg")
+            .to_lowercase(),
+    };
+    Ok((data, extension))
+}
+
+/// Writes the artwork of an album into the covers directory, along with its
+/// thumbnails (see `THUMBNAIL_SIZES`) in both JPEG and WebP. When the image
+/// can't be decoded, the original file is kept and stands for the thumbnails.
+pub fn save_cover(data: &[u8], extension: &str, album: &str) -> Result<Cover, Error> {
+    let covers_path = format!("{}/covers", get_application_directory());
+    let id = format!("{:x}", md5::compute(album.as_bytes()));
+
+    let original = format!("{}.{}", id, extension);
+    fs::write(format!("{}/{}", covers_path, original), data)?;
+
+    let thumbnails = match save_thumbnails(data, &covers_path, &id) {
+        Ok(thumbnails) => thumbnails,
+        Err(e) => {
+            println!("Error creating the thumbnails of {}: {}", original, e);
+            vec![(original.clone(), None); THUMBNAIL_SIZES.len()]
+        }
+    };
+
+    Ok(Cover {
+        original,
+        small: thumbnails[0].0.clone(),
+        medium: thumbnails[1].0.clone(),
+        large: thumbnails[2].0.clone(),
+        small_webp: thumbnails[0].1.clone(),
+        medium_webp: thumbnails[1].1.clone(),
+        large_webp: thumbnails[2].1.clone(),
+    })
+}
+
+/// The JPEG and WebP file names of each thumbnail.
+fn save_thumbnails(
+    data: &[u8],
+    covers_path: &str,
+    id: &str,
+) -> Result<Vec<(String, Option<String>)>, Error> {
+    let image = image::load_from_memory(data)?;
+    let mut thumbnails = Vec::new();
+    for size in THUMBNAIL_SIZES {
+        let thumbnail = image.resize(size, size, FilterType::Lanczos3);
+        let filename = format!("{}_{}", id, size);
+        thumbnail.to_rgb8().save_with_format(
+            format!("{}/{}.jpg", covers_path, filename),
+            ImageFormat::Jpeg,
+        )?;
+        thumbnail.to_rgba8().save_with_format(
+            format!("{}/{}.webp", covers_path, filename),
+            ImageFormat::WebP,
+        )?;
+        thumbnails.push((
+            format!("{}.jpg", filename),
+            Some(format!("{}.webp", filename)),
+        ));
+    }
+    Ok(thumbnails)
+}
@@ -0,0 +1,241 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use music_player_types::types::Song;
+
+/// Cue sheets count time in frames, 75 per second.
+const FRAMES_PER_SECOND: u32 = 75;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CueSheet {
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    pub genre: Option<String>,
+    pub date: Option<String>,
+    pub tracks: Vec<CueTrack>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CueTrack {
+    /// The audio file holding the track, as written in the sheet
+    pub file: String,
+    pub number: u32,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub songwriter: Option<String>,
+    pub isrc: Option<String>,
+    /// Offsets in the file, in milliseconds. A track without end lasts
+    /// until the end of the file.
+    pub start: u32,
+    pub end: Option<u32>,
+    pregap: Option<u32>,
+}
+
+/// Reads a cue sheet, which may be encoded in UTF-8, with or without a byte
+/// order mark, or in Latin-1 as written by most ripping software.
+pub fn read_cue(path: &Path) -> std::io::Result<CueSheet> {
+    let data = fs::read(path)?;
+    let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(&data);
+    let content = match std::str::from_utf8(data) {
+        Ok(content) => content.to_string(),
+        Err(_) => data.iter().map(|byte| *byte as char).collect(),
+    };
+    Ok(parse_cue(&content))
+}
+
+pub fn parse_cue(content: &str) -> CueSheet {
+    let mut sheet = CueSheet::default();
+    let mut file = String::new();
+    let mut track: Option<CueTrack> = None;
+
+    for line in content.lines() {
+        let (keyword, value) = match line.trim().split_once(char::is_whitespace) {
+            Some((keyword, value)) => (keyword.to_ascii_uppercase(), value.trim()),
+            None => continue,
+        };
+        match (keyword.as_str(), track.as_mut()) {
+            ("FILE", _) => {
+                // The file type comes last: FILE "Album.flac" WAVE
+                let name = match value.rsplit_once(char::is_whitespace) {
+                    Some((name, _)) => name,
+                    None => value,
+                };
+                file = unquote(name);
+            }
+            ("TRACK", _) => {
+                sheet.tracks.extend(track.take());
+                track = Some(CueTrack {
+                    file: file.clone(),
+                    number: value
+                        .split_whitespace()
+                        .next()
+                        .and_then(|number| number.parse().ok())
+                        .unwrap_or(sheet.tracks.len() as u32 + 1),
+                    ..Default::default()
+                });
+            }
+            ("INDEX", Some(track)) => {
+                let mut parts = value.split_whitespace();
+                let index = parts.next().and_then(|index| index.parse::<u32>().ok());
+                let time = parts.next().and_then(parse_time);
+                match (index, time) {
+                    (Some(0), Some(time)) => track.pregap = Some(time),
+                    (Some(1), Some(time)) => track.start = time,
+                    _ => {}
+                }
+            }
+            ("TITLE", Some(track)) => track.title = Some(unquote(value)),
+            ("TITLE", None) => sheet.title = Some(unquote(value)),
+            ("PERFORMER", Some(track)) => track.performer = Some(unquote(value)),
+            ("PERFORMER", None) => sheet.performer = Some(unquote(value)),
+            ("SONGWRITER", Some(track)) => track.songwriter = Some(unquote(value)),
+            ("SONGWRITER", None) => sheet.songwriter = Some(unquote(value)),
+            ("ISRC", Some(track)) => track.isrc = Some(unquote(value)),
+            ("REM", _) => match value.split_once(char::is_whitespace) {
+                Some((field, value)) if field.eq_ignore_ascii_case("GENRE") => {
+                    sheet.genre = Some(unquote(value.trim()))
+                }
+                Some((field, value)) if field.eq_ignore_ascii_case("DATE") => {
+                    sheet.date = Some(unquote(value.trim()))
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+    sheet.tracks.extend(track);
+
+    // A track ends where the next one of the same file starts, its pregap
+    // included
+    let starts: Vec<(String, u32)> = sheet
+        .tracks
+        .iter()
+        .map(|track| (track.file.clone(), track.pregap.unwrap_or(track.start)))
+        .collect();
+    for (track, next) in sheet.tracks.iter_mut().zip(starts.iter().skip(1)) {
+        if track.file == next.0 && next.1 > track.start {
+            track.end = Some(next.1);
+        }
+    }
+    sheet
+}
+
+/// Parses a `mm:ss:ff` timestamp into milliseconds.
+fn parse_time(value: &str) -> Option<u32> {
+    let mut parts = value.split(':').map(|part| part.parse::<u32>().ok());
+    let (minutes, seconds, frames) = (parts.next()??, parts.next()??, parts.next()??);
+    Some((minutes * 60 + seconds) * 1000 + frames * 1000 / FRAMES_PER_SECOND)
+}
+
+fn unquote(value: &str) -> String {
+    value.trim_matches('"').to_string()
+}
+
+impl CueSheet {
+    /// The audio files referenced by the sheet, resolved against its folder.
+    /// Sheets often still name the `.wav` file of the rip while the audio was
+    /// later compressed, the file with the same stem is used then.
+    pub fn files(&self, cue_path: &Path) -> Vec<(String, PathBuf)> {
+        let directory = cue_path.parent().unwrap_or_else(|| Path::new(""));
+        let mut files: Vec<(String, PathBuf)> = vec![];
+        for track in &self.tracks {
+            if files.iter().any(|(name, _)| *name == track.file) {
+                continue;
+            }
+            if let Some(path) = resolve_file(directory, &track.file) {
+                files.push((track.file.clone(), path));
+            }
+        }
+        files
+    }
+
+    /// Builds the virtual tracks stored in `file`, the tags and audio
+    /// properties of the file filling what the sheet leaves out.
+    pub fn songs(&self, file: &str, file_song: &Song) -> Vec<Song> {
+        let tracks: Vec<&CueTrack> = self
+            .tracks
+            .iter()
+            .filter(|track| track.file == file)
+            .collect();
+        let file_duration = file_song.duration.as_millis() as u32;
+        tracks
+            .iter()
+            .filter(|track| track.start < file_duration)
+            .map(|track| {
+                let end = track.end.unwrap_or(file_duration).min(file_duration);
+                Song {
+                    title: track
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| format!("Track {}", track.number)),
+                    artist: track
+                        .performer
+                        .clone()
+                        .or_else(|| self.performer.clone())
+                        .unwrap_or_else(|| file_song.artist.clone()),
+                    artists: vec![],
+                    album: self
+                        .title
+                        .clone()
+                        .unwrap_or_else(|| file_song.album.clone()),
+                    album_artist: self
+                        .performer
+                        .clone()
+                        .unwrap_or_else(|| file_song.album_artist.clone()),
+                    genre: self
+                        .genre
+                        .clone()
+                        .unwrap_or_else(|| file_song.genre.clone()),
+                    year: self
+                        .date
+                        .as_ref()
+                        .and_then(|date| date.get(..4))
+                        .and_then(|year| year.parse().ok())
+                        .or(file_song.year),
+                    track: Some(track.number),
+                    track_total: Some(tracks.len() as u32),
+                    composer: track
+                        .songwriter
+                        .clone()
+                        .or_else(|| self.songwriter.clone())
+                        .or_else(|| file_song.composer.clone()),
+                    isrc: track.isrc.clone(),
+                    duration: Duration::from_millis((end - track.start) as u64),
+                    start_offset: Some(track.start),
+                    end_offset: track.end.map(|_| end),
+                    // The tags of the file describe the whole album
+                    comment: None,
+                    bpm: None,
+                    title_sort: None,
+                    artist_sort: None,
+                    musicbrainz_recording_id: None,
+                    id: None,
+                    content_hash: None,
+                    ..file_song.clone()
+                }
+            })
+            .collect()
+    }
+}
+
+fn resolve_file(directory: &Path, name: &str) -> Option<PathBuf> {
+    let path = directory.join(name);
+    if path.is_file() {
+        return Some(path);
+    }
+    let stem = path.file_stem()?.to_os_string();
+    fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .find(|candidate| {
+            candidate.file_stem() == Some(stem.as_os_str())
+                && candidate
+                    .extension()
+                    .is_some_and(|extension| !extension.eq_ignore_ascii_case("cue"))
+        })
+}
@@ -0,0 +1,160 @@
+use std::{fs::File, io::ErrorKind, path::Path};
+
+use anyhow::Error;
+use music_player_audio::archive::{ArchiveEntry, ArchiveFile};
+use symphonia::core::{
+    audio::SampleBuffer,
+    codecs::{Decoder, DecoderOptions},
+    errors::Error as DecodeError,
+    formats::{FormatOptions, FormatReader, SeekMode, SeekTo},
+    io::{MediaSource, MediaSourceStream},
+    meta::MetadataOptions,
+    probe::Hint,
+    units::Time,
+};
+
+/// Calls `f` with the encoded packets of the default audio track of a file,
+/// as demuxed from its container, returning the number of packets read.
+pub fn for_each_packet(path: &Path, mut f: impl FnMut(&[u8])) -> Result<usize, Error> {
+    let mut format = probe(Box::new(File::open(path)?), path)?;
+    let track_id = format
+        .default_track()
+        .ok_or_else(|| Error::msg("No audio track found"))?
+        .id;
+    let mut packets = 0;
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(DecodeError::IoError(e)) if e.kind() == ErrorKind::UnexpectedEof => break,
+            Err(e) => return Err(e.into()),
+        };
+        if packet.track_id() == track_id {
+            f(packet.buf());
+            packets += 1;
+        }
+    }
+    Ok(packets)
+}
+
+fn probe(source: Box<dyn MediaSource>, path: &Path) -> Result<Box<dyn FormatReader>, Error> {
+    let mut hint = Hint::new();
+    if let Some(extension) = path.extension().and_then(|extension| extension.to_str()) {
+        hint.with_extension(extension);
+    }
+    let mss = MediaSourceStream::new(source, Default::default());
+    let format = symphonia::default::get_probe()
+        .format(
+            &hint,
+            mss,
+            &FormatOptions::default(),
+            &MetadataOptions::default(),
+        )?
+        .format;
+    Ok(format)
+}
+
+/// Decodes the default audio track of a file into interleaved samples.
+pub struct AudioStream {
+    format: Box<dyn FormatReader>,
+    decoder: Box<dyn Decoder>,
+    track_id: u32,
+    pub sample_rate: u32,
+    length: Option<usize>,
+}
+
+impl AudioStream {
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        Self::from_source(Box::new(File::open(path)?), path)
+    }
+
+    /// Opens the file of a track of the library, which may be stored in an
+    /// archive.
+    pub fn open_uri(uri: &str) -> Result<Self, Error> {
+        if ArchiveEntry::is_archive_entry(uri) {
+            let entry = ArchiveEntry::parse(uri)?;
+            return Self::from_source(Box::new(ArchiveFile::open(&entry)?), Path::new(&entry.name));
+        }
+        Self::open(Path::new(uri))
+    }
+
+    fn from_source(source: Box<dyn MediaSource>, path: &Path) -> Result<Self, Error> {
+        let format = probe(source, path)?;
+        let track = format
+            .default_track()
+            .ok_or_else(|| Error::msg("No audio track found"))?;
+        let track_id = track.id;
+        let sample_rate = track
+            .codec_params
+            .sample_rate
+            .ok_or_else(|| Error::msg("Unknown sample rate"))?;
+        let decoder = symphonia::default::get_codecs()
+            .make(&track.codec_params, &DecoderOptions::default())?;
+        Ok(Self {
+            format,
+            decoder,
+            track_id,
+            sample_rate,
+            length: None,
+        })
+    }
+
+    /// Restricts the stream to the slice of a cue sheet track, the offsets
+    /// being in milliseconds.
+    pub fn slice(
+        mut self,
+        start_offset: Option<u32>,
+        end_offset: Option<u32>,
+    ) -> Result<Self, Error> {
+        let start = start_offset.unwrap_or_default();
+        if start > 0 {
+            let time = Time::new((start / 1000) as u64, (start % 1000) as f64 / 1000.0);
+            self.format.seek(
+                SeekMode::Accurate,
+                SeekTo::Time {
+                    time,
+                    track_id: Some(self.track_id),
+                },
+            )?;
+            self.decoder.reset();
+        }
+        self.length = end_offset.map(|end| {
+            (end.saturating_sub(start) as u64 * self.sample_rate as u64 / 1000) as usize
+        });
+        Ok(self)
+    }
+
+    /// Calls `f` with every frame, one sample per channel, until the end of
+    /// the stream or until `max_frames` frames were decoded.
+    pub fn for_each_frame(&mut self, max_frames: Option<usize>, mut f: impl FnMut(&[f32])) {
+        let max_frames = max_frames
+            .unwrap_or(usize::MAX)
+            .min(self.length.unwrap_or(usize::MAX));
+        let mut frames = 0;
+        while frames < max_frames {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(_) => break,
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let decoded = match self.decoder.decode(&packet) {
+                Ok(decoded) => decoded,
+                // A corrupted packet only loses a few milliseconds of audio
+                Err(DecodeError::DecodeError(_)) => continue,
+                Err(_) => break,
+            };
+            let spec = *decoded.spec();
+            let channels = spec.channels.count().max(1);
+            let mut buffer = SampleBuffer::<f32>::new(decoded.capacity() as u64, spec);
+            buffer.copy_interleaved_ref(decoded);
+            for frame in buffer.samples().chunks(channels) {
+                if frames == max_frames {
+                    break;
+                }
+                f(frame);
+                frames += 1;
+            }
+        }
+    }
+}
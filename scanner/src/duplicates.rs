@@ -0,0 +1,248 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+
+use anyhow::Error;
+use music_player_entity::track as track_entity;
+use music_player_storage::{repo::acoustic_fingerprint::AcousticFingerprintRepository, Database};
+use sea_orm::{EntityTrait, QueryOrder};
+
+use crate::fingerprint::{
+    decode_fingerprint, encode_fingerprint, fingerprint_similarity, track_fingerprint,
+};
+
+pub(crate) const TITLE_SIMILARITY: f64 = 0.9;
+pub(crate) const ARTIST_SIMILARITY: f64 = 0.85;
+const MAX_DURATION_DIFFERENCE: f32 = 3.0;
+const ACOUSTIC_SIMILARITY: f32 = 0.85;
+
+/// Copies of the same recording, the suggested best copy comes first.
+#[derive(Debug, Clone)]
+pub struct DuplicateGroup {
+    pub tracks: Vec<track_entity::Model>,
+}
+
+impl DuplicateGroup {
+    pub fn best_copy(&self) -> &track_entity::Model {
+        &self.tracks[0]
+    }
+}
+
+/// Finds the tracks of the library sharing the same audio. Tracks with the
+/// same content hash are always duplicates, otherwise the tracks with
+/// similar tags and durations are compared by acoustic fingerprint, which
+/// catches the same recording in another format or at another bitrate.
+pub async fn find_duplicates(db: &Database) -> Result<Vec<DuplicateGroup>, Error> {
+    let tracks = track_entity::Entity::find()
+        .order_by_asc(track_entity::Column::Duration)
+        .all(db.get_connection())
+        .await?;
+
+    let mut groups = UnionFind::new(tracks.len());
+    let mut by_content: HashMap<&str, usize> = HashMap::new();
+    for (index, track) in tracks.iter().enumerate() {
+        if let Some(content_hash) = &track.content_hash {
+            match by_content.get(content_hash.as_str()) {
+                Some(other) => groups.union(*other, index),
+                None => {
+                    by_content.insert(content_hash, index);
+                }
+            }
+        }
+    }
+
+    let candidates: Vec<(usize, usize)> = candidate_pairs(&tracks)
+        .into_iter()
+        .filter(|(a, b)| groups.find(*a) != groups.find(*b))
+        .collect();
+
+    let mut fingerprints = Fingerprints::load(db, &tracks, &candidates).await?;
+    for (a, b) in candidates {
+        if groups.find(a) == groups.find(b) {
+            continue;
+        }
+        let similarity = match (
+            fingerprints.get(&tracks[a]).await,
+            fingerprints.get(&tracks[b]).await,
+        ) {
+            (Some(a), Some(b)) => fingerprint_similarity(&a, &b),
+            _ => continue,
+        };
+        if similarity >= ACOUSTIC_SIMILARITY {
+            groups.union(a, b);
+        }
+    }
+
+    let mut members: HashMap<usize, Vec<track_entity::Model>> = HashMap::new();
+    for (index, track) in tracks.into_iter().enumerate() {
+        members.entry(groups.find(index)).or_default().push(track);
+    }
+    let mut duplicates: Vec<DuplicateGroup> = members
+        .into_values()
+        .filter(|tracks| tracks.len() > 1)
+        .map(|mut tracks| {
+            tracks.sort_by_key(|track| Reverse(best_copy_order(track)));
+            DuplicateGroup { tracks }
+        })
+        .collect();
+    duplicates.sort_by(|a, b| {
+        (&a.best_copy().artist, &a.best_copy().title)
+            .cmp(&(&b.best_copy().artist, &b.best_copy().title))
+    });
+    Ok(duplicates)
+}
+
+/// Lossless copies come first thanks to their bit depth, then the highest
+/// bitrate and sample rate win.
+pub fn best_copy_order(track: &track_entity::Model) -> (u8, u32, u32) {
+    (
+        track.bit_depth.unwrap_or_default(),
+        track.bitrate.unwrap_or_default(),
+        track.sample_rate.unwrap_or_default(),
+    )
+}
+
+/// Pairs of tracks with close durations and similar titles and artists,
+/// the tracks being sorted by duration.
+fn candidate_pairs(tracks: &[track_entity::Model]) -> Vec<(usize, usize)> {
+    let keys: Vec<(String, String)> = tracks
+        .iter()
+        .map(|track| (normalize(&track.title), normalize(&track.artist)))
+        .collect();
+    let mut pairs = Vec::new();
+    for (a, track) in tracks.iter().enumerate() {
+        let duration = match track.duration {
+            Some(duration) => duration,
+            None => continue,
+        };
+        for (b, other) in tracks.iter().enumerate().skip(a + 1) {
+            match other.duration {
+                Some(other) if other - duration > MAX_DURATION_DIFFERENCE => break,
+                Some(_) => {}
+                None => continue,
+            }
+            if similar_tags(&keys[a], &keys[b]) {
+                pairs.push((a, b));
+            }
+        }
+    }
+    pairs
+}
+
+fn similar_tags(a: &(String, String), b: &(String, String)) -> bool {
+    !a.0.is_empty()
+        && strsim::jaro_winkler(&a.0, &b.0) >= TITLE_SIMILARITY
+        && strsim::jaro_winkler(&a.1, &b.1) >= ARTIST_SIMILARITY
+}
+
+/// Lowercases a title or an artist name and drops the punctuation and the
+/// bracketed parts, such as "(Remastered)" or "[Explicit]", which differ
+/// between releases of the same recording.
+pub fn normalize(value: &str) -> String {
+    let mut normalized = String::new();
+    let mut depth = 0;
+    for c in value.chars() {
+        match c {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth = (depth - 1).max(0),
+            c if depth == 0 && c.is_alphanumeric() => normalized.extend(c.to_lowercase()),
+            c if depth == 0 && c.is_whitespace() && !normalized.ends_with(' ') => {
+                normalized.push(' ')
+            }
+            _ => {}
+        }
+    }
+    normalized.trim().to_string()
+}
+
+/// Acoustic fingerprints of the candidates, computed on first use and cached
+/// in the database by content hash.
+struct Fingerprints {
+    repo: AcousticFingerprintRepository,
+    cached: HashMap<String, Option<Vec<u8>>>,
+}
+
+impl Fingerprints {
+    async fn load(
+        db: &Database,
+        tracks: &[track_entity::Model],
+        candidates: &[(usize, usize)],
+    ) -> Result<Self, Error> {
+        let repo = AcousticFingerprintRepository::new(db.get_connection());
+        let content_hashes: HashSet<String> = candidates
+            .iter()
+            .flat_map(|(a, b)| [&tracks[*a], &tracks[*b]])
+            .filter_map(|track| track.content_hash.clone())
+            .collect();
+        let cached = repo
+            .find_all(content_hashes.into_iter().collect())
+            .await?
+            .into_iter()
+            .map(|(content_hash, fingerprint)| (content_hash, decode_fingerprint(&fingerprint)))
+            .collect();
+        Ok(Self { repo, cached })
+    }
+
+    async fn get(&mut self, track: &track_entity::Model) -> Option<Vec<u8>> {
+        let key = track
+            .content_hash
+            .clone()
+            .unwrap_or_else(|| track.id.clone());
+        if let Some(fingerprint) = self.cached.get(&key) {
+            return fingerprint.clone();
+        }
+
+        let result = tokio::task::spawn_blocking({
+            let track = track.clone();
+            move || track_fingerprint(&track)
+        })
+        .await
+        .map_err(Error::from)
+        .and_then(|result| result);
+        let fingerprint = match result {
+            Ok(fingerprint) => Some(fingerprint),
+            Err(e) => {
+                println!("Error fingerprinting {}: {}", track.uri, e);
+                None
+            }
+        };
+        if let (Some(content_hash), Some(fingerprint)) = (&track.content_hash, &fingerprint) {
+            let encoded = encode_fingerprint(fingerprint);
+            if let Err(e) = self.repo.save(content_hash, &encoded).await {
+                println!("Error saving fingerprint: {}", e);
+            }
+        }
+        self.cached.insert(key, fingerprint.clone());
+        fingerprint
+    }
+}
+
+struct UnionFind {
+    parents: Vec<usize>,
+}
+
+impl UnionFind {
+    fn new(size: usize) -> Self {
+        Self {
+            parents: (0..size).collect(),
+        }
+    }
+
+    fn find(&mut self, index: usize) -> usize {
+        let parent = self.parents[index];
+        if parent == index {
+            return index;
+        }
+        let root = self.find(parent);
+        self.parents[index] = root;
+        root
+    }
+
+    fn union(&mut self, a: usize, b: usize) {
+        let (a, b) = (self.find(a), self.find(b));
+        if a != b {
+            self.parents[b] = a;
+        }
+    }
+}
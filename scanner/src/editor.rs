@@ -0,0 +1,447 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Error;
+use image::ImageFormat;
+use lofty::{AudioFile, MimeType, Picture, PictureType, Probe, Tag, TagExt};
+use music_player_audio::archive::ArchiveEntry;
+use music_player_entity::{
+    album as album_entity, artist as artist_entity, artist_tracks as artist_tracks_entity,
+    track as track_entity,
+};
+use music_player_settings::{read_settings, Settings};
+use music_player_storage::{
+    repo::{
+        genre::{delete_unused_genres, link_track_genres},
+        smart_playlist::SmartPlaylistRepository,
+    },
+    searcher::Searcher,
+    Database,
+};
+use music_player_types::types::{Album, Artist, Song, TagChanges};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelBehavior, ActiveModelTrait, ColumnTrait, ConnectionTrait,
+    EntityTrait, PaginatorTrait, QueryFilter, TransactionTrait,
+};
+
+use crate::{cover::save_cover, fingerprint::audio_hash, update_album_cover};
+
+struct TagEdit {
+    path: PathBuf,
+    original: Option<Tag>,
+    tag: Tag,
+    song: Song,
+}
+
+/// Writes the changes into the audio files of the given tracks, then updates
+/// the `track`/`album`/`artist` rows and the search index. The files are
+/// restored when the database can't be updated. With `dry_run`, nothing is
+/// written and the tracks are returned as they would be after the edit.
+pub async fn edit_tracks(
+    db: &Database,
+    searcher: &Searcher,
+    ids: &[String],
+    changes: &TagChanges,
+    dry_run: bool,
+) -> Result<Vec<track_entity::Model>, Error> {
+    let settings = read_settings()?.try_deserialize::<Settings>()?;
+
+    // The same track may be passed twice
+    let ids: HashSet<String> = ids.iter().cloned().collect();
+    let tracks = track_entity::Entity::find()
+        .filter(track_entity::Column::Id.is_in(ids.clone()))
+        .all(db.get_connection())
+        .await?;
+    if tracks.len() != ids.len() {
+        return Err(Error::msg("Track not found"));
+    }
+    // The tags of a cue sheet track live in the sheet, the file holds the
+    // whole album
+    if tracks.iter().any(|track| track.start_offset.is_some()) {
+        return Err(Error::msg("Tracks of a cue sheet can't be edited"));
+    }
+    if tracks
+        .iter()
+        .any(|track| ArchiveEntry::is_archive_entry(&track.uri))
+    {
+        return Err(Error::msg("Tracks inside an archive can't be edited"));
+    }
+
+    let cover = match &changes.cover {
+        Some(data) => Some(cover_picture(data)?),
+        None => None,
+    };
+
+    let mut edits = Vec::new();
+    for track in &tracks {
+        let album = album_entity::Entity::find_by_id(track.album_id.clone().unwrap_or_default())
+            .one(db.get_connection())
+            .await?;
+        let mut edit = prepare_edit(Path::new(&track.uri), changes, cover.as_ref(), &settings)?;
+        edit.song.id = Some(track.id.clone());
+        edit.song.content_hash = track.content_hash.clone();
+        edit.song.cover = match &cover {
+            Some((_, extension)) => Some(format!(
+                "{:x}.{}",
+                md5::compute(edit.song.album.as_bytes()),
+                extension
+            )),
+            None => album.and_then(|album| album.cover),
+        };
+        edits.push(edit);
+    }
+
+    if dry_run {
+        return Ok(edits.iter().map(|edit| (&edit.song).into()).collect());
+    }
+
+    write_tags(&edits)?;
+    // Rewriting the tags may move the audio data within the file
+    for edit in &mut edits {
+        if let Ok(content_hash) = audio_hash(&edit.path) {
+            edit.song.content_hash = Some(content_hash);
+        }
+    }
+    let orphans = match update_database(db, &tracks, &edits).await {
+        Ok(orphans) => orphans,
+        Err(e) => {
+            restore_tags(&edits);
+            return Err(e);
+        }
+    };
+
+    if let (Some(data), Some((_, extension))) = (&changes.cover, &cover) {
+        let albums: HashSet<&String> = edits.iter().map(|edit| &edit.song.album).collect();
+        for album in albums {
+            // The tags and the database are already updated, the index must
+            // follow even when the artwork can't be stored
+            match save_cover(data, extension, album) {
+                Ok(cover) => update_album_cover(album, &cover, db).await,
+                Err(e) => println!("Error saving album cover: {}", e),
+            }
+        }
+    }
+
+    update_index(searcher, &edits, &orphans);
+
+    if let Err(e) = SmartPlaylistRepository::new(db.get_connection())
+        .refresh_all()
+        .await
+    {
+        println!("Error refreshing smart playlists: {}", e);
+    }
+
+    Ok(edits.iter().map(|edit| (&edit.song).into()).collect())
+}
+
+/// Edits every track of an album at once, only the fields shared by the
+/// tracks can be changed.
+pub async fn edit_album(
+    db: &Database,
+    searcher: &Searcher,
+    id: &str,
+    changes: &TagChanges,
+    dry_run: bool,
+) -> Result<Vec<track_entity::Model>, Error> {
+    if changes.is_track_specific() {
+        return Err(Error::msg(
+            "Title and track number can't be set on a whole album",
+        ));
+    }
+    let ids: Vec<String> = track_entity::Entity::find()
+        .filter(track_entity::Column::AlbumId.eq(id))
+        .all(db.get_connection())
+        .await?
+        .into_iter()
+        .map(|track| track.id)
+        .collect();
+    if ids.is_empty() {
+        return Err(Error::msg("Album not found"));
+    }
+    edit_tracks(db, searcher, &ids, changes, dry_run).await
+}
+
+fn cover_picture(data: &[u8]) -> Result<(Picture, &'static str), Error> {
+    let (mime_type, extension) = match image::guess_format(data)? {
+        ImageFormat::Png => (MimeType::Png, "png"),
+        ImageFormat::Jpeg => (MimeType::Jpeg, "jpg"),
+        format => {
+            return Err(Error::msg(format!(
+                "Unsupported cover format: {:?}",
+                format
+            )))
+        }
+    };
+    let picture = Picture::new_unchecked(PictureType::CoverFront, mime_type, None, data.to_vec());
+    Ok((picture, extension))
+}
+
+fn prepare_edit(
+    path: &Path,
+    changes: &TagChanges,
+    cover: Option<&(Picture, &'static str)>,
+    settings: &Settings,
+) -> Result<TagEdit, Error> {
+    let tagged_file = Probe::open(path)?.read()?;
+    let original = match tagged_file.primary_tag() {
+        Some(primary_tag) => Some(primary_tag.clone()),
+        None => tagged_file.first_tag().cloned(),
+    };
+
+    let mut tag = original
+        .clone()
+        .unwrap_or_else(|| Tag::new(tagged_file.primary_tag_type()));
+    changes.apply(&mut tag);
+    if let Some((picture, _)) = cover {
+        tag.remove_picture_type(PictureType::CoverFront);
+        tag.push_picture(picture.clone());
+    }
+
+    let mut song = Song::from(&tag);
+    song.with_properties(tagged_file.properties());
+    song.uri = Some(format!("{}", path.display()));
+    song.split_artists(&settings.artist_separators);
+
+    Ok(TagEdit {
+        path: path.to_path_buf(),
+        original,
+        tag,
+        song,
+    })
+}
+
+fn write_tags(edits: &[TagEdit]) -> Result<(), Error> {
+    for (index, edit) in edits.iter().enumerate() {
+        if let Err(e) = edit.tag.save_to_path(&edit.path) {
+            restore_tags(&edits[..index]);
+            return Err(Error::msg(format!(
+                "Failed to write tags to {}: {}",
+                edit.path.display(),
+                e
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn restore_tags(edits: &[TagEdit]) {
+    for edit in edits {
+        let result = match &edit.original {
+            Some(original) => original.save_to_path(&edit.path),
+            None => edit.tag.remove_from_path(&edit.path),
+        };
+        if let Err(e) = result {
+            println!("Error restoring tags of {}: {}", edit.path.display(), e);
+        }
+    }
+}
+
+/// The albums, artists and genres left without any track by an edit.
+struct Orphans {
+    albums: Vec<String>,
+    artists: Vec<String>,
+    genres: Vec<String>,
+}
+
+async fn update_database(
+    db: &Database,
+    tracks: &[track_entity::Model],
+    edits: &[TagEdit],
+) -> Result<Orphans, Error> {
+    let txn = db.get_connection().begin().await?;
+    let artist_ids = linked_artists(&txn, tracks).await?;
+
+    for edit in edits {
+        let song = &edit.song;
+        let track_id = song.track_id();
+
+        for item in artist_entity::ActiveModel::from_artists(song) {
+            artist_entity::Entity::insert(item)
+                .on_conflict(
+                    OnConflict::column(artist_entity::Column::Id)
+                        .do_nothing()
+                        .to_owned(),
+                )
+                .exec(&txn)
+                .await?;
+        }
+
+        // The upsert doesn't run the hooks dating the albums it creates
+        let item = album_entity::ActiveModel::from(song).before_save(true)?;
+        album_entity::Entity::insert(item)
+            .on_conflict(
+                OnConflict::column(album_entity::Column::Id)
+                    .update_columns([
+                        album_entity::Column::Artist,
+                        album_entity::Column::ArtistId,
+                        album_entity::Column::Year,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+
+        let item: track_entity::ActiveModel = song.into();
+        item.update(&txn).await?;
+
+        artist_tracks_entity::Entity::delete_many()
+            .filter(artist_tracks_entity::Column::TrackId.eq(track_id))
+            .exec(&txn)
+            .await?;
+        for item in artist_tracks_entity::ActiveModel::from_artists(song) {
+            item.insert(&txn).await?;
+        }
+        link_track_genres(&txn, &song.track_id(), &song.genre).await?;
+    }
+
+    let albums = orphan_albums(&txn, tracks).await?;
+    for album_id in &albums {
+        album_entity::Entity::delete_by_id(album_id.clone())
+            .exec(&txn)
+            .await?;
+    }
+
+    let artists = orphan_artists(&txn, artist_ids).await?;
+    if !artists.is_empty() {
+        artist_entity::Entity::delete_many()
+            .filter(artist_entity::Column::Id.is_in(artists.clone()))
+            .exec(&txn)
+            .await?;
+    }
+
+    let genres = delete_unused_genres(&txn).await?;
+
+    txn.commit().await?;
+    Ok(Orphans {
+        albums,
+        artists,
+        genres,
+    })
+}
+
+/// Albums left without any track once their tracks were moved to another album.
+async fn orphan_albums<C: ConnectionTrait>(
+    db: &C,
+    tracks: &[track_entity::Model],
+) -> Result<Vec<String>, Error> {
+    let album_ids: HashSet<String> = tracks
+        .iter()
+        .filter_map(|track| track.album_id.clone())
+        .collect();
+    let mut orphans = Vec::new();
+    for album_id in album_ids {
+        let count = track_entity::Entity::find()
+            .filter(track_entity::Column::AlbumId.eq(album_id.clone()))
+            .count(db)
+            .await?;
+        if count == 0 {
+            orphans.push(album_id);
+        }
+    }
+    Ok(orphans)
+}
+
+/// Artists of the tracks and of their albums before the edit.
+async fn linked_artists<C: ConnectionTrait>(
+    db: &C,
+    tracks: &[track_entity::Model],
+) -> Result<HashSet<String>, Error> {
+    let track_ids: Vec<String> = tracks.iter().map(|track| track.id.clone()).collect();
+    let album_ids: Vec<String> = tracks
+        .iter()
+        .filter_map(|track| track.album_id.clone())
+        .collect();
+    let mut artist_ids: HashSet<String> = tracks
+        .iter()
+        .filter_map(|track| track.artist_id.clone())
+        .collect();
+    artist_ids.extend(
+        artist_tracks_entity::Entity::find()
+            .filter(artist_tracks_entity::Column::TrackId.is_in(track_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .map(|artist_track| artist_track.artist_id),
+    );
+    artist_ids.extend(
+        album_entity::Entity::find()
+            .filter(album_entity::Column::Id.is_in(album_ids))
+            .all(db)
+            .await?
+            .into_iter()
+            .filter_map(|album| album.artist_id),
+    );
+    Ok(artist_ids)
+}
+
+/// Artists left without any track or album once their tracks were credited
+/// to other artists.
+async fn orphan_artists<C: ConnectionTrait>(
+    db: &C,
+    artist_ids: HashSet<String>,
+) -> Result<Vec<String>, Error> {
+    let mut orphans = Vec::new();
+    for artist_id in artist_ids {
+        let links = artist_tracks_entity::Entity::find()
+            .filter(artist_tracks_entity::Column::ArtistId.eq(artist_id.clone()))
+            .count(db)
+            .await?;
+        let tracks = track_entity::Entity::find()
+            .filter(track_entity::Column::ArtistId.eq(artist_id.clone()))
+            .count(db)
+            .await?;
+        let albums = album_entity::Entity::find()
+            .filter(album_entity::Column::ArtistId.eq(artist_id.clone()))
+            .count(db)
+            .await?;
+        if links + tracks + albums == 0 {
+            orphans.push(artist_id);
+        }
+    }
+    Ok(orphans)
+}
+
+fn update_index(searcher: &Searcher, edits: &[TagEdit], orphans: &Orphans) {
+    for edit in edits {
+        let song = edit.song.clone();
+        let id = song.track_id();
+        for name in song.linked_artists() {
+            let artist = Artist {
+                id: format!("{:x}", md5::compute(&name)),
+                name,
+                ..Default::default()
+            };
+            if let Err(e) = searcher.insert_artist(artist) {
+                println!("Error inserting artist: {}", e);
+            }
+        }
+        if let Err(e) = searcher.insert_album(Album::from(&song)) {
+            println!("Error updating album: {}", e);
+        }
+        if let Err(e) = searcher.insert_song(song, &id) {
+            println!("Error updating song: {}", e);
+        }
+    }
+
+    for album_id in &orphans.albums {
+        if let Err(e) = searcher.delete_album(album_id) {
+            println!("Error deleting album: {}", e);
+        }
+    }
+    for artist_id in &orphans.artists {
+        if let Err(e) = searcher.delete_artist(artist_id) {
+            println!("Error deleting artist: {}", e);
+        }
+    }
+    for genre_id in &orphans.genres {
+        if let Err(e) = searcher.delete_genre(genre_id) {
+            println!("Error deleting genre: {}", e);
+        }
+    }
+
+    if let Err(e) = searcher.commit() {
+        println!("Error committing the search indexes: {}", e);
+    }
+}
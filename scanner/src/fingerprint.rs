@@ -0,0 +1,174 @@
+use std::{f32::consts::PI, fs::File, io, path::Path};
+
+use anyhow::Error;
+use music_player_entity::track as track_entity;
+
+use crate::decoder::{for_each_packet, AudioStream};
+
+/// Length of the frames the acoustic fingerprint is made of.
+pub const FRAME_MILLISECONDS: u32 = 100;
+/// Only the beginning of a track is decoded, it is enough to tell two
+/// recordings apart.
+const FINGERPRINT_SECONDS: u32 = 120;
+/// Encoders add different amounts of padding at the start of a stream, the
+/// fingerprints are compared at a few frames of offset.
+const MAX_FRAME_OFFSET: usize = 5;
+const MIN_OVERLAP: usize = 50;
+const BITS_PER_FRAME: u32 = 5;
+
+/// Hashes the audio packets of a file, as demuxed by symphonia: the tags of
+/// every container (ID3 and APE tags, MP4 atoms, FLAC metadata blocks, Ogg
+/// comment packets, RIFF chunks) are left out, so that editing the metadata,
+/// renaming or moving the file keeps the same hash.
+pub fn audio_hash(path: &Path) -> Result<String, Error> {
+    let mut context = md5::Context::new();
+    match for_each_packet(path, |packet| context.consume(packet)) {
+        Ok(packets) if packets > 0 => (),
+        // Unknown layout, the whole file is better than no identity at all
+        _ => {
+            context = md5::Context::new();
+            io::copy(&mut File::open(path)?, &mut context)?;
+        }
+    }
+    Ok(format!("{:x}", context.compute()))
+}
+
+/// Computes an acoustic fingerprint from the decoded audio of a file. The
+/// signal is split into three frequency bands, and every frame records
+/// whether the energy of each band, and the balance between neighbouring
+/// bands, went up or down. Those variations survive transcoding, so the
+/// same recording encoded in another format or at another bitrate gets a
+/// close fingerprint.
+pub fn acoustic_fingerprint(path: &Path) -> Result<Vec<u8>, Error> {
+    fingerprint_stream(AudioStream::open(path)?)
+}
+
+/// Fingerprints a track of the library, starting at its offset in the file
+/// for the tracks of a cue sheet.
+pub fn track_fingerprint(track: &track_entity::Model) -> Result<Vec<u8>, Error> {
+    let stream = AudioStream::open_uri(&track.uri)?.slice(track.start_offset, track.end_offset)?;
+    fingerprint_stream(stream)
+}
+
+fn fingerprint_stream(mut stream: AudioStream) -> Result<Vec<u8>, Error> {
+    let mut bands = BandEnergies::new(stream.sample_rate);
+    let max_frames = (stream.sample_rate * FINGERPRINT_SECONDS) as usize;
+    stream.for_each_frame(Some(max_frames), |frame| {
+        bands.push(frame.iter().sum::<f32>() / frame.len() as f32)
+    });
+
+    let fingerprint = fingerprint_bits(&bands.frames);
+    if fingerprint.len() < MIN_OVERLAP {
+        return Err(Error::msg("Not enough audio to compute a fingerprint"));
+    }
+    Ok(fingerprint)
+}
+
+struct BandEnergies {
+    frame_length: usize,
+    low_alpha: f32,
+    mid_alpha: f32,
+    low: f32,
+    mid: f32,
+    current: [f32; 3],
+    length: usize,
+    frames: Vec<[f32; 3]>,
+}
+
+impl BandEnergies {
+    fn new(sample_rate: u32) -> Self {
+        let alpha = |cutoff: f32| 1.0 - (-2.0 * PI * cutoff / sample_rate as f32).exp();
+        Self {
+            frame_length: (sample_rate * FRAME_MILLISECONDS / 1000).max(1) as usize,
+            low_alpha: alpha(300.0),
+            mid_alpha: alpha(2000.0),
+            low: 0.0,
+            mid: 0.0,
+            current: [0.0; 3],
+            length: 0,
+            frames: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, sample: f32) {
+        self.low += self.low_alpha * (sample - self.low);
+        self.mid += self.mid_alpha * (sample - self.mid);
+        let bands = [self.low, self.mid - self.low, sample - self.mid];
+        for (energy, band) in self.current.iter_mut().zip(bands) {
+            *energy += band * band;
+        }
+        self.length += 1;
+        if self.length == self.frame_length {
+            self.frames
+                .push(self.current.map(|energy| (energy + 1e-9).ln()));
+            self.current = [0.0; 3];
+            self.length = 0;
+        }
+    }
+}
+
+/// Turns the log energies of the bands into one byte per frame, the lower
+/// bits telling how each band and the differences between bands evolved
+/// since the previous frame.
+pub fn fingerprint_bits(frames: &[[f32; 3]]) -> Vec<u8> {
+    frames
+        .windows(2)
+        .map(|pair| {
+            let (previous, current) = (pair[0], pair[1]);
+            let features = [
+                current[0] > previous[0],
+                current[1] > previous[1],
+                current[2] > previous[2],
+                current[0] - current[1] > previous[0] - previous[1],
+                current[1] - current[2] > previous[1] - previous[2],
+            ];
+            features
+                .iter()
+                .enumerate()
+                .fold(0u8, |byte, (bit, set)| byte | ((*set as u8) << bit))
+        })
+        .collect()
+}
+
+/// Returns the share of matching bits between two fingerprints, at the
+/// offset where they are the closest.
+pub fn fingerprint_similarity(a: &[u8], b: &[u8]) -> f32 {
+    let mut best = 0.0f32;
+    for offset in 0..=MAX_FRAME_OFFSET {
+        for (a, b) in [(a, b), (b, a)] {
+            if offset >= a.len() {
+                continue;
+            }
+            let overlap = (a.len() - offset).min(b.len());
+            if overlap < MIN_OVERLAP {
+                continue;
+            }
+            let different: u32 = a[offset..offset + overlap]
+                .iter()
+                .zip(&b[..overlap])
+                .map(|(a, b)| (a ^ b).count_ones())
+                .sum();
+            let similarity = 1.0 - different as f32 / (overlap as u32 * BITS_PER_FRAME) as f32;
+            best = best.max(similarity);
+        }
+    }
+    best
+}
+
+pub fn encode_fingerprint(fingerprint: &[u8]) -> String {
+    fingerprint
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn decode_fingerprint(encoded: &str) -> Option<Vec<u8>> {
+    (0..encoded.len())
+        .step_by(2)
+        .map(|index| {
+            encoded
+                .get(index..index + 2)
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+        })
+        .collect()
+}
@@ -1,16 +1,66 @@
 #[cfg(test)]
 mod tests;
 
+pub mod analysis;
+pub mod cover;
+pub mod cue;
+pub mod decoder;
+pub mod duplicates;
+pub mod editor;
+pub mod fingerprint;
+pub mod lyrics;
+pub mod playlist_file;
+pub mod rating;
+
+use analysis::analyze_library;
 use anyhow::Error;
+use cover::{embedded_cover, folder_cover, read_folder_cover, save_cover, Cover};
+use cue::read_cue;
+use fingerprint::audio_hash;
 use futures::future::BoxFuture;
-use music_player_storage::{searcher::Searcher, Database};
-use music_player_types::types::{Album, Artist, Song};
-use std::{io::Write, thread};
+use lyrics::find_lyrics;
+use music_player_entity::{
+    album as album_entity, artist as artist_entity, artist_tracks as artist_tracks_entity,
+    lyrics as lyrics_entity, scan_error, track as track_entity,
+};
+use music_player_storage::{
+    repo::{
+        artist::ArtistRepository, genre::GenreRepository, lyrics::LyricsRepository,
+        rating::RatingRepository, scan_error::ScanErrorRepository,
+        smart_playlist::SmartPlaylistRepository, track::TrackRepository,
+    },
+    searcher::Searcher,
+    Database,
+};
+use music_player_types::types::{Album, Artist, Lyrics, ScanError, ScanErrorKind, Song};
+use playlist_file::{import_playlist_file, PlaylistFormat, TrackMatcher};
+use rating::has_own_file;
+use sea_orm::{
+    sea_query::{Expr, OnConflict},
+    ActiveModelBehavior, ActiveModelTrait, ColumnTrait, DbErr, EntityTrait, QueryFilter,
+    TransactionTrait,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+    sync::mpsc::Sender,
+    thread,
+};
 
-use lofty::{AudioFile, Probe, Tag};
-use music_player_settings::{get_application_directory, read_settings, Settings};
+use lofty::{AudioFile, LoftyError, Probe, Tag, TaggedFile};
+use music_player_audio::{
+    archive::{list_entries, ArchiveEntry, ArchiveFile},
+    fetch::AudioFile as RemoteAudioFile,
+    remote::RemoteRoot,
+};
+use music_player_settings::{read_settings, Settings};
 use walkdir::WalkDir;
 
+const REMOTE_BYTES_PER_SECOND: usize = 40 * 1024;
+
+/// Number of tracks indexed between two commits of the search indexes.
+const INDEX_BATCH_SIZE: usize = 1000;
+
 pub async fn scan_directory(
     save: impl for<'a> Fn(&'a Song, &'a Database) -> BoxFuture<'a, ()> + 'static,
     db: &Database,
@@ -18,8 +68,15 @@ pub async fn scan_directory(
 ) -> Result<Vec<Song>, Error> {
     let config = read_settings().unwrap();
     let settings = config.try_deserialize::<Settings>().unwrap();
-
-    let mut songs: Vec<Song> = Vec::new();
+    let music_directory = Path::new(&settings.music_directory);
+    let local_roots: Vec<&String> = std::iter::once(&settings.music_directory)
+        .chain(
+            settings
+                .library_roots
+                .iter()
+                .filter(|root| !RemoteRoot::is_remote(root)),
+        )
+        .collect();
 
     let supported_formats = vec![
         "audio/mpeg",
@@ -29,120 +86,719 @@ pub async fn scan_directory(
         "audio/aac",
     ];
 
-    let total = WalkDir::new(&settings.music_directory)
-        .follow_links(true)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .count();
+    // The report only describes the latest scan
+    ScanErrorRepository::new(db.get_connection())
+        .clear()
+        .await?;
 
     let cloned_db = db.clone();
-    let (done_tx, done_rx) = std::sync::mpsc::channel::<()>();
-    let (tx, rx) = std::sync::mpsc::channel::<(Album, Song, Artist, usize)>();
-    let searcher = searcher.clone();
-
-    thread::spawn(move || {
-        while let Ok((album, track, artist, index)) = rx.recv() {
-            let id = format!("{:x}", md5::compute(track.uri.as_ref().unwrap()));
-            match searcher.insert_artist(artist) {
-                Ok(_) => {}
-                Err(e) => println!("Error inserting artist: {}", e),
-            };
-            match searcher.insert_album(album) {
-                Ok(_) => {}
-                Err(e) => println!("Error inserting album: {}", e),
-            };
-            match searcher.insert_song(track, &id) {
+    let (tx, rx) = std::sync::mpsc::channel::<(Album, Song, Vec<Artist>)>();
+    let index_searcher = searcher.clone();
+
+    // The documents are committed once per batch of tracks, the artists and
+    // albums shared by several tracks are only added once
+    let indexer = thread::spawn(move || {
+        let mut artist_ids = HashSet::new();
+        let mut album_ids = HashSet::new();
+        let mut pending = 0;
+        while let Ok((album, track, artists)) = rx.recv() {
+            let id = track.track_id();
+            for artist in artists {
+                if !artist_ids.insert(artist.id.clone()) {
+                    continue;
+                }
+                match index_searcher.insert_artist(artist) {
+                    Ok(_) => {}
+                    Err(e) => println!("Error inserting artist: {}", e),
+                };
+            }
+            if album_ids.insert(album.id.clone()) {
+                match index_searcher.insert_album(album) {
+                    Ok(_) => {}
+                    Err(e) => println!("Error inserting album: {}", e),
+                };
+            }
+            match index_searcher.insert_song(track, &id) {
                 Ok(_) => {}
                 Err(e) => println!("Error inserting song: {}", e),
             };
 
-            if index + 1 == total {
-                done_tx.send(()).unwrap();
-                break;
+            pending += 1;
+            if pending == INDEX_BATCH_SIZE {
+                if let Err(e) = index_searcher.commit() {
+                    println!("Error committing the search indexes: {}", e);
+                }
+                pending = 0;
             }
         }
+        if let Err(e) = index_searcher.commit() {
+            println!("Error committing the search indexes: {}", e);
+        }
     });
 
-    for (index, entry) in WalkDir::new(&settings.music_directory)
-        .follow_links(true)
-        .into_iter()
+    // The files split by a cue sheet are only scanned as the tracks of the
+    // sheet
+    let mut cue_sheets = vec![];
+    let mut cue_files = HashSet::new();
+    for entry in local_roots
+        .iter()
+        .flat_map(|root| WalkDir::new(root).follow_links(true).into_iter())
+        .filter_map(|e| e.ok())
+        .filter(|entry| {
+            entry
+                .path()
+                .extension()
+                .is_some_and(|extension| extension.eq_ignore_ascii_case("cue"))
+        })
+    {
+        match read_cue(entry.path()) {
+            Ok(sheet) => {
+                for (_, path) in sheet.files(entry.path()) {
+                    cue_files.insert(path);
+                }
+                cue_sheets.push((entry.path().to_path_buf(), sheet));
+            }
+            Err(e) => {
+                let error = ScanError {
+                    path: format!("{}", entry.path().display()),
+                    kind: ScanErrorKind::Io,
+                    message: e.to_string(),
+                };
+                report_error(&error, &cloned_db).await;
+            }
+        }
+    }
+
+    let mut library = ScannedLibrary {
+        songs: Vec::new(),
+        covers: HashMap::new(),
+        tx,
+    };
+    let mut archives = vec![];
+
+    for entry in local_roots
+        .iter()
+        .flat_map(|root| WalkDir::new(root).follow_links(true).into_iter())
         .filter_map(|e| e.ok())
-        .enumerate()
     {
+        if entry
+            .path()
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("zip"))
+        {
+            archives.push(entry.path().to_path_buf());
+            continue;
+        }
+
         let path = format!("{}", entry.path().display());
         let guess = mime_guess::from_path(&path);
         let mime = guess.first_or_octet_stream();
 
-        if supported_formats.iter().any(|x| x.to_owned() == mime) {
-            match Probe::open(&path)
-                .expect("ERROR: Bad path provided!")
-                .read()
-            {
-                Ok(tagged_file) => {
-                    let tag = match tagged_file.primary_tag() {
-                        Some(primary_tag) => primary_tag,
-                        // If the "primary" tag doesn't exist, we just grab the
-                        // first tag we can find. Realistically, a tag reader would likely
-                        // iterate through the tags to find a suitable one.
-                        None => tagged_file.first_tag().expect("ERROR: No tags found!"),
-                    };
+        if !supported_formats.iter().any(|x| *x == mime) {
+            continue;
+        }
+        if cue_files.contains(entry.path()) {
+            continue;
+        }
+
+        let (mut song, tagged_file) = match read_song(entry.path(), music_directory) {
+            Ok(result) => result,
+            Err(error) => {
+                report_error(&error, &cloned_db).await;
+                continue;
+            }
+        };
+        song.split_artists(&settings.artist_separators);
+        resolve_track_id(&mut song, &cloned_db).await;
+
+        let tag = match tagged_file.primary_tag() {
+            Some(primary_tag) => Some(primary_tag),
+            None => tagged_file.first_tag(),
+        };
+        let lyrics = find_lyrics(entry.path(), tag);
+        library
+            .add(song, entry.path(), tag, lyrics, &save, &cloned_db)
+            .await;
+    }
+
+    for (cue_path, sheet) in cue_sheets {
+        for (file, path) in sheet.files(&cue_path) {
+            let (file_song, tagged_file) = match read_song(&path, music_directory) {
+                Ok(result) => result,
+                Err(error) => {
+                    report_error(&error, &cloned_db).await;
+                    continue;
+                }
+            };
+            let tag = match tagged_file.primary_tag() {
+                Some(primary_tag) => Some(primary_tag),
+                None => tagged_file.first_tag(),
+            };
+            for mut song in sheet.songs(&file, &file_song) {
+                song.split_artists(&settings.artist_separators);
+                library.add(song, &path, tag, None, &save, &cloned_db).await;
+            }
+        }
+    }
+
+    // The audio files of an archive are read in place, without unpacking it
+    for archive in archives {
+        let entries = match list_entries(&archive) {
+            Ok(entries) => entries,
+            Err(e) => {
+                let error = ScanError {
+                    path: format!("{}", archive.display()),
+                    kind: ScanErrorKind::Io,
+                    message: e.to_string(),
+                };
+                report_error(&error, &cloned_db).await;
+                continue;
+            }
+        };
+        for entry in entries {
+            let mime = mime_guess::from_path(&entry.name).first_or_octet_stream();
+            if !supported_formats.iter().any(|x| mime == *x) {
+                continue;
+            }
+            let (mut song, tagged_file) = match read_archive_song(&entry, music_directory) {
+                Ok(result) => result,
+                Err(error) => {
+                    report_error(&error, &cloned_db).await;
+                    continue;
+                }
+            };
+            song.split_artists(&settings.artist_separators);
+            let tag = match tagged_file.primary_tag() {
+                Some(primary_tag) => Some(primary_tag),
+                None => tagged_file.first_tag(),
+            };
+            // The pictures next to the archive are the covers of its album
+            library
+                .add(song, &archive, tag, None, &save, &cloned_db)
+                .await;
+        }
+    }
 
-                    let properties = tagged_file.properties();
-                    let mut song: Song = tag.try_into().unwrap();
-                    song.with_properties(properties);
-                    song.uri = Some(path.clone());
+    // Only the ranges of the files holding the tags are downloaded
+    for root in settings
+        .library_roots
+        .iter()
+        .filter(|root| RemoteRoot::is_remote(root))
+    {
+        let files = match RemoteRoot::parse(root) {
+            Ok(root) => root.list().await.map(|files| (root, files)),
+            Err(e) => Err(e),
+        };
+        let (root, files) = match files {
+            Ok(result) => result,
+            Err(e) => {
+                let error = ScanError {
+                    path: root.rsplit('@').next().unwrap_or_default().to_string(),
+                    kind: ScanErrorKind::Io,
+                    message: e.to_string(),
+                };
+                report_error(&error, &cloned_db).await;
+                continue;
+            }
+        };
+        for file in files {
+            let mime = mime_guess::from_path(&file.uri).first_or_octet_stream();
+            if !supported_formats.iter().any(|x| mime == *x) {
+                continue;
+            }
+            let (mut song, tagged_file) = match read_remote_song(&root, &file.uri).await {
+                Ok(result) => result,
+                Err(error) => {
+                    report_error(&error, &cloned_db).await;
+                    continue;
+                }
+            };
+            song.split_artists(&settings.artist_separators);
+            let tag = match tagged_file.primary_tag() {
+                Some(primary_tag) => Some(primary_tag),
+                None => tagged_file.first_tag(),
+            };
+            library
+                .add(song, Path::new(&file.uri), tag, None, &save, &cloned_db)
+                .await;
+        }
+    }
 
-                    let album = song.album.clone();
-                    let cover = extract_and_save_album_cover(tag, &album);
-                    song.cover = cover.clone();
-                    save(&song, &cloned_db).await;
-                    songs.push(song);
+    let ScannedLibrary { songs, tx, .. } = library;
+    drop(tx);
+    indexer.join().unwrap();
 
-                    let mut track: Song = tag.try_into().unwrap();
-                    track.uri = Some(path.clone());
-                    track.cover = cover.clone();
-                    track.with_properties(properties);
+    // The playlist files of the library are imported once its tracks are saved
+    let mut matcher = TrackMatcher::default();
+    for entry in local_roots
+        .iter()
+        .flat_map(|root| WalkDir::new(root).follow_links(true).into_iter())
+        .filter_map(|e| e.ok())
+        .filter(|entry| PlaylistFormat::from_path(entry.path()).is_some())
+    {
+        if let Err(e) = import_playlist_file(entry.path(), db, &mut matcher).await {
+            let error = ScanError {
+                path: format!("{}", entry.path().display()),
+                kind: ScanErrorKind::Io,
+                message: e.to_string(),
+            };
+            report_error(&error, &cloned_db).await;
+        }
+    }
 
-                    let artist: Artist = tag.try_into().unwrap();
-                    let mut album: Album = tag.try_into().unwrap();
-                    album.cover = cover.clone();
+    if settings.analyze_audio {
+        match analyze_library(db, settings.write_replaygain_tags).await {
+            Ok(count) => println!("Analyzed {} tracks", count),
+            Err(e) => println!("Error analyzing tracks: {}", e),
+        }
+    }
 
-                    tx.send((album, track, artist, index)).unwrap();
+    // The artists of the previous scans no longer linked to any track, e.g.
+    // the tags split since then
+    match ArtistRepository::new(db.get_connection())
+        .delete_unused()
+        .await
+    {
+        Ok(artists) => {
+            for artist_id in &artists {
+                if let Err(e) = searcher.delete_artist(artist_id) {
+                    println!("Error deleting artist: {}", e);
                 }
-                Err(e) => println!("ERROR: {}, {}", e, path),
             }
         }
+        Err(e) => println!("Error deleting unused artists: {}", e),
     }
-    done_rx.recv().unwrap();
-    Ok(songs)
-}
 
-fn extract_and_save_album_cover(tag: &Tag, album: &str) -> Option<String> {
-    let pictures = tag.pictures();
-    if pictures.len() > 0 {
-        let covers_path = format!("{}/covers", get_application_directory());
-        let picture = &pictures[0];
-        let album = md5::compute(album.as_bytes());
-        let filename = format!("{}/{:x}", covers_path, album);
-        match picture.mime_type() {
-            lofty::MimeType::Jpeg => {
-                let filename = format!("{}.jpg", filename);
-                let mut file = std::fs::File::create(filename).unwrap();
-                file.write_all(picture.data()).unwrap();
-                Some(format!("{:x}.jpg", album))
+    match GenreRepository::new(db.get_connection())
+        .delete_unused()
+        .await
+    {
+        Ok(genres) => {
+            for genre_id in &genres {
+                if let Err(e) = searcher.delete_genre(genre_id) {
+                    println!("Error deleting genre: {}", e);
+                }
             }
-            lofty::MimeType::Png => {
-                let filename = format!("{}.png", filename);
-                let mut file = std::fs::File::create(filename).unwrap();
-                file.write_all(picture.data()).unwrap();
-                Some(format!("{:x}.png", album))
+            if let Err(e) = searcher.commit() {
+                println!("Error committing the search indexes: {}", e);
             }
-            _ => {
-                println!("Unsupported picture format");
-                None
+        }
+        Err(e) => println!("Error deleting unused genres: {}", e),
+    }
+
+    if let Err(e) = SmartPlaylistRepository::new(db.get_connection())
+        .refresh_all()
+        .await
+    {
+        println!("Error refreshing smart playlists: {}", e);
+    }
+    Ok(songs)
+}
+
+/// The songs saved by a scan, and the covers of their albums.
+struct ScannedLibrary {
+    songs: Vec<Song>,
+    covers: HashMap<String, Cover>,
+    tx: Sender<(Album, Song, Vec<Artist>)>,
+}
+
+impl ScannedLibrary {
+    async fn add<F>(
+        &mut self,
+        mut song: Song,
+        path: &Path,
+        tag: Option<&Tag>,
+        lyrics: Option<Lyrics>,
+        save: &F,
+        db: &Database,
+    ) where
+        F: for<'a> Fn(&'a Song, &'a Database) -> BoxFuture<'a, ()>,
+    {
+        let new_album = !self.covers.contains_key(&song.album);
+        if new_album {
+            match extract_and_save_album_cover(path, tag, &song.album) {
+                Ok(Some(cover)) => {
+                    self.covers.insert(song.album.clone(), cover);
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    let error = ScanError {
+                        path: format!("{}", path.display()),
+                        kind: ScanErrorKind::Cover,
+                        message: e.to_string(),
+                    };
+                    report_error(&error, db).await;
+                }
+            };
+        }
+        song.cover = self
+            .covers
+            .get(&song.album)
+            .map(|cover| cover.original.clone());
+
+        save(&song, db).await;
+        link_genres(&song, db).await;
+
+        if let (true, Some(cover)) = (new_album, self.covers.get(&song.album)) {
+            update_album_cover(&song.album, cover, db).await;
+        }
+
+        if let Some(lyrics) = lyrics {
+            save_lyrics(&song, &lyrics, db).await;
+        }
+        if let Some(rating) = song.rating {
+            import_rating(&song, rating, db).await;
+        }
+        self.songs.push(song.clone());
+
+        let artists = song
+            .linked_artists()
+            .into_iter()
+            .map(|name| Artist {
+                id: format!("{:x}", md5::compute(&name)),
+                name,
+                ..Default::default()
+            })
+            .collect();
+        let album = Album::from(&song);
+        self.tx.send((album, song, artists)).unwrap();
+    }
+}
+
+/// Reads the metadata of an audio file, falling back to the folder layout
+/// when the file has no tag at all.
+fn read_song(path: &Path, music_directory: &Path) -> Result<(Song, TaggedFile), ScanError> {
+    let tagged_file = Probe::open(path)
+        .and_then(|probe| probe.read())
+        .map_err(|e| ScanError {
+            path: format!("{}", path.display()),
+            kind: ScanErrorKind::from(&e),
+            message: e.to_string(),
+        })?;
+
+    // If the "primary" tag doesn't exist, we just grab the
+    // first tag we can find.
+    let tag = match tagged_file.primary_tag() {
+        Some(primary_tag) => Some(primary_tag),
+        None => tagged_file.first_tag(),
+    };
+
+    let mut song = match tag {
+        Some(tag) => Song::from(tag),
+        None => Song::from_path(path, music_directory),
+    };
+    song.with_properties(tagged_file.properties());
+    song.uri = Some(format!("{}", path.display()));
+    song.content_hash = audio_hash(path).ok();
+    Ok((song, tagged_file))
+}
+
+/// Reads the tags of an audio file stored in an archive.
+fn read_archive_song(
+    entry: &ArchiveEntry,
+    music_directory: &Path,
+) -> Result<(Song, TaggedFile), ScanError> {
+    let scan_error = |kind: ScanErrorKind, message: String| ScanError {
+        path: entry.uri(),
+        kind,
+        message,
+    };
+    let archive_file =
+        ArchiveFile::open(entry).map_err(|e| scan_error(ScanErrorKind::Io, e.to_string()))?;
+    let tagged_file = Probe::new(archive_file)
+        .guess_file_type()
+        .map_err(LoftyError::from)
+        .and_then(|probe| probe.read())
+        .map_err(|e| scan_error(ScanErrorKind::from(&e), e.to_string()))?;
+
+    let tag = match tagged_file.primary_tag() {
+        Some(primary_tag) => Some(primary_tag),
+        None => tagged_file.first_tag(),
+    };
+    let mut song = match tag {
+        Some(tag) => Song::from(tag),
+        None => Song::from_path(&entry.path(), music_directory),
+    };
+    song.with_properties(tagged_file.properties());
+    song.uri = Some(entry.uri());
+    Ok((song, tagged_file))
+}
+
+/// Reads the tags of a file of a remote library root through ranged
+/// requests.
+async fn read_remote_song(root: &RemoteRoot, uri: &str) -> Result<(Song, TaggedFile), ScanError> {
+    let scan_error = |kind: ScanErrorKind, message: String| ScanError {
+        path: uri.to_string(),
+        kind,
+        message,
+    };
+    let request = root
+        .request(uri)
+        .map_err(|e| scan_error(ScanErrorKind::Io, e.to_string()))?;
+    let audio_file = RemoteAudioFile::open_request(uri, request, REMOTE_BYTES_PER_SECOND)
+        .await
+        .map_err(|e| scan_error(ScanErrorKind::Io, e.to_string()))?;
+    let tagged_file = tokio::task::spawn_blocking(move || -> Result<TaggedFile, LoftyError> {
+        Probe::new(audio_file).guess_file_type()?.read()
+    })
+    .await
+    .map_err(|e| scan_error(ScanErrorKind::Io, e.to_string()))?
+    .map_err(|e| scan_error(ScanErrorKind::from(&e), e.to_string()))?;
+
+    let tag = match tagged_file.primary_tag() {
+        Some(primary_tag) => Some(primary_tag),
+        None => tagged_file.first_tag(),
+    };
+    let mut song = match tag {
+        Some(tag) => Song::from(tag),
+        None => Song::from_path(&root.path(uri), Path::new("")),
+    };
+    song.with_properties(tagged_file.properties());
+    song.uri = Some(uri.to_string());
+    Ok((song, tagged_file))
+}
+
+/// Reuses the id of the track previously stored for the same audio content
+/// when its file is gone, so that moving or renaming a file keeps it in the
+/// playlists referencing it. Archive entries, remote files and the tracks of
+/// a cue sheet can't be told gone from the file system and are never taken.
+async fn resolve_track_id(song: &mut Song, db: &Database) {
+    let repo = TrackRepository::new(db.get_connection());
+    let uri = song.uri.clone().unwrap_or_default();
+    let content_hash = match &song.content_hash {
+        Some(content_hash) => content_hash.clone(),
+        None => return,
+    };
+
+    match repo.find_by_uri(&uri).await {
+        Ok(Some(track)) => {
+            // Rows stored before content hashes were introduced, or hashed
+            // by an older version
+            if track.content_hash.as_deref() != Some(content_hash.as_str()) {
+                if let Err(e) = repo.set_content_hash(&track.id, &content_hash).await {
+                    println!("Error saving content hash: {}", e);
+                }
             }
+            song.id = Some(track.id);
+            return;
         }
-    } else {
-        None
+        Ok(None) => {}
+        Err(e) => {
+            println!("Error looking up track: {}", e);
+            return;
+        }
+    }
+
+    let moved = repo
+        .find_by_content(&content_hash)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find(|track| has_own_file(track) && !Path::new(&track.uri).exists());
+    if let Some(track) = moved {
+        println!("Relinking {} to {}", track.uri, uri);
+        match repo.relink(&track.id, &uri, &content_hash).await {
+            Ok(_) => song.id = Some(track.id),
+            Err(e) => println!("Error relinking track: {}", e),
+        }
+    }
+}
+
+/// Saves the error of a file, replacing the one of the same kind reported
+/// earlier for the same file.
+/// Saves a scanned song, the rows already stored are updated with the tags
+/// read from the file.
+pub async fn save_song(song: &Song, db: &Database) {
+    if let Err(e) = upsert_song(song, db).await {
+        println!("Error saving {}: {}", song.location(), e);
+    }
+}
+
+async fn upsert_song(song: &Song, db: &Database) -> Result<(), DbErr> {
+    let txn = db.get_connection().begin().await?;
+
+    for item in artist_entity::ActiveModel::from_artists(song) {
+        artist_entity::Entity::insert(item)
+            .on_conflict(
+                OnConflict::column(artist_entity::Column::Id)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec(&txn)
+            .await?;
+    }
+
+    // The upserts don't run the hooks dating the rows they create, the covers
+    // are saved once per album by `update_album_cover`
+    let item = album_entity::ActiveModel::from(song).before_save(true)?;
+    album_entity::Entity::insert(item)
+        .on_conflict(
+            OnConflict::column(album_entity::Column::Id)
+                .update_columns([
+                    album_entity::Column::Title,
+                    album_entity::Column::Artist,
+                    album_entity::Column::ArtistId,
+                    album_entity::Column::Year,
+                    album_entity::Column::Compilation,
+                    album_entity::Column::Label,
+                    album_entity::Column::TitleSort,
+                    album_entity::Column::ArtistSort,
+                    album_entity::Column::MusicbrainzReleaseId,
+                ])
+                .to_owned(),
+        )
+        .exec(&txn)
+        .await?;
+
+    // The analysis columns are left to the analysis pass
+    let item = track_entity::ActiveModel::from(song).before_save(true)?;
+    track_entity::Entity::insert(item)
+        .on_conflict(
+            OnConflict::column(track_entity::Column::Id)
+                .update_columns([
+                    track_entity::Column::Title,
+                    track_entity::Column::Artist,
+                    track_entity::Column::Genre,
+                    track_entity::Column::Year,
+                    track_entity::Column::Track,
+                    track_entity::Column::Bitrate,
+                    track_entity::Column::SampleRate,
+                    track_entity::Column::BitDepth,
+                    track_entity::Column::Channels,
+                    track_entity::Column::Duration,
+                    track_entity::Column::Uri,
+                    track_entity::Column::AlbumId,
+                    track_entity::Column::ArtistId,
+                    track_entity::Column::DiscNumber,
+                    track_entity::Column::DiscTotal,
+                    track_entity::Column::TrackTotal,
+                    track_entity::Column::Composer,
+                    track_entity::Column::Conductor,
+                    track_entity::Column::Isrc,
+                    track_entity::Column::Comment,
+                    track_entity::Column::Bpm,
+                    track_entity::Column::TitleSort,
+                    track_entity::Column::ArtistSort,
+                    track_entity::Column::MusicbrainzRecordingId,
+                    track_entity::Column::ContentHash,
+                    track_entity::Column::StartOffset,
+                    track_entity::Column::EndOffset,
+                ])
+                .to_owned(),
+        )
+        .exec(&txn)
+        .await?;
+
+    // The links of a rescanned track are replaced, its artists may have been
+    // split differently
+    artist_tracks_entity::Entity::delete_many()
+        .filter(artist_tracks_entity::Column::TrackId.eq(song.track_id()))
+        .exec(&txn)
+        .await?;
+    for item in artist_tracks_entity::ActiveModel::from_artists(song) {
+        item.insert(&txn).await?;
+    }
+
+    txn.commit().await
+}
+
+async fn report_error(error: &ScanError, db: &Database) {
+    println!("ERROR: {}, {}", error.message, error.path);
+    let item: scan_error::ActiveModel = error.into();
+    let result = scan_error::Entity::insert(item)
+        .on_conflict(
+            OnConflict::column(scan_error::Column::Id)
+                .update_columns([scan_error::Column::Message, scan_error::Column::CreatedAt])
+                .to_owned(),
+        )
+        .exec(db.get_connection())
+        .await;
+    if let Err(e) = result {
+        println!("Error saving scan error: {}", e);
+    }
+}
+
+fn extract_and_save_album_cover(
+    path: &Path,
+    tag: Option<&Tag>,
+    album: &str,
+) -> Result<Option<Cover>, Error> {
+    // Embedded artwork wins over the pictures lying in the album folder
+    let (data, extension) = match tag.and_then(embedded_cover) {
+        Some((data, extension)) => (data, extension.to_owned()),
+        None => match path.parent().and_then(folder_cover) {
+            Some(cover_path) => read_folder_cover(&cover_path)?,
+            None => return Ok(None),
+        },
+    };
+    save_cover(&data, &extension, album).map(Some)
+}
+
+async fn update_album_cover(album: &str, cover: &Cover, db: &Database) {
+    let id = format!("{:x}", md5::compute(album.as_bytes()));
+    let result = album_entity::Entity::update_many()
+        .col_expr(
+            album_entity::Column::Cover,
+            Expr::value(cover.original.clone()),
+        )
+        .col_expr(
+            album_entity::Column::CoverSmall,
+            Expr::value(cover.small.clone()),
+        )
+        .col_expr(
+            album_entity::Column::CoverMedium,
+            Expr::value(cover.medium.clone()),
+        )
+        .col_expr(
+            album_entity::Column::CoverLarge,
+            Expr::value(cover.large.clone()),
+        )
+        .col_expr(
+            album_entity::Column::CoverSmallWebp,
+            Expr::value(cover.small_webp.clone()),
+        )
+        .col_expr(
+            album_entity::Column::CoverMediumWebp,
+            Expr::value(cover.medium_webp.clone()),
+        )
+        .col_expr(
+            album_entity::Column::CoverLargeWebp,
+            Expr::value(cover.large_webp.clone()),
+        )
+        .filter(album_entity::Column::Id.eq(id))
+        .exec(db.get_connection())
+        .await;
+    match result {
+        Ok(_) => (),
+        Err(e) => println!("Error saving album cover: {}", e),
+    }
+}
+
+async fn save_lyrics(song: &Song, lyrics: &Lyrics, db: &Database) {
+    let track_id = song.track_id();
+    let item = lyrics_entity::ActiveModel::from_lyrics(&track_id, lyrics);
+    match LyricsRepository::new(db.get_connection()).save(item).await {
+        Ok(_) => (),
+        Err(e) => println!("Error saving lyrics: {}", e),
+    }
+}
+
+async fn link_genres(song: &Song, db: &Database) {
+    match GenreRepository::new(db.get_connection())
+        .link_track(&song.track_id(), &song.genre)
+        .await
+    {
+        Ok(_) => (),
+        Err(e) => println!("Error linking genres: {}", e),
+    }
+}
+
+async fn import_rating(song: &Song, rating: u8, db: &Database) {
+    match RatingRepository::new(db.get_connection())
+        .import_rating(&song.track_id(), rating)
+        .await
+    {
+        Ok(_) => (),
+        Err(e) => println!("Error importing rating: {}", e),
     }
 }
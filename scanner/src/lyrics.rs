@@ -0,0 +1,70 @@
+use std::{fs, path::Path};
+
+use lofty::{
+    id3::v2::{SynchronizedText, TimestampFormat},
+    ItemKey, Tag, TagType,
+};
+use music_player_types::types::{format_lrc_timestamp, Lyrics, LyricsSource};
+
+/// Picks the lyrics of an audio file: synced lyrics win over plain ones, and
+/// embedded lyrics win over the `.lrc`/`.txt` files lying next to it.
+pub fn find_lyrics(path: &Path, tag: Option<&Tag>) -> Option<Lyrics> {
+    let candidates: Vec<Lyrics> = [
+        tag.and_then(embedded_lyrics),
+        sidecar_lyrics(path, "lrc", LyricsSource::Lrc),
+        sidecar_lyrics(path, "txt", LyricsSource::Txt),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    candidates
+        .iter()
+        .find(|lyrics| lyrics.synced)
+        .or_else(|| candidates.first())
+        .cloned()
+}
+
+/// Reads the lyrics stored in the tag: ID3 SYLT frames first, then the
+/// unsynchronised lyrics (ID3 USLT, Vorbis LYRICS, MP4 ©lyr).
+pub fn embedded_lyrics(tag: &Tag) -> Option<Lyrics> {
+    if let Some(lyrics) = synchronized_lyrics(tag) {
+        return Some(lyrics);
+    }
+    let content = match tag.tag_type() {
+        // lofty splits ID3v2 text frames on '/', join them back
+        TagType::ID3v2 => tag
+            .get_strings(&ItemKey::Lyrics)
+            .collect::<Vec<_>>()
+            .join("/"),
+        _ => tag.get_string(&ItemKey::Lyrics)?.to_string(),
+    };
+    match content.trim().is_empty() {
+        true => None,
+        false => Some(Lyrics::new(&content, LyricsSource::Embedded)),
+    }
+}
+
+/// SYLT frames are not mapped by lofty, they are kept as raw frame content.
+fn synchronized_lyrics(tag: &Tag) -> Option<Lyrics> {
+    let data = tag.get_binary(&ItemKey::Unknown("SYLT".to_string()), false)?;
+    let text = SynchronizedText::parse(data).ok()?;
+    if text.information.timestamp_format != TimestampFormat::MS || text.content.is_empty() {
+        return None;
+    }
+    let content = text
+        .content
+        .iter()
+        .map(|(time_ms, line)| format!("{}{}", format_lrc_timestamp(*time_ms), line.trim()))
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(Lyrics::new(&content, LyricsSource::Embedded))
+}
+
+/// Reads `<track>.lrc` or `<track>.txt` from the directory of the audio file.
+pub fn sidecar_lyrics(path: &Path, extension: &str, source: LyricsSource) -> Option<Lyrics> {
+    let content = fs::read_to_string(path.with_extension(extension)).ok()?;
+    match content.trim().is_empty() {
+        true => None,
+        false => Some(Lyrics::new(&content, source)),
+    }
+}
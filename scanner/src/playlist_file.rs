@@ -0,0 +1,616 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    path::{Component, Path, PathBuf},
+    str::FromStr,
+};
+
+use anyhow::Error;
+use music_player_entity::{playlist as playlist_entity, track as track_entity};
+use music_player_settings::{read_settings, Settings};
+use music_player_storage::{
+    repo::{playlist::PlaylistRepository, track::TrackRepository},
+    Database,
+};
+use music_player_types::types::{Playlist, Track};
+use percent_encoding::percent_decode_str;
+use quick_xml::{escape::escape, events::Event, Reader};
+use sea_orm::EntityTrait;
+use url::Url;
+
+use crate::duplicates::{normalize, ARTIST_SIMILARITY, TITLE_SIMILARITY};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    M3u8,
+    Pls,
+    Xspf,
+}
+
+impl FromStr for PlaylistFormat {
+    type Err = Error;
+
+    /// Parses the name or the extension of a format, M3U files being read as
+    /// M3U8 ones.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim_start_matches('.').to_ascii_lowercase().as_str() {
+            "m3u" | "m3u8" => Ok(Self::M3u8),
+            "pls" => Ok(Self::Pls),
+            "xspf" => Ok(Self::Xspf),
+            _ => Err(Error::msg(format!(
+                "Unsupported playlist format: {}",
+                value
+            ))),
+        }
+    }
+}
+
+impl PlaylistFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        path.extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.parse().ok())
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::M3u8 => "m3u8",
+            Self::Pls => "pls",
+            Self::Xspf => "xspf",
+        }
+    }
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            Self::M3u8 => "audio/x-mpegurl",
+            Self::Pls => "audio/x-scpls",
+            Self::Xspf => "application/xspf+xml",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistEntry {
+    /// A path, absolute or relative to the playlist file, or a URL
+    pub location: String,
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    /// In seconds
+    pub duration: Option<u32>,
+}
+
+impl fmt::Display for PlaylistEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.artist, &self.title) {
+            (Some(artist), Some(title)) => write!(f, "{} - {}", artist, title),
+            (None, Some(title)) => write!(f, "{}", title),
+            _ => write!(f, "{}", self.location),
+        }
+    }
+}
+
+impl From<&Track> for PlaylistEntry {
+    fn from(track: &Track) -> Self {
+        Self {
+            location: track.uri.clone(),
+            title: Some(track.title.clone()),
+            artist: Some(track.artist.clone()).filter(|artist| !artist.is_empty()),
+            duration: track.duration.map(|duration| duration.round() as u32),
+        }
+    }
+}
+
+impl From<&track_entity::Model> for PlaylistEntry {
+    fn from(track: &track_entity::Model) -> Self {
+        Self {
+            location: track.uri.clone(),
+            title: Some(track.title.clone()),
+            artist: Some(track.artist.clone()).filter(|artist| !artist.is_empty()),
+            duration: track.duration.map(|duration| duration.round() as u32),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlaylistFile {
+    pub title: Option<String>,
+    pub entries: Vec<PlaylistEntry>,
+}
+
+impl From<&Playlist> for PlaylistFile {
+    fn from(playlist: &Playlist) -> Self {
+        Self {
+            title: Some(playlist.name.clone()),
+            entries: playlist.tracks.iter().map(Into::into).collect(),
+        }
+    }
+}
+
+/// Reads a playlist file, which may be encoded in UTF-8, with or without a
+/// byte order mark, or in Latin-1 as the M3U files of older players.
+pub fn read_playlist(format: PlaylistFormat, path: &Path) -> Result<PlaylistFile, Error> {
+    decode_playlist(format, &fs::read(path)?)
+}
+
+/// Parses the raw content of a playlist file, decoded like `read_playlist`.
+pub fn decode_playlist(format: PlaylistFormat, data: &[u8]) -> Result<PlaylistFile, Error> {
+    let data = data.strip_prefix(b"\xef\xbb\xbf").unwrap_or(data);
+    let content = match std::str::from_utf8(data) {
+        Ok(content) => content.to_string(),
+        Err(_) => data.iter().map(|byte| *byte as char).collect(),
+    };
+    parse_playlist(format, &content)
+}
+
+pub fn parse_playlist(format: PlaylistFormat, content: &str) -> Result<PlaylistFile, Error> {
+    match format {
+        PlaylistFormat::M3u8 => Ok(parse_m3u(content)),
+        PlaylistFormat::Pls => Ok(parse_pls(content)),
+        PlaylistFormat::Xspf => parse_xspf(content),
+    }
+}
+
+fn parse_m3u(content: &str) -> PlaylistFile {
+    let mut file = PlaylistFile::default();
+    let mut info = None;
+    for line in content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+    {
+        if let Some(value) = line.strip_prefix("#EXTINF:") {
+            // #EXTINF:288,Artist - Title, the duration being -1 when unknown
+            let (duration, name) = value.split_once(',').unwrap_or((value, ""));
+            info = Some((
+                parse_duration(duration.split_whitespace().next()),
+                name.trim(),
+            ));
+        } else if let Some(title) = line.strip_prefix("#PLAYLIST:") {
+            file.title = Some(title.trim().to_string());
+        } else if !line.starts_with('#') {
+            let mut entry = PlaylistEntry {
+                location: line.to_string(),
+                ..Default::default()
+            };
+            if let Some((duration, name)) = info.take() {
+                (entry.artist, entry.title) = split_name(name);
+                entry.duration = duration;
+            }
+            file.entries.push(entry);
+        }
+    }
+    file
+}
+
+fn parse_pls(content: &str) -> PlaylistFile {
+    let mut entries: BTreeMap<u32, PlaylistEntry> = BTreeMap::new();
+    for line in content.lines() {
+        let (key, value) = match line.trim().split_once('=') {
+            Some((key, value)) => (key.trim().to_ascii_lowercase(), value.trim()),
+            None => continue,
+        };
+        let (field, number) =
+            key.split_at(key.trim_end_matches(|c: char| c.is_ascii_digit()).len());
+        let entry = match number.parse() {
+            Ok(number) => entries.entry(number).or_default(),
+            Err(_) => continue,
+        };
+        match field {
+            "file" => entry.location = value.to_string(),
+            "title" => (entry.artist, entry.title) = split_name(value),
+            "length" => entry.duration = parse_duration(Some(value)),
+            _ => {}
+        }
+    }
+    PlaylistFile {
+        title: None,
+        entries: entries
+            .into_values()
+            .filter(|entry| !entry.location.is_empty())
+            .collect(),
+    }
+}
+
+fn parse_xspf(content: &str) -> Result<PlaylistFile, Error> {
+    let mut reader = Reader::from_str(content);
+    reader.trim_text(true);
+
+    let mut file = PlaylistFile::default();
+    let mut entry: Option<PlaylistEntry> = None;
+    let mut element = vec![];
+    loop {
+        match reader.read_event()? {
+            Event::Start(start) => {
+                element = start.local_name().as_ref().to_vec();
+                if element == b"track" {
+                    entry = Some(PlaylistEntry::default());
+                }
+            }
+            Event::Text(text) => {
+                let text = text.unescape()?.to_string();
+                match (element.as_slice(), entry.as_mut()) {
+                    (b"location", Some(entry)) if entry.location.is_empty() => {
+                        entry.location = decode_location(&text)
+                    }
+                    (b"title", Some(entry)) => entry.title = Some(text),
+                    (b"creator", Some(entry)) => entry.artist = Some(text),
+                    (b"duration", Some(entry)) => {
+                        entry.duration = text.parse::<u32>().ok().map(|duration| duration / 1000)
+                    }
+                    (b"title", None) => file.title = Some(text),
+                    _ => {}
+                }
+            }
+            Event::End(end) => {
+                if end.local_name().as_ref() == b"track" {
+                    file.entries
+                        .extend(entry.take().filter(|entry| !entry.location.is_empty()));
+                }
+                element.clear();
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(file)
+}
+
+/// The locations of XSPF files are URIs, the file URLs and the relative ones
+/// are decoded to paths like the ones of the other formats.
+fn decode_location(location: &str) -> String {
+    match Url::parse(location) {
+        Ok(url) => match url.to_file_path() {
+            Ok(path) if url.scheme() == "file" => path.to_string_lossy().to_string(),
+            _ => location.to_string(),
+        },
+        Err(_) => percent_decode_str(location).decode_utf8_lossy().to_string(),
+    }
+}
+
+/// Parses a duration in seconds, negative ones meaning an unknown duration.
+fn parse_duration(value: Option<&str>) -> Option<u32> {
+    value
+        .and_then(|value| value.parse::<f32>().ok())
+        .filter(|duration| *duration >= 0.0)
+        .map(|duration| duration.round() as u32)
+}
+
+/// Splits "Artist - Title" into the artist and the title.
+fn split_name(name: &str) -> (Option<String>, Option<String>) {
+    match name.split_once(" - ") {
+        _ if name.is_empty() => (None, None),
+        Some((artist, title)) => (
+            Some(artist.trim().to_string()),
+            Some(title.trim().to_string()),
+        ),
+        None => (None, Some(name.to_string())),
+    }
+}
+
+pub fn write_playlist(format: PlaylistFormat, file: &PlaylistFile) -> String {
+    match format {
+        PlaylistFormat::M3u8 => write_m3u(file),
+        PlaylistFormat::Pls => write_pls(file),
+        PlaylistFormat::Xspf => write_xspf(file),
+    }
+}
+
+fn write_m3u(file: &PlaylistFile) -> String {
+    let mut content = String::from("#EXTM3U\n");
+    if let Some(title) = &file.title {
+        content.push_str(&format!("#PLAYLIST:{}\n", title));
+    }
+    for entry in &file.entries {
+        if entry.title.is_some() {
+            let duration = entry.duration.map(|duration| duration as i64).unwrap_or(-1);
+            content.push_str(&format!("#EXTINF:{},{}\n", duration, entry));
+        }
+        content.push_str(&format!("{}\n", entry.location));
+    }
+    content
+}
+
+fn write_pls(file: &PlaylistFile) -> String {
+    let mut content = String::from("[playlist]\n");
+    for (index, entry) in file.entries.iter().enumerate() {
+        let number = index + 1;
+        content.push_str(&format!("File{}={}\n", number, entry.location));
+        if entry.title.is_some() {
+            content.push_str(&format!("Title{}={}\n", number, entry));
+        }
+        let duration = entry.duration.map(|duration| duration as i64).unwrap_or(-1);
+        content.push_str(&format!("Length{}={}\n", number, duration));
+    }
+    content.push_str(&format!("NumberOfEntries={}\n", file.entries.len()));
+    content.push_str("Version=2\n");
+    content
+}
+
+fn write_xspf(file: &PlaylistFile) -> String {
+    let mut content = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<playlist version=\"1\" xmlns=\"http://xspf.org/ns/0/\">\n",
+    );
+    if let Some(title) = &file.title {
+        content.push_str(&format!("  <title>{}</title>\n", escape(title)));
+    }
+    content.push_str("  <trackList>\n");
+    for entry in &file.entries {
+        // Absolute paths are written as file URLs
+        let location = match Path::new(&entry.location).is_absolute() {
+            true => Url::from_file_path(&entry.location)
+                .map(|url| url.to_string())
+                .unwrap_or_else(|_| entry.location.clone()),
+            false => entry.location.clone(),
+        };
+        content.push_str("    <track>\n");
+        content.push_str(&format!(
+            "      <location>{}</location>\n",
+            escape(&location)
+        ));
+        if let Some(title) = &entry.title {
+            content.push_str(&format!("      <title>{}</title>\n", escape(title)));
+        }
+        if let Some(artist) = &entry.artist {
+            content.push_str(&format!("      <creator>{}</creator>\n", escape(artist)));
+        }
+        if let Some(duration) = entry.duration {
+            content.push_str(&format!("      <duration>{}</duration>\n", duration * 1000));
+        }
+        content.push_str("    </track>\n");
+    }
+    content.push_str("  </trackList>\n</playlist>\n");
+    content
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ResolvedPlaylist {
+    pub track_ids: Vec<String>,
+    /// The entries matching no track of the library
+    pub missing: Vec<PlaylistEntry>,
+}
+
+/// Finds the tracks of the library the entries point to: by the URL of the
+/// track on the webui server, by path, relative paths being resolved from
+/// `base`, and at last by artist and title, as written in the entry or in the
+/// name of the file, for the playlists made on another computer.
+pub async fn resolve_entries(
+    db: &Database,
+    entries: &[PlaylistEntry],
+    base: Option<&Path>,
+) -> Result<ResolvedPlaylist, Error> {
+    TrackMatcher::default().resolve(db, entries, base).await
+}
+
+/// Matches the entries of playlists with the tracks of the library, see
+/// `resolve_entries`. The tracks compared by artist and title are loaded
+/// once, when an entry first matches no path, and reused for the following
+/// playlists, e.g. all the playlist files found by a scan.
+#[derive(Default)]
+pub struct TrackMatcher {
+    candidates: Option<Vec<track_entity::Model>>,
+}
+
+impl TrackMatcher {
+    pub async fn resolve(
+        &mut self,
+        db: &Database,
+        entries: &[PlaylistEntry],
+        base: Option<&Path>,
+    ) -> Result<ResolvedPlaylist, Error> {
+        let repo = TrackRepository::new(db.get_connection());
+        let mut resolved = ResolvedPlaylist::default();
+
+        for entry in entries {
+            let mut track = match track_url_id(&entry.location) {
+                Some(id) => {
+                    track_entity::Entity::find_by_id(id)
+                        .one(db.get_connection())
+                        .await?
+                }
+                None => repo.find_by_uri(&entry.location).await?,
+            };
+            if track.is_none() {
+                if let Some(path) = location_path(&entry.location, base) {
+                    track = repo.find_by_uri(&path.to_string_lossy()).await?;
+                }
+            }
+            if track.is_none() {
+                if self.candidates.is_none() {
+                    self.candidates = Some(
+                        track_entity::Entity::find()
+                            .all(db.get_connection())
+                            .await?,
+                    );
+                }
+                if let Some(candidates) = &self.candidates {
+                    track = closest_track(entry, candidates).cloned();
+                }
+            }
+            match track {
+                Some(track) => resolved.track_ids.push(track.id),
+                None => resolved.missing.push(entry.clone()),
+            }
+        }
+        Ok(resolved)
+    }
+}
+
+/// The id of a track streamed by the webui server, from its URL
+/// http://host:port/tracks/{id}.
+fn track_url_id(location: &str) -> Option<String> {
+    let url = Url::parse(location).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+    let segments: Vec<&str> = url.path_segments()?.collect();
+    match segments.as_slice() {
+        [.., "tracks", id] if !id.is_empty() => Some(id.to_string()),
+        _ => None,
+    }
+}
+
+fn location_path(location: &str, base: Option<&Path>) -> Option<PathBuf> {
+    // Windows paths such as C:\Music parse as URLs of scheme "c"
+    if let Ok(url) = Url::parse(location) {
+        if url.scheme().len() > 1 {
+            return match url.scheme() {
+                "file" => url.to_file_path().ok(),
+                _ => None,
+            };
+        }
+    }
+    let path = PathBuf::from(location.replace('\\', "/"));
+    let path = match base {
+        Some(base) if path.is_relative() => base.join(path),
+        _ => path,
+    };
+    // Without touching the filesystem, the file may have moved
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    Some(normalized)
+}
+
+/// The track of the library closest to the artist and title of the entry.
+fn closest_track<'a>(
+    entry: &PlaylistEntry,
+    candidates: &'a [track_entity::Model],
+) -> Option<&'a track_entity::Model> {
+    let (artist, title) = match entry.title {
+        Some(_) => (entry.artist.clone(), entry.title.clone()),
+        None => file_name_parts(&entry.location),
+    };
+    let title = normalize(&title?);
+    if title.is_empty() {
+        return None;
+    }
+    let artist = artist.map(|artist| normalize(&artist));
+    candidates
+        .iter()
+        .filter_map(|track| {
+            let title_score = strsim::jaro_winkler(&title, &normalize(&track.title));
+            if title_score < TITLE_SIMILARITY {
+                return None;
+            }
+            let artist_score = match &artist {
+                Some(artist) => strsim::jaro_winkler(artist, &normalize(&track.artist)),
+                None => 1.0,
+            };
+            if artist_score < ARTIST_SIMILARITY {
+                return None;
+            }
+            Some((title_score + artist_score, track))
+        })
+        .max_by(|(a, _), (b, _)| a.total_cmp(b))
+        .map(|(_, track)| track)
+}
+
+/// The artist and title of a file named "01 - Artist - Title.mp3".
+fn file_name_parts(location: &str) -> (Option<String>, Option<String>) {
+    let location = percent_decode_str(location)
+        .decode_utf8_lossy()
+        .replace('\\', "/");
+    let stem = match Path::new(&location).file_stem() {
+        Some(stem) => stem.to_string_lossy().to_string(),
+        None => return (None, None),
+    };
+    let name = stem
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches([' ', '-', '.', '_']);
+    match name.is_empty() {
+        true => split_name(&stem),
+        false => split_name(name),
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ImportedPlaylist {
+    pub id: String,
+    pub name: String,
+    pub track_count: usize,
+    pub missing: Vec<PlaylistEntry>,
+}
+
+/// Saves the entries of `file` as the tracks of the playlist `id`, replacing
+/// the previous ones when it exists. Relative paths are resolved from `base`,
+/// or from the music directory for the uploaded files.
+pub async fn import_playlist(
+    db: &Database,
+    id: &str,
+    name: &str,
+    file: &PlaylistFile,
+    base: Option<&Path>,
+) -> Result<ImportedPlaylist, Error> {
+    save_playlist(db, &mut TrackMatcher::default(), id, name, file, base).await
+}
+
+async fn save_playlist(
+    db: &Database,
+    matcher: &mut TrackMatcher,
+    id: &str,
+    name: &str,
+    file: &PlaylistFile,
+    base: Option<&Path>,
+) -> Result<ImportedPlaylist, Error> {
+    let base = match base {
+        Some(base) => base.to_path_buf(),
+        None => {
+            let settings = read_settings()?.try_deserialize::<Settings>()?;
+            PathBuf::from(settings.music_directory)
+        }
+    };
+    let resolved = matcher.resolve(db, &file.entries, Some(&base)).await?;
+    PlaylistRepository::new(db.get_connection())
+        .replace(id, name, &resolved.track_ids)
+        .await?;
+    Ok(ImportedPlaylist {
+        id: id.to_string(),
+        name: name.to_string(),
+        track_count: resolved.track_ids.len(),
+        missing: resolved.missing,
+    })
+}
+
+/// Imports a playlist file of the library, the id of the playlist being
+/// derived from the path so that scanning again updates the same playlist.
+/// The file is only imported again once its content changed, the edits made
+/// to the playlist in the meantime being kept otherwise: returns `None` when
+/// the file is unchanged.
+pub async fn import_playlist_file(
+    path: &Path,
+    db: &Database,
+    matcher: &mut TrackMatcher,
+) -> Result<Option<ImportedPlaylist>, Error> {
+    let format = PlaylistFormat::from_path(path)
+        .ok_or_else(|| Error::msg(format!("Unsupported playlist file: {}", path.display())))?;
+    let data = fs::read(path)?;
+    let file_hash = format!("{:x}", md5::compute(&data));
+    let id = format!("{:x}", md5::compute(path.to_string_lossy().as_bytes()));
+    let imported = playlist_entity::Entity::find_by_id(id.clone())
+        .one(db.get_connection())
+        .await?;
+    if imported.and_then(|playlist| playlist.file_hash) == Some(file_hash.clone()) {
+        return Ok(None);
+    }
+
+    let file = decode_playlist(format, &data)?;
+    let name = match &file.title {
+        Some(title) => title.clone(),
+        None => path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    let imported = save_playlist(db, matcher, &id, &name, &file, path.parent()).await?;
+    PlaylistRepository::new(db.get_connection())
+        .set_file_hash(&id, &file_hash)
+        .await?;
+    Ok(Some(imported))
+}
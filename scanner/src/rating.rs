@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use anyhow::Error;
+use lofty::{Probe, TagExt};
+use music_player_audio::{archive::ArchiveEntry, remote::RemoteRoot};
+use music_player_entity::{track as track_entity, track_rating as track_rating_entity};
+use music_player_storage::Database;
+use music_player_types::rating::write_rating;
+use sea_orm::EntityTrait;
+
+/// Writes the stars into the tag of a file, a POPM frame for ID3v2 tags and
+/// FMPS_Rating for the other formats.
+pub fn write_rating_tag(path: &Path, stars: u8) -> Result<(), Error> {
+    let mut tagged_file = Probe::open(path)?.read()?;
+    let tag = tagged_file
+        .primary_tag_mut()
+        .ok_or_else(|| Error::msg("The file has no tag"))?;
+    write_rating(tag, stars);
+    tag.save_to_path(path)?;
+    Ok(())
+}
+
+/// Whether the track is the only one of a plain local file. The file of a cue
+/// sheet holds several tracks, a single tag can't carry their ratings.
+/// Archives and remote files are left untouched.
+pub(crate) fn has_own_file(track: &track_entity::Model) -> bool {
+    track.start_offset.is_none()
+        && !ArchiveEntry::is_archive_entry(&track.uri)
+        && !RemoteRoot::is_remote(&track.uri)
+}
+
+/// Writes the current rating of a track into its file, 0 stars when the
+/// track isn't rated.
+pub async fn export_rating(db: &Database, track_id: &str) -> Result<(), Error> {
+    let track = track_entity::Entity::find_by_id(track_id.to_string())
+        .one(db.get_connection())
+        .await?
+        .ok_or_else(|| Error::msg("Track not found"))?;
+    if !has_own_file(&track) {
+        return Ok(());
+    }
+    let stars = track_rating_entity::Entity::find_by_id(track_id.to_string())
+        .one(db.get_connection())
+        .await?
+        .map(|rating| rating.rating)
+        .unwrap_or_default();
+    write_rating_tag(Path::new(&track.uri), stars)
+}
+
+/// Writes the ratings of the library into the files of the rated tracks.
+/// Returns the number of files written.
+pub async fn export_ratings(db: &Database) -> Result<usize, Error> {
+    let ratings = track_rating_entity::Entity::find()
+        .find_also_related(track_entity::Entity)
+        .all(db.get_connection())
+        .await?;
+
+    let mut count = 0;
+    for (rating, track) in ratings {
+        let track = match track {
+            Some(track) if has_own_file(&track) => track,
+            _ => continue,
+        };
+        match write_rating_tag(Path::new(&track.uri), rating.rating) {
+            Ok(_) => count += 1,
+            Err(e) => println!("Error writing the rating of {}: {}", track.uri, e),
+        }
+    }
+    Ok(count)
+}
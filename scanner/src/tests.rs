@@ -1,6 +1,7 @@
 use futures::future::FutureExt;
 use music_player_entity::{album, artist, artist_tracks, track};
 use music_player_storage::{searcher::Searcher, Database};
+use music_player_types::types::LyricsSource;
 use sea_orm::{ActiveModelTrait, EntityTrait, PaginatorTrait};
 use std::env;
 
@@ -18,10 +19,11 @@ async fn scan_directory() {
     super::scan_directory(
         move |song, db| {
             async move {
-                let item: artist::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
+                for item in artist::ActiveModel::from_artists(song) {
+                    match item.insert(db.get_connection()).await {
+                        Ok(_) => (),
+                        Err(_) => (),
+                    }
                 }
 
                 let item: album::ActiveModel = song.try_into().unwrap();
@@ -37,10 +39,11 @@ async fn scan_directory() {
                     Err(_) => (),
                 }
 
-                let item: artist_tracks::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
+                for item in artist_tracks::ActiveModel::from_artists(song) {
+                    match item.insert(db.get_connection()).await {
+                        Ok(_) => (),
+                        Err(_) => (),
+                    }
                 }
             }
             .boxed()
@@ -56,3 +59,913 @@ async fn scan_directory() {
     assert_eq!(album::Entity::find().count(conn).await.unwrap(), 1);
     assert_eq!(track::Entity::find().count(conn).await.unwrap(), 2);
 }
+
+#[test]
+fn folder_cover() {
+    let directory = env::temp_dir().join("music-player-folder-cover");
+    std::fs::create_dir_all(&directory).unwrap();
+    for file in [
+        "01 - Intro.mp3",
+        "AlbumArtSmall.jpg",
+        "Front.png",
+        "notes.txt",
+    ] {
+        std::fs::write(directory.join(file), b"").unwrap();
+    }
+    assert_eq!(
+        super::cover::folder_cover(&directory),
+        Some(directory.join("Front.png"))
+    );
+
+    std::fs::write(directory.join("cover.jpg"), b"").unwrap();
+    assert_eq!(
+        super::cover::folder_cover(&directory),
+        Some(directory.join("cover.jpg"))
+    );
+
+    // A PNG picture saved with the extension of a JPEG one
+    std::fs::write(directory.join("cover.jpg"), b"\x89PNG\r\n\x1a\nbroken").unwrap();
+    let (data, extension) = super::cover::read_folder_cover(&directory.join("cover.jpg")).unwrap();
+    assert_eq!(extension, "png");
+
+    // The picture can't be decoded, the original file stands for the thumbnails
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", "/tmp");
+    let cover = super::cover::save_cover(&data, &extension, "Broken Cover").unwrap();
+    assert_eq!(
+        cover.original,
+        format!("{:x}.png", md5::compute("Broken Cover"))
+    );
+    assert_eq!(cover.small, cover.original);
+    assert_eq!(cover.large, cover.original);
+    assert_eq!(cover.large_webp, None);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn sidecar_lyrics() {
+    let directory = env::temp_dir().join("music-player-sidecar-lyrics");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("01 - Intro.mp3");
+    std::fs::write(&path, b"").unwrap();
+    assert_eq!(super::lyrics::find_lyrics(&path, None), None);
+
+    std::fs::write(directory.join("01 - Intro.txt"), "Plain line\n").unwrap();
+    let lyrics = super::lyrics::find_lyrics(&path, None).unwrap();
+    assert_eq!(lyrics.source, LyricsSource::Txt);
+    assert!(!lyrics.synced);
+
+    std::fs::write(directory.join("01 - Intro.lrc"), "[00:01.00]Synced line\n").unwrap();
+    let lyrics = super::lyrics::find_lyrics(&path, None).unwrap();
+    assert_eq!(lyrics.source, LyricsSource::Lrc);
+    assert!(lyrics.synced);
+    assert_eq!(lyrics.content, "[00:01.00]Synced line");
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+/// Rewrites the title in the primary tag of a file, creating the tag if needed.
+fn retag(path: &std::path::Path, title: &str) {
+    use lofty::{Accessor, Probe, Tag, TagExt};
+
+    let tagged_file = Probe::open(path).unwrap().read().unwrap();
+    let mut tag = match tagged_file.primary_tag() {
+        Some(tag) => tag.clone(),
+        None => Tag::new(tagged_file.primary_tag_type()),
+    };
+    tag.set_title(title.to_owned());
+    tag.save_to_path(path).unwrap();
+}
+
+#[test]
+fn audio_hash_ignores_tags() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let directory = std::env::temp_dir().join("audio_hash_ignores_tags");
+    std::fs::create_dir_all(&directory).unwrap();
+
+    let m4a = directory.join("Wet Dreamz.m4a");
+    std::fs::copy(
+        fixtures.join("03 - J. Cole - Wet Dreamz(Explicit).m4a"),
+        &m4a,
+    )
+    .unwrap();
+    let wav = directory.join("tone.wav");
+    let samples: Vec<[f32; 2]> = (0..44100)
+        .map(|index| {
+            let sample = (index as f32 * 440.0 * 2.0 * std::f32::consts::PI / 44100.0).sin();
+            [sample * 0.5, sample * 0.5]
+        })
+        .collect();
+    write_wav(&wav, 44100, &samples);
+
+    for path in [&m4a, &wav] {
+        let hash = super::fingerprint::audio_hash(path).unwrap();
+        let original = std::fs::read(path).unwrap();
+        retag(path, "A much longer title than the original one");
+        assert_ne!(std::fs::read(path).unwrap(), original);
+        assert_eq!(super::fingerprint::audio_hash(path).unwrap(), hash);
+    }
+    assert_ne!(
+        super::fingerprint::audio_hash(&m4a).unwrap(),
+        super::fingerprint::audio_hash(&wav).unwrap()
+    );
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn relink_renamed_and_retagged_track() {
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", "/tmp");
+    env::set_var("MUSIC_PLAYER_MUSIC_DIRECTORY", "/tmp/audio");
+    env::set_var(
+        "MUSIC_PLAYER_DATABASE_URL",
+        "sqlite:///tmp/music-player.sqlite3",
+    );
+    let db = Database::new().await;
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let directory = std::env::temp_dir().join("relink_renamed_track");
+    std::fs::create_dir_all(&directory).unwrap();
+    let old_path = directory.join("Wet Dreamz.m4a");
+    let new_path = directory.join("01 - Wet Dreamz (Retagged).m4a");
+    std::fs::copy(
+        fixtures.join("03 - J. Cole - Wet Dreamz(Explicit).m4a"),
+        &old_path,
+    )
+    .unwrap();
+
+    let (mut song, _) = super::read_song(&old_path, &directory).unwrap();
+    song.id = Some("relink-renamed-track".to_owned());
+    let _ = track::Entity::delete_by_id("relink-renamed-track".to_owned())
+        .exec(db.get_connection())
+        .await;
+    let item: track::ActiveModel = (&song).into();
+    item.insert(db.get_connection()).await.unwrap();
+
+    std::fs::rename(&old_path, &new_path).unwrap();
+    retag(&new_path, "Wet Dreamz (Retagged)");
+    let (mut song, _) = super::read_song(&new_path, &directory).unwrap();
+    assert_eq!(song.title, "Wet Dreamz (Retagged)");
+    super::resolve_track_id(&mut song, &db).await;
+    assert_eq!(song.id.as_deref(), Some("relink-renamed-track"));
+
+    let track = track::Entity::find_by_id("relink-renamed-track".to_owned())
+        .one(db.get_connection())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(track.uri, new_path.display().to_string());
+
+    track::Entity::delete_by_id("relink-renamed-track".to_owned())
+        .exec(db.get_connection())
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn acoustic_fingerprint() {
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let wet_dreamz = super::fingerprint::acoustic_fingerprint(
+        &fixtures.join("03 - J. Cole - Wet Dreamz(Explicit).m4a"),
+    )
+    .unwrap();
+    let fire_squad = super::fingerprint::acoustic_fingerprint(
+        &fixtures.join("06 - J. Cole - Fire Squad(Explicit).m4a"),
+    )
+    .unwrap();
+
+    let encoded = super::fingerprint::encode_fingerprint(&wet_dreamz);
+    assert_eq!(
+        super::fingerprint::decode_fingerprint(&encoded),
+        Some(wet_dreamz.clone())
+    );
+    assert_eq!(
+        super::fingerprint::fingerprint_similarity(&wet_dreamz, &wet_dreamz),
+        1.0
+    );
+    // The same audio with a bit of padding at the start
+    let mut padded = vec![0; 2];
+    padded.extend_from_slice(&wet_dreamz);
+    assert_eq!(
+        super::fingerprint::fingerprint_similarity(&padded, &wet_dreamz),
+        1.0
+    );
+    assert!(super::fingerprint::fingerprint_similarity(&wet_dreamz, &fire_squad) < 0.85);
+}
+
+#[test]
+fn normalize_duplicate_tags() {
+    assert_eq!(
+        super::duplicates::normalize("Wet Dreamz (Explicit) [Remastered]"),
+        "wet dreamz"
+    );
+    assert_eq!(super::duplicates::normalize("J. Cole"), "j cole");
+
+    let mp3 = track::Model {
+        bitrate: Some(320),
+        ..Default::default()
+    };
+    let flac = track::Model {
+        bitrate: Some(900),
+        bit_depth: Some(16),
+        ..Default::default()
+    };
+    assert!(super::duplicates::best_copy_order(&flac) > super::duplicates::best_copy_order(&mp3));
+}
+
+fn write_wav(path: &std::path::Path, sample_rate: u32, samples: &[[f32; 2]]) {
+    let mut data = Vec::new();
+    for frame in samples {
+        for sample in frame {
+            data.extend_from_slice(&((sample * i16::MAX as f32) as i16).to_le_bytes());
+        }
+    }
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&(36 + data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVEfmt ");
+    wav.extend_from_slice(&16u32.to_le_bytes());
+    wav.extend_from_slice(&1u16.to_le_bytes());
+    wav.extend_from_slice(&2u16.to_le_bytes());
+    wav.extend_from_slice(&sample_rate.to_le_bytes());
+    wav.extend_from_slice(&(sample_rate * 4).to_le_bytes());
+    wav.extend_from_slice(&4u16.to_le_bytes());
+    wav.extend_from_slice(&16u16.to_le_bytes());
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&data);
+    std::fs::write(path, wav).unwrap();
+}
+
+#[test]
+fn analyze_loudness_and_tempo() {
+    let directory = env::temp_dir().join("music-player-analysis");
+    std::fs::create_dir_all(&directory).unwrap();
+    let sample_rate = 48000;
+
+    // EBU R128 reference: a stereo 1kHz sine at -23 dBFS measures -23 LUFS
+    let amplitude = 10f32.powf(-23.0 / 20.0);
+    let sine: Vec<[f32; 2]> = (0..sample_rate * 20)
+        .map(|index| {
+            let t = index as f32 / sample_rate as f32;
+            let sample = amplitude * (2.0 * std::f32::consts::PI * 1000.0 * t).sin();
+            [sample, sample]
+        })
+        .collect();
+    let path = directory.join("sine.wav");
+    write_wav(&path, sample_rate, &sine);
+    let analysis = super::analysis::analyze(&path).unwrap();
+    let loudness = analysis.loudness.unwrap();
+    assert!((loudness + 23.0).abs() < 0.2, "{}", loudness);
+    assert!((analysis.true_peak + 23.0).abs() < 0.2, "{}", analysis.true_peak);
+    assert!((analysis.replaygain_gain().unwrap() - 5.0).abs() < 0.2);
+
+    // A click every half second
+    let clicks: Vec<[f32; 2]> = (0..sample_rate * 30)
+        .map(|index| {
+            let position = index % (sample_rate / 2);
+            let sample = if position < 480 {
+                0.8 * (1.0 - position as f32 / 480.0)
+            } else {
+                0.0
+            };
+            [sample, sample]
+        })
+        .collect();
+    let path = directory.join("clicks.wav");
+    write_wav(&path, sample_rate, &clicks);
+    let tempo = super::analysis::analyze(&path).unwrap().tempo.unwrap();
+    assert!((tempo - 120.0).abs() < 1.0, "{}", tempo);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn detect_musical_key() {
+    let directory = env::temp_dir().join("music-player-key");
+    std::fs::create_dir_all(&directory).unwrap();
+    let sample_rate = 44100;
+
+    // Each chord of the progression held for two seconds, the notes given as
+    // MIDI numbers
+    let progression = |chords: &[[u8; 3]]| -> Vec<[f32; 2]> {
+        chords
+            .iter()
+            .flat_map(|chord| {
+                (0..sample_rate * 2).map(move |index| {
+                    let t = index as f32 / sample_rate as f32;
+                    let sample: f32 = chord
+                        .iter()
+                        .map(|note| {
+                            let frequency = 440.0 * 2f32.powf((*note as f32 - 69.0) / 12.0);
+                            0.2 * (2.0 * std::f32::consts::PI * frequency * t).sin()
+                        })
+                        .sum();
+                    [sample, sample]
+                })
+            })
+            .collect()
+    };
+
+    // C, F and G major
+    let path = directory.join("major.wav");
+    let chords = [[60, 64, 67], [65, 69, 72], [67, 71, 74], [60, 64, 67]];
+    write_wav(&path, sample_rate, &progression(&chords));
+    let key = super::analysis::analyze(&path).unwrap().key;
+    assert_eq!(key.as_deref(), Some("C major"));
+
+    // A minor, D minor and E major
+    let path = directory.join("minor.wav");
+    let chords = [[57, 60, 64], [62, 65, 69], [64, 68, 71], [57, 60, 64]];
+    write_wav(&path, sample_rate, &progression(&chords));
+    let key = super::analysis::analyze(&path).unwrap().key;
+    assert_eq!(key.as_deref(), Some("A minor"));
+
+    // Silence has no key
+    let path = directory.join("silence.wav");
+    write_wav(&path, sample_rate, &[[0.0, 0.0]; 4410]);
+    assert_eq!(super::analysis::analyze(&path).unwrap().key, None);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn cue_sheet_tracks() {
+    let sheet = super::cue::parse_cue(
+        r#"REM GENRE "Hip-Hop"
+REM DATE 2014
+PERFORMER "J. Cole"
+TITLE "2014 Forest Hills Drive"
+FILE "album.flac" WAVE
+  TRACK 01 AUDIO
+    TITLE "Intro"
+    INDEX 01 00:00:00
+  TRACK 02 AUDIO
+    TITLE "January 28th"
+    PERFORMER "J. Cole feat. Nobody"
+    INDEX 00 02:08:50
+    INDEX 01 02:09:00
+  TRACK 03 AUDIO
+    TITLE "Wet Dreamz"
+    INDEX 01 06:11:37
+"#,
+    );
+    assert_eq!(sheet.title.as_deref(), Some("2014 Forest Hills Drive"));
+    assert_eq!(sheet.genre.as_deref(), Some("Hip-Hop"));
+    assert_eq!(sheet.tracks.len(), 3);
+    assert_eq!(sheet.tracks[0].end, Some(128_666));
+    assert_eq!(sheet.tracks[1].start, 129_000);
+    assert_eq!(sheet.tracks[1].end, Some(371_493));
+    assert_eq!(sheet.tracks[2].end, None);
+
+    let file = music_player_types::types::Song {
+        album: "Untitled".to_string(),
+        duration: std::time::Duration::from_secs(600),
+        uri: Some("/music/album.flac".to_string()),
+        ..Default::default()
+    };
+    let songs = sheet.songs("album.flac", &file);
+    assert_eq!(songs.len(), 3);
+    assert_eq!(songs[1].title, "January 28th");
+    assert_eq!(songs[1].artist, "J. Cole feat. Nobody");
+    assert_eq!(songs[1].album, "2014 Forest Hills Drive");
+    assert_eq!(songs[1].album_artist, "J. Cole");
+    assert_eq!(songs[1].year, Some(2014));
+    assert_eq!(songs[1].duration.as_millis(), 242_493);
+    assert_eq!(songs[2].end_offset, None);
+    assert_eq!(songs[2].duration.as_millis(), 228_507);
+    assert_ne!(songs[0].track_id(), songs[1].track_id());
+}
+
+#[test]
+fn archive_tracks() {
+    use std::io::Write;
+    use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let directory = env::temp_dir().join("music-player-archive-tracks");
+    std::fs::create_dir_all(&directory).unwrap();
+    let archive = directory.join("J. Cole.zip");
+    let mut zip = ZipWriter::new(std::fs::File::create(&archive).unwrap());
+    let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("06 - Fire Squad.m4a", options).unwrap();
+    let data = std::fs::read(fixtures.join("06 - J. Cole - Fire Squad(Explicit).m4a")).unwrap();
+    zip.write_all(&data).unwrap();
+    zip.finish().unwrap();
+
+    let entries = music_player_audio::archive::list_entries(&archive).unwrap();
+    assert_eq!(entries.len(), 1);
+    let (song, _) = super::read_archive_song(&entries[0], &directory).unwrap();
+    assert_eq!(song.title, "Fire Squad");
+    assert_eq!(song.artist, "J. Cole");
+    assert!(song.duration.as_secs() > 0);
+    let uri = song.uri.unwrap();
+    assert!(uri.ends_with("J. Cole.zip#06%20-%20Fire%20Squad.m4a"));
+
+    let mut stream = super::decoder::AudioStream::open_uri(&uri).unwrap();
+    let mut frames = 0;
+    stream.for_each_frame(Some(1000), |_| frames += 1);
+    assert!(frames > 0);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn import_library_playlist_once() {
+    use super::playlist_file::{import_playlist_file, TrackMatcher};
+    use music_player_entity::playlist;
+    use music_player_storage::repo::playlist::PlaylistRepository;
+
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", "/tmp");
+    env::set_var(
+        "MUSIC_PLAYER_DATABASE_URL",
+        "sqlite:///tmp/music-player.sqlite3",
+    );
+    let db = Database::new().await;
+    let directory = env::temp_dir().join("import_library_playlist");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("Road Trip.m3u8");
+    std::fs::write(&path, "#EXTM3U\nMissing Song.mp3\n").unwrap();
+
+    let mut matcher = TrackMatcher::default();
+    let imported = import_playlist_file(&path, &db, &mut matcher)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(imported.name, "Road Trip");
+    assert_eq!(imported.missing.len(), 1);
+
+    // Renamed in the library, the unchanged file leaves it alone
+    let repo = PlaylistRepository::new(db.get_connection());
+    repo.replace(&imported.id, "Summer Road Trip", &[])
+        .await
+        .unwrap();
+    let unchanged = import_playlist_file(&path, &db, &mut matcher)
+        .await
+        .unwrap();
+    assert!(unchanged.is_none());
+    assert_eq!(
+        repo.find(&imported.id).await.unwrap().name,
+        "Summer Road Trip"
+    );
+
+    std::fs::write(&path, "#EXTM3U\nMissing Song.mp3\nOther Song.mp3\n").unwrap();
+    let changed = import_playlist_file(&path, &db, &mut matcher)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(changed.name, "Road Trip");
+    assert_eq!(changed.missing.len(), 2);
+
+    playlist::Entity::delete_by_id(imported.id)
+        .exec(db.get_connection())
+        .await
+        .unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[test]
+fn playlist_files() {
+    use super::playlist_file::{parse_playlist, write_playlist, PlaylistFormat};
+
+    let file = parse_playlist(
+        PlaylistFormat::M3u8,
+        "#EXTM3U\n#PLAYLIST:Forest Hills\n#EXTINF:288,J. Cole - Fire Squad\nMusic\\Fire Squad.m4a\n\n/music/Wet Dreamz.m4a\n",
+    )
+    .unwrap();
+    assert_eq!(file.title.as_deref(), Some("Forest Hills"));
+    assert_eq!(file.entries.len(), 2);
+    assert_eq!(file.entries[0].location, "Music\\Fire Squad.m4a");
+    assert_eq!(file.entries[0].artist.as_deref(), Some("J. Cole"));
+    assert_eq!(file.entries[0].title.as_deref(), Some("Fire Squad"));
+    assert_eq!(file.entries[0].duration, Some(288));
+    assert_eq!(file.entries[1].title, None);
+
+    let pls = parse_playlist(
+        PlaylistFormat::Pls,
+        "[playlist]\nFile2=/music/b.mp3\nFile1=/music/a.mp3\nTitle1=A\nLength1=-1\nNumberOfEntries=2\n",
+    )
+    .unwrap();
+    assert_eq!(pls.entries.len(), 2);
+    assert_eq!(pls.entries[0].location, "/music/a.mp3");
+    assert_eq!(pls.entries[0].title.as_deref(), Some("A"));
+    assert_eq!(pls.entries[0].duration, None);
+
+    for format in [
+        PlaylistFormat::M3u8,
+        PlaylistFormat::Pls,
+        PlaylistFormat::Xspf,
+    ] {
+        let content = write_playlist(format, &file);
+        let parsed = parse_playlist(format, &content).unwrap();
+        assert_eq!(parsed.entries[0].artist.as_deref(), Some("J. Cole"));
+        assert_eq!(parsed.entries[0].title.as_deref(), Some("Fire Squad"));
+        assert_eq!(parsed.entries[0].duration, Some(288));
+        assert_eq!(parsed.entries[1].location, "/music/Wet Dreamz.m4a");
+    }
+    let xspf = write_playlist(PlaylistFormat::Xspf, &file);
+    assert!(xspf.contains("<location>file:///music/Wet%20Dreamz.m4a</location>"));
+    assert!(xspf.contains("<title>Forest Hills</title>"));
+}
+
+#[tokio::test]
+async fn record_scan_errors() {
+    use music_player_entity::scan_error;
+    use sea_orm::{ConnectOptions, ConnectionTrait, Schema};
+
+    // A single connection, each connection to an in-memory database has its own
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let connection = sea_orm::Database::connect(opt).await.unwrap();
+    let statement =
+        Schema::new(connection.get_database_backend()).create_table_from_entity(scan_error::Entity);
+    connection
+        .execute(connection.get_database_backend().build(&statement))
+        .await
+        .unwrap();
+    let db = Database { connection };
+
+    let directory = env::temp_dir().join("record_scan_errors");
+    std::fs::create_dir_all(&directory).unwrap();
+
+    // Untagged, the folders tell what the song is
+    let untagged = directory.join("Untagged.wav");
+    write_wav(&untagged, 44100, &[[0.0, 0.0]; 4410]);
+    let (song, _) = super::read_song(&untagged, &env::temp_dir()).unwrap();
+    assert_eq!(song.title, "Untagged");
+
+    let broken = directory.join("Broken.mp3");
+    std::fs::write(&broken, b"not an audio file").unwrap();
+    let error = match super::read_song(&broken, &directory) {
+        Ok(_) => panic!("{} was read", broken.display()),
+        Err(error) => error,
+    };
+
+    // Scanned again, the file keeps a single error
+    super::report_error(&error, &db).await;
+    super::report_error(&error, &db).await;
+    let errors = scan_error::Entity::find()
+        .all(db.get_connection())
+        .await
+        .unwrap();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].path, format!("{}", broken.display()));
+    assert_eq!(errors[0].kind, error.kind.as_str());
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn group_duplicate_tracks() {
+    use music_player_entity::acoustic_fingerprint;
+    use sea_orm::{ConnectOptions, ConnectionTrait, Schema};
+
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let connection = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = Schema::new(connection.get_database_backend());
+    for statement in [
+        schema.create_table_from_entity(album::Entity),
+        schema.create_table_from_entity(artist::Entity),
+        schema.create_table_from_entity(track::Entity),
+        schema.create_table_from_entity(acoustic_fingerprint::Entity),
+    ] {
+        connection
+            .execute(connection.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+    let db = Database { connection };
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let wet_dreamz = fixtures.join("03 - J. Cole - Wet Dreamz(Explicit).m4a");
+    let fire_squad = fixtures.join("06 - J. Cole - Fire Squad(Explicit).m4a");
+    let copy = track::Model {
+        artist: "J. Cole".to_owned(),
+        uri: format!("{}", wet_dreamz.display()),
+        duration: Some(191.0),
+        bitrate: Some(320),
+        ..Default::default()
+    };
+    let tracks = [
+        // The same recording in two files, told apart by their fingerprints
+        track::Model {
+            id: "1".to_owned(),
+            title: "Wet Dreamz".to_owned(),
+            bitrate: Some(256),
+            content_hash: Some("a".to_owned()),
+            ..copy.clone()
+        },
+        track::Model {
+            id: "2".to_owned(),
+            title: "Wet Dreamz (Explicit)".to_owned(),
+            duration: Some(192.0),
+            content_hash: Some("b".to_owned()),
+            ..copy.clone()
+        },
+        track::Model {
+            id: "3".to_owned(),
+            title: "Wet Dreams".to_owned(),
+            uri: format!("{}", fire_squad.display()),
+            duration: Some(192.5),
+            content_hash: Some("c".to_owned()),
+            ..copy.clone()
+        },
+        // The same audio with other tags
+        track::Model {
+            id: "4".to_owned(),
+            title: "Hey Ya!".to_owned(),
+            artist: "Outkast".to_owned(),
+            duration: None,
+            bitrate: Some(128),
+            content_hash: Some("d".to_owned()),
+            ..copy.clone()
+        },
+        track::Model {
+            id: "5".to_owned(),
+            title: "Hey Ya! (Radio Edit)".to_owned(),
+            artist: "OutKast".to_owned(),
+            duration: None,
+            content_hash: Some("d".to_owned()),
+            ..copy
+        },
+    ];
+    for item in tracks {
+        let item: track::ActiveModel = item.into();
+        item.insert(db.get_connection()).await.unwrap();
+    }
+
+    let groups = super::duplicates::find_duplicates(&db).await.unwrap();
+    let ids: Vec<Vec<&str>> = groups
+        .iter()
+        .map(|group| group.tracks.iter().map(|track| track.id.as_str()).collect())
+        .collect();
+    assert_eq!(ids, vec![vec!["2", "1"], vec!["5", "4"]]);
+
+    // The fingerprints of the candidates are kept for the next run
+    assert_eq!(
+        acoustic_fingerprint::Entity::find()
+            .count(db.get_connection())
+            .await
+            .unwrap(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn rescan_updates_tags() {
+    use sea_orm::{ConnectOptions, ConnectionTrait, Schema, Set};
+
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let connection = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = Schema::new(connection.get_database_backend());
+    for statement in [
+        schema.create_table_from_entity(album::Entity),
+        schema.create_table_from_entity(artist::Entity),
+        schema.create_table_from_entity(track::Entity),
+        schema.create_table_from_entity(artist_tracks::Entity),
+    ] {
+        connection
+            .execute(connection.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+    let db = Database { connection };
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let path = fixtures.join("03 - J. Cole - Wet Dreamz(Explicit).m4a");
+    let (mut song, _) = super::read_song(&path, &fixtures).unwrap();
+    song.disc_number = Some(1);
+    super::save_song(&song, &db).await;
+
+    let id = song.track_id();
+    let scanned = track::Entity::find_by_id(id.clone())
+        .one(db.get_connection())
+        .await
+        .unwrap()
+        .unwrap();
+    let mut item: track::ActiveModel = scanned.clone().into();
+    item.loudness = Set(Some(-9.5));
+    item.update(db.get_connection()).await.unwrap();
+
+    // Retagged outside of the app, the next scan stores the new tags
+    song.disc_number = Some(2);
+    song.label = Some("Dreamville".to_owned());
+    super::save_song(&song, &db).await;
+
+    let rescanned = track::Entity::find_by_id(id)
+        .one(db.get_connection())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(rescanned.disc_number, Some(2));
+    assert_eq!(rescanned.created_at, scanned.created_at);
+    assert_eq!(rescanned.loudness, Some(-9.5));
+    let album = album::Entity::find_by_id(rescanned.album_id.unwrap())
+        .one(db.get_connection())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(album.label.as_deref(), Some("Dreamville"));
+    assert_eq!(
+        track::Entity::find()
+            .count(db.get_connection())
+            .await
+            .unwrap(),
+        1
+    );
+}
+
+#[tokio::test]
+async fn keep_archive_track_of_same_content() {
+    use sea_orm::{ConnectOptions, ConnectionTrait, Schema};
+    use std::io::Write;
+    use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let connection = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = Schema::new(connection.get_database_backend());
+    for statement in [
+        schema.create_table_from_entity(album::Entity),
+        schema.create_table_from_entity(artist::Entity),
+        schema.create_table_from_entity(track::Entity),
+        schema.create_table_from_entity(artist_tracks::Entity),
+    ] {
+        connection
+            .execute(connection.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+    let db = Database { connection };
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let fixture = fixtures.join("06 - J. Cole - Fire Squad(Explicit).m4a");
+    let directory = env::temp_dir().join("music-player-keep-archive-track");
+    std::fs::create_dir_all(&directory).unwrap();
+    let archive = directory.join("J. Cole.zip");
+    let mut zip = ZipWriter::new(std::fs::File::create(&archive).unwrap());
+    let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("06 - Fire Squad.m4a", options).unwrap();
+    zip.write_all(&std::fs::read(&fixture).unwrap()).unwrap();
+    zip.finish().unwrap();
+    let path = directory.join("06 - Fire Squad.m4a");
+    std::fs::copy(&fixture, &path).unwrap();
+
+    let (mut song, _) = super::read_song(&path, &directory).unwrap();
+    let entries = music_player_audio::archive::list_entries(&archive).unwrap();
+    let (mut archived, _) = super::read_archive_song(&entries[0], &directory).unwrap();
+    archived.content_hash = song.content_hash.clone();
+    super::save_song(&archived, &db).await;
+
+    // The entry doesn't exist on the file system, the local copy is a new track
+    super::resolve_track_id(&mut song, &db).await;
+    assert_ne!(song.track_id(), archived.track_id());
+    let track = track::Entity::find_by_id(archived.track_id())
+        .one(db.get_connection())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(Some(track.uri), archived.uri);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn rescan_splits_artists() {
+    use music_player_storage::repo::artist::ArtistRepository;
+    use sea_orm::{ColumnTrait, ConnectOptions, ConnectionTrait, QueryFilter, Schema};
+
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let connection = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = Schema::new(connection.get_database_backend());
+    for statement in [
+        schema.create_table_from_entity(album::Entity),
+        schema.create_table_from_entity(artist::Entity),
+        schema.create_table_from_entity(track::Entity),
+        schema.create_table_from_entity(artist_tracks::Entity),
+    ] {
+        connection
+            .execute(connection.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+    let db = Database { connection };
+
+    let credited = "J. Cole feat. Kendrick Lamar";
+    let mut song = music_player_types::types::Song {
+        title: "Forbidden Fruit".to_owned(),
+        artist: credited.to_owned(),
+        album_artist: "J. Cole".to_owned(),
+        album: "Born Sinner".to_owned(),
+        uri: Some("/tmp/audio/Forbidden Fruit.m4a".to_owned()),
+        ..Default::default()
+    };
+    super::save_song(&song, &db).await;
+
+    // Scanned again with the separators of the settings
+    song.split_artists(&[" feat. ".to_owned()]);
+    super::save_song(&song, &db).await;
+
+    let mut linked: Vec<String> = artist_tracks::Entity::find()
+        .filter(artist_tracks::Column::TrackId.eq(song.track_id()))
+        .all(db.get_connection())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|link| link.artist_id)
+        .collect();
+    linked.sort();
+    let mut expected = vec![
+        format!("{:x}", md5::compute("J. Cole")),
+        format!("{:x}", md5::compute("Kendrick Lamar")),
+    ];
+    expected.sort();
+    assert_eq!(linked, expected);
+
+    let unused = ArtistRepository::new(db.get_connection())
+        .delete_unused()
+        .await
+        .unwrap();
+    assert_eq!(unused, vec![format!("{:x}", md5::compute(credited))]);
+}
+
+#[tokio::test]
+async fn edit_artist_deletes_orphans() {
+    use music_player_entity::{genre, genre_tracks};
+    use music_player_types::types::TagChanges;
+    use sea_orm::{ConnectOptions, ConnectionTrait, Schema};
+
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let connection = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = Schema::new(connection.get_database_backend());
+    for statement in [
+        schema.create_table_from_entity(album::Entity),
+        schema.create_table_from_entity(artist::Entity),
+        schema.create_table_from_entity(track::Entity),
+        schema.create_table_from_entity(artist_tracks::Entity),
+        schema.create_table_from_entity(genre::Entity),
+        schema.create_table_from_entity(genre_tracks::Entity),
+    ] {
+        connection
+            .execute(connection.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+    let db = Database { connection };
+
+    let fixtures = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("../fixtures/audio");
+    let directory = env::temp_dir().join("music-player-edit-artist");
+    std::fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("06 - Fire Squad.m4a");
+    std::fs::copy(
+        fixtures.join("06 - J. Cole - Fire Squad(Explicit).m4a"),
+        &path,
+    )
+    .unwrap();
+    let (mut song, _) = super::read_song(&path, &directory).unwrap();
+    song.artist = "Before Edit".to_owned();
+    song.album_artist = "Before Edit".to_owned();
+    song.album = "Edited Album".to_owned();
+    super::save_song(&song, &db).await;
+
+    // The same track passed twice is edited once. The names are unique to
+    // this test, the search indexes are shared with the other tests.
+    let id = song.track_id();
+    let changes = TagChanges {
+        title: Some("Edited Track".to_owned()),
+        artist: Some("After Edit".to_owned()),
+        album: Some("Edited Album".to_owned()),
+        album_artist: Some("After Edit".to_owned()),
+        ..Default::default()
+    };
+    let searcher = Searcher::new();
+    let tracks =
+        super::editor::edit_tracks(&db, &searcher, &[id.clone(), id.clone()], &changes, false)
+            .await
+            .unwrap();
+    assert_eq!(tracks.len(), 1);
+
+    let names: Vec<String> = artist::Entity::find()
+        .all(db.get_connection())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|artist| artist.name)
+        .collect();
+    assert_eq!(names, vec!["After Edit"]);
+
+    searcher.delete_song(&id, &[]).unwrap();
+    searcher
+        .delete_artist(&format!("{:x}", md5::compute("After Edit")))
+        .unwrap();
+    searcher
+        .delete_album(&format!("{:x}", md5::compute("Edited Album")))
+        .unwrap();
+    searcher.commit().unwrap();
+    std::fs::remove_dir_all(&directory).unwrap();
+}
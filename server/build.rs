@@ -3,6 +3,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         &[
             "proto/metadata/v1alpha1/artist.proto",
             "proto/metadata/v1alpha1/album.proto",
+            "proto/metadata/v1alpha1/genre.proto",
             "proto/metadata/v1alpha1/lyrics.proto",
             "proto/metadata/v1alpha1/track.proto",
             "proto/objects/v1alpha1/addon.proto",
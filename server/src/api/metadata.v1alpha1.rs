@@ -41,6 +41,24 @@ pub struct Album {
     pub genres: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
     #[prost(message, repeated, tag = "8")]
     pub tracks: ::prost::alloc::vec::Vec<Song>,
+    #[prost(string, tag = "9")]
+    pub cover_small: ::prost::alloc::string::String,
+    #[prost(string, tag = "10")]
+    pub cover_medium: ::prost::alloc::string::String,
+    #[prost(string, tag = "11")]
+    pub cover_large: ::prost::alloc::string::String,
+    #[prost(bool, tag = "12")]
+    pub compilation: bool,
+    #[prost(string, tag = "13")]
+    pub label: ::prost::alloc::string::String,
+    #[prost(string, tag = "14")]
+    pub title_sort: ::prost::alloc::string::String,
+    #[prost(string, tag = "15")]
+    pub artist_sort: ::prost::alloc::string::String,
+    #[prost(string, tag = "16")]
+    pub musicbrainz_release_id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "17")]
+    pub favourite: bool,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -83,6 +101,16 @@ pub struct Artist {
     pub albums: ::prost::alloc::vec::Vec<Album>,
     #[prost(message, repeated, tag = "9")]
     pub songs: ::prost::alloc::vec::Vec<ArtistSong>,
+    #[prost(bool, tag = "10")]
+    pub favourite: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct LyricLine {
+    #[prost(uint32, tag = "1")]
+    pub time_ms: u32,
+    #[prost(string, tag = "2")]
+    pub text: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -91,6 +119,12 @@ pub struct Lyrics {
     pub id: ::prost::alloc::string::String,
     #[prost(string, tag = "2")]
     pub content: ::prost::alloc::string::String,
+    #[prost(bool, tag = "3")]
+    pub synced: bool,
+    #[prost(string, tag = "4")]
+    pub source: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "5")]
+    pub lines: ::prost::alloc::vec::Vec<LyricLine>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -115,4 +149,45 @@ pub struct Track {
     pub uri: ::prost::alloc::string::String,
     #[prost(string, tag = "10")]
     pub artist: ::prost::alloc::string::String,
+    #[prost(int32, tag = "11")]
+    pub disc_total: i32,
+    #[prost(int32, tag = "12")]
+    pub track_total: i32,
+    #[prost(string, tag = "13")]
+    pub composer: ::prost::alloc::string::String,
+    #[prost(string, tag = "14")]
+    pub conductor: ::prost::alloc::string::String,
+    #[prost(string, tag = "15")]
+    pub isrc: ::prost::alloc::string::String,
+    #[prost(string, tag = "16")]
+    pub comment: ::prost::alloc::string::String,
+    #[prost(int32, tag = "17")]
+    pub bpm: i32,
+    #[prost(string, tag = "18")]
+    pub title_sort: ::prost::alloc::string::String,
+    #[prost(string, tag = "19")]
+    pub artist_sort: ::prost::alloc::string::String,
+    #[prost(string, tag = "20")]
+    pub musicbrainz_recording_id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "21")]
+    pub start_offset: i32,
+    #[prost(int32, tag = "22")]
+    pub end_offset: i32,
+    /// From 1 to 5 stars, 0 when the track isn't rated
+    #[prost(int32, tag = "23")]
+    pub rating: i32,
+    #[prost(bool, tag = "24")]
+    pub favourite: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Genre {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub albums: ::prost::alloc::vec::Vec<Album>,
+    #[prost(message, repeated, tag = "4")]
+    pub tracks: ::prost::alloc::vec::Vec<Track>,
 }
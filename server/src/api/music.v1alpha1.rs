@@ -635,10 +635,74 @@ pub mod core_service_server {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetHistoryRequest {}
+pub struct HistoryEntry {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub track: ::core::option::Option<super::super::metadata::v1alpha1::Track>,
+    /// RFC 3339 date and time
+    #[prost(string, tag = "3")]
+    pub started_at: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "4")]
+    pub listened_ms: u32,
+    #[prost(bool, tag = "5")]
+    pub completed: bool,
+    #[prost(string, tag = "6")]
+    pub device: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PlayStats {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub play_count: u32,
+    #[prost(uint32, tag = "3")]
+    pub skip_count: u32,
+    /// RFC 3339 date and time, empty when the track was never played
+    #[prost(string, tag = "4")]
+    pub last_played: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetHistoryRequest {
+    #[prost(int32, tag = "1")]
+    pub limit: i32,
+    #[prost(int32, tag = "2")]
+    pub offset: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetHistoryResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub entries: ::prost::alloc::vec::Vec<HistoryEntry>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPlayStatsRequest {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetPlayStatsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub stats: ::core::option::Option<PlayStats>,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct GetHistoryResponse {}
+pub struct GetMostPlayedRequest {
+    #[prost(int32, tag = "1")]
+    pub limit: i32,
+    #[prost(int32, tag = "2")]
+    pub offset: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetMostPlayedResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tracks: ::prost::alloc::vec::Vec<PlayStats>,
+}
 /// Generated client implementations.
 pub mod history_service_client {
     #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
@@ -727,6 +791,44 @@ pub mod history_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn get_play_stats(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetPlayStatsRequest>,
+        ) -> Result<tonic::Response<super::GetPlayStatsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.HistoryService/GetPlayStats",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn get_most_played(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetMostPlayedRequest>,
+        ) -> Result<tonic::Response<super::GetMostPlayedResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.HistoryService/GetMostPlayed",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -740,6 +842,14 @@ pub mod history_service_server {
             &self,
             request: tonic::Request<super::GetHistoryRequest>,
         ) -> Result<tonic::Response<super::GetHistoryResponse>, tonic::Status>;
+        async fn get_play_stats(
+            &self,
+            request: tonic::Request<super::GetPlayStatsRequest>,
+        ) -> Result<tonic::Response<super::GetPlayStatsResponse>, tonic::Status>;
+        async fn get_most_played(
+            &self,
+            request: tonic::Request<super::GetMostPlayedRequest>,
+        ) -> Result<tonic::Response<super::GetMostPlayedResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct HistoryServiceServer<T: HistoryService> {
@@ -838,6 +948,86 @@ pub mod history_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/music.v1alpha1.HistoryService/GetPlayStats" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetPlayStatsSvc<T: HistoryService>(pub Arc<T>);
+                    impl<
+                        T: HistoryService,
+                    > tonic::server::UnaryService<super::GetPlayStatsRequest>
+                    for GetPlayStatsSvc<T> {
+                        type Response = super::GetPlayStatsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPlayStatsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_play_stats(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetPlayStatsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.HistoryService/GetMostPlayed" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetMostPlayedSvc<T: HistoryService>(pub Arc<T>);
+                    impl<
+                        T: HistoryService,
+                    > tonic::server::UnaryService<super::GetMostPlayedRequest>
+                    for GetMostPlayedSvc<T> {
+                        type Response = super::GetMostPlayedResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetMostPlayedRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_most_played(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetMostPlayedSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -883,15 +1073,63 @@ pub struct ScanRequest {}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct ScanResponse {}
+/// The query can target fields and exclude words, e.g.
+/// artist:"miles davis" year:1955..1960 genre:jazz -live
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SearchRequest {
     #[prost(string, tag = "1")]
     pub query: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub limit: i32,
+    #[prost(int32, tag = "3")]
+    pub offset: i32,
+}
+/// A page of the tracks, albums and artists ranked by relevance, with the
+/// number of matches across all the pages
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SearchResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tracks: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Track>,
+    #[prost(message, repeated, tag = "2")]
+    pub albums: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Album>,
+    #[prost(message, repeated, tag = "3")]
+    pub artists: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Artist>,
+    #[prost(int32, tag = "4")]
+    pub total_tracks: i32,
+    #[prost(int32, tag = "5")]
+    pub total_albums: i32,
+    #[prost(int32, tag = "6")]
+    pub total_artists: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SuggestRequest {
+    #[prost(string, tag = "1")]
+    pub input: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub limit: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Suggestion {
+    /// "track", "album", "artist" or "genre"
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    /// The id of the track, album, artist or genre
+    #[prost(string, tag = "2")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub text: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
-pub struct SearchResponse {}
+pub struct SuggestResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub suggestions: ::prost::alloc::vec::Vec<Suggestion>,
+}
+/// The albums having at least one track matching the filters
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetAlbumsRequest {
@@ -901,6 +1139,28 @@ pub struct GetAlbumsRequest {
     pub offset: i32,
     #[prost(string, tag = "3")]
     pub filter: ::prost::alloc::string::String,
+    /// "title", "year", "added_at", "duration", "artist", "play_count" or
+    /// "rating", the title when empty
+    #[prost(string, tag = "4")]
+    pub sort: ::prost::alloc::string::String,
+    #[prost(bool, tag = "5")]
+    pub descending: bool,
+    /// Zeros and empty strings leave the filters unset
+    #[prost(int32, tag = "6")]
+    pub min_year: i32,
+    #[prost(int32, tag = "7")]
+    pub max_year: i32,
+    /// Any spelling of a genre
+    #[prost(string, tag = "8")]
+    pub genre: ::prost::alloc::string::String,
+    /// The extension of the files, e.g. "flac"
+    #[prost(string, tag = "9")]
+    pub format: ::prost::alloc::string::String,
+    /// In kbps
+    #[prost(int32, tag = "10")]
+    pub min_bitrate: i32,
+    #[prost(int32, tag = "11")]
+    pub max_bitrate: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -926,6 +1186,23 @@ pub struct GetArtistsResponse {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGenresRequest {
+    #[prost(int32, tag = "1")]
+    pub limit: i32,
+    #[prost(int32, tag = "2")]
+    pub offset: i32,
+    #[prost(string, tag = "3")]
+    pub filter: ::prost::alloc::string::String,
+}
+/// The genres without their albums and tracks
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGenresResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub genres: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Genre>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetTracksRequest {
     #[prost(int32, tag = "1")]
     pub limit: i32,
@@ -933,6 +1210,28 @@ pub struct GetTracksRequest {
     pub offset: i32,
     #[prost(string, tag = "3")]
     pub filter: ::prost::alloc::string::String,
+    /// "title", "year", "added_at", "duration", "artist", "play_count",
+    /// "rating", "loudness", "tempo" or "musical_key", the title when empty
+    #[prost(string, tag = "4")]
+    pub sort: ::prost::alloc::string::String,
+    #[prost(bool, tag = "5")]
+    pub descending: bool,
+    /// Zeros and empty strings leave the filters unset
+    #[prost(int32, tag = "6")]
+    pub min_year: i32,
+    #[prost(int32, tag = "7")]
+    pub max_year: i32,
+    /// Any spelling of a genre
+    #[prost(string, tag = "8")]
+    pub genre: ::prost::alloc::string::String,
+    /// The extension of the files, e.g. "flac"
+    #[prost(string, tag = "9")]
+    pub format: ::prost::alloc::string::String,
+    /// In kbps
+    #[prost(int32, tag = "10")]
+    pub min_bitrate: i32,
+    #[prost(int32, tag = "11")]
+    pub max_bitrate: i32,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -966,6 +1265,18 @@ pub struct GetArtistDetailsResponse {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGenreDetailsRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetGenreDetailsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub genre: ::core::option::Option<super::super::metadata::v1alpha1::Genre>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetTrackDetailsRequest {
     #[prost(string, tag = "1")]
     pub id: ::prost::alloc::string::String,
@@ -976,76 +1287,211 @@ pub struct GetTrackDetailsResponse {
     #[prost(message, optional, tag = "1")]
     pub track: ::core::option::Option<super::super::metadata::v1alpha1::Track>,
 }
-/// Generated client implementations.
-pub mod library_service_client {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    use tonic::codegen::http::Uri;
-    #[derive(Debug, Clone)]
-    pub struct LibraryServiceClient<T> {
-        inner: tonic::client::Grpc<T>,
-    }
-    impl LibraryServiceClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: std::convert::TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
-        }
-    }
-    impl<T> LibraryServiceClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
-        }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> LibraryServiceClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
-            T::ResponseBody: Default,
-            T: tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-                Response = http::Response<
-                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
-                >,
-            >,
-            <T as tonic::codegen::Service<
-                http::Request<tonic::body::BoxBody>,
-            >>::Error: Into<StdError> + Send + Sync,
-        {
-            LibraryServiceClient::new(InterceptedService::new(inner, interceptor))
-        }
-        /// Compress requests with the given encoding.
-        ///
-        /// This requires the server to support it otherwise it might respond with an
-        /// error.
-        #[must_use]
-        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.send_compressed(encoding);
-            self
-        }
-        /// Enable decompressing responses.
-        #[must_use]
-        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
-            self.inner = self.inner.accept_compressed(encoding);
-            self
-        }
-        pub async fn scan(
+/// Empty strings, zeros and empty bytes leave the tag unchanged
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct TagChanges {
+    #[prost(string, tag = "1")]
+    pub title: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub artist: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub album: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub album_artist: ::prost::alloc::string::String,
+    #[prost(int32, tag = "5")]
+    pub year: i32,
+    #[prost(string, tag = "6")]
+    pub genre: ::prost::alloc::string::String,
+    #[prost(int32, tag = "7")]
+    pub track_number: i32,
+    #[prost(int32, tag = "8")]
+    pub disc_number: i32,
+    #[prost(bytes = "vec", tag = "9")]
+    pub cover: ::prost::alloc::vec::Vec<u8>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTrackTagsRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub changes: ::core::option::Option<TagChanges>,
+    #[prost(bool, tag = "3")]
+    pub dry_run: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateTrackTagsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub track: ::core::option::Option<super::super::metadata::v1alpha1::Track>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateAlbumTagsRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(message, optional, tag = "2")]
+    pub changes: ::core::option::Option<TagChanges>,
+    #[prost(bool, tag = "3")]
+    pub dry_run: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateAlbumTagsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tracks: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Track>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLyricsRequest {
+    #[prost(string, tag = "1")]
+    pub track_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLyricsResponse {
+    #[prost(message, optional, tag = "1")]
+    pub lyrics: ::core::option::Option<super::super::metadata::v1alpha1::Lyrics>,
+}
+/// 0 removes the rating of the track
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RateTrackRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(int32, tag = "2")]
+    pub rating: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RateTrackResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetFavouriteRequest {
+    /// "track", "album" or "artist"
+    #[prost(string, tag = "1")]
+    pub kind: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(bool, tag = "3")]
+    pub favourite: bool,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetFavouriteResponse {}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLikedTracksRequest {
+    #[prost(int32, tag = "1")]
+    pub limit: i32,
+    #[prost(int32, tag = "2")]
+    pub offset: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetLikedTracksResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub tracks: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Track>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFavouriteAlbumsRequest {
+    #[prost(int32, tag = "1")]
+    pub limit: i32,
+    #[prost(int32, tag = "2")]
+    pub offset: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFavouriteAlbumsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub albums: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Album>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFavouriteArtistsRequest {
+    #[prost(int32, tag = "1")]
+    pub limit: i32,
+    #[prost(int32, tag = "2")]
+    pub offset: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct GetFavouriteArtistsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub artists: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Artist>,
+}
+/// Generated client implementations.
+pub mod library_service_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct LibraryServiceClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl LibraryServiceClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: std::convert::TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> LibraryServiceClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> LibraryServiceClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
+            T::ResponseBody: Default,
+            T: tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+                Response = http::Response<
+                    <T as tonic::client::GrpcService<tonic::body::BoxBody>>::ResponseBody,
+                >,
+            >,
+            <T as tonic::codegen::Service<
+                http::Request<tonic::body::BoxBody>,
+            >>::Error: Into<StdError> + Send + Sync,
+        {
+            LibraryServiceClient::new(InterceptedService::new(inner, interceptor))
+        }
+        /// Compress requests with the given encoding.
+        ///
+        /// This requires the server to support it otherwise it might respond with an
+        /// error.
+        #[must_use]
+        pub fn send_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.send_compressed(encoding);
+            self
+        }
+        /// Enable decompressing responses.
+        #[must_use]
+        pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
+            self.inner = self.inner.accept_compressed(encoding);
+            self
+        }
+        pub async fn scan(
             &mut self,
             request: impl tonic::IntoRequest<super::ScanRequest>,
         ) -> Result<tonic::Response<super::ScanResponse>, tonic::Status> {
@@ -1083,6 +1529,25 @@ pub mod library_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn suggest(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SuggestRequest>,
+        ) -> Result<tonic::Response<super::SuggestResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/Suggest",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         pub async fn get_albums(
             &mut self,
             request: impl tonic::IntoRequest<super::GetAlbumsRequest>,
@@ -1197,77 +1662,311 @@ pub mod library_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
-    }
-}
-/// Generated server implementations.
-pub mod library_service_server {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    /// Generated trait containing gRPC methods that should be implemented for use with LibraryServiceServer.
-    #[async_trait]
-    pub trait LibraryService: Send + Sync + 'static {
-        async fn scan(
-            &self,
-            request: tonic::Request<super::ScanRequest>,
-        ) -> Result<tonic::Response<super::ScanResponse>, tonic::Status>;
-        async fn search(
-            &self,
-            request: tonic::Request<super::SearchRequest>,
-        ) -> Result<tonic::Response<super::SearchResponse>, tonic::Status>;
-        async fn get_albums(
-            &self,
-            request: tonic::Request<super::GetAlbumsRequest>,
-        ) -> Result<tonic::Response<super::GetAlbumsResponse>, tonic::Status>;
-        async fn get_album_details(
-            &self,
-            request: tonic::Request<super::GetAlbumDetailsRequest>,
-        ) -> Result<tonic::Response<super::GetAlbumDetailsResponse>, tonic::Status>;
-        async fn get_tracks(
-            &self,
-            request: tonic::Request<super::GetTracksRequest>,
-        ) -> Result<tonic::Response<super::GetTracksResponse>, tonic::Status>;
-        async fn get_track_details(
-            &self,
-            request: tonic::Request<super::GetTrackDetailsRequest>,
-        ) -> Result<tonic::Response<super::GetTrackDetailsResponse>, tonic::Status>;
-        async fn get_artists(
-            &self,
-            request: tonic::Request<super::GetArtistsRequest>,
-        ) -> Result<tonic::Response<super::GetArtistsResponse>, tonic::Status>;
-        async fn get_artist_details(
-            &self,
-            request: tonic::Request<super::GetArtistDetailsRequest>,
-        ) -> Result<tonic::Response<super::GetArtistDetailsResponse>, tonic::Status>;
-    }
-    #[derive(Debug)]
-    pub struct LibraryServiceServer<T: LibraryService> {
-        inner: _Inner<T>,
-        accept_compression_encodings: EnabledCompressionEncodings,
-        send_compression_encodings: EnabledCompressionEncodings,
-    }
-    struct _Inner<T>(Arc<T>);
-    impl<T: LibraryService> LibraryServiceServer<T> {
-        pub fn new(inner: T) -> Self {
-            Self::from_arc(Arc::new(inner))
-        }
-        pub fn from_arc(inner: Arc<T>) -> Self {
-            let inner = _Inner(inner);
-            Self {
-                inner,
-                accept_compression_encodings: Default::default(),
-                send_compression_encodings: Default::default(),
-            }
-        }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> InterceptedService<Self, F>
-        where
-            F: tonic::service::Interceptor,
-        {
-            InterceptedService::new(Self::new(inner), interceptor)
+        pub async fn get_genres(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetGenresRequest>,
+        ) -> Result<tonic::Response<super::GetGenresResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/GetGenres",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
         }
-        /// Enable decompressing requests with the given encoding.
+        pub async fn get_genre_details(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetGenreDetailsRequest>,
+        ) -> Result<tonic::Response<super::GetGenreDetailsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/GetGenreDetails",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn get_lyrics(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetLyricsRequest>,
+        ) -> Result<tonic::Response<super::GetLyricsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/GetLyrics",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn update_track_tags(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateTrackTagsRequest>,
+        ) -> Result<tonic::Response<super::UpdateTrackTagsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/UpdateTrackTags",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn update_album_tags(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateAlbumTagsRequest>,
+        ) -> Result<tonic::Response<super::UpdateAlbumTagsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/UpdateAlbumTags",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn rate_track(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RateTrackRequest>,
+        ) -> Result<tonic::Response<super::RateTrackResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/RateTrack",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn set_favourite(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetFavouriteRequest>,
+        ) -> Result<tonic::Response<super::SetFavouriteResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/SetFavourite",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn get_liked_tracks(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetLikedTracksRequest>,
+        ) -> Result<tonic::Response<super::GetLikedTracksResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/GetLikedTracks",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn get_favourite_albums(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetFavouriteAlbumsRequest>,
+        ) -> Result<tonic::Response<super::GetFavouriteAlbumsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/GetFavouriteAlbums",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn get_favourite_artists(
+            &mut self,
+            request: impl tonic::IntoRequest<super::GetFavouriteArtistsRequest>,
+        ) -> Result<tonic::Response<super::GetFavouriteArtistsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.LibraryService/GetFavouriteArtists",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+    }
+}
+/// Generated server implementations.
+pub mod library_service_server {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    /// Generated trait containing gRPC methods that should be implemented for use with LibraryServiceServer.
+    #[async_trait]
+    pub trait LibraryService: Send + Sync + 'static {
+        async fn scan(
+            &self,
+            request: tonic::Request<super::ScanRequest>,
+        ) -> Result<tonic::Response<super::ScanResponse>, tonic::Status>;
+        async fn search(
+            &self,
+            request: tonic::Request<super::SearchRequest>,
+        ) -> Result<tonic::Response<super::SearchResponse>, tonic::Status>;
+        async fn suggest(
+            &self,
+            request: tonic::Request<super::SuggestRequest>,
+        ) -> Result<tonic::Response<super::SuggestResponse>, tonic::Status>;
+        async fn get_albums(
+            &self,
+            request: tonic::Request<super::GetAlbumsRequest>,
+        ) -> Result<tonic::Response<super::GetAlbumsResponse>, tonic::Status>;
+        async fn get_album_details(
+            &self,
+            request: tonic::Request<super::GetAlbumDetailsRequest>,
+        ) -> Result<tonic::Response<super::GetAlbumDetailsResponse>, tonic::Status>;
+        async fn get_tracks(
+            &self,
+            request: tonic::Request<super::GetTracksRequest>,
+        ) -> Result<tonic::Response<super::GetTracksResponse>, tonic::Status>;
+        async fn get_track_details(
+            &self,
+            request: tonic::Request<super::GetTrackDetailsRequest>,
+        ) -> Result<tonic::Response<super::GetTrackDetailsResponse>, tonic::Status>;
+        async fn get_artists(
+            &self,
+            request: tonic::Request<super::GetArtistsRequest>,
+        ) -> Result<tonic::Response<super::GetArtistsResponse>, tonic::Status>;
+        async fn get_artist_details(
+            &self,
+            request: tonic::Request<super::GetArtistDetailsRequest>,
+        ) -> Result<tonic::Response<super::GetArtistDetailsResponse>, tonic::Status>;
+        async fn get_genres(
+            &self,
+            request: tonic::Request<super::GetGenresRequest>,
+        ) -> Result<tonic::Response<super::GetGenresResponse>, tonic::Status>;
+        async fn get_genre_details(
+            &self,
+            request: tonic::Request<super::GetGenreDetailsRequest>,
+        ) -> Result<tonic::Response<super::GetGenreDetailsResponse>, tonic::Status>;
+        async fn get_lyrics(
+            &self,
+            request: tonic::Request<super::GetLyricsRequest>,
+        ) -> Result<tonic::Response<super::GetLyricsResponse>, tonic::Status>;
+        async fn update_track_tags(
+            &self,
+            request: tonic::Request<super::UpdateTrackTagsRequest>,
+        ) -> Result<tonic::Response<super::UpdateTrackTagsResponse>, tonic::Status>;
+        async fn update_album_tags(
+            &self,
+            request: tonic::Request<super::UpdateAlbumTagsRequest>,
+        ) -> Result<tonic::Response<super::UpdateAlbumTagsResponse>, tonic::Status>;
+        async fn rate_track(
+            &self,
+            request: tonic::Request<super::RateTrackRequest>,
+        ) -> Result<tonic::Response<super::RateTrackResponse>, tonic::Status>;
+        async fn set_favourite(
+            &self,
+            request: tonic::Request<super::SetFavouriteRequest>,
+        ) -> Result<tonic::Response<super::SetFavouriteResponse>, tonic::Status>;
+        async fn get_liked_tracks(
+            &self,
+            request: tonic::Request<super::GetLikedTracksRequest>,
+        ) -> Result<tonic::Response<super::GetLikedTracksResponse>, tonic::Status>;
+        async fn get_favourite_albums(
+            &self,
+            request: tonic::Request<super::GetFavouriteAlbumsRequest>,
+        ) -> Result<tonic::Response<super::GetFavouriteAlbumsResponse>, tonic::Status>;
+        async fn get_favourite_artists(
+            &self,
+            request: tonic::Request<super::GetFavouriteArtistsRequest>,
+        ) -> Result<tonic::Response<super::GetFavouriteArtistsResponse>, tonic::Status>;
+    }
+    #[derive(Debug)]
+    pub struct LibraryServiceServer<T: LibraryService> {
+        inner: _Inner<T>,
+        accept_compression_encodings: EnabledCompressionEncodings,
+        send_compression_encodings: EnabledCompressionEncodings,
+    }
+    struct _Inner<T>(Arc<T>);
+    impl<T: LibraryService> LibraryServiceServer<T> {
+        pub fn new(inner: T) -> Self {
+            Self::from_arc(Arc::new(inner))
+        }
+        pub fn from_arc(inner: Arc<T>) -> Self {
+            let inner = _Inner(inner);
+            Self {
+                inner,
+                accept_compression_encodings: Default::default(),
+                send_compression_encodings: Default::default(),
+            }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> InterceptedService<Self, F>
+        where
+            F: tonic::service::Interceptor,
+        {
+            InterceptedService::new(Self::new(inner), interceptor)
+        }
+        /// Enable decompressing requests with the given encoding.
         #[must_use]
         pub fn accept_compressed(mut self, encoding: CompressionEncoding) -> Self {
             self.accept_compression_encodings.enable(encoding);
@@ -1300,21 +1999,409 @@ pub mod library_service_server {
             match req.uri().path() {
                 "/music.v1alpha1.LibraryService/Scan" => {
                     #[allow(non_camel_case_types)]
-                    struct ScanSvc<T: LibraryService>(pub Arc<T>);
+                    struct ScanSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::ScanRequest> for ScanSvc<T> {
+                        type Response = super::ScanResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ScanRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).scan(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ScanSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/Search" => {
+                    #[allow(non_camel_case_types)]
+                    struct SearchSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::SearchRequest>
+                    for SearchSvc<T> {
+                        type Response = super::SearchResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SearchRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).search(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SearchSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/Suggest" => {
+                    #[allow(non_camel_case_types)]
+                    struct SuggestSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::SuggestRequest>
+                    for SuggestSvc<T> {
+                        type Response = super::SuggestResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SuggestRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).suggest(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SuggestSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetAlbums" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAlbumsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetAlbumsRequest>
+                    for GetAlbumsSvc<T> {
+                        type Response = super::GetAlbumsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAlbumsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_albums(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetAlbumsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetAlbumDetails" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetAlbumDetailsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetAlbumDetailsRequest>
+                    for GetAlbumDetailsSvc<T> {
+                        type Response = super::GetAlbumDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetAlbumDetailsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_album_details(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetAlbumDetailsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetTracks" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTracksSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetTracksRequest>
+                    for GetTracksSvc<T> {
+                        type Response = super::GetTracksResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTracksRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_tracks(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTracksSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetTrackDetails" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetTrackDetailsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetTrackDetailsRequest>
+                    for GetTrackDetailsSvc<T> {
+                        type Response = super::GetTrackDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetTrackDetailsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_track_details(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetTrackDetailsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetArtists" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetArtistsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetArtistsRequest>
+                    for GetArtistsSvc<T> {
+                        type Response = super::GetArtistsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetArtistsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_artists(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetArtistsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetArtistDetails" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetArtistDetailsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetArtistDetailsRequest>
+                    for GetArtistDetailsSvc<T> {
+                        type Response = super::GetArtistDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetArtistDetailsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_artist_details(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetArtistDetailsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetGenres" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetGenresSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetGenresRequest>
+                    for GetGenresSvc<T> {
+                        type Response = super::GetGenresResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetGenresRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).get_genres(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetGenresSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetGenreDetails" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetGenreDetailsSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::ScanRequest> for ScanSvc<T> {
-                        type Response = super::ScanResponse;
+                    > tonic::server::UnaryService<super::GetGenreDetailsRequest>
+                    for GetGenreDetailsSvc<T> {
+                        type Response = super::GetGenreDetailsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::ScanRequest>,
+                            request: tonic::Request<super::GetGenreDetailsRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).scan(request).await };
+                            let fut = async move {
+                                (*inner).get_genre_details(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -1323,7 +2410,7 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = ScanSvc(inner);
+                        let method = GetGenreDetailsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1335,24 +2422,24 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/music.v1alpha1.LibraryService/Search" => {
+                "/music.v1alpha1.LibraryService/GetLyrics" => {
                     #[allow(non_camel_case_types)]
-                    struct SearchSvc<T: LibraryService>(pub Arc<T>);
+                    struct GetLyricsSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::SearchRequest>
-                    for SearchSvc<T> {
-                        type Response = super::SearchResponse;
+                    > tonic::server::UnaryService<super::GetLyricsRequest>
+                    for GetLyricsSvc<T> {
+                        type Response = super::GetLyricsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::SearchRequest>,
+                            request: tonic::Request<super::GetLyricsRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).search(request).await };
+                            let fut = async move { (*inner).get_lyrics(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -1361,7 +2448,7 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = SearchSvc(inner);
+                        let method = GetLyricsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1373,24 +2460,26 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/music.v1alpha1.LibraryService/GetAlbums" => {
+                "/music.v1alpha1.LibraryService/UpdateTrackTags" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAlbumsSvc<T: LibraryService>(pub Arc<T>);
+                    struct UpdateTrackTagsSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::GetAlbumsRequest>
-                    for GetAlbumsSvc<T> {
-                        type Response = super::GetAlbumsResponse;
+                    > tonic::server::UnaryService<super::UpdateTrackTagsRequest>
+                    for UpdateTrackTagsSvc<T> {
+                        type Response = super::UpdateTrackTagsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetAlbumsRequest>,
+                            request: tonic::Request<super::UpdateTrackTagsRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).get_albums(request).await };
+                            let fut = async move {
+                                (*inner).update_track_tags(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -1399,7 +2488,7 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetAlbumsSvc(inner);
+                        let method = UpdateTrackTagsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1411,25 +2500,25 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/music.v1alpha1.LibraryService/GetAlbumDetails" => {
+                "/music.v1alpha1.LibraryService/UpdateAlbumTags" => {
                     #[allow(non_camel_case_types)]
-                    struct GetAlbumDetailsSvc<T: LibraryService>(pub Arc<T>);
+                    struct UpdateAlbumTagsSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::GetAlbumDetailsRequest>
-                    for GetAlbumDetailsSvc<T> {
-                        type Response = super::GetAlbumDetailsResponse;
+                    > tonic::server::UnaryService<super::UpdateAlbumTagsRequest>
+                    for UpdateAlbumTagsSvc<T> {
+                        type Response = super::UpdateAlbumTagsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetAlbumDetailsRequest>,
+                            request: tonic::Request<super::UpdateAlbumTagsRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
                             let fut = async move {
-                                (*inner).get_album_details(request).await
+                                (*inner).update_album_tags(request).await
                             };
                             Box::pin(fut)
                         }
@@ -1439,7 +2528,7 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetAlbumDetailsSvc(inner);
+                        let method = UpdateAlbumTagsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1451,24 +2540,24 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/music.v1alpha1.LibraryService/GetTracks" => {
+                "/music.v1alpha1.LibraryService/RateTrack" => {
                     #[allow(non_camel_case_types)]
-                    struct GetTracksSvc<T: LibraryService>(pub Arc<T>);
+                    struct RateTrackSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::GetTracksRequest>
-                    for GetTracksSvc<T> {
-                        type Response = super::GetTracksResponse;
+                    > tonic::server::UnaryService<super::RateTrackRequest>
+                    for RateTrackSvc<T> {
+                        type Response = super::RateTrackResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetTracksRequest>,
+                            request: tonic::Request<super::RateTrackRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).get_tracks(request).await };
+                            let fut = async move { (*inner).rate_track(request).await };
                             Box::pin(fut)
                         }
                     }
@@ -1477,7 +2566,7 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetTracksSvc(inner);
+                        let method = RateTrackSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1489,25 +2578,25 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/music.v1alpha1.LibraryService/GetTrackDetails" => {
+                "/music.v1alpha1.LibraryService/SetFavourite" => {
                     #[allow(non_camel_case_types)]
-                    struct GetTrackDetailsSvc<T: LibraryService>(pub Arc<T>);
+                    struct SetFavouriteSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::GetTrackDetailsRequest>
-                    for GetTrackDetailsSvc<T> {
-                        type Response = super::GetTrackDetailsResponse;
+                    > tonic::server::UnaryService<super::SetFavouriteRequest>
+                    for SetFavouriteSvc<T> {
+                        type Response = super::SetFavouriteResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetTrackDetailsRequest>,
+                            request: tonic::Request<super::SetFavouriteRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
                             let fut = async move {
-                                (*inner).get_track_details(request).await
+                                (*inner).set_favourite(request).await
                             };
                             Box::pin(fut)
                         }
@@ -1517,7 +2606,7 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetTrackDetailsSvc(inner);
+                        let method = SetFavouriteSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1529,24 +2618,26 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/music.v1alpha1.LibraryService/GetArtists" => {
+                "/music.v1alpha1.LibraryService/GetLikedTracks" => {
                     #[allow(non_camel_case_types)]
-                    struct GetArtistsSvc<T: LibraryService>(pub Arc<T>);
+                    struct GetLikedTracksSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::GetArtistsRequest>
-                    for GetArtistsSvc<T> {
-                        type Response = super::GetArtistsResponse;
+                    > tonic::server::UnaryService<super::GetLikedTracksRequest>
+                    for GetLikedTracksSvc<T> {
+                        type Response = super::GetLikedTracksResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetArtistsRequest>,
+                            request: tonic::Request<super::GetLikedTracksRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
-                            let fut = async move { (*inner).get_artists(request).await };
+                            let fut = async move {
+                                (*inner).get_liked_tracks(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -1555,7 +2646,7 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetArtistsSvc(inner);
+                        let method = GetLikedTracksSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -1567,25 +2658,25 @@ pub mod library_service_server {
                     };
                     Box::pin(fut)
                 }
-                "/music.v1alpha1.LibraryService/GetArtistDetails" => {
+                "/music.v1alpha1.LibraryService/GetFavouriteAlbums" => {
                     #[allow(non_camel_case_types)]
-                    struct GetArtistDetailsSvc<T: LibraryService>(pub Arc<T>);
+                    struct GetFavouriteAlbumsSvc<T: LibraryService>(pub Arc<T>);
                     impl<
                         T: LibraryService,
-                    > tonic::server::UnaryService<super::GetArtistDetailsRequest>
-                    for GetArtistDetailsSvc<T> {
-                        type Response = super::GetArtistDetailsResponse;
+                    > tonic::server::UnaryService<super::GetFavouriteAlbumsRequest>
+                    for GetFavouriteAlbumsSvc<T> {
+                        type Response = super::GetFavouriteAlbumsResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetArtistDetailsRequest>,
+                            request: tonic::Request<super::GetFavouriteAlbumsRequest>,
                         ) -> Self::Future {
                             let inner = self.0.clone();
                             let fut = async move {
-                                (*inner).get_artist_details(request).await
+                                (*inner).get_favourite_albums(request).await
                             };
                             Box::pin(fut)
                         }
@@ -1595,7 +2686,47 @@ pub mod library_service_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetArtistDetailsSvc(inner);
+                        let method = GetFavouriteAlbumsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.LibraryService/GetFavouriteArtists" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetFavouriteArtistsSvc<T: LibraryService>(pub Arc<T>);
+                    impl<
+                        T: LibraryService,
+                    > tonic::server::UnaryService<super::GetFavouriteArtistsRequest>
+                    for GetFavouriteArtistsSvc<T> {
+                        type Response = super::GetFavouriteArtistsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetFavouriteArtistsRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).get_favourite_artists(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetFavouriteArtistsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -2989,6 +4120,71 @@ pub struct AddItemResponse {
     #[prost(message, repeated, tag = "3")]
     pub tracks: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Track>,
 }
+/// Inserts a track at a position, the following tracks shifting down.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct InsertItemRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub track_id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "3")]
+    pub position: u32,
+}
+/// Moves the track at `from` to `to`, the tracks in between shifting by one.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct MoveItemRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(uint32, tag = "2")]
+    pub from: u32,
+    #[prost(uint32, tag = "3")]
+    pub to: u32,
+}
+/// Imports a M3U/M3U8, PLS or XSPF playlist file, the format being named by
+/// its extension. Relative paths are resolved from `base_path`.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub content: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub format: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub base_path: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ImportResponse {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(message, repeated, tag = "3")]
+    pub tracks: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Track>,
+    /// The entries of the file matching no track of the library
+    #[prost(string, repeated, tag = "4")]
+    pub missing: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub format: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportResponse {
+    #[prost(string, tag = "1")]
+    pub content: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub file_name: ::prost::alloc::string::String,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct FindAllRequest {}
@@ -3015,6 +4211,28 @@ pub struct GetPlaylistDetailsResponse {
     pub description: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "4")]
     pub tracks: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Track>,
+    #[prost(string, tag = "5")]
+    pub rules: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct CreateSmartRequest {
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub rules: ::prost::alloc::string::String,
+    #[prost(string, tag = "3")]
+    pub description: ::prost::alloc::string::String,
+    #[prost(string, tag = "4")]
+    pub folder_id: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateSmartRequest {
+    #[prost(string, tag = "1")]
+    pub id: ::prost::alloc::string::String,
+    #[prost(string, tag = "2")]
+    pub rules: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3268,6 +4486,82 @@ pub mod playlist_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn insert_item(
+            &mut self,
+            request: impl tonic::IntoRequest<super::InsertItemRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.PlaylistService/InsertItem",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn move_item(
+            &mut self,
+            request: impl tonic::IntoRequest<super::MoveItemRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.PlaylistService/MoveItem",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn import(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ImportRequest>,
+        ) -> Result<tonic::Response<super::ImportResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.PlaylistService/Import",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn export(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportRequest>,
+        ) -> Result<tonic::Response<super::ExportResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.PlaylistService/Export",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
         pub async fn find_all(
             &mut self,
             request: impl tonic::IntoRequest<super::FindAllRequest>,
@@ -3289,7 +4583,45 @@ pub mod playlist_service_client {
         }
         pub async fn get_playlist_details(
             &mut self,
-            request: impl tonic::IntoRequest<super::GetPlaylistDetailsRequest>,
+            request: impl tonic::IntoRequest<super::GetPlaylistDetailsRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.PlaylistService/GetPlaylistDetails",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn create_smart(
+            &mut self,
+            request: impl tonic::IntoRequest<super::CreateSmartRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.PlaylistService/CreateSmart",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
+        pub async fn update_smart(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateSmartRequest>,
         ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status> {
             self.inner
                 .ready()
@@ -3302,7 +4634,7 @@ pub mod playlist_service_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/music.v1alpha1.PlaylistService/GetPlaylistDetails",
+                "/music.v1alpha1.PlaylistService/UpdateSmart",
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
@@ -3434,6 +4766,22 @@ pub mod playlist_service_server {
             &self,
             request: tonic::Request<super::AddItemRequest>,
         ) -> Result<tonic::Response<super::AddItemResponse>, tonic::Status>;
+        async fn insert_item(
+            &self,
+            request: tonic::Request<super::InsertItemRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status>;
+        async fn move_item(
+            &self,
+            request: tonic::Request<super::MoveItemRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status>;
+        async fn import(
+            &self,
+            request: tonic::Request<super::ImportRequest>,
+        ) -> Result<tonic::Response<super::ImportResponse>, tonic::Status>;
+        async fn export(
+            &self,
+            request: tonic::Request<super::ExportRequest>,
+        ) -> Result<tonic::Response<super::ExportResponse>, tonic::Status>;
         async fn find_all(
             &self,
             request: tonic::Request<super::FindAllRequest>,
@@ -3442,6 +4790,14 @@ pub mod playlist_service_server {
             &self,
             request: tonic::Request<super::GetPlaylistDetailsRequest>,
         ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status>;
+        async fn create_smart(
+            &self,
+            request: tonic::Request<super::CreateSmartRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status>;
+        async fn update_smart(
+            &self,
+            request: tonic::Request<super::UpdateSmartRequest>,
+        ) -> Result<tonic::Response<super::GetPlaylistDetailsResponse>, tonic::Status>;
         async fn create_folder(
             &self,
             request: tonic::Request<super::CreateFolderRequest>,
@@ -3750,6 +5106,158 @@ pub mod playlist_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/music.v1alpha1.PlaylistService/InsertItem" => {
+                    #[allow(non_camel_case_types)]
+                    struct InsertItemSvc<T: PlaylistService>(pub Arc<T>);
+                    impl<
+                        T: PlaylistService,
+                    > tonic::server::UnaryService<super::InsertItemRequest>
+                    for InsertItemSvc<T> {
+                        type Response = super::GetPlaylistDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::InsertItemRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).insert_item(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = InsertItemSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.PlaylistService/MoveItem" => {
+                    #[allow(non_camel_case_types)]
+                    struct MoveItemSvc<T: PlaylistService>(pub Arc<T>);
+                    impl<
+                        T: PlaylistService,
+                    > tonic::server::UnaryService<super::MoveItemRequest>
+                    for MoveItemSvc<T> {
+                        type Response = super::GetPlaylistDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::MoveItemRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).move_item(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = MoveItemSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.PlaylistService/Import" => {
+                    #[allow(non_camel_case_types)]
+                    struct ImportSvc<T: PlaylistService>(pub Arc<T>);
+                    impl<
+                        T: PlaylistService,
+                    > tonic::server::UnaryService<super::ImportRequest>
+                    for ImportSvc<T> {
+                        type Response = super::ImportResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ImportRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).import(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ImportSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.PlaylistService/Export" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExportSvc<T: PlaylistService>(pub Arc<T>);
+                    impl<
+                        T: PlaylistService,
+                    > tonic::server::UnaryService<super::ExportRequest>
+                    for ExportSvc<T> {
+                        type Response = super::ExportResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExportRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move { (*inner).export(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ExportSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/music.v1alpha1.PlaylistService/FindAll" => {
                     #[allow(non_camel_case_types)]
                     struct FindAllSvc<T: PlaylistService>(pub Arc<T>);
@@ -3828,6 +5336,86 @@ pub mod playlist_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/music.v1alpha1.PlaylistService/CreateSmart" => {
+                    #[allow(non_camel_case_types)]
+                    struct CreateSmartSvc<T: PlaylistService>(pub Arc<T>);
+                    impl<
+                        T: PlaylistService,
+                    > tonic::server::UnaryService<super::CreateSmartRequest>
+                    for CreateSmartSvc<T> {
+                        type Response = super::GetPlaylistDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::CreateSmartRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).create_smart(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = CreateSmartSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/music.v1alpha1.PlaylistService/UpdateSmart" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateSmartSvc<T: PlaylistService>(pub Arc<T>);
+                    impl<
+                        T: PlaylistService,
+                    > tonic::server::UnaryService<super::UpdateSmartRequest>
+                    for UpdateSmartSvc<T> {
+                        type Response = super::GetPlaylistDetailsResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdateSmartRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).update_smart(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateSmartSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/music.v1alpha1.PlaylistService/CreateFolder" => {
                     #[allow(non_camel_case_types)]
                     struct CreateFolderSvc<T: PlaylistService>(pub Arc<T>);
@@ -4178,6 +5766,19 @@ pub struct ShuffleRequest {}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct GetTracklistTracksRequest {}
+/// Exports the previous and next tracks as a M3U8, PLS or XSPF playlist file.
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportTracklistRequest {
+    #[prost(string, tag = "1")]
+    pub format: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExportTracklistResponse {
+    #[prost(string, tag = "1")]
+    pub content: ::prost::alloc::string::String,
+}
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct PlayNextRequest {
@@ -4569,6 +6170,25 @@ pub mod tracklist_service_client {
             );
             self.inner.unary(request.into_request(), path, codec).await
         }
+        pub async fn export_tracklist(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ExportTracklistRequest>,
+        ) -> Result<tonic::Response<super::ExportTracklistResponse>, tonic::Status> {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/music.v1alpha1.TracklistService/ExportTracklist",
+            );
+            self.inner.unary(request.into_request(), path, codec).await
+        }
     }
 }
 /// Generated server implementations.
@@ -4642,6 +6262,10 @@ pub mod tracklist_service_server {
             &self,
             request: tonic::Request<super::PlayTrackAtRequest>,
         ) -> Result<tonic::Response<super::PlayTrackAtResponse>, tonic::Status>;
+        async fn export_tracklist(
+            &self,
+            request: tonic::Request<super::ExportTracklistRequest>,
+        ) -> Result<tonic::Response<super::ExportTracklistResponse>, tonic::Status>;
     }
     #[derive(Debug)]
     pub struct TracklistServiceServer<T: TracklistService> {
@@ -5324,6 +6948,46 @@ pub mod tracklist_service_server {
                     };
                     Box::pin(fut)
                 }
+                "/music.v1alpha1.TracklistService/ExportTracklist" => {
+                    #[allow(non_camel_case_types)]
+                    struct ExportTracklistSvc<T: TracklistService>(pub Arc<T>);
+                    impl<
+                        T: TracklistService,
+                    > tonic::server::UnaryService<super::ExportTracklistRequest>
+                    for ExportTracklistSvc<T> {
+                        type Response = super::ExportTracklistResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ExportTracklistRequest>,
+                        ) -> Self::Future {
+                            let inner = self.0.clone();
+                            let fut = async move {
+                                (*inner).export_tracklist(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ExportTracklistSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 _ => {
                     Box::pin(async move {
                         Ok(
@@ -29,6 +29,8 @@ pub struct Playlist {
     pub description: ::prost::alloc::string::String,
     #[prost(message, repeated, tag = "4")]
     pub tracks: ::prost::alloc::vec::Vec<super::super::metadata::v1alpha1::Track>,
+    #[prost(string, tag = "5")]
+    pub rules: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
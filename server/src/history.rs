@@ -1,10 +1,8 @@
-use std::sync::Arc;
-
-use music_player_storage::Database;
-use tokio::sync::Mutex;
+use music_player_storage::{repo::history::HistoryRepository, Database};
 
 use crate::api::music::v1alpha1::{
     history_service_server::HistoryService, GetHistoryRequest, GetHistoryResponse,
+    GetMostPlayedRequest, GetMostPlayedResponse, GetPlayStatsRequest, GetPlayStatsResponse,
 };
 
 pub struct History {
@@ -21,9 +19,53 @@ impl History {
 impl HistoryService for History {
     async fn get_history(
         &self,
-        _request: tonic::Request<GetHistoryRequest>,
+        request: tonic::Request<GetHistoryRequest>,
     ) -> Result<tonic::Response<GetHistoryResponse>, tonic::Status> {
-        let response = GetHistoryResponse {};
+        let request = request.into_inner();
+        let entries = HistoryRepository::new(self.db.get_connection())
+            .find_all(request.offset.max(0) as u64, page_size(request.limit))
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let response = GetHistoryResponse {
+            entries: entries.into_iter().map(Into::into).collect(),
+        };
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn get_play_stats(
+        &self,
+        request: tonic::Request<GetPlayStatsRequest>,
+    ) -> Result<tonic::Response<GetPlayStatsResponse>, tonic::Status> {
+        let stats = HistoryRepository::new(self.db.get_connection())
+            .stats(&request.get_ref().track_id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let response = GetPlayStatsResponse {
+            stats: Some(stats.into()),
+        };
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn get_most_played(
+        &self,
+        request: tonic::Request<GetMostPlayedRequest>,
+    ) -> Result<tonic::Response<GetMostPlayedResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let tracks = HistoryRepository::new(self.db.get_connection())
+            .most_played(request.offset.max(0) as u64, page_size(request.limit))
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let response = GetMostPlayedResponse {
+            tracks: tracks.into_iter().map(Into::into).collect(),
+        };
         Ok(tonic::Response::new(response))
     }
 }
+
+/// An unset limit returns a page of 100 entries.
+fn page_size(limit: i32) -> u64 {
+    match limit {
+        limit if limit > 0 => limit as u64,
+        _ => 100,
+    }
+}
@@ -18,9 +18,13 @@ pub mod api {
     #[path = ""]
     pub mod music {
         use music_player_entity::folder;
-        use music_player_types::types::Playlist;
+        use music_player_types::types::{self, Playlist};
 
-        use self::v1alpha1::{GetFolderDetailsResponse, GetPlaylistDetailsResponse};
+        use self::v1alpha1::{
+            GetFolderDetailsResponse, GetPlaylistDetailsResponse, HistoryEntry, PlayStats,
+            TagChanges,
+        };
+        use super::metadata::{non_empty, non_zero};
 
         #[path = "music.v1alpha1.rs"]
         pub mod v1alpha1;
@@ -43,10 +47,84 @@ pub mod api {
                     name: self.name,
                     description: Some(self.description),
                     tracks: self.tracks.into_iter().map(Into::into).collect(),
+                    rules: Some(self.rules).filter(|rules| !rules.is_empty()),
                 }
             }
         }
 
+        impl From<TagChanges> for types::TagChanges {
+            fn from(changes: TagChanges) -> Self {
+                Self {
+                    title: non_empty(changes.title),
+                    artist: non_empty(changes.artist),
+                    album: non_empty(changes.album),
+                    album_artist: non_empty(changes.album_artist),
+                    year: non_zero(changes.year),
+                    genre: non_empty(changes.genre),
+                    track_number: non_zero(changes.track_number),
+                    disc_number: non_zero(changes.disc_number),
+                    cover: Some(changes.cover).filter(|cover| !cover.is_empty()),
+                }
+            }
+        }
+
+        impl From<types::HistoryEntry> for HistoryEntry {
+            fn from(entry: types::HistoryEntry) -> Self {
+                Self {
+                    id: entry.id,
+                    track: Some(entry.track.into()),
+                    started_at: entry.started_at.to_rfc3339(),
+                    listened_ms: entry.listened_ms,
+                    completed: entry.completed,
+                    device: entry.device.unwrap_or_default(),
+                }
+            }
+        }
+
+        impl From<HistoryEntry> for types::HistoryEntry {
+            fn from(entry: HistoryEntry) -> Self {
+                Self {
+                    id: entry.id,
+                    track: entry.track.map(Into::into).unwrap_or_default(),
+                    started_at: parse_date_time(&entry.started_at).unwrap_or_default(),
+                    listened_ms: entry.listened_ms,
+                    completed: entry.completed,
+                    device: non_empty(entry.device),
+                }
+            }
+        }
+
+        impl From<types::PlayStats> for PlayStats {
+            fn from(stats: types::PlayStats) -> Self {
+                Self {
+                    track_id: stats.track_id,
+                    play_count: stats.play_count,
+                    skip_count: stats.skip_count,
+                    last_played: stats
+                        .last_played
+                        .map(|date| date.to_rfc3339())
+                        .unwrap_or_default(),
+                }
+            }
+        }
+
+        impl From<PlayStats> for types::PlayStats {
+            fn from(stats: PlayStats) -> Self {
+                Self {
+                    track_id: stats.track_id,
+                    play_count: stats.play_count,
+                    skip_count: stats.skip_count,
+                    last_played: parse_date_time(&stats.last_played),
+                }
+            }
+        }
+
+        fn parse_date_time(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+            chrono::DateTime::parse_from_rfc3339(value)
+                .ok()
+                .map(|date| date.with_timezone(&chrono::Utc))
+        }
+
         impl From<Playlist> for GetPlaylistDetailsResponse {
             fn from(playlist: Playlist) -> Self {
                 Self {
@@ -54,6 +132,7 @@ pub mod api {
                     name: playlist.name,
                     description: playlist.description.unwrap_or_default(),
                     tracks: playlist.tracks.into_iter().map(Into::into).collect(),
+                    rules: playlist.rules.unwrap_or_default(),
                 }
             }
         }
@@ -87,6 +166,7 @@ pub mod api {
                     name: self.name,
                     description: Some(self.description),
                     tracks: self.tracks.into_iter().map(Into::into).collect(),
+                    rules: Some(self.rules).filter(|rules| !rules.is_empty()),
                 }
             }
         }
@@ -94,10 +174,21 @@ pub mod api {
 
     #[path = ""]
     pub mod metadata {
-        use music_player_entity::{album, artist, track};
+        use music_player_entity::{album, artist, genre, lyrics, track};
         use music_player_types::types;
 
-        use self::v1alpha1::{Album, Artist, ArtistSong, Song, SongArtist, Track};
+        use self::v1alpha1::{
+            Album, Artist, ArtistSong, Genre, LyricLine, Lyrics, Song, SongArtist, Track,
+        };
+
+        // proto3 scalars can't be absent, empty strings and zeros mean unknown
+        pub(crate) fn non_empty(value: String) -> Option<String> {
+            Some(value).filter(|value| !value.is_empty())
+        }
+
+        pub(crate) fn non_zero(value: i32) -> Option<u32> {
+            u32::try_from(value).ok().filter(|value| *value > 0)
+        }
 
         #[path = "metadata.v1alpha1.rs"]
         pub mod v1alpha1;
@@ -114,12 +205,52 @@ pub mod api {
             }
         }
 
+        impl From<genre::Model> for Genre {
+            fn from(model: genre::Model) -> Self {
+                Self {
+                    id: model.id,
+                    name: model.name,
+                    albums: model.albums.into_iter().map(Into::into).collect(),
+                    tracks: model.tracks.into_iter().map(Into::into).collect(),
+                }
+            }
+        }
+
+        impl From<lyrics::Model> for Lyrics {
+            fn from(model: lyrics::Model) -> Self {
+                let id = model.track_id.clone();
+                let lyrics = types::Lyrics::from(model);
+                Self {
+                    id,
+                    lines: lyrics
+                        .lines()
+                        .into_iter()
+                        .map(|line| LyricLine {
+                            time_ms: line.time_ms.unwrap_or_default(),
+                            text: line.text,
+                        })
+                        .collect(),
+                    source: lyrics.source.as_str().to_string(),
+                    content: lyrics.content,
+                    synced: lyrics.synced,
+                }
+            }
+        }
+
         impl From<album::Model> for Album {
             fn from(model: album::Model) -> Self {
                 Self {
                     id: model.id,
                     title: model.title,
                     cover: model.cover.unwrap_or_default(),
+                    cover_small: model.cover_small.unwrap_or_default(),
+                    cover_medium: model.cover_medium.unwrap_or_default(),
+                    cover_large: model.cover_large.unwrap_or_default(),
+                    compilation: model.compilation,
+                    label: model.label.unwrap_or_default(),
+                    title_sort: model.title_sort.unwrap_or_default(),
+                    artist_sort: model.artist_sort.unwrap_or_default(),
+                    musicbrainz_release_id: model.musicbrainz_release_id.unwrap_or_default(),
                     artist: model.artist,
                     year: i32::try_from(model.year.unwrap_or_default()).unwrap_or_default(),
                     tracks: model.tracks.into_iter().map(Into::into).collect(),
@@ -136,6 +267,19 @@ pub mod api {
                     uri: model.uri,
                     duration: model.duration.unwrap_or(0.0),
                     track_number: i32::try_from(model.track.unwrap_or_default()).unwrap(),
+                    disc_number: i32::try_from(model.disc_number.unwrap_or_default()).unwrap_or_default(),
+                    disc_total: i32::try_from(model.disc_total.unwrap_or_default()).unwrap_or_default(),
+                    track_total: i32::try_from(model.track_total.unwrap_or_default()).unwrap_or_default(),
+                    composer: model.composer.unwrap_or_default(),
+                    conductor: model.conductor.unwrap_or_default(),
+                    isrc: model.isrc.unwrap_or_default(),
+                    comment: model.comment.unwrap_or_default(),
+                    bpm: i32::try_from(model.bpm.unwrap_or_default()).unwrap_or_default(),
+                    title_sort: model.title_sort.unwrap_or_default(),
+                    artist_sort: model.artist_sort.unwrap_or_default(),
+                    musicbrainz_recording_id: model.musicbrainz_recording_id.unwrap_or_default(),
+                    start_offset: i32::try_from(model.start_offset.unwrap_or_default()).unwrap_or_default(),
+                    end_offset: i32::try_from(model.end_offset.unwrap_or_default()).unwrap_or_default(),
                     artists: model.artists.into_iter().map(Into::into).collect(),
                     album: Some(model.album.into()),
                     artist: model.artist,
@@ -233,6 +377,18 @@ pub mod api {
                     duration: Some(self.duration),
                     track_number: Some(u32::try_from(self.track_number).unwrap_or_default()),
                     disc_number: u32::try_from(self.disc_number).unwrap_or_default(),
+                    disc_total: non_zero(self.disc_total),
+                    track_total: non_zero(self.track_total),
+                    composer: non_empty(self.composer),
+                    conductor: non_empty(self.conductor),
+                    isrc: non_empty(self.isrc),
+                    comment: non_empty(self.comment),
+                    bpm: non_zero(self.bpm),
+                    title_sort: non_empty(self.title_sort),
+                    artist_sort: non_empty(self.artist_sort),
+                    musicbrainz_recording_id: non_empty(self.musicbrainz_recording_id),
+                    start_offset: non_zero(self.start_offset),
+                    end_offset: non_zero(self.end_offset),
                     artists: self.artists.into_iter().map(Into::into).collect(),
                     artist: self.artist,
                     album: match self.album {
@@ -251,6 +407,19 @@ pub mod api {
                     uri: self.uri,
                     duration: Some(self.duration),
                     track: Some(u32::try_from(self.track_number).unwrap_or_default()),
+                    disc_number: non_zero(self.disc_number),
+                    disc_total: non_zero(self.disc_total),
+                    track_total: non_zero(self.track_total),
+                    composer: non_empty(self.composer),
+                    conductor: non_empty(self.conductor),
+                    isrc: non_empty(self.isrc),
+                    comment: non_empty(self.comment),
+                    bpm: non_zero(self.bpm),
+                    title_sort: non_empty(self.title_sort),
+                    artist_sort: non_empty(self.artist_sort),
+                    musicbrainz_recording_id: non_empty(self.musicbrainz_recording_id),
+                    start_offset: non_zero(self.start_offset),
+                    end_offset: non_zero(self.end_offset),
                     artists: self.artists.into_iter().map(Into::into).collect(),
                     artist: self.artist,
                     album: self.album.unwrap().into(),
@@ -267,7 +436,19 @@ pub mod api {
                     uri: track.uri,
                     duration: track.duration.unwrap_or_default(),
                     track_number: i32::try_from(track.track_number.unwrap_or_default()).unwrap(),
-                    disc_number: i32::try_from(track.disc_number).unwrap(),
+                    disc_number: i32::try_from(track.disc_number).unwrap_or_default(),
+                    disc_total: i32::try_from(track.disc_total.unwrap_or_default()).unwrap_or_default(),
+                    track_total: i32::try_from(track.track_total.unwrap_or_default()).unwrap_or_default(),
+                    composer: track.composer.unwrap_or_default(),
+                    conductor: track.conductor.unwrap_or_default(),
+                    isrc: track.isrc.unwrap_or_default(),
+                    comment: track.comment.unwrap_or_default(),
+                    bpm: i32::try_from(track.bpm.unwrap_or_default()).unwrap_or_default(),
+                    title_sort: track.title_sort.unwrap_or_default(),
+                    artist_sort: track.artist_sort.unwrap_or_default(),
+                    musicbrainz_recording_id: track.musicbrainz_recording_id.unwrap_or_default(),
+                    start_offset: i32::try_from(track.start_offset.unwrap_or_default()).unwrap_or_default(),
+                    end_offset: i32::try_from(track.end_offset.unwrap_or_default()).unwrap_or_default(),
                     artists: track.artists.into_iter().map(Into::into).collect(),
                     artist: track.artist,
                     album: match track.album {
@@ -343,10 +524,20 @@ pub mod api {
                     id: self.id,
                     title: self.title,
                     cover: Some(self.cover),
+                    cover_small: non_empty(self.cover_small),
+                    cover_medium: non_empty(self.cover_medium),
+                    cover_large: non_empty(self.cover_large),
+                    compilation: self.compilation,
+                    label: non_empty(self.label),
+                    title_sort: non_empty(self.title_sort),
+                    artist_sort: non_empty(self.artist_sort),
+                    musicbrainz_release_id: non_empty(self.musicbrainz_release_id),
                     artist: self.artist.clone(),
                     year: Some(u32::try_from(self.year).unwrap_or_default()),
                     artist_id: Some(format!("{:x}", md5::compute(self.artist.as_str()))),
                     tracks: self.tracks.into_iter().map(Into::into).collect(),
+                    // The gRPC albums only carry the JPEG thumbnails
+                    ..Default::default()
                 }
             }
         }
@@ -357,6 +548,14 @@ pub mod api {
                     id: self.id,
                     title: self.title,
                     cover: Some(self.cover),
+                    cover_small: non_empty(self.cover_small),
+                    cover_medium: non_empty(self.cover_medium),
+                    cover_large: non_empty(self.cover_large),
+                    compilation: self.compilation,
+                    label: non_empty(self.label),
+                    title_sort: non_empty(self.title_sort),
+                    artist_sort: non_empty(self.artist_sort),
+                    musicbrainz_release_id: non_empty(self.musicbrainz_release_id),
                     artist: self.artist.clone(),
                     year: Some(u32::try_from(self.year).unwrap_or_default()),
                     artist_id: Some(format!("{:x}", md5::compute(self.artist.as_str()))),
@@ -371,6 +570,14 @@ pub mod api {
                     id: album.id,
                     title: album.title,
                     cover: album.cover.unwrap_or_default(),
+                    cover_small: album.cover_small.unwrap_or_default(),
+                    cover_medium: album.cover_medium.unwrap_or_default(),
+                    cover_large: album.cover_large.unwrap_or_default(),
+                    compilation: album.compilation,
+                    label: album.label.unwrap_or_default(),
+                    title_sort: album.title_sort.unwrap_or_default(),
+                    artist_sort: album.artist_sort.unwrap_or_default(),
+                    musicbrainz_release_id: album.musicbrainz_release_id.unwrap_or_default(),
                     artist: album.artist,
                     year: i32::try_from(album.year.unwrap_or_default()).unwrap_or_default(),
                     tracks: album.tracks.into_iter().map(Into::into).collect(),
@@ -378,5 +585,29 @@ pub mod api {
                 }
             }
         }
+
+        // The search indexes only store what the results display
+        impl From<types::SimplifiedSong> for Track {
+            fn from(song: types::SimplifiedSong) -> Self {
+                Self {
+                    id: song.id,
+                    title: song.title,
+                    duration: song.duration.as_secs_f32(),
+                    artists: vec![Artist {
+                        id: song.artist_id,
+                        name: song.artist.clone(),
+                        ..Default::default()
+                    }],
+                    artist: song.artist,
+                    album: Some(Album {
+                        id: song.album_id,
+                        title: song.album,
+                        cover: song.cover.unwrap_or_default(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }
+            }
+        }
     }
 }
@@ -1,26 +1,109 @@
 use futures::future::FutureExt;
-use music_player_entity::{album, artist, artist_tracks, track};
-use music_player_scanner::scan_directory;
+use music_player_scanner::{
+    editor::{edit_album, edit_tracks},
+    rating::export_rating,
+    save_song, scan_directory,
+};
+use music_player_settings::{read_settings, Settings};
 use music_player_storage::repo::artist::ArtistRepository;
-use music_player_storage::repo::track::TrackRepository;
+use music_player_storage::repo::genre::GenreRepository;
+use music_player_storage::repo::lyrics::LyricsRepository;
+use music_player_storage::repo::rating::RatingRepository;
+use music_player_storage::repo::track::{TrackField, TrackFilter, TrackOrder, TrackRepository};
 use music_player_storage::Database;
-use music_player_storage::{repo::album::AlbumRepository, searcher::Searcher};
-use sea_orm::ActiveModelTrait;
+use music_player_storage::{
+    repo::album::{AlbumField, AlbumOrder, AlbumRepository},
+    searcher::{
+        query::{MAX_LIMIT, MAX_OFFSET},
+        Searcher,
+    },
+};
+use music_player_types::rating::FavouriteKind;
+use std::sync::Arc;
+use tokio::sync::Mutex;
 
+use crate::api::metadata::v1alpha1::{Album, Artist, Genre, Track};
 use crate::api::music::v1alpha1::{
     library_service_server::LibraryService, GetAlbumDetailsRequest, GetAlbumDetailsResponse,
     GetAlbumsRequest, GetAlbumsResponse, GetArtistDetailsRequest, GetArtistDetailsResponse,
-    GetArtistsRequest, GetArtistsResponse, GetTrackDetailsRequest, GetTrackDetailsResponse,
-    GetTracksRequest, GetTracksResponse, ScanRequest, ScanResponse, SearchRequest, SearchResponse,
+    GetArtistsRequest, GetArtistsResponse, GetFavouriteAlbumsRequest, GetFavouriteAlbumsResponse,
+    GetFavouriteArtistsRequest, GetFavouriteArtistsResponse, GetGenreDetailsRequest,
+    GetGenreDetailsResponse, GetGenresRequest, GetGenresResponse, GetLikedTracksRequest,
+    GetLikedTracksResponse, GetLyricsRequest, GetLyricsResponse, GetTrackDetailsRequest,
+    GetTrackDetailsResponse, GetTracksRequest, GetTracksResponse, RateTrackRequest,
+    RateTrackResponse, ScanRequest, ScanResponse, SearchRequest, SearchResponse,
+    SetFavouriteRequest, SetFavouriteResponse, SuggestRequest, SuggestResponse, Suggestion,
+    UpdateAlbumTagsRequest, UpdateAlbumTagsResponse, UpdateTrackTagsRequest,
+    UpdateTrackTagsResponse,
 };
 
 pub struct Library {
     db: Database,
+    /// Opened once, the indexes are shared by the requests.
+    searcher: Arc<Mutex<Searcher>>,
 }
 
 impl Library {
     pub fn new(db: Database) -> Self {
-        Self { db }
+        Self {
+            db,
+            searcher: Arc::new(Mutex::new(Searcher::new())),
+        }
+    }
+
+    /// Fills the ratings and favourite flags, stored apart from the tracks.
+    async fn with_ratings(&self, mut tracks: Vec<Track>) -> Result<Vec<Track>, tonic::Status> {
+        let ids: Vec<String> = tracks.iter().map(|track| track.id.clone()).collect();
+        let repo = RatingRepository::new(self.db.get_connection());
+        let ratings = repo
+            .ratings(&ids)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let favourites = repo
+            .favourites(FavouriteKind::Track, &ids)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        for track in tracks.iter_mut() {
+            track.rating = ratings.get(&track.id).copied().unwrap_or_default() as i32;
+            track.favourite = favourites.contains(&track.id);
+        }
+        Ok(tracks)
+    }
+
+    async fn favourites(
+        &self,
+        kind: FavouriteKind,
+        ids: Vec<String>,
+    ) -> Result<Vec<bool>, tonic::Status> {
+        let favourites = RatingRepository::new(self.db.get_connection())
+            .favourites(kind, &ids)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(ids.iter().map(|id| favourites.contains(id)).collect())
+    }
+
+    async fn with_favourite_albums(
+        &self,
+        mut albums: Vec<Album>,
+    ) -> Result<Vec<Album>, tonic::Status> {
+        let ids = albums.iter().map(|album| album.id.clone()).collect();
+        let favourites = self.favourites(FavouriteKind::Album, ids).await?;
+        for (album, favourite) in albums.iter_mut().zip(favourites) {
+            album.favourite = favourite;
+        }
+        Ok(albums)
+    }
+
+    async fn with_favourite_artists(
+        &self,
+        mut artists: Vec<Artist>,
+    ) -> Result<Vec<Artist>, tonic::Status> {
+        let ids = artists.iter().map(|artist| artist.id.clone()).collect();
+        let favourites = self.favourites(FavouriteKind::Artist, ids).await?;
+        for (artist, favourite) in artists.iter_mut().zip(favourites) {
+            artist.favourite = favourite;
+        }
+        Ok(artists)
     }
 }
 
@@ -31,37 +114,9 @@ impl LibraryService for Library {
         _request: tonic::Request<ScanRequest>,
     ) -> Result<tonic::Response<ScanResponse>, tonic::Status> {
         scan_directory(
-            move |song, db| {
-                async move {
-                    let item: artist::ActiveModel = song.try_into().unwrap();
-                    match item.insert(db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
-
-                    let item: album::ActiveModel = song.try_into().unwrap();
-                    match item.insert(db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
-
-                    let item: track::ActiveModel = song.try_into().unwrap();
-
-                    match item.insert(db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
-
-                    let item: artist_tracks::ActiveModel = song.try_into().unwrap();
-                    match item.insert(db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
-                }
-                .boxed()
-            },
+            move |song, db| save_song(song, db).boxed(),
             &Database::new().await,
-            &Searcher::new(),
+            &*self.searcher.lock().await,
         )
         .await
         .map_err(|e| tonic::Status::internal(e.to_string()))?;
@@ -72,9 +127,54 @@ impl LibraryService for Library {
 
     async fn search(
         &self,
-        _request: tonic::Request<SearchRequest>,
+        request: tonic::Request<SearchRequest>,
     ) -> Result<tonic::Response<SearchResponse>, tonic::Status> {
-        let response = SearchResponse {};
+        let request = request.into_inner();
+        let (offset, limit) =
+            search_page(request.offset, request.limit).map_err(tonic::Status::invalid_argument)?;
+        let results = self
+            .searcher
+            .lock()
+            .await
+            .search(&request.query, offset, limit)
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+        let tracks = results.tracks.items.into_iter().map(Into::into).collect();
+        let albums = results.albums.items.into_iter().map(Into::into).collect();
+        let artists = results.artists.items.into_iter().map(Into::into).collect();
+        let response = SearchResponse {
+            tracks: self.with_ratings(tracks).await?,
+            albums: self.with_favourite_albums(albums).await?,
+            artists: self.with_favourite_artists(artists).await?,
+            total_tracks: results.tracks.total as i32,
+            total_albums: results.albums.total as i32,
+            total_artists: results.artists.total as i32,
+        };
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn suggest(
+        &self,
+        request: tonic::Request<SuggestRequest>,
+    ) -> Result<tonic::Response<SuggestResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let (_, limit) = search_page(0, request.limit).map_err(tonic::Status::invalid_argument)?;
+        let suggestions = self
+            .searcher
+            .lock()
+            .await
+            .suggest(&request.input, limit)
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let response = SuggestResponse {
+            suggestions: suggestions
+                .into_iter()
+                .map(|suggestion| Suggestion {
+                    kind: suggestion.kind.as_str().to_string(),
+                    id: suggestion.id,
+                    text: suggestion.text,
+                })
+                .collect(),
+        };
         Ok(tonic::Response::new(response))
     }
 
@@ -94,8 +194,9 @@ impl LibraryService for Library {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
+        let artists = results.into_iter().map(Into::into).collect();
         let response = GetArtistsResponse {
-            artists: results.into_iter().map(Into::into).collect(),
+            artists: self.with_favourite_artists(artists).await?,
         };
         Ok(tonic::Response::new(response))
     }
@@ -109,15 +210,39 @@ impl LibraryService for Library {
             "" => None,
             _ => Some(request.filter),
         };
+        let field = match request.sort.as_str() {
+            "" => AlbumField::default(),
+            sort => AlbumField::parse(sort)
+                .ok_or_else(|| tonic::Status::invalid_argument("Invalid sort"))?,
+        };
+        let order = AlbumOrder {
+            field,
+            descending: request.descending,
+        };
+        let tracks = TrackFilter {
+            min_year: positive(request.min_year),
+            max_year: positive(request.max_year),
+            genre: non_empty(request.genre),
+            format: non_empty(request.format),
+            min_bitrate: positive(request.min_bitrate),
+            max_bitrate: positive(request.max_bitrate),
+        };
         let offset = request.offset;
         let limit = request.limit;
-        let results = AlbumRepository::new(&self.db.get_connection())
-            .find_all(filter, Some(offset as u64), Some(limit as u64))
+        let results = AlbumRepository::new(self.db.get_connection())
+            .find_all_matching(
+                filter,
+                &tracks,
+                order,
+                Some(offset as u64),
+                Some(limit as u64),
+            )
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
+        let albums = results.into_iter().map(Into::into).collect();
         let response = GetAlbumsResponse {
-            albums: results.into_iter().map(Into::into).collect(),
+            albums: self.with_favourite_albums(albums).await?,
         };
         Ok(tonic::Response::new(response))
     }
@@ -136,13 +261,38 @@ impl LibraryService for Library {
             0 => 100,
             _ => request.limit,
         };
-        let tracks = TrackRepository::new(&self.db.get_connection())
-            .find_all(filter, Some(offset as u64), limit as u64)
+        let field = match request.sort.as_str() {
+            "" => TrackField::default(),
+            sort => TrackField::parse(sort)
+                .ok_or_else(|| tonic::Status::invalid_argument("Invalid sort"))?,
+        };
+        let order = TrackOrder {
+            field,
+            descending: request.descending,
+        };
+        let tags = TrackFilter {
+            min_year: positive(request.min_year),
+            max_year: positive(request.max_year),
+            genre: non_empty(request.genre),
+            format: non_empty(request.format),
+            min_bitrate: positive(request.min_bitrate),
+            max_bitrate: positive(request.max_bitrate),
+        };
+        let tracks = TrackRepository::new(self.db.get_connection())
+            .find_all_matching(
+                filter,
+                &tags,
+                &Default::default(),
+                order,
+                Some(offset as u64),
+                limit as u64,
+            )
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
+        let tracks = tracks.into_iter().map(Into::into).collect();
         let response = GetTracksResponse {
-            tracks: tracks.into_iter().map(Into::into).collect(),
+            tracks: self.with_ratings(tracks).await?,
         };
         Ok(tonic::Response::new(response))
     }
@@ -158,8 +308,67 @@ impl LibraryService for Library {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
+        let tracks = self.with_ratings(vec![track.into()]).await?;
         Ok(tonic::Response::new(GetTrackDetailsResponse {
-            track: Some(track.into()),
+            track: tracks.into_iter().next(),
+        }))
+    }
+
+    async fn get_lyrics(
+        &self,
+        request: tonic::Request<GetLyricsRequest>,
+    ) -> Result<tonic::Response<GetLyricsResponse>, tonic::Status> {
+        let track_id = request.into_inner().track_id;
+        let lyrics = LyricsRepository::new(self.db.get_connection())
+            .find_by_track(&track_id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .ok_or_else(|| tonic::Status::not_found("Lyrics not found"))?;
+
+        Ok(tonic::Response::new(GetLyricsResponse {
+            lyrics: Some(lyrics.into()),
+        }))
+    }
+
+    async fn update_track_tags(
+        &self,
+        request: tonic::Request<UpdateTrackTagsRequest>,
+    ) -> Result<tonic::Response<UpdateTrackTagsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let changes = request.changes.unwrap_or_default().into();
+        let mut tracks = edit_tracks(
+            &self.db,
+            &*self.searcher.lock().await,
+            &[request.id],
+            &changes,
+            request.dry_run,
+        )
+        .await
+        .map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+        Ok(tonic::Response::new(UpdateTrackTagsResponse {
+            track: Some(tracks.remove(0).into()),
+        }))
+    }
+
+    async fn update_album_tags(
+        &self,
+        request: tonic::Request<UpdateAlbumTagsRequest>,
+    ) -> Result<tonic::Response<UpdateAlbumTagsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let changes = request.changes.unwrap_or_default().into();
+        let tracks = edit_album(
+            &self.db,
+            &*self.searcher.lock().await,
+            &request.id,
+            &changes,
+            request.dry_run,
+        )
+        .await
+        .map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+        Ok(tonic::Response::new(UpdateAlbumTagsResponse {
+            tracks: tracks.into_iter().map(Into::into).collect(),
         }))
     }
 
@@ -173,8 +382,9 @@ impl LibraryService for Library {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
+        let albums = self.with_favourite_albums(vec![album.into()]).await?;
         Ok(tonic::Response::new(GetAlbumDetailsResponse {
-            album: Some(album.into()),
+            album: albums.into_iter().next(),
         }))
     }
 
@@ -189,9 +399,183 @@ impl LibraryService for Library {
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?;
 
+        let artists = self.with_favourite_artists(vec![artist.into()]).await?;
         let response = GetArtistDetailsResponse {
-            artist: Some(artist.into()),
+            artist: artists.into_iter().next(),
         };
         Ok(tonic::Response::new(response))
     }
+
+    async fn get_genres(
+        &self,
+        request: tonic::Request<GetGenresRequest>,
+    ) -> Result<tonic::Response<GetGenresResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let filter = match request.filter.as_str() {
+            "" => None,
+            _ => Some(request.filter),
+        };
+        let results = GenreRepository::new(self.db.get_connection())
+            .find_all(
+                filter,
+                Some(request.offset as u64),
+                Some(request.limit as u64),
+            )
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+
+        let response = GetGenresResponse {
+            genres: results.into_iter().map(Into::into).collect(),
+        };
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn get_genre_details(
+        &self,
+        request: tonic::Request<GetGenreDetailsRequest>,
+    ) -> Result<tonic::Response<GetGenreDetailsResponse>, tonic::Status> {
+        let id = request.into_inner().id;
+
+        let genre = GenreRepository::new(self.db.get_connection())
+            .find(&id)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+
+        let mut genre: Genre = genre.into();
+        genre.tracks = self.with_ratings(genre.tracks).await?;
+        genre.albums = self.with_favourite_albums(genre.albums).await?;
+        let response = GetGenreDetailsResponse { genre: Some(genre) };
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn rate_track(
+        &self,
+        request: tonic::Request<RateTrackRequest>,
+    ) -> Result<tonic::Response<RateTrackResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let rating = u8::try_from(request.rating)
+            .map_err(|_| tonic::Status::invalid_argument("Invalid rating"))?;
+        RatingRepository::new(self.db.get_connection())
+            .set_rating(&request.id, rating)
+            .await
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+
+        let settings = read_settings()
+            .and_then(|config| config.try_deserialize::<Settings>())
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        if settings.write_rating_tags {
+            export_rating(&self.db, &request.id)
+                .await
+                .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        }
+        Ok(tonic::Response::new(RateTrackResponse {}))
+    }
+
+    async fn set_favourite(
+        &self,
+        request: tonic::Request<SetFavouriteRequest>,
+    ) -> Result<tonic::Response<SetFavouriteResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let kind = FavouriteKind::parse(&request.kind)
+            .ok_or_else(|| tonic::Status::invalid_argument("Invalid kind"))?;
+        RatingRepository::new(self.db.get_connection())
+            .set_favourite(kind, &request.id, request.favourite)
+            .await
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        Ok(tonic::Response::new(SetFavouriteResponse {}))
+    }
+
+    async fn get_liked_tracks(
+        &self,
+        request: tonic::Request<GetLikedTracksRequest>,
+    ) -> Result<tonic::Response<GetLikedTracksResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let tracks = RatingRepository::new(self.db.get_connection())
+            .liked_tracks(request.offset.max(0) as u64, page_size(request.limit))
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let tracks = tracks.into_iter().map(Into::into).collect();
+        Ok(tonic::Response::new(GetLikedTracksResponse {
+            tracks: self.with_ratings(tracks).await?,
+        }))
+    }
+
+    async fn get_favourite_albums(
+        &self,
+        request: tonic::Request<GetFavouriteAlbumsRequest>,
+    ) -> Result<tonic::Response<GetFavouriteAlbumsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let albums = RatingRepository::new(self.db.get_connection())
+            .favourite_albums(request.offset.max(0) as u64, page_size(request.limit))
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetFavouriteAlbumsResponse {
+            albums: albums
+                .into_iter()
+                .map(|album| Album {
+                    favourite: true,
+                    ..album.into()
+                })
+                .collect(),
+        }))
+    }
+
+    async fn get_favourite_artists(
+        &self,
+        request: tonic::Request<GetFavouriteArtistsRequest>,
+    ) -> Result<tonic::Response<GetFavouriteArtistsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let artists = RatingRepository::new(self.db.get_connection())
+            .favourite_artists(request.offset.max(0) as u64, page_size(request.limit))
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(GetFavouriteArtistsResponse {
+            artists: artists
+                .into_iter()
+                .map(|artist| Artist {
+                    favourite: true,
+                    ..artist.into()
+                })
+                .collect(),
+        }))
+    }
+}
+
+fn page_size(limit: i32) -> u64 {
+    match limit {
+        limit if limit > 0 => limit as u64,
+        _ => 100,
+    }
+}
+
+/// Zero leaves a bound of the filters unset.
+fn positive(value: i32) -> Option<u32> {
+    match value {
+        value if value > 0 => Some(value as u32),
+        _ => None,
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    match value.is_empty() {
+        true => None,
+        false => Some(value),
+    }
+}
+
+/// The offset and limit of a page of search results or suggestions, 10
+/// results when the limit is unset.
+fn search_page(offset: i32, limit: i32) -> Result<(usize, usize), String> {
+    let limit = match limit {
+        0 => 10,
+        limit => limit,
+    };
+    if limit < 0 || limit as usize > MAX_LIMIT {
+        return Err(format!("The limit must be between 1 and {}", MAX_LIMIT));
+    }
+    let offset = offset.max(0) as usize;
+    if offset > MAX_OFFSET {
+        return Err(format!("The offset must be at most {}", MAX_OFFSET));
+    }
+    Ok((offset, limit as usize))
 }
@@ -8,10 +8,12 @@ use futures_channel::mpsc::UnboundedSender;
 use music_player_playback::{
     audio_backend::{self, rodio::RodioSink},
     config::AudioFormat,
+    history::ListeningTracker,
     player::Player,
 };
 use music_player_server::server::MusicPlayerServer;
-use music_player_storage::Database;
+use music_player_settings::{read_settings, Settings};
+use music_player_storage::{repo::history::HistoryRepository, Database};
 use music_player_tracklist::Tracklist;
 use tokio::sync::Mutex;
 use tungstenite::Message;
@@ -31,9 +33,25 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cmd_rx = Arc::new(std::sync::Mutex::new(cmd_rx));
     let db = Database::new().await;
 
+    let settings = read_settings()?.try_deserialize::<Settings>()?;
+    let listening_tracker = sync::Mutex::new(ListeningTracker::new(Some(settings.device_name)));
+    let history_db = db.clone();
+    let runtime = tokio::runtime::Handle::current();
     let (_, _) = Player::new(
         move || backend(None, audio_format),
-        |_| {},
+        move |event| {
+            if let Some(entry) = listening_tracker.lock().unwrap().handle(&event) {
+                let db = history_db.clone();
+                runtime.spawn(async move {
+                    if let Err(e) = HistoryRepository::new(db.get_connection())
+                        .record(&entry)
+                        .await
+                    {
+                        println!("Error recording history: {}", e);
+                    }
+                });
+            }
+        },
         Arc::clone(&cmd_tx),
         Arc::clone(&cmd_rx),
         Arc::clone(&tracklist),
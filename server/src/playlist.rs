@@ -1,9 +1,17 @@
 use music_player_entity::{playlist, playlist_tracks, track};
-use music_player_storage::{repo::playlist::PlaylistRepository, Database};
+use music_player_scanner::playlist_file::{
+    import_playlist, parse_playlist, write_playlist, PlaylistFile, PlaylistFormat,
+};
+use music_player_storage::{
+    repo::{playlist::PlaylistRepository, smart_playlist::SmartPlaylistRepository},
+    Database,
+};
+use music_player_types::smart_playlist::SmartQuery;
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, ModelTrait, QueryFilter, Set,
+    ActiveModelTrait, ActiveValue, ColumnTrait, EntityTrait, ModelTrait, QueryFilter, QueryOrder,
+    Set,
 };
-use std::sync::Arc;
+use std::path::Path;
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
@@ -12,12 +20,13 @@ use crate::api::{
     music::v1alpha1::{
         playlist_service_server::PlaylistService, AddItemRequest, AddItemResponse,
         CreateFolderRequest, CreateFolderResponse, CreateRequest, CreateResponse,
-        DeleteFolderRequest, DeleteFolderResponse, DeleteRequest, DeleteResponse,
-        FindAllFoldersRequest, FindAllFoldersResponse, FindAllRequest, FindAllResponse,
-        GetFolderDetailsRequest, GetFolderDetailsResponse, GetItemsRequest, GetItemsResponse,
-        GetPlaylistDetailsRequest, GetPlaylistDetailsResponse, RemoveItemRequest,
-        RemoveItemResponse, RenameFolderRequest, RenameFolderResponse, RenameRequest,
-        RenameResponse,
+        CreateSmartRequest, DeleteFolderRequest, DeleteFolderResponse, DeleteRequest,
+        DeleteResponse, ExportRequest, ExportResponse, FindAllFoldersRequest,
+        FindAllFoldersResponse, FindAllRequest, FindAllResponse, GetFolderDetailsRequest,
+        GetFolderDetailsResponse, GetItemsRequest, GetItemsResponse, GetPlaylistDetailsRequest,
+        GetPlaylistDetailsResponse, ImportRequest, ImportResponse, InsertItemRequest,
+        MoveItemRequest, RemoveItemRequest, RemoveItemResponse, RenameFolderRequest,
+        RenameFolderResponse, RenameRequest, RenameResponse, UpdateSmartRequest,
     },
 };
 
@@ -29,6 +38,36 @@ impl Playlist {
     pub fn new(db: Database) -> Self {
         Self { db }
     }
+
+    async fn save_rules(
+        &self,
+        id: &str,
+        rules: &str,
+    ) -> Result<tonic::Response<GetPlaylistDetailsResponse>, tonic::Status> {
+        let query = SmartQuery::parse(rules).map_err(tonic::Status::invalid_argument)?;
+        SmartPlaylistRepository::new(self.db.get_connection())
+            .save(id, &query)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let result = PlaylistRepository::new(self.db.get_connection())
+            .find(id)
+            .await
+            .map_err(|_| tonic::Status::internal("Failed to get playlist"))?;
+        Ok(tonic::Response::new(result.into()))
+    }
+
+    async fn reject_smart_playlist(&self, id: &str) -> Result<(), tonic::Status> {
+        let smart = SmartPlaylistRepository::new(self.db.get_connection())
+            .is_smart(id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        match smart {
+            true => Err(tonic::Status::failed_precondition(
+                "Tracks of a smart playlist can't be edited",
+            )),
+            false => Ok(()),
+        }
+    }
 }
 
 #[tonic::async_trait]
@@ -44,17 +83,12 @@ impl PlaylistService for Playlist {
         };
         match item.insert(self.db.get_connection()).await {
             Ok(saved) => {
+                let repo = PlaylistRepository::new(self.db.get_connection());
                 for track in request.get_ref().tracks.iter() {
-                    let item = playlist_tracks::ActiveModel {
-                        id: ActiveValue::set(Uuid::new_v4().to_string()),
-                        playlist_id: ActiveValue::set(saved.id.clone()),
-                        track_id: ActiveValue::set(track.id.clone()),
-                        created_at: ActiveValue::set(chrono::Utc::now()),
-                    };
-                    match item.insert(self.db.get_connection()).await {
-                        Ok(_) => (),
-                        Err(_) => (),
-                    }
+                    // The tracks missing from the library are skipped
+                    let _ = repo
+                        .insert_tracks(&saved.id, std::slice::from_ref(&track.id), None)
+                        .await;
                 }
                 Ok(tonic::Response::new(CreateResponse {
                     id: saved.id,
@@ -71,6 +105,10 @@ impl PlaylistService for Playlist {
         &self,
         request: tonic::Request<DeleteRequest>,
     ) -> Result<tonic::Response<DeleteResponse>, tonic::Status> {
+        SmartPlaylistRepository::new(self.db.get_connection())
+            .delete(&request.get_ref().id)
+            .await
+            .map_err(|_| tonic::Status::internal("Failed to delete playlist"))?;
         playlist::Entity::delete_by_id(request.get_ref().id.clone())
             .exec(self.db.get_connection())
             .await
@@ -99,6 +137,7 @@ impl PlaylistService for Playlist {
                     .clone()
                     .unwrap()
                     .find_related(track::Entity)
+                    .order_by_asc(playlist_tracks::Column::Position)
                     .all(self.db.get_connection())
                     .await
                     .map(|tracks| {
@@ -152,51 +191,137 @@ impl PlaylistService for Playlist {
         &self,
         request: tonic::Request<RemoveItemRequest>,
     ) -> Result<tonic::Response<RemoveItemResponse>, tonic::Status> {
+        self.reject_smart_playlist(&request.get_ref().id).await?;
         let item = playlist_tracks::Entity::find()
             .filter(
                 playlist_tracks::Column::PlaylistId
                     .eq(request.get_ref().id.clone())
                     .and(playlist_tracks::Column::TrackId.eq(request.get_ref().track_id.clone())),
             )
+            .order_by_asc(playlist_tracks::Column::Position)
             .one(self.db.get_connection())
-            .await;
-        if item.is_err() {
-            return Err(tonic::Status::internal(
-                "Failed to remove item from playlist",
-            ));
-        }
-        playlist_tracks::Entity::delete(playlist_tracks::ActiveModel {
-            id: Set(item.unwrap().unwrap().id),
-            ..Default::default()
-        })
-        .exec(self.db.get_connection())
-        .await
-        .map(|_| {
-            tonic::Response::new(RemoveItemResponse {
-                id: request.get_ref().id.clone(),
-                ..Default::default()
+            .await
+            .map_err(|_| tonic::Status::internal("Failed to remove item from playlist"))?
+            .ok_or_else(|| tonic::Status::not_found("Track not found"))?;
+        PlaylistRepository::new(self.db.get_connection())
+            .remove_track(&request.get_ref().id, item.position as u32)
+            .await
+            .map(|_| {
+                tonic::Response::new(RemoveItemResponse {
+                    id: request.get_ref().id.clone(),
+                    ..Default::default()
+                })
             })
-        })
-        .map_err(|_| tonic::Status::internal("Failed to remove item from playlist"))
+            .map_err(|_| tonic::Status::internal("Failed to remove item from playlist"))
     }
 
     async fn add_item(
         &self,
         request: tonic::Request<AddItemRequest>,
     ) -> Result<tonic::Response<AddItemResponse>, tonic::Status> {
-        let item = playlist_tracks::ActiveModel {
-            id: ActiveValue::set(Uuid::new_v4().to_string()),
-            playlist_id: ActiveValue::set(request.get_ref().id.clone()),
-            track_id: ActiveValue::set(request.get_ref().track_id.clone()),
-            created_at: ActiveValue::set(chrono::Utc::now()),
+        let request = request.into_inner();
+        playlist::Entity::find_by_id(request.id.clone())
+            .one(self.db.get_connection())
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?
+            .ok_or_else(|| tonic::Status::not_found("Playlist not found"))?;
+        self.reject_smart_playlist(&request.id).await?;
+        let mut items = PlaylistRepository::new(self.db.get_connection())
+            .insert_tracks(&request.id, &[request.track_id], None)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(tonic::Response::new(AddItemResponse {
+            id: items.remove(0).id,
+            ..Default::default()
+        }))
+    }
+
+    async fn insert_item(
+        &self,
+        request: tonic::Request<InsertItemRequest>,
+    ) -> Result<tonic::Response<GetPlaylistDetailsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        self.reject_smart_playlist(&request.id).await?;
+        let repo = PlaylistRepository::new(self.db.get_connection());
+        repo.insert_tracks(&request.id, &[request.track_id], Some(request.position))
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let result = repo
+            .find(&request.id)
+            .await
+            .map_err(|_| tonic::Status::internal("Failed to get playlist"))?;
+        Ok(tonic::Response::new(result.into()))
+    }
+
+    async fn move_item(
+        &self,
+        request: tonic::Request<MoveItemRequest>,
+    ) -> Result<tonic::Response<GetPlaylistDetailsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        self.reject_smart_playlist(&request.id).await?;
+        let repo = PlaylistRepository::new(self.db.get_connection());
+        repo.move_track(&request.id, request.from, request.to)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        let result = repo
+            .find(&request.id)
+            .await
+            .map_err(|_| tonic::Status::internal("Failed to get playlist"))?;
+        Ok(tonic::Response::new(result.into()))
+    }
+
+    async fn import(
+        &self,
+        request: tonic::Request<ImportRequest>,
+    ) -> Result<tonic::Response<ImportResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let format: PlaylistFormat = request
+            .format
+            .parse()
+            .map_err(|e| tonic::Status::invalid_argument(format!("{}", e)))?;
+        let file = parse_playlist(format, &request.content)
+            .map_err(|e| tonic::Status::invalid_argument(e.to_string()))?;
+        let name = match request.name.is_empty() {
+            true => file
+                .title
+                .clone()
+                .unwrap_or_else(|| "Imported playlist".into()),
+            false => request.name,
         };
-        match item.insert(self.db.get_connection()).await {
-            Ok(saved) => Ok(tonic::Response::new(AddItemResponse {
-                id: saved.id,
-                ..Default::default()
-            })),
-            Err(e) => Err(tonic::Status::internal(e.to_string())),
-        }
+        let base = Some(Path::new(&request.base_path)).filter(|_| !request.base_path.is_empty());
+        let id = Uuid::new_v4().to_string();
+        let imported = import_playlist(&self.db, &id, &name, &file, base)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let result = PlaylistRepository::new(self.db.get_connection())
+            .find(&id)
+            .await
+            .map_err(|_| tonic::Status::internal("Failed to get playlist"))?;
+        Ok(tonic::Response::new(ImportResponse {
+            id,
+            name: result.name,
+            tracks: result.tracks.into_iter().map(Into::into).collect(),
+            missing: imported.missing.iter().map(ToString::to_string).collect(),
+        }))
+    }
+
+    async fn export(
+        &self,
+        request: tonic::Request<ExportRequest>,
+    ) -> Result<tonic::Response<ExportResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let format: PlaylistFormat = request
+            .format
+            .parse()
+            .map_err(|e| tonic::Status::invalid_argument(format!("{}", e)))?;
+        let result = PlaylistRepository::new(self.db.get_connection())
+            .find(&request.id)
+            .await
+            .map_err(|e| tonic::Status::not_found(e.to_string()))?;
+        Ok(tonic::Response::new(ExportResponse {
+            content: write_playlist(format, &PlaylistFile::from(&result)),
+            file_name: format!("{}.{}", result.name, format.extension()),
+        }))
     }
 
     async fn find_all(
@@ -223,6 +348,46 @@ impl PlaylistService for Playlist {
         Ok(tonic::Response::new(result.into()))
     }
 
+    async fn create_smart(
+        &self,
+        request: tonic::Request<CreateSmartRequest>,
+    ) -> Result<tonic::Response<GetPlaylistDetailsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let query = SmartQuery::parse(&request.rules).map_err(tonic::Status::invalid_argument)?;
+        let item = playlist::ActiveModel {
+            id: ActiveValue::set(Uuid::new_v4().to_string()),
+            name: ActiveValue::set(request.name),
+            description: ActiveValue::set(Some(request.description).filter(|d| !d.is_empty())),
+            folder_id: ActiveValue::set(Some(request.folder_id).filter(|id| !id.is_empty())),
+            created_at: ActiveValue::set(chrono::Utc::now()),
+            file_hash: ActiveValue::set(None),
+        };
+        let saved = SmartPlaylistRepository::new(self.db.get_connection())
+            .create(item, &query)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        let result = PlaylistRepository::new(self.db.get_connection())
+            .find(&saved.id)
+            .await
+            .map_err(|_| tonic::Status::internal("Failed to get playlist"))?;
+        Ok(tonic::Response::new(result.into()))
+    }
+
+    async fn update_smart(
+        &self,
+        request: tonic::Request<UpdateSmartRequest>,
+    ) -> Result<tonic::Response<GetPlaylistDetailsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let smart = SmartPlaylistRepository::new(self.db.get_connection())
+            .is_smart(&request.id)
+            .await
+            .map_err(|e| tonic::Status::internal(e.to_string()))?;
+        if !smart {
+            return Err(tonic::Status::not_found("Smart playlist not found"));
+        }
+        self.save_rules(&request.id, &request.rules).await
+    }
+
     async fn create_folder(
         &self,
         _request: tonic::Request<CreateFolderRequest>,
@@ -8,7 +8,7 @@ use crate::{
     api::music::v1alpha1::{
         library_service_client::LibraryServiceClient, library_service_server::LibraryServiceServer,
         GetAlbumDetailsRequest, GetAlbumsRequest, GetArtistDetailsRequest, GetArtistsRequest,
-        GetTrackDetailsRequest, GetTracksRequest,
+        GetTrackDetailsRequest, GetTracksRequest, SearchRequest,
     },
     library::Library,
 };
@@ -57,7 +57,16 @@ async fn search() {
     });
     tokio::time::sleep(std::time::Duration::from_millis(100)).await;
 
-    let _client = LibraryServiceClient::connect(url).await.unwrap();
+    let mut client = LibraryServiceClient::connect(url).await.unwrap();
+    let status = client
+        .search(tonic::Request::new(SearchRequest {
+            query: "fire".to_owned(),
+            limit: i32::MAX,
+            ..Default::default()
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), tonic::Code::InvalidArgument);
     tx.send(()).unwrap();
     jh.await.unwrap();
 }
@@ -120,6 +129,7 @@ async fn get_albums() {
         offset: 0,
         limit: 10,
         filter: "".to_string(),
+        ..Default::default()
     });
     let response = client.get_albums(request).await.unwrap();
     let response = response.into_inner();
@@ -153,6 +163,7 @@ async fn get_tracks() {
         offset: 0,
         limit: 10,
         filter: "".to_string(),
+        ..Default::default()
     });
     let response = client.get_tracks(request).await.unwrap();
     let response = response.into_inner();
@@ -176,6 +187,25 @@ async fn get_tracks() {
         response.tracks[1].uri,
         "/tmp/audio/03 - J. Cole - Wet Dreamz(Explicit).m4a"
     );
+
+    let request = tonic::Request::new(GetTracksRequest {
+        limit: 10,
+        sort: "duration".to_string(),
+        format: "m4a".to_string(),
+        ..Default::default()
+    });
+    let response = client.get_tracks(request).await.unwrap().into_inner();
+    assert_eq!(response.tracks.len(), 2);
+    assert_eq!(response.tracks[0].title, "Wet Dreamz");
+    assert_eq!(response.tracks[1].title, "Fire Squad");
+
+    let request = tonic::Request::new(GetTracksRequest {
+        limit: 10,
+        format: "flac".to_string(),
+        ..Default::default()
+    });
+    let response = client.get_tracks(request).await.unwrap().into_inner();
+    assert!(response.tracks.is_empty());
     tx.send(()).unwrap();
     jh.await.unwrap();
 }
@@ -16,6 +16,7 @@ use tokio::sync::mpsc::{UnboundedReceiver, UnboundedSender};
 
 pub mod library;
 pub mod playback;
+pub mod playlist;
 pub mod server;
 pub mod tracklist;
 
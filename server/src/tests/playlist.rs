@@ -0,0 +1,86 @@
+use futures_util::FutureExt;
+use music_player_entity::playlist;
+use sea_orm::{ActiveModelTrait, ActiveValue};
+use tokio::sync::oneshot;
+use tonic::{transport::Server, Code};
+
+use crate::{
+    api::music::v1alpha1::{
+        playlist_service_client::PlaylistServiceClient,
+        playlist_service_server::PlaylistServiceServer, AddItemRequest, DeleteRequest,
+        GetItemsRequest,
+    },
+    playlist::Playlist as PlaylistService,
+};
+
+use super::setup_new_params;
+
+#[tokio::test]
+async fn add_item() -> Result<(), Box<dyn std::error::Error>> {
+    let (_backend, _audio_format, _cmd_tx, _cmd_rx, _tracklist, db, addr, url) =
+        setup_new_params(7084).await;
+    let playlist = playlist::ActiveModel {
+        id: ActiveValue::set(format!("add-item-{}", chrono::Utc::now().timestamp_nanos())),
+        name: ActiveValue::set("Add item".to_owned()),
+        description: ActiveValue::set(None),
+        folder_id: ActiveValue::set(None),
+        created_at: ActiveValue::set(chrono::Utc::now()),
+        file_hash: ActiveValue::set(None),
+    }
+    .insert(db.get_connection())
+    .await?;
+    let (tx, rx) = oneshot::channel();
+    let jh = tokio::spawn(async move {
+        Server::builder()
+            .accept_http1(true)
+            .add_service(tonic_web::enable(PlaylistServiceServer::new(
+                PlaylistService::new(db),
+            )))
+            .serve_with_shutdown(addr, rx.map(drop))
+            .await
+            .unwrap();
+    });
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+    let mut client = PlaylistServiceClient::connect(url).await?;
+
+    // The same track twice, each time a new item
+    let mut ids = vec![];
+    for _ in 0..2 {
+        let response = client
+            .add_item(tonic::Request::new(AddItemRequest {
+                id: playlist.id.clone(),
+                track_id: "dd77dd0ea2de5208e4987001a59ba8e4".to_owned(),
+            }))
+            .await?
+            .into_inner();
+        assert_ne!(response.id, playlist.id);
+        ids.push(response.id);
+    }
+    assert_ne!(ids[0], ids[1]);
+
+    let items = client
+        .get_items(tonic::Request::new(GetItemsRequest {
+            id: playlist.id.clone(),
+        }))
+        .await?
+        .into_inner();
+    assert_eq!(items.tracks.len(), 2);
+
+    let status = client
+        .add_item(tonic::Request::new(AddItemRequest {
+            id: "missing".to_owned(),
+            track_id: "dd77dd0ea2de5208e4987001a59ba8e4".to_owned(),
+        }))
+        .await
+        .unwrap_err();
+    assert_eq!(status.code(), Code::NotFound);
+
+    client
+        .delete(tonic::Request::new(DeleteRequest { id: playlist.id }))
+        .await?;
+
+    tx.send(()).unwrap();
+    jh.await?;
+    Ok(())
+}
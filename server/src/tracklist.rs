@@ -2,6 +2,7 @@ use std::sync::Arc;
 
 use music_player_entity::{album, artist, track};
 use music_player_playback::player::PlayerCommand;
+use music_player_scanner::playlist_file::{write_playlist, PlaylistFile, PlaylistFormat};
 use music_player_storage::{repo::track::TrackRepository, Database};
 use music_player_tracklist::Tracklist as TracklistState;
 use sea_orm::EntityTrait;
@@ -12,7 +13,8 @@ use crate::api::{
     music::v1alpha1::{
         tracklist_service_server::TracklistService, AddTrackRequest, AddTrackResponse,
         AddTracksRequest, AddTracksResponse, ClearTracklistRequest, ClearTracklistResponse,
-        FilterTracklistRequest, FilterTracklistResponse, GetNextTrackRequest, GetNextTrackResponse,
+        ExportTracklistRequest, ExportTracklistResponse, FilterTracklistRequest,
+        FilterTracklistResponse, GetNextTrackRequest, GetNextTrackResponse,
         GetPreviousTrackRequest, GetPreviousTrackResponse, GetRandomRequest, GetRandomResponse,
         GetRepeatRequest, GetRepeatResponse, GetSingleRequest, GetSingleResponse,
         GetTracklistTracksRequest, GetTracklistTracksResponse, LoadTracksRequest,
@@ -194,6 +196,29 @@ impl TracklistService for Tracklist {
         Ok(tonic::Response::new(response))
     }
 
+    async fn export_tracklist(
+        &self,
+        request: tonic::Request<ExportTracklistRequest>,
+    ) -> Result<tonic::Response<ExportTracklistResponse>, tonic::Status> {
+        let format: PlaylistFormat = request
+            .into_inner()
+            .format
+            .parse()
+            .map_err(|e| tonic::Status::invalid_argument(format!("{}", e)))?;
+        let (previous_tracks, next_tracks) = self.state.lock().unwrap().tracks();
+        let file = PlaylistFile {
+            title: None,
+            entries: previous_tracks
+                .iter()
+                .chain(next_tracks.iter())
+                .map(Into::into)
+                .collect(),
+        };
+        Ok(tonic::Response::new(ExportTracklistResponse {
+            content: write_playlist(format, &file),
+        }))
+    }
+
     async fn play_next(
         &self,
         request: tonic::Request<PlayNextRequest>,
@@ -24,6 +24,18 @@ pub struct Settings {
     pub device_id: String,
     pub http_port: u16,
     pub tauri_enable_graphql_server: bool,
+    /// Split the artist tags into several artists. "/" and " & " are only
+    /// split when added, they are part of names like "AC/DC"
+    pub artist_separators: Vec<String>,
+    pub analyze_audio: bool,
+    pub write_replaygain_tags: bool,
+    pub write_rating_tags: bool,
+    /// More directories to scan, or `webdav://`, `webdavs://` and `s3://`
+    /// URLs of remote storage
+    pub library_roots: Vec<String>,
+    /// Reduces the searched words to their stem, e.g. "english" or "french",
+    /// changing it rebuilds the search indexes
+    pub search_language: Option<String>,
 }
 
 pub fn read_settings() -> Result<Config, ConfigError> {
@@ -76,6 +88,16 @@ pub fn read_settings() -> Result<Config, ConfigError> {
         device_id,
         http_port: 5053,
         tauri_enable_graphql_server: false,
+        artist_separators: vec![
+            " feat. ".to_string(),
+            " ft. ".to_string(),
+            ";".to_string(),
+        ],
+        analyze_audio: false,
+        write_replaygain_tags: false,
+        write_rating_tags: false,
+        library_roots: vec![],
+        search_language: None,
     };
 
     let settings_path = format!("{}/settings.toml", path);
@@ -106,6 +128,14 @@ pub fn read_settings() -> Result<Config, ConfigError> {
             "tauri_enable_graphql_server",
             default_settings.tauri_enable_graphql_server,
         )?
+        .set_default("artist_separators", default_settings.artist_separators)?
+        .set_default("analyze_audio", default_settings.analyze_audio)?
+        .set_default(
+            "write_replaygain_tags",
+            default_settings.write_replaygain_tags,
+        )?
+        .set_default("write_rating_tags", default_settings.write_rating_tags)?
+        .set_default("library_roots", default_settings.library_roots)?
         .build()
 }
 
@@ -122,12 +152,14 @@ pub fn get_application_directory() -> String {
     let artists = format!("{}/artists", path);
     let playlists = format!("{}/playlists", path);
     let tracks = format!("{}/tracks", path);
+    let suggestions = format!("{}/suggestions", path);
     let covers = format!("{}/covers", path);
     let cache = format!("{}/cache", path);
     fs::create_dir_all(&albums).unwrap();
     fs::create_dir_all(&artists).unwrap();
     fs::create_dir_all(&playlists).unwrap();
     fs::create_dir_all(&tracks).unwrap();
+    fs::create_dir_all(&suggestions).unwrap();
     fs::create_dir_all(&covers).unwrap();
     fs::create_dir_all(&cache).unwrap();
 
@@ -19,6 +19,7 @@ fn read_settings() {
     assert_eq!(settings.get_string("device_name").unwrap(), "Music Player");
     assert_eq!(settings.get_string("device_id").unwrap().len(), 32);
     assert_eq!(settings.get_array("addons").unwrap().len(), 8);
+    assert_eq!(settings.get_array("artist_separators").unwrap().len(), 3);
     assert_eq!(
         settings.get_string("music_directory").unwrap(),
         dirs::audio_dir()
@@ -1,7 +1,7 @@
 use std::{sync::mpsc::Sender, time::Instant};
 
 use music_player_server::api::{
-    metadata::v1alpha1::{Album, Artist, Track},
+    metadata::v1alpha1::{Album, Artist, Genre, Track},
     music::v1alpha1::GetCurrentlyPlayingSongResponse,
 };
 use tui::layout::Rect;
@@ -30,6 +30,12 @@ pub struct ArtistTable {
     pub selected_index: usize,
 }
 
+#[derive(Default)]
+pub struct GenreTable {
+    pub genres: Vec<Genre>,
+    pub selected_index: usize,
+}
+
 #[derive(Default)]
 pub struct AlbumTable {
     pub albums: Vec<Album>,
@@ -52,6 +58,7 @@ pub struct App {
     pub active_playlist_index: Option<usize>,
     pub artist_table: ArtistTable,
     pub album_table: AlbumTable,
+    pub genre_table: GenreTable,
     pub track_table: TrackTable,
     pub selected_album: Option<Album>,
     pub current_playback_context: Option<CurrentlyPlaybackContext>,
@@ -78,6 +85,7 @@ impl App {
             track_table: Default::default(),
             artist_table: Default::default(),
             album_table: Default::default(),
+            genre_table: Default::default(),
             selected_album: None,
             current_playback_context: None,
             seek_ms: None,
@@ -210,7 +218,15 @@ impl App {
     }
 }
 
-pub const LIBRARY_OPTIONS: [&str; 4] = ["Tracks", "Albums", "Artists", "Play Queue"];
+pub const LIBRARY_OPTIONS: [&str; 7] = [
+    "Tracks",
+    "Liked Songs",
+    "Albums",
+    "Favourite Albums",
+    "Artists",
+    "Genres",
+    "Play Queue",
+];
 
 const DEFAULT_ROUTE: Route = Route {
     id: RouteId::TrackTable,
@@ -276,6 +292,7 @@ pub enum RouteId {
     Search,
     TrackTable,
     Artists,
+    Genres,
     PlayQueue,
 }
 
@@ -289,6 +306,7 @@ pub enum ActiveBlock {
     Playlists,
     TrackTable,
     Artists,
+    Genres,
     SearchResultBlock,
     Input,
     PlayQueue,
@@ -1,10 +1,10 @@
-use std::{env, fs, sync::Mutex};
+use std::{env, fs, path::Path, sync::Mutex};
 
 use clap::ArgMatches;
 use futures::StreamExt;
 use music_player_client::{
-    library::LibraryClient, playback::PlaybackClient, playlist::PlaylistClient,
-    tracklist::TracklistClient,
+    history::HistoryClient, library::LibraryClient, playback::PlaybackClient,
+    playlist::PlaylistClient, tracklist::TracklistClient,
 };
 use music_player_discovery::{discover, SERVICE_NAME};
 use music_player_playback::{
@@ -12,8 +12,12 @@ use music_player_playback::{
     config::AudioFormat,
     player::{Player, PlayerEngine},
 };
+use music_player_scanner::{
+    analysis::analyze_library, duplicates::find_duplicates, playlist_file::PlaylistFormat,
+    rating::export_ratings,
+};
 use music_player_settings::{read_settings, Settings};
-use music_player_storage::{searcher::Searcher, Database};
+use music_player_storage::{repo::scan_error::ScanErrorRepository, searcher::Searcher, Database};
 use music_player_tracklist::Tracklist;
 use owo_colors::OwoColorize;
 use std::sync::Arc;
@@ -48,12 +52,104 @@ pub async fn parse_args(matches: ArgMatches) -> Result<(), Box<dyn std::error::E
         return Ok(());
     }
 
-    if let Some(_) = matches.subcommand_matches("scan") {
+    if let Some(matches) = matches.subcommand_matches("scan") {
         let db = Database::new().await;
+
+        if matches.subcommand_matches("report").is_some() {
+            let result = ScanErrorRepository::new(db.get_connection())
+                .find_all()
+                .await
+                .map_err(|e| e.to_string())?;
+
+            if result.is_empty() {
+                println!("No errors during the last scan");
+                return Ok(());
+            }
+
+            let mut builder = Builder::default();
+            builder.set_columns(["path", "kind", "message"]);
+            result.iter().for_each(|error| {
+                builder.add_record([
+                    error.path.as_str(),
+                    error.kind.magenta().to_string().as_str(),
+                    error.message.as_str(),
+                ]);
+            });
+            let table = builder.build().with(Style::psql());
+            println!("\n{}", table);
+
+            return Ok(());
+        }
+
+        if matches.subcommand_matches("reindex").is_some() {
+            Searcher::new()
+                .rebuild_from_database(&db)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("Rebuilt the search indexes");
+            return Ok(());
+        }
+
         let searcher = Searcher::new();
-        scan_music_library(true, db, searcher)
+        scan_music_library(true, db.clone(), searcher)
             .await
             .map_err(|e| e.to_string())?;
+
+        // The scan already ran the analysis when it is enabled in the settings
+        if matches.is_present("analyze") && !settings.analyze_audio {
+            let count = analyze_library(&db, settings.write_replaygain_tags)
+                .await
+                .map_err(|e| e.to_string())?;
+            println!("Analyzed {} tracks", count);
+        }
+        return Ok(());
+    }
+
+    if matches.subcommand_matches("duplicates").is_some() {
+        let db = Database::new().await;
+        let groups = find_duplicates(&db).await.map_err(|e| e.to_string())?;
+
+        if groups.is_empty() {
+            println!("No duplicates found");
+            return Ok(());
+        }
+
+        let mut builder = Builder::default();
+        builder.set_columns(["group", "", "title", "artist", "bitrate", "bit depth", "path"]);
+        groups.iter().enumerate().for_each(|(index, group)| {
+            group.tracks.iter().enumerate().for_each(|(position, track)| {
+                let best = if position == 0 { "*" } else { "" };
+                let bitrate = track
+                    .bitrate
+                    .map(|bitrate| format!("{} kbps", bitrate))
+                    .unwrap_or_default();
+                let bit_depth = track
+                    .bit_depth
+                    .map(|bit_depth| format!("{} bit", bit_depth))
+                    .unwrap_or_default();
+                builder.add_record([
+                    (index + 1).to_string().as_str(),
+                    best.green().to_string().as_str(),
+                    track.title.as_str(),
+                    track.artist.magenta().to_string().as_str(),
+                    bitrate.as_str(),
+                    bit_depth.as_str(),
+                    track.uri.as_str(),
+                ]);
+            });
+        });
+        let table = builder.build().with(Style::psql());
+        println!("\n{}", table);
+
+        return Ok(());
+    }
+
+    if let Some(matches) = matches.subcommand_matches("ratings") {
+        if matches.subcommand_matches("export").is_some() {
+            let db = Database::new().await;
+            let count = export_ratings(&db).await.map_err(|e| e.to_string())?;
+            println!("Wrote the rating of {} tracks", count);
+        }
         return Ok(());
     }
 
@@ -129,6 +225,107 @@ pub async fn parse_args(matches: ArgMatches) -> Result<(), Box<dyn std::error::E
         }
 
         if let Some(_matches) = matches.subcommand_matches("ls") {
+            let result = client.list_all().await?;
+            let mut builder = Builder::default();
+            builder.set_columns(["id", "name", "rules"]);
+            result.iter().for_each(|playlist| {
+                builder.add_record([
+                    playlist.id.as_str(),
+                    playlist.name.magenta().to_string().as_str(),
+                    playlist.rules.as_deref().unwrap_or_default(),
+                ]);
+            });
+            let table = builder.build().with(Style::psql());
+            println!("\n{}", table);
+            return Ok(());
+        }
+
+        if let Some(matches) = matches.subcommand_matches("create-smart") {
+            let name = matches.value_of("name").unwrap();
+            let rules = matches.value_of("rules").unwrap();
+            let playlist = client.create_smart(name, rules).await?;
+            println!(
+                "Created {} with {} tracks",
+                playlist.name.magenta(),
+                playlist.tracks.len()
+            );
+            return Ok(());
+        }
+
+        if let Some(matches) = matches.subcommand_matches("update-smart") {
+            let id = matches.value_of("id").unwrap();
+            let rules = matches.value_of("rules").unwrap();
+            let playlist = client.update_smart(id, rules).await?;
+            println!(
+                "Updated {} with {} tracks",
+                playlist.name.magenta(),
+                playlist.tracks.len()
+            );
+            return Ok(());
+        }
+
+        if let Some(matches) = matches.subcommand_matches("show") {
+            let id = matches.value_of("id").unwrap();
+            let playlist = client.find(id).await?;
+            println!("{}", playlist.name.magenta());
+            if let Some(rules) = &playlist.rules {
+                println!("{}", rules);
+            }
+            let mut builder = Builder::default();
+            builder.set_columns(["id", "title", "artist"]);
+            playlist.tracks.iter().for_each(|track| {
+                builder.add_record([
+                    track.id.as_str(),
+                    track.title.as_str(),
+                    track.artist.as_str(),
+                ]);
+            });
+            let table = builder.build().with(Style::psql());
+            println!("\n{}", table);
+            return Ok(());
+        }
+
+        if let Some(matches) = matches.subcommand_matches("import") {
+            let path = Path::new(matches.value_of("file").unwrap());
+            let format = PlaylistFormat::from_path(path).ok_or("Unsupported playlist file")?;
+            let content = String::from_utf8_lossy(&fs::read(path)?).to_string();
+            // Relative paths are resolved by the server from the directory of the file
+            let base_path = fs::canonicalize(path)?
+                .parent()
+                .map(|parent| parent.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let name = matches.value_of("name").unwrap_or_default();
+            let (playlist, missing) = client
+                .import(name, &content, format.extension(), &base_path)
+                .await?;
+            println!(
+                "Imported {} with {} tracks",
+                playlist.name.magenta(),
+                playlist.tracks.len()
+            );
+            for entry in missing {
+                println!("Not found in the library: {}", entry);
+            }
+            return Ok(());
+        }
+
+        if let Some(matches) = matches.subcommand_matches("export") {
+            let format = matches.value_of("format").unwrap();
+            let content = match matches.value_of("id") {
+                Some(id) => client.export(id, format).await?.0,
+                None => {
+                    let mut client =
+                        TracklistClient::new(settings.host.clone(), settings.port).await?;
+                    client.export(format).await?
+                }
+            };
+            match matches.value_of("output") {
+                Some(output) => {
+                    fs::write(output, content)?;
+                    println!("Exported to {}", output.magenta());
+                }
+                None => print!("{}", content),
+            }
             return Ok(());
         }
 
@@ -217,6 +414,63 @@ pub async fn parse_args(matches: ArgMatches) -> Result<(), Box<dyn std::error::E
         return Ok(());
     }
 
+    if let Some(matches) = matches.subcommand_matches("history") {
+        let mut client = HistoryClient::new(settings.host.clone(), settings.port).await?;
+
+        if let Some(matches) = matches.subcommand_matches("stats") {
+            let track_id = matches.value_of("track_id").unwrap();
+            let stats = client.stats(track_id).await?;
+            println!("Plays: {}", stats.play_count);
+            println!("Skips: {}", stats.skip_count);
+            if let Some(last_played) = stats.last_played {
+                println!("Last played: {}", last_played.to_rfc2822());
+            }
+            return Ok(());
+        }
+
+        if matches.subcommand_matches("top").is_some() {
+            let result = client.most_played(0, 20).await?;
+            let mut builder = Builder::default();
+            builder.set_columns(["track id", "plays", "skips"]);
+            result.iter().for_each(|stats| {
+                builder.add_record([
+                    stats.track_id.clone(),
+                    stats.play_count.to_string(),
+                    stats.skip_count.to_string(),
+                ]);
+            });
+            let table = builder.build().with(Style::psql());
+            println!("\n{}", table);
+            return Ok(());
+        }
+
+        let limit = matches.value_of("limit").unwrap().parse()?;
+        let offset = matches.value_of("offset").unwrap().parse()?;
+        let result = client.history(offset, limit).await?;
+        let mut builder = Builder::default();
+        builder.set_columns(["played at", "title", "artist", "listened", "device"]);
+        result.iter().for_each(|entry| {
+            let listened = match entry.completed {
+                true => format_duration(entry.listened_ms),
+                false => format!("{} (skipped)", format_duration(entry.listened_ms)),
+            };
+            builder.add_record([
+                entry
+                    .started_at
+                    .with_timezone(&chrono::Local)
+                    .format("%Y-%m-%d %H:%M")
+                    .to_string(),
+                entry.track.title.magenta().to_string(),
+                entry.track.artist.clone(),
+                listened,
+                entry.device.clone().unwrap_or_default(),
+            ]);
+        });
+        let table = builder.build().with(Style::psql());
+        println!("\n{}", table);
+        return Ok(());
+    }
+
     if let Some(matches) = matches.subcommand_matches("search") {
         let client = LibraryClient::new(settings.host.clone(), settings.port).await?;
 
@@ -332,3 +586,8 @@ fn format_number(number: usize) -> String {
     }
     format!("{}", number)
 }
+
+fn format_duration(milliseconds: u32) -> String {
+    let seconds = milliseconds / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
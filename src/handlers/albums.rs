@@ -35,6 +35,14 @@ pub fn handler(key: Key, app: &mut App) {
             ));
             app.push_navigation_stack(RouteId::AlbumTracks, ActiveBlock::AlbumTracks);
         }
+        _ if key == app.user_config.keys.toggle_favourite => {
+            if let Some(album) = app.album_table.albums.get(app.album_table.selected_index) {
+                app.dispatch(IoEvent::SetFavouriteAlbum(
+                    album.id.clone(),
+                    !album.favourite,
+                ));
+            }
+        }
         _ => (),
     }
 }
@@ -108,6 +108,9 @@ pub fn handle_right_event(app: &mut App) {
             RouteId::Artists => {
                 app.set_current_route_state(Some(ActiveBlock::Artists), Some(ActiveBlock::Artists));
             }
+            RouteId::Genres => {
+                app.set_current_route_state(Some(ActiveBlock::Genres), Some(ActiveBlock::Genres));
+            }
             RouteId::Search => {
                 app.set_current_route_state(
                     Some(ActiveBlock::SearchResultBlock),
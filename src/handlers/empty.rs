@@ -19,6 +19,7 @@ pub fn handler(key: Key, app: &mut App) {
             | ActiveBlock::AlbumList
             | ActiveBlock::AlbumTracks
             | ActiveBlock::Artists
+            | ActiveBlock::Genres
             | ActiveBlock::TrackTable => {
                 app.set_current_route_state(None, Some(ActiveBlock::PlayBar));
             }
@@ -38,6 +39,7 @@ pub fn handler(key: Key, app: &mut App) {
             | ActiveBlock::AlbumList
             | ActiveBlock::AlbumTracks
             | ActiveBlock::Artists
+            | ActiveBlock::Genres
             | ActiveBlock::TrackTable => {
                 app.set_current_route_state(None, Some(ActiveBlock::Library));
             }
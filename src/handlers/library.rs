@@ -44,18 +44,33 @@ pub fn handler(key: Key, app: &mut App) {
                 app.dispatch(IoEvent::GetTracks);
                 app.push_navigation_stack(RouteId::TrackTable, ActiveBlock::TrackTable);
             }
-            // Albums,
+            // Liked Songs,
             1 => {
+                app.dispatch(IoEvent::GetLikedTracks);
+                app.push_navigation_stack(RouteId::TrackTable, ActiveBlock::TrackTable);
+            }
+            // Albums,
+            2 => {
                 app.dispatch(IoEvent::GetAlbums);
                 app.push_navigation_stack(RouteId::AlbumList, ActiveBlock::AlbumList);
             }
+            // Favourite Albums,
+            3 => {
+                app.dispatch(IoEvent::GetFavouriteAlbums);
+                app.push_navigation_stack(RouteId::AlbumList, ActiveBlock::AlbumList);
+            }
             //  Artists,
-            2 => {
+            4 => {
                 app.dispatch(IoEvent::GetArtists);
                 app.push_navigation_stack(RouteId::Artists, ActiveBlock::Artists);
             }
+            // Genres,
+            5 => {
+                app.dispatch(IoEvent::GetGenres);
+                app.push_navigation_stack(RouteId::Genres, ActiveBlock::Genres);
+            }
             // PlayQueue,
-            3 => {
+            6 => {
                 app.dispatch(IoEvent::GetPlayQueue);
                 app.push_navigation_stack(RouteId::PlayQueue, ActiveBlock::PlayQueue);
             }
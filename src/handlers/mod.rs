@@ -10,6 +10,7 @@ pub mod artist_tracks;
 pub mod artists;
 pub mod common_key_events;
 pub mod empty;
+pub mod genres;
 pub mod input;
 pub mod library;
 pub mod play_queue;
@@ -99,6 +100,9 @@ fn handle_block_events(key: Key, app: &mut App) {
         ActiveBlock::Artists => {
             artists::handler(key, app);
         }
+        ActiveBlock::Genres => {
+            genres::handler(key, app);
+        }
         ActiveBlock::SearchResultBlock => todo!(),
         ActiveBlock::PlayQueue => {
             play_queue::handler(key, app);
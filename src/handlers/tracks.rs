@@ -30,6 +30,21 @@ pub fn handler(key: Key, app: &mut App) {
                     .clone(),
             ));
         }
+        _ if key == app.user_config.keys.toggle_favourite => {
+            if let Some(track) = app.track_table.tracks.get(app.track_table.selected_index) {
+                app.dispatch(IoEvent::SetFavouriteTrack(
+                    track.id.clone(),
+                    !track.favourite,
+                ));
+            }
+        }
+        // Cycles through 1 to 5 stars, then back to no rating
+        _ if key == app.user_config.keys.rate_track => {
+            if let Some(track) = app.track_table.tracks.get(app.track_table.selected_index) {
+                let rating = (track.rating + 1) % 6;
+                app.dispatch(IoEvent::RateTrack(track.id.clone(), rating as u8));
+            }
+        }
         _ => (),
     }
 }
@@ -27,7 +27,7 @@ use music_player_client::{library::LibraryClient, ws_client::WebsocketClient};
 use music_player_discovery::register_services;
 use music_player_graphql::{
     schema::{
-        objects::{player_state::PlayerState, track::Track},
+        objects::{history::HistoryEntry, player_state::PlayerState, track::Track},
         playback::PositionMilliseconds,
     },
     simple_broker::SimpleBroker,
@@ -35,12 +35,13 @@ use music_player_graphql::{
 use music_player_playback::{
     audio_backend::{self, rodio::RodioSink},
     config::AudioFormat,
+    history::ListeningTracker,
     player::{Player, PlayerEvent},
 };
 use music_player_server::event::{Event, TrackEvent};
 use music_player_server::server::MusicPlayerServer;
 use music_player_settings::{read_settings, Settings};
-use music_player_storage::{searcher::Searcher, Database};
+use music_player_storage::{repo::history::HistoryRepository, searcher::Searcher, Database};
 use music_player_tracklist::Tracklist;
 use music_player_webui::start_webui;
 use network::{IoEvent, Network};
@@ -87,7 +88,31 @@ A simple music player written in Rust"#,
                 .about("open audio file")
                 .arg_from_usage("<song> 'The path to the song'"),
         )
-        .subcommand(Command::new("scan").about("Scan music library: $HOME/Music"))
+        .subcommand(
+            Command::new("scan")
+                .about("Scan music library: $HOME/Music")
+                .arg(
+                    arg!(--analyze "Measure the loudness, tempo and key of the new tracks")
+                        .required(false),
+                )
+                .subcommand(
+                    Command::new("report").about("Show the files the last scan failed to read"),
+                )
+                .subcommand(
+                    Command::new("reindex")
+                        .about("Rebuild the search indexes from the music library database"),
+                ),
+        )
+        .subcommand(
+            Command::new("duplicates")
+                .about("Find the tracks stored more than once, the best copy is marked with *"),
+        )
+        .subcommand(
+            Command::new("ratings").subcommand(
+                Command::new("export")
+                    .about("Write the ratings into the POPM/FMPS_Rating tags of the files"),
+            ),
+        )
         .subcommand(Command::new("albums").arg(
             arg!(-i --id <id> "Show the album with the given id").required(false)
         ).about("List all albums"))
@@ -100,6 +125,18 @@ A simple music player written in Rust"#,
                         .arg_from_usage("<id> 'The track id'"),
                 )
                 .subcommand(Command::new("ls").about("List all playlists"))
+                .subcommand(
+                    Command::new("create-smart")
+                        .about("Create a playlist filled from rules, e.g. \"genre is Jazz and year between 1955 and 1965, sorted by random, limit 100\"")
+                        .arg_from_usage("<name> 'The playlist name'")
+                        .arg_from_usage("<rules> 'The rules of the playlist'"),
+                )
+                .subcommand(
+                    Command::new("update-smart")
+                        .about("Replace the rules of a smart playlist")
+                        .arg_from_usage("<id> 'The playlist id'")
+                        .arg_from_usage("<rules> 'The rules of the playlist'"),
+                )
                 .subcommand(Command::new("clear").about("Clear the playlist").arg_from_usage(
                     "[id] 'The playlist id, if not specified, the current playlist will be cleared'",
                 ))
@@ -118,7 +155,20 @@ A simple music player written in Rust"#,
                 .subcommand(
                     Command::new("show")
                         .about("Show the playlist details")
-                        .arg_from_usage("<id> 'The track id'")
+                        .arg_from_usage("<id> 'The playlist id'")
+                )
+                .subcommand(
+                    Command::new("import")
+                        .about("Import a M3U/M3U8, PLS or XSPF playlist file")
+                        .arg_from_usage("<file> 'The path to the playlist file'")
+                        .arg(arg!(-n --name <name> "The playlist name, the title of the file by default").required(false))
+                )
+                .subcommand(
+                    Command::new("export")
+                        .about("Export a playlist to a M3U8, PLS or XSPF file")
+                        .arg_from_usage("[id] 'The playlist id, if not specified, the current tracklist will be exported'")
+                        .arg(arg!(-f --format <format> "The format of the file: m3u8, pls or xspf").default_value("m3u8").required(false))
+                        .arg(arg!(-o --output <output> "The file to write, the standard output by default").required(false))
                 )
                 .about("Manage playlists")
                 .arg_required_else_help(true),
@@ -149,6 +199,18 @@ A simple music player written in Rust"#,
                 .arg_required_else_help(true),
         )
         .subcommand(Command::new("tracks").about("List all tracks"))
+        .subcommand(
+            Command::new("history")
+                .about("Show the tracks played, from the most recent one")
+                .arg(arg!(-l --limit <limit> "The number of entries to show").default_value("20").required(false))
+                .arg(arg!(-o --offset <offset> "The number of entries to skip").default_value("0").required(false))
+                .subcommand(
+                    Command::new("stats")
+                        .about("Show how many times a track was played and skipped")
+                        .arg_from_usage("<track_id> 'The track id'"),
+                )
+                .subcommand(Command::new("top").about("Show the tracks played the most")),
+        )
         .subcommand(
             Command::new("search")
                 .about("Search for a song, album, artist or playlist")
@@ -213,9 +275,26 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let cloned_cmd_rx = Arc::clone(&cmd_rx);
     let cmd_tx_ws = Arc::clone(&cloned_cmd_tx);
     let cmd_tx_webui = Arc::clone(&cloned_cmd_tx);
+    let settings = read_settings()?.try_deserialize::<Settings>()?;
+    let listening_tracker = sync::Mutex::new(ListeningTracker::new(Some(settings.device_name)));
+    let history_db = db.clone();
+    let runtime = tokio::runtime::Handle::current();
     let (_, _) = Player::new(
         move || backend(None, audio_format),
         move |event| {
+            if let Some(entry) = listening_tracker.lock().unwrap().handle(&event) {
+                let db = history_db.clone();
+                runtime.spawn(async move {
+                    match HistoryRepository::new(db.get_connection())
+                        .record(&entry)
+                        .await
+                    {
+                        Ok(entry) => SimpleBroker::publish(HistoryEntry::from(entry)),
+                        Err(e) => println!("Error recording history: {}", e),
+                    }
+                });
+            }
+
             let peers = cloned_peer_map.lock().unwrap();
 
             let broadcast_recipients = peers.iter().map(|(_, ws_sink)| ws_sink);
@@ -8,7 +8,7 @@ use music_player_settings::{read_settings, Settings};
 use std::{sync::Arc, time::Instant};
 use tokio::sync::Mutex;
 
-use crate::app::{AlbumTable, App, ArtistTable, CurrentlyPlaybackContext, TrackTable};
+use crate::app::{AlbumTable, App, ArtistTable, CurrentlyPlaybackContext, GenreTable, TrackTable};
 
 #[derive(Debug)]
 pub enum IoEvent {
@@ -20,6 +20,8 @@ pub enum IoEvent {
     GetAlbum(String),
     GetArtists,
     GetArtist(String),
+    GetGenres,
+    GetGenre(String),
     GetPlayQueue,
     GetAlbumTracks(String),
     AddItemToQueue(String),
@@ -28,6 +30,11 @@ pub enum IoEvent {
     GetCurrentPlayback,
     TogglePlayback,
     PlayTrackAt(usize),
+    GetLikedTracks,
+    GetFavouriteAlbums,
+    RateTrack(String, u8),
+    SetFavouriteTrack(String, bool),
+    SetFavouriteAlbum(String, bool),
 }
 
 pub struct Network<'a> {
@@ -66,6 +73,8 @@ impl<'a> Network<'a> {
             IoEvent::GetAlbum(id) => self.get_album(id).await,
             IoEvent::GetArtists => self.get_artists().await,
             IoEvent::GetArtist(id) => self.get_artist(id).await,
+            IoEvent::GetGenres => self.get_genres().await,
+            IoEvent::GetGenre(id) => self.get_genre(id).await,
             IoEvent::GetPlayQueue => self.get_play_queue().await,
             IoEvent::GetAlbumTracks(id) => self.get_album_tracks(id).await,
             IoEvent::AddItemToQueue(id) => self.add_item_to_queue(id).await,
@@ -74,6 +83,15 @@ impl<'a> Network<'a> {
             IoEvent::GetCurrentPlayback => self.get_current_playback().await,
             IoEvent::TogglePlayback => self.toggle_playback().await,
             IoEvent::PlayTrackAt(index) => self.play_track_at(index).await,
+            IoEvent::GetLikedTracks => self.get_liked_tracks().await,
+            IoEvent::GetFavouriteAlbums => self.get_favourite_albums().await,
+            IoEvent::RateTrack(id, rating) => self.rate_track(id, rating).await,
+            IoEvent::SetFavouriteTrack(id, favourite) => {
+                self.set_favourite_track(id, favourite).await
+            }
+            IoEvent::SetFavouriteAlbum(id, favourite) => {
+                self.set_favourite_album(id, favourite).await
+            }
         }
     }
 
@@ -169,6 +187,26 @@ impl<'a> Network<'a> {
         Ok(())
     }
 
+    async fn get_genres(&mut self) -> Result<(), Error> {
+        let genres = self.library.genres(None, 0, 10000).await?;
+        let mut app = self.app.lock().await;
+        app.genre_table = GenreTable {
+            genres,
+            selected_index: 0,
+        };
+        Ok(())
+    }
+
+    async fn get_genre(&mut self, id: String) -> Result<(), Error> {
+        let genre = self.library.genre(&id).await?;
+        let mut app = self.app.lock().await;
+        app.track_table = TrackTable {
+            tracks: genre.unwrap_or_default().tracks,
+            selected_index: 0,
+        };
+        Ok(())
+    }
+
     async fn get_play_queue(&mut self) -> Result<(), Error> {
         let (played_tracks, next_tracks) = self.tracklist.list().await?;
         let mut app = self.app.lock().await;
@@ -219,4 +257,51 @@ impl<'a> Network<'a> {
     async fn play_track_at(&mut self, index: usize) -> Result<(), Error> {
         self.tracklist.play_track_at(index).await
     }
+
+    async fn get_liked_tracks(&mut self) -> Result<(), Error> {
+        let tracks = self.library.liked_songs(0, 10000).await?;
+        let mut app = self.app.lock().await;
+        app.track_table = TrackTable {
+            tracks,
+            selected_index: 0,
+        };
+        Ok(())
+    }
+
+    async fn get_favourite_albums(&mut self) -> Result<(), Error> {
+        let albums = self.library.favourite_albums(0, 10000).await?;
+        let mut app = self.app.lock().await;
+        app.album_table = AlbumTable {
+            albums,
+            selected_index: 0,
+        };
+        Ok(())
+    }
+
+    async fn rate_track(&mut self, id: String, rating: u8) -> Result<(), Error> {
+        self.library.rate(&id, rating).await?;
+        let mut app = self.app.lock().await;
+        for track in app.track_table.tracks.iter_mut().filter(|t| t.id == id) {
+            track.rating = rating as i32;
+        }
+        Ok(())
+    }
+
+    async fn set_favourite_track(&mut self, id: String, favourite: bool) -> Result<(), Error> {
+        self.library.set_favourite("track", &id, favourite).await?;
+        let mut app = self.app.lock().await;
+        for track in app.track_table.tracks.iter_mut().filter(|t| t.id == id) {
+            track.favourite = favourite;
+        }
+        Ok(())
+    }
+
+    async fn set_favourite_album(&mut self, id: String, favourite: bool) -> Result<(), Error> {
+        self.library.set_favourite("album", &id, favourite).await?;
+        let mut app = self.app.lock().await;
+        for album in app.album_table.albums.iter_mut().filter(|a| a.id == id) {
+            album.favourite = favourite;
+        }
+        Ok(())
+    }
 }
@@ -1,11 +1,11 @@
 use anyhow::Error;
 use futures::future::FutureExt;
-use music_player_entity::{album, artist, artist_tracks, track};
-use music_player_scanner::scan_directory;
-use music_player_storage::{searcher::Searcher, Database};
+use music_player_entity::track;
+use music_player_scanner::{save_song, scan_directory};
+use music_player_storage::{repo::genre::GenreRepository, searcher::Searcher, Database};
 use music_player_types::types::Song;
 use owo_colors::OwoColorize;
-use sea_orm::{ActiveModelTrait, EntityTrait};
+use sea_orm::EntityTrait;
 
 pub async fn auto_scan_music_library(db: Database, searcher: Searcher) {
     match track::Entity::find().all(db.clone().get_connection()).await {
@@ -14,6 +14,13 @@ pub async fn auto_scan_music_library(db: Database, searcher: Searcher) {
                 scan_music_library(false, db, searcher)
                     .await
                     .unwrap_or_default();
+            } else {
+                if searcher.is_outdated() {
+                    if let Err(e) = searcher.rebuild_from_database(&db).await {
+                        println!("Error rebuilding the search indexes: {}", e);
+                    }
+                }
+                link_genres(&db).await;
             }
         }
         Err(e) => {
@@ -22,6 +29,21 @@ pub async fn auto_scan_music_library(db: Database, searcher: Searcher) {
     }
 }
 
+/// Libraries scanned before the genres were split from the tags have no
+/// genre yet, their tracks are linked to their genres once.
+async fn link_genres(db: &Database) {
+    let genres = GenreRepository::new(db.get_connection());
+    match genres.is_empty().await {
+        Ok(true) => {
+            if let Err(e) = genres.link_all_tracks().await {
+                println!("Error linking the genres: {}", e);
+            }
+        }
+        Ok(false) => {}
+        Err(e) => println!("Error: {}", e),
+    }
+}
+
 pub async fn scan_music_library(
     enable_log: bool,
     db: Database,
@@ -30,36 +52,13 @@ pub async fn scan_music_library(
     scan_directory(
         move |song, db| {
             async move {
-                let item: artist::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
-                }
-
-                let item: album::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
-                }
-
-                let item: track::ActiveModel = song.try_into().unwrap();
-
                 if enable_log {
                     let filename = song.uri.as_ref().unwrap().split("/").last().unwrap();
                     let path = song.uri.as_ref().unwrap().replace(filename, "");
                     println!("{}{}", path, filename.magenta());
                 }
 
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
-                }
-
-                let item: artist_tracks::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
-                }
+                save_song(song, db).await;
             }
             .boxed()
         },
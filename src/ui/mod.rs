@@ -14,8 +14,8 @@ use crate::{
 };
 
 use self::util::{
-    display_track_progress, get_color, get_percentage_width, get_track_progress_percentage,
-    millis_to_minutes,
+    display_rating, display_track_progress, get_color, get_percentage_width,
+    get_track_progress_percentage, millis_to_minutes,
 };
 
 pub mod util;
@@ -25,6 +25,7 @@ pub enum TableId {
     AlbumList,
     Artist,
     ArtistList,
+    GenreList,
     Song,
     PlayQueue,
 }
@@ -36,6 +37,7 @@ pub enum ColumnId {
     Title,
     Artist,
     Album,
+    Genre,
 }
 
 pub struct TableHeader<'a> {
@@ -112,6 +114,7 @@ where
         RouteId::Search => draw_search_results(f, app, chunks[1]),
         RouteId::TrackTable => draw_song_table(f, app, chunks[1]),
         RouteId::Artists => draw_artist_table(f, app, chunks[1]),
+        RouteId::Genres => draw_genre_table(f, app, chunks[1]),
         RouteId::PlayQueue => draw_play_queue(f, app, chunks[1]),
     }
 }
@@ -358,6 +361,46 @@ where
     )
 }
 
+pub fn draw_genre_table<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
+where
+    B: Backend,
+{
+    let header = TableHeader {
+        id: TableId::GenreList,
+        items: vec![TableHeaderItem {
+            id: ColumnId::Genre,
+            text: "Name",
+            width: get_percentage_width(layout_chunk.width, 1.0),
+        }],
+    };
+
+    let items = app
+        .genre_table
+        .genres
+        .iter()
+        .map(|item| TableItem {
+            id: item.id.clone(),
+            format: vec![item.name.clone()],
+        })
+        .collect::<Vec<TableItem>>();
+
+    let current_route = app.get_current_route();
+    let highlight_state = (
+        current_route.active_block == ActiveBlock::Genres,
+        current_route.hovered_block == ActiveBlock::Genres,
+    );
+
+    draw_table(
+        f,
+        app,
+        layout_chunk,
+        ("Genres", &header),
+        &items,
+        app.genre_table.selected_index,
+        highlight_state,
+    )
+}
+
 pub fn draw_artist_song_table<B>(f: &mut Frame<B>, app: &App, layout_chunk: Rect)
 where
     B: Backend,
@@ -434,12 +477,17 @@ where
             },
             TableHeaderItem {
                 text: "Artist",
-                width: get_percentage_width(layout_chunk.width, 0.3),
+                width: get_percentage_width(layout_chunk.width, 0.25),
                 ..Default::default()
             },
             TableHeaderItem {
                 text: "Album",
-                width: get_percentage_width(layout_chunk.width, 0.3),
+                width: get_percentage_width(layout_chunk.width, 0.25),
+                ..Default::default()
+            },
+            TableHeaderItem {
+                text: "Rating",
+                width: get_percentage_width(layout_chunk.width, 0.1),
                 ..Default::default()
             },
             TableHeaderItem {
@@ -460,6 +508,7 @@ where
                 item.title.clone(),
                 item.artist.clone(),
                 item.album.clone().unwrap_or_default().title,
+                display_rating(item.rating, item.favourite),
                 millis_to_minutes((item.duration * 1000.0) as u128),
             ],
         })
@@ -520,7 +569,10 @@ where
         .map(|item| TableItem {
             id: item.id.clone(),
             format: vec![
-                item.title.clone(),
+                match item.favourite {
+                    true => format!("♥ {}", item.title),
+                    false => item.title.clone(),
+                },
                 item.artist.clone(),
                 item.year.to_string(),
             ],
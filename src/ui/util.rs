@@ -46,6 +46,15 @@ pub fn millis_to_minutes(millis: u128) -> String {
     }
 }
 
+/// Stars of a track, preceded by a heart for the favourite ones.
+pub fn display_rating(rating: i32, favourite: bool) -> String {
+    let stars = "★".repeat(rating.clamp(0, 5) as usize);
+    match favourite {
+        true => format!("♥ {}", stars),
+        false => stars,
+    }
+}
+
 pub fn create_artist_string(artists: &[Artist]) -> String {
     artists
         .iter()
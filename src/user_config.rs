@@ -63,6 +63,8 @@ pub struct KeyBindings {
     pub search: Key,
     pub submit: Key,
     pub add_item_to_queue: Key,
+    pub toggle_favourite: Key,
+    pub rate_track: Key,
 }
 
 #[derive(Clone)]
@@ -102,6 +104,8 @@ impl UserConfig {
                 search: Key::Char('/'),
                 submit: Key::Enter,
                 add_item_to_queue: Key::Char('z'),
+                toggle_favourite: Key::Char('f'),
+                rate_track: Key::Char('r'),
             },
             behavior: BehaviorConfig {
                 enable_text_emphasis: true,
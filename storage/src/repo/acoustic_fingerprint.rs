@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use music_player_entity::acoustic_fingerprint as acoustic_fingerprint_entity;
+use sea_orm::{
+    sea_query::OnConflict, ActiveValue, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter,
+};
+
+/// Content hashes looked up per statement, SQLite limits the number of bound
+/// values.
+const LOOKUP_BATCH_SIZE: usize = 500;
+
+pub struct AcousticFingerprintRepository {
+    db: DatabaseConnection,
+}
+
+impl AcousticFingerprintRepository {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    /// Returns the cached fingerprints, keyed by content hash.
+    pub async fn find_all(
+        &self,
+        content_hashes: Vec<String>,
+    ) -> Result<HashMap<String, String>, Error> {
+        let mut fingerprints = HashMap::new();
+        for batch in content_hashes.chunks(LOOKUP_BATCH_SIZE) {
+            let results = acoustic_fingerprint_entity::Entity::find()
+                .filter(acoustic_fingerprint_entity::Column::Id.is_in(batch.to_vec()))
+                .all(&self.db)
+                .await?;
+            fingerprints.extend(
+                results
+                    .into_iter()
+                    .map(|result| (result.id, result.fingerprint)),
+            );
+        }
+        Ok(fingerprints)
+    }
+
+    pub async fn save(&self, content_hash: &str, fingerprint: &str) -> Result<(), Error> {
+        let item = acoustic_fingerprint_entity::ActiveModel {
+            id: ActiveValue::set(content_hash.to_string()),
+            fingerprint: ActiveValue::Set(fingerprint.to_string()),
+        };
+        acoustic_fingerprint_entity::Entity::insert(item)
+            .on_conflict(
+                OnConflict::column(acoustic_fingerprint_entity::Column::Id)
+                    .update_column(acoustic_fingerprint_entity::Column::Fingerprint)
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,9 +1,85 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
 use music_player_entity::{album as album_entity, artist as artist_entity, track as track_entity};
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, ModelTrait, QueryFilter, QueryOrder, QuerySelect,
+    sea_query::{Expr, Query, SimpleExpr},
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, IntoSimpleExpr, ModelTrait, Order,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+};
+
+use super::{
+    page::{Cursor, Page, PageRequest},
+    track::TrackFilter,
 };
 
+/// Total duration of the tracks of an album.
+const DURATION: &str = "(SELECT SUM(duration) FROM track WHERE track.album_id = album.id)";
+
+/// Number of times the tracks of an album were played, skips left out.
+const PLAY_COUNT: &str = "(SELECT COUNT(*) FROM history \
+    INNER JOIN track ON track.id = history.track_id \
+    WHERE track.album_id = album.id AND history.completed)";
+
+/// Average rating of the rated tracks of an album.
+const RATING: &str = "(SELECT AVG(rating) FROM track_rating \
+    INNER JOIN track ON track.id = track_rating.track_id \
+    WHERE track.album_id = album.id AND track_rating.rating > 0)";
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum AlbumField {
+    #[default]
+    Title,
+    Year,
+    /// When the album was first scanned, descending for the recently added
+    AddedAt,
+    Duration,
+    Artist,
+    PlayCount,
+    Rating,
+}
+
+impl AlbumField {
+    pub fn parse(field: &str) -> Option<Self> {
+        match field {
+            "title" => Some(AlbumField::Title),
+            "year" => Some(AlbumField::Year),
+            "added_at" => Some(AlbumField::AddedAt),
+            "duration" => Some(AlbumField::Duration),
+            "artist" => Some(AlbumField::Artist),
+            "play_count" => Some(AlbumField::PlayCount),
+            "rating" => Some(AlbumField::Rating),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct AlbumOrder {
+    pub field: AlbumField,
+    pub descending: bool,
+}
+
+impl AlbumOrder {
+    fn apply(&self, query: Select<album_entity::Entity>) -> Select<album_entity::Entity> {
+        let expr: SimpleExpr = match self.field {
+            AlbumField::Title => album_entity::Column::Title.into_simple_expr(),
+            AlbumField::Year => album_entity::Column::Year.into_simple_expr(),
+            AlbumField::AddedAt => album_entity::Column::CreatedAt.into_simple_expr(),
+            AlbumField::Duration => Expr::cust(DURATION),
+            AlbumField::Artist => album_entity::Column::Artist.into_simple_expr(),
+            AlbumField::PlayCount => Expr::cust(PLAY_COUNT),
+            AlbumField::Rating => Expr::cust(RATING),
+        };
+        let query = match self.descending {
+            true => query.order_by(expr, Order::Desc),
+            false => query.order_by(expr, Order::Asc),
+        };
+        // Keeps a stable order between the albums sharing the same value
+        query.order_by_asc(album_entity::Column::Id)
+    }
+}
+
 pub struct AlbumRepository {
     db: DatabaseConnection,
 }
@@ -23,6 +99,7 @@ impl AlbumRepository {
         let mut album = result.unwrap();
         let mut tracks = album
             .find_related(track_entity::Entity)
+            .order_by_asc(track_entity::Column::DiscNumber)
             .order_by_asc(track_entity::Column::Track)
             .all(&self.db)
             .await?;
@@ -43,32 +120,102 @@ impl AlbumRepository {
         offset: Option<u64>,
         limit: Option<u64>,
     ) -> Result<Vec<album_entity::Model>, Error> {
-        let mut query = match offset {
-            Some(offset) => album_entity::Entity::find()
-                .order_by_asc(album_entity::Column::Title)
-                .offset(offset),
-            None => album_entity::Entity::find().order_by_asc(album_entity::Column::Title),
-        };
-        query = match limit {
-            Some(limit) => query.limit(limit),
-            None => query,
-        };
-        match filter {
-            Some(filter) => {
-                if filter.is_empty() {
-                    let results = query.all(&self.db).await?;
-                    return Ok(results);
-                }
-                let results = query
-                    .filter(album_entity::Column::Title.like(format!("%{}%", filter).as_str()))
-                    .all(&self.db)
-                    .await?;
-                Ok(results)
-            }
-            None => {
-                let results = query.all(&self.db).await?;
-                Ok(results)
+        self.find_all_matching(
+            filter,
+            &TrackFilter::default(),
+            AlbumOrder::default(),
+            offset,
+            limit,
+        )
+        .await
+    }
+
+    /// Lists the albums whose title contains `filter` and having at least one
+    /// track matching `tracks`.
+    pub async fn find_all_matching(
+        &self,
+        filter: Option<String>,
+        tracks: &TrackFilter,
+        order: AlbumOrder,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<album_entity::Model>, Error> {
+        let condition = Self::matching(filter, tracks);
+        let mut query = order.apply(album_entity::Entity::find().filter(condition));
+        if let Some(offset) = offset {
+            query = query.offset(offset);
+        }
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+        Ok(query.all(&self.db).await?)
+    }
+
+    /// A page of the albums whose title contains `filter` and having at least
+    /// one track matching `tracks`, sorted by title.
+    pub async fn find_page(
+        &self,
+        filter: Option<String>,
+        tracks: &TrackFilter,
+        request: &PageRequest,
+    ) -> Result<Page<album_entity::Model>, Error> {
+        let query = album_entity::Entity::find().filter(Self::matching(filter, tracks));
+        let total_count = query.clone().count(&self.db).await?;
+        let albums = request
+            .apply(
+                query,
+                album_entity::Column::Title,
+                album_entity::Column::Id,
+                false,
+                |key| Some(key.into()),
+            )?
+            .all(&self.db)
+            .await?;
+        Ok(request.page(albums, total_count, |album| {
+            Cursor::new(&album.title, &album.id)
+        }))
+    }
+
+    fn matching(filter: Option<String>, tracks: &TrackFilter) -> Condition {
+        let mut condition = Condition::all().add(
+            album_entity::Column::Id.in_subquery(
+                Query::select()
+                    .column(track_entity::Column::AlbumId)
+                    .from(track_entity::Entity)
+                    .cond_where(tracks.condition())
+                    .to_owned(),
+            ),
+        );
+        if let Some(filter) = filter.filter(|filter| !filter.is_empty()) {
+            condition =
+                condition.add(album_entity::Column::Title.like(format!("%{}%", filter).as_str()));
+        }
+        condition
+    }
+
+    pub async fn find_by_ids(&self, ids: &[String]) -> Result<Vec<album_entity::Model>, Error> {
+        Ok(album_entity::Entity::find()
+            .filter(album_entity::Column::Id.is_in(ids.to_vec()))
+            .all(&self.db)
+            .await?)
+    }
+
+    /// The albums of the artists, by artist id, in title order.
+    pub async fn find_by_artists(
+        &self,
+        artist_ids: &[String],
+    ) -> Result<HashMap<String, Vec<album_entity::Model>>, Error> {
+        let results = album_entity::Entity::find()
+            .filter(album_entity::Column::ArtistId.is_in(artist_ids.to_vec()))
+            .order_by_asc(album_entity::Column::Title)
+            .all(&self.db)
+            .await?;
+        let mut albums: HashMap<String, Vec<album_entity::Model>> = HashMap::new();
+        for album in results {
+            if let Some(artist_id) = album.artist_id.clone() {
+                albums.entry(artist_id).or_default().push(album);
             }
         }
+        Ok(albums)
     }
 }
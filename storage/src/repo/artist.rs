@@ -1,6 +1,14 @@
 use anyhow::Error;
-use music_player_entity::{album as album_entity, artist as artist_entity, track as track_entity};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use music_player_entity::{
+    album as album_entity, artist as artist_entity, artist_tracks as artist_tracks_entity,
+    track as track_entity,
+};
+use sea_orm::{
+    sea_query::Query, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+use super::page::{Cursor, Page, PageRequest};
 
 pub struct ArtistRepository {
     db: DatabaseConnection,
@@ -83,4 +91,82 @@ impl ArtistRepository {
             }
         }
     }
+
+    /// A page of the artists whose name contains `filter`, sorted by name.
+    pub async fn find_page(
+        &self,
+        filter: Option<String>,
+        request: &PageRequest,
+    ) -> Result<Page<artist_entity::Model>, Error> {
+        let mut query = artist_entity::Entity::find();
+        if let Some(filter) = filter.filter(|filter| !filter.is_empty()) {
+            query =
+                query.filter(artist_entity::Column::Name.like(format!("%{}%", filter).as_str()));
+        }
+        let total_count = query.clone().count(&self.db).await?;
+        let artists = request
+            .apply(
+                query,
+                artist_entity::Column::Name,
+                artist_entity::Column::Id,
+                false,
+                |key| Some(key.into()),
+            )?
+            .all(&self.db)
+            .await?;
+        Ok(request.page(artists, total_count, |artist| {
+            Cursor::new(&artist.name, &artist.id)
+        }))
+    }
+
+    /// Deletes the artists left without any track or album, returns their ids.
+    pub async fn delete_unused(&self) -> Result<Vec<String>, Error> {
+        Ok(delete_unused_artists(&self.db).await?)
+    }
+}
+
+/// Deletes the artists left without any track or album, e.g. "A feat. B" once
+/// the tag is split, returns their ids so that their documents and
+/// suggestions can be dropped as well.
+pub async fn delete_unused_artists<C: ConnectionTrait>(db: &C) -> Result<Vec<String>, DbErr> {
+    let unused: Vec<String> = artist_entity::Entity::find()
+        .filter(
+            artist_entity::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(artist_tracks_entity::Column::ArtistId)
+                    .from(artist_tracks_entity::Entity)
+                    .to_owned(),
+            ),
+        )
+        .filter(
+            artist_entity::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(album_entity::Column::ArtistId)
+                    .from(album_entity::Entity)
+                    .and_where(album_entity::Column::ArtistId.is_not_null())
+                    .to_owned(),
+            ),
+        )
+        .filter(
+            artist_entity::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(track_entity::Column::ArtistId)
+                    .from(track_entity::Entity)
+                    .and_where(track_entity::Column::ArtistId.is_not_null())
+                    .to_owned(),
+            ),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|artist| artist.id)
+        .collect();
+    if unused.is_empty() {
+        return Ok(unused);
+    }
+    artist_entity::Entity::delete_many()
+        .filter(artist_entity::Column::Id.is_in(unused.clone()))
+        .exec(db)
+        .await?;
+    Ok(unused)
 }
@@ -0,0 +1,161 @@
+use std::collections::HashSet;
+
+use anyhow::Error;
+use music_player_entity::{
+    album as album_entity, artist as artist_entity, genre as genre_entity,
+    genre_tracks as genre_tracks_entity, track as track_entity,
+};
+use sea_orm::{
+    sea_query::{OnConflict, Query},
+    ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, ModelTrait,
+    PaginatorTrait, QueryFilter, QueryOrder, QuerySelect,
+};
+
+/// The genres split from the genre tags of the tracks, see `split_genres`.
+pub struct GenreRepository {
+    db: DatabaseConnection,
+}
+
+impl GenreRepository {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    /// A genre with its tracks and the albums of its tracks.
+    pub async fn find(&self, id: &str) -> Result<genre_entity::Model, Error> {
+        let mut genre = genre_entity::Entity::find_by_id(id.to_string())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| Error::msg("Genre not found"))?;
+
+        let results: Vec<(track_entity::Model, Option<album_entity::Model>)> = genre
+            .find_related(track_entity::Entity)
+            .order_by_asc(track_entity::Column::Title)
+            .find_also_related(album_entity::Entity)
+            .all(&self.db)
+            .await?;
+
+        let mut album_ids = HashSet::new();
+        for (mut track, album) in results {
+            track.artists = track
+                .find_related(artist_entity::Entity)
+                .all(&self.db)
+                .await?;
+            if let Some(album) = album {
+                if album_ids.insert(album.id.clone()) {
+                    genre.albums.push(album.clone());
+                }
+                track.album = album;
+            }
+            genre.tracks.push(track);
+        }
+        genre.albums.sort_by_key(|album| album.title.to_lowercase());
+        Ok(genre)
+    }
+
+    pub async fn find_all(
+        &self,
+        filter: Option<String>,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<genre_entity::Model>, Error> {
+        let mut query = genre_entity::Entity::find().order_by_asc(genre_entity::Column::Name);
+        if let Some(offset) = offset {
+            query = query.offset(offset);
+        }
+        if let Some(limit) = limit {
+            query = query.limit(limit);
+        }
+        if let Some(filter) = filter.filter(|filter| !filter.is_empty()) {
+            query = query.filter(genre_entity::Column::Name.like(format!("%{}%", filter).as_str()));
+        }
+        Ok(query.all(&self.db).await?)
+    }
+
+    /// Links a track to the genres of its genre tag, replacing its links.
+    pub async fn link_track(&self, track_id: &str, genre: &str) -> Result<(), Error> {
+        link_track_genres(&self.db, track_id, genre).await?;
+        Ok(())
+    }
+
+    /// Links all the tracks to their genres, for the libraries scanned before
+    /// the genres were split from the tags.
+    pub async fn link_all_tracks(&self) -> Result<usize, Error> {
+        let tracks = track_entity::Entity::find().all(&self.db).await?;
+        for track in &tracks {
+            link_track_genres(&self.db, &track.id, &track.genre).await?;
+        }
+        Ok(tracks.len())
+    }
+
+    pub async fn is_empty(&self) -> Result<bool, Error> {
+        Ok(genre_entity::Entity::find().count(&self.db).await? == 0)
+    }
+
+    /// Deletes the genres left without any track, returns their ids.
+    pub async fn delete_unused(&self) -> Result<Vec<String>, Error> {
+        Ok(delete_unused_genres(&self.db).await?)
+    }
+}
+
+/// Links a track to the genres of its genre tag, replacing its links. Takes
+/// any connection so that a transaction can link the tracks it updates.
+pub async fn link_track_genres<C: ConnectionTrait>(
+    db: &C,
+    track_id: &str,
+    genre: &str,
+) -> Result<(), DbErr> {
+    genre_tracks_entity::Entity::delete_many()
+        .filter(genre_tracks_entity::Column::TrackId.eq(track_id))
+        .exec(db)
+        .await?;
+
+    for item in genre_entity::ActiveModel::from_genres(genre) {
+        genre_entity::Entity::insert(item)
+            .on_conflict(
+                OnConflict::column(genre_entity::Column::Id)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec(db)
+            .await?;
+    }
+    for item in genre_tracks_entity::ActiveModel::from_genres(track_id, genre) {
+        genre_tracks_entity::Entity::insert(item)
+            .on_conflict(
+                OnConflict::column(genre_tracks_entity::Column::Id)
+                    .do_nothing()
+                    .to_owned(),
+            )
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+/// Deletes the genres left without any track, returns their ids so that their
+/// suggestions can be dropped as well.
+pub async fn delete_unused_genres<C: ConnectionTrait>(db: &C) -> Result<Vec<String>, DbErr> {
+    let unused: Vec<String> = genre_entity::Entity::find()
+        .filter(
+            genre_entity::Column::Id.not_in_subquery(
+                Query::select()
+                    .column(genre_tracks_entity::Column::GenreId)
+                    .from(genre_tracks_entity::Entity)
+                    .to_owned(),
+            ),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|genre| genre.id)
+        .collect();
+    if unused.is_empty() {
+        return Ok(unused);
+    }
+    genre_entity::Entity::delete_many()
+        .filter(genre_entity::Column::Id.is_in(unused.clone()))
+        .exec(db)
+        .await?;
+    Ok(unused)
+}
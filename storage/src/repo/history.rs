@@ -0,0 +1,156 @@
+use std::collections::HashMap;
+
+use anyhow::Error;
+use chrono::{DateTime, Utc};
+use music_player_entity::{history as history_entity, track as track_entity};
+use music_player_types::types::{HistoryEntry, PlayStats};
+use sea_orm::{
+    sea_query::Expr, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    FromQueryResult, PaginatorTrait, QueryFilter, QueryOrder, QuerySelect, Select,
+};
+
+use super::page::{Cursor, Page, PageRequest};
+
+#[derive(Debug, FromQueryResult)]
+struct PlayStatsResult {
+    track_id: String,
+    play_count: i64,
+    skip_count: i64,
+    last_played: Option<DateTime<Utc>>,
+}
+
+impl From<PlayStatsResult> for PlayStats {
+    fn from(result: PlayStatsResult) -> Self {
+        Self {
+            track_id: result.track_id,
+            play_count: result.play_count as u32,
+            skip_count: result.skip_count as u32,
+            last_played: result.last_played,
+        }
+    }
+}
+
+pub struct HistoryRepository {
+    db: DatabaseConnection,
+}
+
+impl HistoryRepository {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    /// Saves a play of a track, the id is derived from the track and the
+    /// start time when the entry doesn't have one yet.
+    pub async fn record(&self, entry: &HistoryEntry) -> Result<HistoryEntry, Error> {
+        let mut entry = entry.clone();
+        if entry.id.is_empty() {
+            entry.id = format!(
+                "{:x}",
+                md5::compute(format!(
+                    "{}{}",
+                    entry.track.id,
+                    entry.started_at.to_rfc3339()
+                ))
+            );
+        }
+        history_entity::ActiveModel::from(&entry)
+            .insert(&self.db)
+            .await?;
+        Ok(entry)
+    }
+
+    /// Lists the plays from the most recent one.
+    pub async fn find_all(&self, offset: u64, limit: u64) -> Result<Vec<HistoryEntry>, Error> {
+        let results = history_entity::Entity::find()
+            .find_also_related(track_entity::Entity)
+            .order_by_desc(history_entity::Column::StartedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(|(history, track)| history.into_entry(track))
+            .collect())
+    }
+
+    /// A page of the plays, from the most recent one. The cursors hold the
+    /// start time of the plays in RFC 3339.
+    pub async fn find_page(&self, request: &PageRequest) -> Result<Page<HistoryEntry>, Error> {
+        let query = history_entity::Entity::find();
+        let total_count = query.clone().count(&self.db).await?;
+        let results = request
+            .apply(
+                query,
+                history_entity::Column::StartedAt,
+                history_entity::Column::Id,
+                true,
+                |key| {
+                    DateTime::parse_from_rfc3339(key)
+                        .ok()
+                        .map(|started_at| started_at.with_timezone(&Utc).into())
+                },
+            )?
+            .find_also_related(track_entity::Entity)
+            .all(&self.db)
+            .await?;
+        Ok(request.page(
+            results
+                .into_iter()
+                .map(|(history, track)| history.into_entry(track))
+                .collect(),
+            total_count,
+            |entry: &HistoryEntry| Cursor::new(&entry.started_at.to_rfc3339(), &entry.id),
+        ))
+    }
+
+    pub async fn stats(&self, track_id: &str) -> Result<PlayStats, Error> {
+        let result = Self::select_stats()
+            .filter(history_entity::Column::TrackId.eq(track_id))
+            .into_model::<PlayStatsResult>()
+            .one(&self.db)
+            .await?;
+        Ok(result.map(Into::into).unwrap_or_else(|| PlayStats {
+            track_id: track_id.to_string(),
+            ..Default::default()
+        }))
+    }
+
+    /// Number of completed plays of the given tracks, the tracks never played
+    /// being left out.
+    pub async fn play_counts(&self, track_ids: &[String]) -> Result<HashMap<String, u32>, Error> {
+        let results = Self::select_stats()
+            .filter(history_entity::Column::TrackId.is_in(track_ids.to_vec()))
+            .into_model::<PlayStatsResult>()
+            .all(&self.db)
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(|result| (result.track_id, result.play_count as u32))
+            .collect())
+    }
+
+    /// Lists the statistics of the tracks played the most, skips left out.
+    pub async fn most_played(&self, offset: u64, limit: u64) -> Result<Vec<PlayStats>, Error> {
+        let results = Self::select_stats()
+            .having(Expr::expr(Expr::cust("SUM(completed)")).gt(0))
+            .order_by_desc(Expr::cust("play_count"))
+            .order_by_desc(Expr::cust("last_played"))
+            .offset(offset)
+            .limit(limit)
+            .into_model::<PlayStatsResult>()
+            .all(&self.db)
+            .await?;
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    fn select_stats() -> Select<history_entity::Entity> {
+        history_entity::Entity::find()
+            .select_only()
+            .column(history_entity::Column::TrackId)
+            .column_as(Expr::cust("SUM(completed)"), "play_count")
+            .column_as(Expr::cust("COUNT(*) - SUM(completed)"), "skip_count")
+            .column_as(history_entity::Column::StartedAt.max(), "last_played")
+            .group_by(history_entity::Column::TrackId)
+    }
+}
@@ -0,0 +1,40 @@
+use anyhow::Error;
+use music_player_entity::lyrics as lyrics_entity;
+use sea_orm::{sea_query::OnConflict, DatabaseConnection, EntityTrait};
+
+pub struct LyricsRepository {
+    db: DatabaseConnection,
+}
+
+impl LyricsRepository {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    pub async fn find_by_track(
+        &self,
+        track_id: &str,
+    ) -> Result<Option<lyrics_entity::Model>, Error> {
+        let result = lyrics_entity::Entity::find_by_id(track_id.to_string())
+            .one(&self.db)
+            .await?;
+        Ok(result)
+    }
+
+    /// Inserts the lyrics of a track, replacing the ones found by a previous scan.
+    pub async fn save(&self, lyrics: lyrics_entity::ActiveModel) -> Result<(), Error> {
+        lyrics_entity::Entity::insert(lyrics)
+            .on_conflict(
+                OnConflict::column(lyrics_entity::Column::Id)
+                    .update_columns([
+                        lyrics_entity::Column::Content,
+                        lyrics_entity::Column::Synced,
+                        lyrics_entity::Column::Source,
+                    ])
+                    .to_owned(),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,5 +1,13 @@
+pub mod acoustic_fingerprint;
 pub mod album;
 pub mod artist;
 pub mod folder;
+pub mod genre;
+pub mod history;
+pub mod lyrics;
+pub mod page;
 pub mod playlist;
+pub mod rating;
+pub mod scan_error;
+pub mod smart_playlist;
 pub mod track;
@@ -0,0 +1,197 @@
+use anyhow::Error;
+use sea_orm::{
+    sea_query::{Expr, SimpleExpr},
+    ColumnTrait, Condition, EntityTrait, IntoSimpleExpr, Order, QueryFilter, QueryOrder,
+    QuerySelect, Select, Value,
+};
+
+/// Number of rows of a page when neither `first` nor `last` is set.
+const DEFAULT_PAGE_SIZE: u64 = 100;
+
+/// A position in a list sorted by a key, then by id to break the ties. The
+/// rows following a cursor stay the same when rows are added elsewhere in the
+/// list, unlike with an offset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cursor {
+    /// Unset for the rows missing the key, e.g. the tracks without a year
+    pub key: Option<String>,
+    pub id: String,
+}
+
+impl Cursor {
+    pub fn new(key: &str, id: &str) -> Self {
+        Self {
+            key: Some(key.to_string()),
+            id: id.to_string(),
+        }
+    }
+}
+
+/// Where the rows missing the key go in a list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Nulls {
+    /// The key is always set
+    Never,
+    /// Before the smallest keys, the order of SQLite
+    Smallest,
+    /// At the end of the list, whatever the direction
+    Last,
+}
+
+/// The rows after and before cursors, the first or last ones of them.
+#[derive(Debug, Clone, Default)]
+pub struct PageRequest {
+    pub after: Option<Cursor>,
+    pub before: Option<Cursor>,
+    pub first: Option<u64>,
+    pub last: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Page<T> {
+    pub items: Vec<(Cursor, T)>,
+    pub has_previous_page: bool,
+    pub has_next_page: bool,
+    /// Number of rows of the whole list
+    pub total_count: usize,
+}
+
+impl PageRequest {
+    /// Counts from the end of the list when only `last` is set.
+    fn backward(&self) -> bool {
+        self.last.is_some() && self.first.is_none()
+    }
+
+    fn size(&self) -> u64 {
+        self.first.or(self.last).unwrap_or(DEFAULT_PAGE_SIZE)
+    }
+
+    /// Restricts `query` to the rows of the page, sorted by `key` then `id`,
+    /// with one more row telling whether the list goes on. `parse_key` turns
+    /// the key of a cursor back into a value of the `key` column.
+    pub(crate) fn apply<E: EntityTrait>(
+        &self,
+        query: Select<E>,
+        key: E::Column,
+        id: E::Column,
+        descending: bool,
+        parse_key: impl Fn(&str) -> Option<Value>,
+    ) -> Result<Select<E>, Error> {
+        self.apply_by(
+            query,
+            key.into_simple_expr(),
+            Nulls::Never,
+            id,
+            descending,
+            parse_key,
+        )
+    }
+
+    /// Same as `apply` for a key computed by an expression, which may be NULL.
+    pub(crate) fn apply_by<E: EntityTrait>(
+        &self,
+        query: Select<E>,
+        key: SimpleExpr,
+        nulls: Nulls,
+        id: E::Column,
+        descending: bool,
+        parse_key: impl Fn(&str) -> Option<Value>,
+    ) -> Result<Select<E>, Error> {
+        let nulls_first = match nulls {
+            Nulls::Never => None,
+            Nulls::Smallest => Some(!descending),
+            Nulls::Last => Some(false),
+        };
+        let mut condition = Condition::all();
+        let mut bound = |cursor: &Cursor, after: bool| -> Result<(), Error> {
+            let id_after = match after != descending {
+                true => id.gt(cursor.id.as_str()),
+                false => id.lt(cursor.id.as_str()),
+            };
+            // Whether the rows missing the key come after the cursor
+            let nulls_after = nulls_first.map(|nulls_first| nulls_first != after);
+            let rows_after = match (&cursor.key, nulls_after) {
+                (Some(key_value), _) => {
+                    let value = parse_key(key_value).ok_or_else(|| Error::msg("Invalid cursor"))?;
+                    // The rows coming after a cursor, in the order of the list
+                    let key_after = match after != descending {
+                        true => Expr::expr(key.clone()).gt(value.clone()),
+                        false => Expr::expr(key.clone()).lt(value.clone()),
+                    };
+                    let rows_after = Condition::any().add(key_after).add(
+                        Condition::all()
+                            .add(Expr::expr(key.clone()).eq(value))
+                            .add(id_after),
+                    );
+                    match nulls_after {
+                        Some(true) => rows_after.add(Expr::expr(key.clone()).is_null()),
+                        _ => rows_after,
+                    }
+                }
+                (None, Some(nulls_after)) => {
+                    let rows_after = Condition::any().add(
+                        Condition::all()
+                            .add(Expr::expr(key.clone()).is_null())
+                            .add(id_after),
+                    );
+                    match nulls_after {
+                        true => rows_after,
+                        false => rows_after.add(Expr::expr(key.clone()).is_not_null()),
+                    }
+                }
+                (None, None) => return Err(Error::msg("Invalid cursor")),
+            };
+            condition = condition.clone().add(rows_after);
+            Ok(())
+        };
+        if let Some(cursor) = &self.after {
+            bound(cursor, true)?;
+        }
+        if let Some(cursor) = &self.before {
+            bound(cursor, false)?;
+        }
+
+        let query = query.filter(condition);
+        let query = match nulls_first {
+            Some(nulls_first) => query.order_by(
+                Expr::expr(key.clone()).is_null(),
+                match nulls_first != self.backward() {
+                    true => Order::Desc,
+                    false => Order::Asc,
+                },
+            ),
+            None => query,
+        };
+        let query = match descending != self.backward() {
+            true => query.order_by(key, Order::Desc).order_by_desc(id),
+            false => query.order_by(key, Order::Asc).order_by_asc(id),
+        };
+        Ok(query.limit(self.size() + 1))
+    }
+
+    /// Makes a page of the rows fetched with the query of `apply`.
+    pub(crate) fn page<T>(
+        &self,
+        mut rows: Vec<T>,
+        total_count: usize,
+        cursor: impl Fn(&T) -> Cursor,
+    ) -> Page<T> {
+        let more = rows.len() as u64 > self.size();
+        rows.truncate(self.size() as usize);
+        if self.backward() {
+            rows.reverse();
+        }
+        Page {
+            items: rows.into_iter().map(|row| (cursor(&row), row)).collect(),
+            has_previous_page: match self.backward() {
+                true => more,
+                false => self.after.is_some(),
+            },
+            has_next_page: match self.backward() {
+                true => self.before.is_some(),
+                false => more,
+            },
+            total_count,
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use super::{
+    page::{Cursor, Page, PageRequest},
+    smart_playlist::SmartPlaylistRepository,
+};
 use anyhow::Error;
 use music_player_entity::{
     album as album_entity, artist as artist_entity, playlist as playlist_entity,
@@ -5,8 +9,9 @@ use music_player_entity::{
 };
 use music_player_types::types::Playlist;
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, EntityTrait, JoinType, QueryFilter, QueryOrder, QuerySelect,
-    RelationTrait,
+    sea_query::Expr, ActiveModelTrait, ActiveValue, ColumnTrait, ConnectionTrait,
+    DatabaseConnection, EntityTrait, JoinType, ModelTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect, RelationTrait, TransactionTrait,
 };
 
 pub struct PlaylistRepository {
@@ -62,6 +67,7 @@ impl PlaylistRepository {
                     )
                     .join(JoinType::LeftJoin, track_entity::Relation::Album.def())
                     .join(JoinType::LeftJoin, track_entity::Relation::Artist.def())
+                    .order_by_asc(playlist_tracks_entity::Column::Position)
                     .into_model::<select_result::PlaylistTrack>()
                     .all(&self.db)
                     .await?;
@@ -82,11 +88,16 @@ impl PlaylistRepository {
             }
         };
         let playlist = playlist.unwrap();
+        let rules = SmartPlaylistRepository::new(&self.db)
+            .find(id)
+            .await?
+            .map(|query| query.to_string());
         Ok(Playlist {
             id: playlist.id,
             name: playlist.name,
             description: playlist.description,
             tracks: result.into_iter().map(Into::into).collect(),
+            rules,
         })
     }
 
@@ -99,6 +110,27 @@ impl PlaylistRepository {
             .map_err(|e| Error::msg(e.to_string()))
     }
 
+    /// A page of the playlists, sorted by name.
+    pub async fn find_page(&self, request: &PageRequest) -> Result<Page<Playlist>, Error> {
+        let query = playlist_entity::Entity::find();
+        let total_count = query.clone().count(&self.db).await?;
+        let playlists = request
+            .apply(
+                query,
+                playlist_entity::Column::Name,
+                playlist_entity::Column::Id,
+                false,
+                |key| Some(key.into()),
+            )?
+            .all(&self.db)
+            .await?;
+        Ok(request.page(
+            playlists.into_iter().map(Into::into).collect(),
+            total_count,
+            |playlist: &Playlist| Cursor::new(&playlist.name, &playlist.id),
+        ))
+    }
+
     pub async fn main_playlists(&self) -> Result<Vec<Playlist>, Error> {
         playlist_entity::Entity::find()
             .order_by_asc(playlist_entity::Column::Name)
@@ -118,4 +150,177 @@ impl PlaylistRepository {
             .map(|playlists| playlists.into_iter().map(Into::into).collect())
             .map_err(|e| Error::msg(e.to_string()))
     }
+
+    /// Inserts tracks at `position`, after the last track when `None`, the
+    /// following tracks shifting down. A track may appear several times in a
+    /// playlist. Returns the inserted items, in order.
+    pub async fn insert_tracks(
+        &self,
+        id: &str,
+        track_ids: &[String],
+        position: Option<u32>,
+    ) -> Result<Vec<playlist_tracks_entity::Model>, Error> {
+        let txn = self.db.begin().await?;
+        let len = playlist_tracks_entity::Entity::find()
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .count(&txn)
+            .await? as u32;
+        let position = position.unwrap_or(len).min(len);
+        playlist_tracks_entity::Entity::update_many()
+            .col_expr(
+                playlist_tracks_entity::Column::Position,
+                Expr::col(playlist_tracks_entity::Column::Position).add(track_ids.len() as i32),
+            )
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .filter(playlist_tracks_entity::Column::Position.gte(position))
+            .exec(&txn)
+            .await?;
+        let items = insert_rows(&txn, id, track_ids, position).await?;
+        txn.commit().await?;
+        Ok(items)
+    }
+
+    /// Creates the playlist `id`, or renames it when it exists, with the
+    /// tracks `track_ids` in place of its previous ones. Used when importing a
+    /// playlist file a second time.
+    pub async fn replace(&self, id: &str, name: &str, track_ids: &[String]) -> Result<(), Error> {
+        let txn = self.db.begin().await?;
+        match playlist_entity::Entity::find_by_id(id.to_string())
+            .one(&txn)
+            .await?
+        {
+            Some(playlist) => {
+                let mut playlist: playlist_entity::ActiveModel = playlist.into();
+                playlist.name = ActiveValue::set(name.to_string());
+                playlist.update(&txn).await?;
+            }
+            None => {
+                playlist_entity::ActiveModel {
+                    id: ActiveValue::set(id.to_string()),
+                    name: ActiveValue::set(name.to_string()),
+                    description: ActiveValue::set(None),
+                    folder_id: ActiveValue::set(None),
+                    created_at: ActiveValue::set(chrono::Utc::now()),
+                    file_hash: ActiveValue::set(None),
+                }
+                .insert(&txn)
+                .await?;
+            }
+        }
+        playlist_tracks_entity::Entity::delete_many()
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .exec(&txn)
+            .await?;
+        insert_rows(&txn, id, track_ids, 0).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Records the hash of the library file the playlist was imported from.
+    pub async fn set_file_hash(&self, id: &str, file_hash: &str) -> Result<(), Error> {
+        playlist_entity::Entity::update_many()
+            .col_expr(playlist_entity::Column::FileHash, Expr::value(file_hash))
+            .filter(playlist_entity::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Removes the track at `position`, the following tracks shifting up.
+    /// Returns the id of the removed track.
+    pub async fn remove_track(&self, id: &str, position: u32) -> Result<String, Error> {
+        let txn = self.db.begin().await?;
+        let item = playlist_tracks_entity::Entity::find()
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .filter(playlist_tracks_entity::Column::Position.eq(position))
+            .one(&txn)
+            .await?
+            .ok_or_else(|| Error::msg("Track not found"))?;
+        let track_id = item.track_id.clone();
+        item.delete(&txn).await?;
+        playlist_tracks_entity::Entity::update_many()
+            .col_expr(
+                playlist_tracks_entity::Column::Position,
+                Expr::col(playlist_tracks_entity::Column::Position).sub(1),
+            )
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .filter(playlist_tracks_entity::Column::Position.gt(position))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+        Ok(track_id)
+    }
+
+    /// Moves the track at `from` to `to`, the tracks in between shifting by
+    /// one. A `to` past the end moves the track last. Returns the position the
+    /// track ends up at.
+    pub async fn move_track(&self, id: &str, from: u32, to: u32) -> Result<u32, Error> {
+        let txn = self.db.begin().await?;
+        let len = playlist_tracks_entity::Entity::find()
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .count(&txn)
+            .await? as u32;
+        let item = playlist_tracks_entity::Entity::find()
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .filter(playlist_tracks_entity::Column::Position.eq(from))
+            .one(&txn)
+            .await?
+            .ok_or_else(|| Error::msg("Track not found"))?;
+        let to = to.min(len - 1);
+        let (shift, lower, upper) = match from < to {
+            true => (-1, from + 1, to),
+            false => (1, to, from.saturating_sub(1)),
+        };
+        if from != to {
+            playlist_tracks_entity::Entity::update_many()
+                .col_expr(
+                    playlist_tracks_entity::Column::Position,
+                    Expr::col(playlist_tracks_entity::Column::Position).add(shift),
+                )
+                .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+                .filter(playlist_tracks_entity::Column::Position.between(lower, upper))
+                .exec(&txn)
+                .await?;
+        }
+        let mut item: playlist_tracks_entity::ActiveModel = item.into();
+        item.position = ActiveValue::set(to as i32);
+        item.update(&txn).await?;
+        txn.commit().await?;
+        Ok(to)
+    }
+}
+
+/// Inserts the rows of `track_ids` from `position` on, the positions being
+/// free.
+async fn insert_rows<C: ConnectionTrait>(
+    db: &C,
+    id: &str,
+    track_ids: &[String],
+    position: u32,
+) -> Result<Vec<playlist_tracks_entity::Model>, Error> {
+    let now = chrono::Utc::now();
+    let mut items = vec![];
+    for (index, track_id) in track_ids.iter().enumerate() {
+        let position = position + index as u32;
+        let item = playlist_tracks_entity::ActiveModel {
+            id: ActiveValue::set(format!(
+                "{:x}",
+                md5::compute(format!(
+                    "{}{}{}{}",
+                    id,
+                    track_id,
+                    now.to_rfc3339(),
+                    position
+                ))
+            )),
+            playlist_id: ActiveValue::set(id.to_string()),
+            track_id: ActiveValue::set(track_id.clone()),
+            position: ActiveValue::set(position as i32),
+            created_at: ActiveValue::set(now),
+        }
+        .insert(db)
+        .await?;
+        items.push(item);
+    }
+    Ok(items)
 }
@@ -0,0 +1,261 @@
+use std::collections::{HashMap, HashSet};
+
+use anyhow::Error;
+use music_player_entity::{
+    album as album_entity, artist as artist_entity,
+    favourite::{self as favourite_entity, favourite_id},
+    track as track_entity, track_rating as track_rating_entity,
+};
+use music_player_types::rating::{FavouriteKind, MAX_RATING};
+use sea_orm::{
+    sea_query::OnConflict, ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait,
+    QueryFilter, QueryOrder, QuerySelect,
+};
+
+use super::smart_playlist::SmartPlaylistRepository;
+
+/// Star ratings of the tracks and favourite tracks, albums and artists.
+pub struct RatingRepository {
+    db: DatabaseConnection,
+}
+
+impl RatingRepository {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    /// Rates a track from 1 to 5 stars, 0 removes its rating. A removed
+    /// rating is kept as 0 stars, so that a scan doesn't import the one of the
+    /// file again. The smart playlists are refreshed as their rules can test
+    /// the rating.
+    pub async fn set_rating(&self, track_id: &str, rating: u8) -> Result<(), Error> {
+        if rating > MAX_RATING {
+            return Err(Error::msg(format!(
+                "The rating must be between 0 and {}",
+                MAX_RATING
+            )));
+        }
+        track_entity::Entity::find_by_id(track_id.to_string())
+            .one(&self.db)
+            .await?
+            .ok_or_else(|| Error::msg("Track not found"))?;
+
+        track_rating_entity::Entity::insert(track_rating_entity::ActiveModel::new(
+            track_id, rating,
+        ))
+        .on_conflict(
+            OnConflict::column(track_rating_entity::Column::TrackId)
+                .update_columns([
+                    track_rating_entity::Column::Rating,
+                    track_rating_entity::Column::UpdatedAt,
+                ])
+                .to_owned(),
+        )
+        .exec(&self.db)
+        .await?;
+
+        SmartPlaylistRepository::new(&self.db).refresh_all().await?;
+        Ok(())
+    }
+
+    /// Keeps the rating read from the tags of a track when it never had one,
+    /// the ratings set or removed in the player win over the ones of the
+    /// files.
+    pub async fn import_rating(&self, track_id: &str, rating: u8) -> Result<(), Error> {
+        if track_rating_entity::Entity::find_by_id(track_id.to_string())
+            .one(&self.db)
+            .await?
+            .is_some()
+        {
+            return Ok(());
+        }
+        track_rating_entity::ActiveModel::new(track_id, rating.min(MAX_RATING))
+            .insert(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn rating(&self, track_id: &str) -> Result<Option<u8>, Error> {
+        let result = track_rating_entity::Entity::find_by_id(track_id.to_string())
+            .one(&self.db)
+            .await?;
+        Ok(result
+            .map(|rating| rating.rating)
+            .filter(|rating| *rating > 0))
+    }
+
+    /// The ratings of the given tracks, unrated ones are left out.
+    pub async fn ratings(&self, track_ids: &[String]) -> Result<HashMap<String, u8>, Error> {
+        let results = track_rating_entity::Entity::find()
+            .filter(track_rating_entity::Column::TrackId.is_in(track_ids.to_vec()))
+            .filter(track_rating_entity::Column::Rating.gt(0))
+            .all(&self.db)
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(|rating| (rating.track_id, rating.rating))
+            .collect())
+    }
+
+    pub async fn set_favourite(
+        &self,
+        kind: FavouriteKind,
+        item_id: &str,
+        favourite: bool,
+    ) -> Result<(), Error> {
+        if !favourite {
+            favourite_entity::Entity::delete_by_id(favourite_id(kind, item_id))
+                .exec(&self.db)
+                .await?;
+            return Ok(());
+        }
+
+        let exists = match kind {
+            FavouriteKind::Track => track_entity::Entity::find_by_id(item_id.to_string())
+                .one(&self.db)
+                .await?
+                .is_some(),
+            FavouriteKind::Album => album_entity::Entity::find_by_id(item_id.to_string())
+                .one(&self.db)
+                .await?
+                .is_some(),
+            FavouriteKind::Artist => artist_entity::Entity::find_by_id(item_id.to_string())
+                .one(&self.db)
+                .await?
+                .is_some(),
+        };
+        if !exists {
+            return Err(Error::msg(format!("{} not found", kind.as_str())));
+        }
+
+        // Marking a favourite twice keeps the date it was first marked
+        if self.is_favourite(kind, item_id).await? {
+            return Ok(());
+        }
+        favourite_entity::ActiveModel::new(kind, item_id)
+            .insert(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn is_favourite(&self, kind: FavouriteKind, item_id: &str) -> Result<bool, Error> {
+        let result = favourite_entity::Entity::find_by_id(favourite_id(kind, item_id))
+            .one(&self.db)
+            .await?;
+        Ok(result.is_some())
+    }
+
+    /// The favourites among the given items.
+    pub async fn favourites(
+        &self,
+        kind: FavouriteKind,
+        item_ids: &[String],
+    ) -> Result<HashSet<String>, Error> {
+        let ids: Vec<String> = item_ids
+            .iter()
+            .map(|item_id| favourite_id(kind, item_id))
+            .collect();
+        let results = favourite_entity::Entity::find()
+            .filter(favourite_entity::Column::Id.is_in(ids))
+            .all(&self.db)
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(|favourite| favourite.item_id)
+            .collect())
+    }
+
+    /// The favourite tracks, from the most recently liked one.
+    pub async fn liked_tracks(
+        &self,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<track_entity::Model>, Error> {
+        let ids = self
+            .favourite_ids(FavouriteKind::Track, offset, limit)
+            .await?;
+        let query =
+            || track_entity::Entity::find().filter(track_entity::Column::Id.is_in(ids.clone()));
+
+        let mut artists: HashMap<String, Vec<artist_entity::Model>> = query()
+            .find_with_related(artist_entity::Entity)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|(track, artists)| (track.id, artists))
+            .collect();
+        let mut tracks: HashMap<String, track_entity::Model> = query()
+            .find_also_related(album_entity::Entity)
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|(track, album)| {
+                let track = track_entity::Model {
+                    artists: artists.remove(&track.id).unwrap_or_default(),
+                    album: album.unwrap_or_default(),
+                    ..track
+                };
+                (track.id.clone(), track)
+            })
+            .collect();
+
+        Ok(ids.iter().filter_map(|id| tracks.remove(id)).collect())
+    }
+
+    /// The favourite albums, from the most recently marked one.
+    pub async fn favourite_albums(
+        &self,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<album_entity::Model>, Error> {
+        let ids = self
+            .favourite_ids(FavouriteKind::Album, offset, limit)
+            .await?;
+        let mut albums: HashMap<String, album_entity::Model> = album_entity::Entity::find()
+            .filter(album_entity::Column::Id.is_in(ids.clone()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|album| (album.id.clone(), album))
+            .collect();
+        Ok(ids.iter().filter_map(|id| albums.remove(id)).collect())
+    }
+
+    /// The favourite artists, from the most recently marked one.
+    pub async fn favourite_artists(
+        &self,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<artist_entity::Model>, Error> {
+        let ids = self
+            .favourite_ids(FavouriteKind::Artist, offset, limit)
+            .await?;
+        let mut artists: HashMap<String, artist_entity::Model> = artist_entity::Entity::find()
+            .filter(artist_entity::Column::Id.is_in(ids.clone()))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|artist| (artist.id.clone(), artist))
+            .collect();
+        Ok(ids.iter().filter_map(|id| artists.remove(id)).collect())
+    }
+
+    async fn favourite_ids(
+        &self,
+        kind: FavouriteKind,
+        offset: u64,
+        limit: u64,
+    ) -> Result<Vec<String>, Error> {
+        let results = favourite_entity::Entity::find()
+            .filter(favourite_entity::Column::Kind.eq(kind.as_str()))
+            .order_by_desc(favourite_entity::Column::CreatedAt)
+            .offset(offset)
+            .limit(limit)
+            .all(&self.db)
+            .await?;
+        Ok(results
+            .into_iter()
+            .map(|favourite| favourite.item_id)
+            .collect())
+    }
+}
@@ -0,0 +1,28 @@
+use anyhow::Error;
+use music_player_entity::scan_error as scan_error_entity;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryOrder};
+
+pub struct ScanErrorRepository {
+    db: DatabaseConnection,
+}
+
+impl ScanErrorRepository {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    pub async fn find_all(&self) -> Result<Vec<scan_error_entity::Model>, Error> {
+        let results = scan_error_entity::Entity::find()
+            .order_by_asc(scan_error_entity::Column::Path)
+            .all(&self.db)
+            .await?;
+        Ok(results)
+    }
+
+    pub async fn clear(&self) -> Result<(), Error> {
+        scan_error_entity::Entity::delete_many()
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,427 @@
+use anyhow::Error;
+use music_player_entity::{
+    album as album_entity, artist as artist_entity, artist_tracks as artist_tracks_entity,
+    genre as genre_entity, genre_tracks as genre_tracks_entity, playlist as playlist_entity,
+    playlist_tracks as playlist_tracks_entity, smart_playlist as smart_playlist_entity,
+    smart_playlist_rule as smart_playlist_rule_entity, track as track_entity,
+    track_rating as track_rating_entity,
+};
+use music_player_types::{
+    genre::genre_id,
+    smart_playlist::{SmartCondition, SmartField, SmartOperator, SmartQuery, SmartRule, SmartSort},
+};
+use sea_orm::{
+    sea_query::{Expr, Func, LikeExpr, Query, SimpleExpr},
+    ActiveModelTrait, ActiveValue, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection,
+    DbErr, EntityTrait, FromQueryResult, JoinType, Order, QueryFilter, QueryOrder, QuerySelect,
+    RelationTrait, TransactionTrait,
+};
+
+/// Rows inserted per statement, SQLite limits the number of bound values.
+const INSERT_BATCH_SIZE: usize = 500;
+
+#[derive(FromQueryResult)]
+struct TrackId {
+    id: String,
+}
+
+/// Smart playlists are regular playlists whose tracks are computed from
+/// their rules. The results are written to `playlist_track`, so they are
+/// read, played and listed like the tracks of any other playlist.
+pub struct SmartPlaylistRepository {
+    db: DatabaseConnection,
+}
+
+impl SmartPlaylistRepository {
+    pub fn new(db: &DatabaseConnection) -> Self {
+        Self { db: db.clone() }
+    }
+
+    /// Creates a playlist along with its rules and fills it with the
+    /// matching tracks.
+    pub async fn create(
+        &self,
+        playlist: playlist_entity::ActiveModel,
+        query: &SmartQuery,
+    ) -> Result<playlist_entity::Model, Error> {
+        let txn = self.db.begin().await?;
+        let playlist = playlist.insert(&txn).await?;
+        write_rules(&txn, &playlist.id, query).await?;
+        txn.commit().await?;
+
+        self.refresh(&playlist.id).await?;
+        Ok(playlist)
+    }
+
+    /// Stores the rules of a playlist, replacing the previous ones, and
+    /// fills it with the matching tracks.
+    pub async fn save(&self, id: &str, query: &SmartQuery) -> Result<usize, Error> {
+        let txn = self.db.begin().await?;
+        smart_playlist_rule_entity::Entity::delete_many()
+            .filter(smart_playlist_rule_entity::Column::PlaylistId.eq(id))
+            .exec(&txn)
+            .await?;
+        smart_playlist_entity::Entity::delete_by_id(id.to_string())
+            .exec(&txn)
+            .await?;
+        write_rules(&txn, id, query).await?;
+        txn.commit().await?;
+
+        self.refresh(id).await
+    }
+
+    pub async fn find(&self, id: &str) -> Result<Option<SmartQuery>, Error> {
+        let playlist = match smart_playlist_entity::Entity::find_by_id(id.to_string())
+            .one(&self.db)
+            .await?
+        {
+            Some(playlist) => playlist,
+            None => return Ok(None),
+        };
+        let rows = smart_playlist_rule_entity::Entity::find()
+            .filter(smart_playlist_rule_entity::Column::PlaylistId.eq(id))
+            .order_by_asc(smart_playlist_rule_entity::Column::Position)
+            .all(&self.db)
+            .await?;
+        let root = rows
+            .iter()
+            .find(|row| row.parent_id.is_none())
+            .ok_or_else(|| Error::msg("Smart playlist without rules"))?;
+
+        Ok(Some(SmartQuery {
+            rule: rule_tree(root, &rows)?,
+            sort: playlist.sort.as_deref().and_then(|sort| match sort {
+                "random" => Some(SmartSort::Random),
+                field => SmartField::parse(field).map(SmartSort::Field),
+            }),
+            descending: playlist.descending,
+            limit: playlist.track_limit,
+        }))
+    }
+
+    pub async fn is_smart(&self, id: &str) -> Result<bool, Error> {
+        Ok(smart_playlist_entity::Entity::find_by_id(id.to_string())
+            .one(&self.db)
+            .await?
+            .is_some())
+    }
+
+    /// Removes the rules of a playlist, its tracks are kept.
+    pub async fn delete(&self, id: &str) -> Result<(), Error> {
+        smart_playlist_rule_entity::Entity::delete_many()
+            .filter(smart_playlist_rule_entity::Column::PlaylistId.eq(id))
+            .exec(&self.db)
+            .await?;
+        smart_playlist_entity::Entity::delete_by_id(id.to_string())
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// The ids of the tracks matching a query, in its order.
+    pub async fn evaluate(&self, query: &SmartQuery) -> Result<Vec<String>, Error> {
+        let select = track_entity::Entity::find()
+            .select_only()
+            .column(track_entity::Column::Id)
+            .join(JoinType::LeftJoin, track_entity::Relation::Album.def())
+            .join_rev(
+                JoinType::LeftJoin,
+                track_rating_entity::Relation::Track.def(),
+            )
+            .filter(rule_condition(&query.rule));
+        let select = match query.sort {
+            Some(SmartSort::Random) => select.order_by(Expr::cust("RANDOM()"), Order::Asc),
+            Some(SmartSort::Field(field)) => select.order_by(
+                SimpleExpr::from(field_expr(field)),
+                match query.descending {
+                    true => Order::Desc,
+                    false => Order::Asc,
+                },
+            ),
+            None => select.order_by_asc(track_entity::Column::Title),
+        };
+        let select = match query.limit {
+            Some(limit) => select.limit(limit as u64),
+            None => select,
+        };
+        Ok(select
+            .into_model::<TrackId>()
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .map(|track| track.id)
+            .collect())
+    }
+
+    /// Evaluates the rules of a playlist again and replaces its tracks with
+    /// the results.
+    pub async fn refresh(&self, id: &str) -> Result<usize, Error> {
+        let query = self
+            .find(id)
+            .await?
+            .ok_or_else(|| Error::msg("Smart playlist not found"))?;
+        let track_ids = self.evaluate(&query).await?;
+
+        let txn = self.db.begin().await?;
+        playlist_tracks_entity::Entity::delete_many()
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq(id))
+            .exec(&txn)
+            .await?;
+        let now = chrono::Utc::now();
+        let rows: Vec<playlist_tracks_entity::ActiveModel> = track_ids
+            .iter()
+            .enumerate()
+            .map(|(index, track_id)| playlist_tracks_entity::ActiveModel {
+                id: ActiveValue::set(format!("{}-{}", id, index)),
+                playlist_id: ActiveValue::set(id.to_string()),
+                track_id: ActiveValue::set(track_id.clone()),
+                position: ActiveValue::set(index as i32),
+                created_at: ActiveValue::set(now),
+            })
+            .collect();
+        for batch in rows.chunks(INSERT_BATCH_SIZE) {
+            playlist_tracks_entity::Entity::insert_many(batch.to_vec())
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+        Ok(track_ids.len())
+    }
+
+    /// Refreshes every smart playlist, after the library changed.
+    pub async fn refresh_all(&self) -> Result<usize, Error> {
+        let playlists = smart_playlist_entity::Entity::find().all(&self.db).await?;
+        for playlist in &playlists {
+            self.refresh(&playlist.id).await?;
+        }
+        Ok(playlists.len())
+    }
+}
+
+async fn write_rules<C: ConnectionTrait>(
+    db: &C,
+    id: &str,
+    query: &SmartQuery,
+) -> Result<(), DbErr> {
+    smart_playlist_entity::Entity::insert(smart_playlist_entity::ActiveModel {
+        id: ActiveValue::set(id.to_string()),
+        sort: ActiveValue::set(query.sort.map(|sort| match sort {
+            SmartSort::Random => "random".to_string(),
+            SmartSort::Field(field) => field.as_str().to_string(),
+        })),
+        descending: ActiveValue::set(query.descending),
+        track_limit: ActiveValue::set(query.limit),
+    })
+    .exec(db)
+    .await?;
+
+    let mut rows = vec![];
+    rule_rows(id, &query.rule, None, 0, &mut rows);
+    smart_playlist_rule_entity::Entity::insert_many(rows)
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+fn rule_rows(
+    playlist_id: &str,
+    rule: &SmartRule,
+    parent_id: Option<String>,
+    position: u32,
+    rows: &mut Vec<smart_playlist_rule_entity::ActiveModel>,
+) {
+    let id = format!("{}-{}", playlist_id, rows.len());
+    let (kind, children, condition) = match rule {
+        SmartRule::All(rules) => ("all", rules.iter().collect(), None),
+        SmartRule::Any(rules) => ("any", rules.iter().collect(), None),
+        SmartRule::Not(rule) => ("not", vec![rule.as_ref()], None),
+        SmartRule::Condition(condition) => ("condition", vec![], Some(condition)),
+    };
+    rows.push(smart_playlist_rule_entity::ActiveModel {
+        id: ActiveValue::set(id.clone()),
+        playlist_id: ActiveValue::set(playlist_id.to_string()),
+        parent_id: ActiveValue::set(parent_id),
+        position: ActiveValue::set(position),
+        kind: ActiveValue::set(kind.to_string()),
+        field: ActiveValue::set(condition.map(|condition| condition.field.as_str().to_string())),
+        operator: ActiveValue::set(
+            condition.map(|condition| condition.operator.as_str().to_string()),
+        ),
+        value: ActiveValue::set(condition.map(|condition| condition.value.clone())),
+    });
+    for (position, child) in children.into_iter().enumerate() {
+        rule_rows(playlist_id, child, Some(id.clone()), position as u32, rows);
+    }
+}
+
+fn rule_tree(
+    row: &smart_playlist_rule_entity::Model,
+    rows: &[smart_playlist_rule_entity::Model],
+) -> Result<SmartRule, Error> {
+    let children = rows
+        .iter()
+        .filter(|child| child.parent_id.as_deref() == Some(row.id.as_str()))
+        .map(|child| rule_tree(child, rows))
+        .collect::<Result<Vec<_>, _>>()?;
+    match row.kind.as_str() {
+        "all" => Ok(SmartRule::All(children)),
+        "any" => Ok(SmartRule::Any(children)),
+        "not" => children
+            .into_iter()
+            .next()
+            .map(|child| SmartRule::Not(Box::new(child)))
+            .ok_or_else(|| Error::msg("Empty `not` rule")),
+        _ => {
+            let invalid = || Error::msg(format!("Invalid rule {}", row.id));
+            Ok(SmartRule::Condition(SmartCondition {
+                field: row
+                    .field
+                    .as_deref()
+                    .and_then(SmartField::parse)
+                    .ok_or_else(invalid)?,
+                operator: row
+                    .operator
+                    .as_deref()
+                    .and_then(SmartOperator::parse)
+                    .ok_or_else(invalid)?,
+                value: row.value.clone().unwrap_or_default(),
+            }))
+        }
+    }
+}
+
+fn field_expr(field: SmartField) -> Expr {
+    match field {
+        SmartField::Title => Expr::tbl(track_entity::Entity, track_entity::Column::Title),
+        SmartField::Artist => Expr::tbl(track_entity::Entity, track_entity::Column::Artist),
+        SmartField::Album => Expr::tbl(album_entity::Entity, album_entity::Column::Title),
+        SmartField::AlbumArtist => Expr::tbl(album_entity::Entity, album_entity::Column::Artist),
+        SmartField::Genre => Expr::tbl(track_entity::Entity, track_entity::Column::Genre),
+        SmartField::Year => Expr::tbl(track_entity::Entity, track_entity::Column::Year),
+        SmartField::Duration => Expr::tbl(track_entity::Entity, track_entity::Column::Duration),
+        SmartField::Bitrate => Expr::tbl(track_entity::Entity, track_entity::Column::Bitrate),
+        SmartField::Composer => Expr::tbl(track_entity::Entity, track_entity::Column::Composer),
+        SmartField::Label => Expr::tbl(album_entity::Entity, album_entity::Column::Label),
+        SmartField::Bpm => Expr::tbl(track_entity::Entity, track_entity::Column::Bpm),
+        SmartField::Tempo => Expr::tbl(track_entity::Entity, track_entity::Column::Tempo),
+        SmartField::Loudness => Expr::tbl(track_entity::Entity, track_entity::Column::Loudness),
+        SmartField::Key => Expr::tbl(track_entity::Entity, track_entity::Column::MusicalKey),
+        SmartField::Format => Expr::tbl(track_entity::Entity, track_entity::Column::Uri),
+        // A removed rating is kept as 0 stars, the track is unrated
+        SmartField::Rating => Expr::expr(Expr::cust("NULLIF(track_rating.rating, 0)")),
+    }
+}
+
+fn rule_condition(rule: &SmartRule) -> Condition {
+    match rule {
+        SmartRule::All(rules) => rules
+            .iter()
+            .fold(Condition::all(), |all, rule| all.add(rule_condition(rule))),
+        SmartRule::Any(rules) => rules
+            .iter()
+            .fold(Condition::any(), |any, rule| any.add(rule_condition(rule))),
+        SmartRule::Not(rule) => rule_condition(rule).not(),
+        SmartRule::Condition(condition) => condition_expr(condition),
+    }
+}
+
+/// Text comparisons ignore case, and the negated ones match the tracks
+/// without a value.
+fn condition_expr(condition: &SmartCondition) -> Condition {
+    let (operator, negated) = match condition.operator {
+        SmartOperator::IsNot => (SmartOperator::Is, true),
+        SmartOperator::NotContains => (SmartOperator::Contains, true),
+        operator => (operator, false),
+    };
+    let value = condition.value.as_str();
+    let positive = match condition.field {
+        // Every artist of the track is matched, not only the main one
+        SmartField::Artist => Expr::tbl(track_entity::Entity, track_entity::Column::Id)
+            .in_subquery(
+                Query::select()
+                    .column(artist_tracks_entity::Column::TrackId)
+                    .from(artist_tracks_entity::Entity)
+                    .inner_join(
+                        artist_entity::Entity,
+                        Expr::tbl(artist_entity::Entity, artist_entity::Column::Id).equals(
+                            artist_tracks_entity::Entity,
+                            artist_tracks_entity::Column::ArtistId,
+                        ),
+                    )
+                    .and_where(compare(
+                        Expr::tbl(artist_entity::Entity, artist_entity::Column::Name),
+                        operator,
+                        value,
+                        false,
+                    ))
+                    .to_owned(),
+            ),
+        // Every genre of the track is matched, under any spelling of its name
+        SmartField::Genre => Expr::tbl(track_entity::Entity, track_entity::Column::Id).in_subquery(
+            Query::select()
+                .column(genre_tracks_entity::Column::TrackId)
+                .from(genre_tracks_entity::Entity)
+                .inner_join(
+                    genre_entity::Entity,
+                    Expr::tbl(genre_entity::Entity, genre_entity::Column::Id).equals(
+                        genre_tracks_entity::Entity,
+                        genre_tracks_entity::Column::GenreId,
+                    ),
+                )
+                .and_where(match operator {
+                    SmartOperator::Is => Expr::tbl(
+                        genre_tracks_entity::Entity,
+                        genre_tracks_entity::Column::GenreId,
+                    )
+                    .eq(genre_id(value)),
+                    operator => compare(
+                        Expr::tbl(genre_entity::Entity, genre_entity::Column::Name),
+                        operator,
+                        value,
+                        false,
+                    ),
+                })
+                .to_owned(),
+        ),
+        SmartField::Format => Expr::tbl(track_entity::Entity, track_entity::Column::Uri)
+            .like(like_pattern("%.", value.trim_start_matches('.'), "")),
+        field => compare(field_expr(field), operator, value, field.is_numeric()),
+    };
+    match negated {
+        true => Condition::any()
+            .add(field_expr(condition.field).is_null())
+            .add(Condition::all().add(positive).not()),
+        false => Condition::all().add(positive),
+    }
+}
+
+fn compare(expr: Expr, operator: SmartOperator, value: &str, numeric: bool) -> SimpleExpr {
+    if numeric {
+        let number = value.parse::<f64>().unwrap_or_default();
+        return match operator {
+            SmartOperator::Greater => expr.gt(number),
+            SmartOperator::GreaterOrEqual => expr.gte(number),
+            SmartOperator::Less => expr.lt(number),
+            SmartOperator::LessOrEqual => expr.lte(number),
+            _ => expr.eq(number),
+        };
+    }
+    match operator {
+        SmartOperator::Contains => expr.like(like_pattern("%", value, "%")),
+        SmartOperator::StartsWith => expr.like(like_pattern("", value, "%")),
+        SmartOperator::EndsWith => expr.like(like_pattern("%", value, "")),
+        SmartOperator::Greater => expr.gt(value),
+        SmartOperator::GreaterOrEqual => expr.gte(value),
+        SmartOperator::Less => expr.lt(value),
+        SmartOperator::LessOrEqual => expr.lte(value),
+        _ => Expr::expr(Func::lower(expr)).eq(value.to_lowercase()),
+    }
+}
+
+fn like_pattern(prefix: &str, value: &str, suffix: &str) -> LikeExpr {
+    let value = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    LikeExpr::new(format!("{}{}{}", prefix, value, suffix)).escape('\\')
+}
@@ -1,6 +1,258 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
-use music_player_entity::{album as album_entity, artist as artist_entity, track as track_entity};
-use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect};
+use chrono::{DateTime, Utc};
+use music_player_entity::{
+    album as album_entity, artist as artist_entity, artist_tracks as artist_tracks_entity,
+    genre_tracks as genre_tracks_entity, track as track_entity,
+};
+use music_player_types::genre::genre_id;
+use sea_orm::{
+    sea_query::{Expr, Query, SimpleExpr},
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, IntoSimpleExpr, Order, PaginatorTrait,
+    QueryFilter, QueryOrder, QuerySelect, Select, Value,
+};
+
+use super::{
+    album::AlbumRepository,
+    history::HistoryRepository,
+    page::{Cursor, Nulls, Page, PageRequest},
+    rating::RatingRepository,
+};
+
+/// Number of times a track was played, from the listening history, the
+/// skipped plays being left out.
+const PLAY_COUNT: &str =
+    "(SELECT COUNT(*) FROM history WHERE history.track_id = track.id AND history.completed)";
+
+/// Rating of a track, unrated tracks come first in ascending order.
+const RATING: &str = "(SELECT rating FROM track_rating \
+    WHERE track_rating.track_id = track.id AND track_rating.rating > 0)";
+
+/// Ranges of the tags and properties of the tracks, the tracks missing a
+/// value never match a set bound.
+#[derive(Default, Clone)]
+pub struct TrackFilter {
+    pub min_year: Option<u32>,
+    pub max_year: Option<u32>,
+    /// Any spelling of a genre, see `genre_key`
+    pub genre: Option<String>,
+    /// The extension of the files, e.g. "flac"
+    pub format: Option<String>,
+    /// In kbps
+    pub min_bitrate: Option<u32>,
+    pub max_bitrate: Option<u32>,
+}
+
+impl TrackFilter {
+    pub(crate) fn condition(&self) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(min) = self.min_year {
+            condition = condition.add(track_entity::Column::Year.gte(min));
+        }
+        if let Some(max) = self.max_year {
+            condition = condition.add(track_entity::Column::Year.lte(max));
+        }
+        if let Some(genre) = &self.genre {
+            condition = condition.add(
+                track_entity::Column::Id.in_subquery(
+                    Query::select()
+                        .column(genre_tracks_entity::Column::TrackId)
+                        .from(genre_tracks_entity::Entity)
+                        .and_where(genre_tracks_entity::Column::GenreId.eq(genre_id(genre)))
+                        .to_owned(),
+                ),
+            );
+        }
+        if let Some(format) = &self.format {
+            let extension = format.trim_start_matches('.');
+            condition = condition.add(track_entity::Column::Uri.like(&format!("%.{}", extension)));
+        }
+        if let Some(min) = self.min_bitrate {
+            condition = condition.add(track_entity::Column::Bitrate.gte(min));
+        }
+        if let Some(max) = self.max_bitrate {
+            condition = condition.add(track_entity::Column::Bitrate.lte(max));
+        }
+        condition
+    }
+}
+
+/// Ranges of the measures computed by the analysis pass, the tracks which
+/// weren't analyzed never match a set bound.
+#[derive(Default, Clone)]
+pub struct AudioFilter {
+    pub min_loudness: Option<f32>,
+    pub max_loudness: Option<f32>,
+    pub min_tempo: Option<f32>,
+    pub max_tempo: Option<f32>,
+    pub musical_key: Option<String>,
+}
+
+impl AudioFilter {
+    fn condition(&self) -> Condition {
+        let mut condition = Condition::all();
+        if let Some(min) = self.min_loudness {
+            condition = condition.add(track_entity::Column::Loudness.gte(min));
+        }
+        if let Some(max) = self.max_loudness {
+            condition = condition.add(track_entity::Column::Loudness.lte(max));
+        }
+        if let Some(min) = self.min_tempo {
+            condition = condition.add(track_entity::Column::Tempo.gte(min));
+        }
+        if let Some(max) = self.max_tempo {
+            condition = condition.add(track_entity::Column::Tempo.lte(max));
+        }
+        if let Some(key) = &self.musical_key {
+            condition = condition.add(track_entity::Column::MusicalKey.eq(key.as_str()));
+        }
+        condition
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub enum TrackField {
+    #[default]
+    Title,
+    Loudness,
+    Tempo,
+    MusicalKey,
+    Year,
+    /// When the track was first scanned, descending for the recently added
+    AddedAt,
+    Duration,
+    Artist,
+    PlayCount,
+    Rating,
+}
+
+impl TrackField {
+    pub fn parse(field: &str) -> Option<Self> {
+        match field {
+            "title" => Some(TrackField::Title),
+            "loudness" => Some(TrackField::Loudness),
+            "tempo" => Some(TrackField::Tempo),
+            "musical_key" => Some(TrackField::MusicalKey),
+            "year" => Some(TrackField::Year),
+            "added_at" => Some(TrackField::AddedAt),
+            "duration" => Some(TrackField::Duration),
+            "artist" => Some(TrackField::Artist),
+            "play_count" => Some(TrackField::PlayCount),
+            "rating" => Some(TrackField::Rating),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct TrackOrder {
+    pub field: TrackField,
+    pub descending: bool,
+}
+
+impl TrackOrder {
+    fn expr(&self) -> SimpleExpr {
+        match self.field {
+            TrackField::Title => track_entity::Column::Title.into_simple_expr(),
+            TrackField::Loudness => track_entity::Column::Loudness.into_simple_expr(),
+            TrackField::Tempo => track_entity::Column::Tempo.into_simple_expr(),
+            TrackField::MusicalKey => track_entity::Column::MusicalKey.into_simple_expr(),
+            TrackField::Year => track_entity::Column::Year.into_simple_expr(),
+            TrackField::AddedAt => track_entity::Column::CreatedAt.into_simple_expr(),
+            TrackField::Duration => track_entity::Column::Duration.into_simple_expr(),
+            TrackField::Artist => track_entity::Column::Artist.into_simple_expr(),
+            TrackField::PlayCount => Expr::cust(PLAY_COUNT),
+            TrackField::Rating => Expr::cust(RATING),
+        }
+    }
+
+    fn nulls(&self) -> Nulls {
+        match self.field {
+            TrackField::Title | TrackField::Artist | TrackField::PlayCount => Nulls::Never,
+            // The tracks which weren't analyzed come last, whatever the direction
+            TrackField::Loudness | TrackField::Tempo | TrackField::MusicalKey => Nulls::Last,
+            TrackField::Year | TrackField::AddedAt | TrackField::Duration | TrackField::Rating => {
+                Nulls::Smallest
+            }
+        }
+    }
+
+    fn apply(&self, query: Select<track_entity::Entity>) -> Select<track_entity::Entity> {
+        let expr = self.expr();
+        let query = match self.nulls() {
+            Nulls::Last => query.order_by(Expr::expr(expr.clone()).is_null(), Order::Asc),
+            _ => query,
+        };
+        let query = match self.descending {
+            true => query.order_by(expr, Order::Desc),
+            false => query.order_by(expr, Order::Asc),
+        };
+        // Keeps a stable order between the tracks sharing the same value
+        query.order_by_asc(track_entity::Column::Id)
+    }
+
+    /// The key of a cursor back into a value of the sorted field.
+    fn parse_key(&self, key: &str) -> Option<Value> {
+        match self.field {
+            TrackField::Title | TrackField::Artist | TrackField::MusicalKey => Some(key.into()),
+            TrackField::Loudness | TrackField::Tempo | TrackField::Duration => {
+                key.parse::<f32>().ok().map(Into::into)
+            }
+            TrackField::Year => key.parse::<u32>().ok().map(Into::into),
+            TrackField::AddedAt => DateTime::parse_from_rfc3339(key)
+                .ok()
+                .map(|created_at| created_at.with_timezone(&Utc).into()),
+            TrackField::PlayCount => key.parse::<u32>().ok().map(Into::into),
+            TrackField::Rating => key.parse::<u8>().ok().map(Into::into),
+        }
+    }
+
+    /// The keys of the cursors of the tracks, by track id. The play counts
+    /// and the ratings are fetched as they aren't columns of the tracks.
+    async fn cursor_keys(
+        &self,
+        db: &DatabaseConnection,
+        tracks: &[track_entity::Model],
+    ) -> Result<HashMap<String, String>, Error> {
+        let ids: Vec<String> = tracks.iter().map(|track| track.id.clone()).collect();
+        let keys = match self.field {
+            TrackField::PlayCount => {
+                let play_counts = HistoryRepository::new(db).play_counts(&ids).await?;
+                ids.into_iter()
+                    .map(|id| {
+                        let play_count = play_counts.get(&id).copied().unwrap_or_default();
+                        (id, play_count.to_string())
+                    })
+                    .collect()
+            }
+            TrackField::Rating => RatingRepository::new(db)
+                .ratings(&ids)
+                .await?
+                .into_iter()
+                .map(|(id, rating)| (id, rating.to_string()))
+                .collect(),
+            field => tracks
+                .iter()
+                .filter_map(|track| {
+                    let key = match field {
+                        TrackField::Title => Some(track.title.clone()),
+                        TrackField::Artist => Some(track.artist.clone()),
+                        TrackField::MusicalKey => track.musical_key.clone(),
+                        TrackField::Loudness => track.loudness.map(|value| value.to_string()),
+                        TrackField::Tempo => track.tempo.map(|value| value.to_string()),
+                        TrackField::Duration => track.duration.map(|value| value.to_string()),
+                        TrackField::Year => track.year.map(|value| value.to_string()),
+                        TrackField::AddedAt => track.created_at.map(|value| value.to_rfc3339()),
+                        TrackField::PlayCount | TrackField::Rating => None,
+                    };
+                    key.map(|key| (track.id.clone(), key))
+                })
+                .collect(),
+        };
+        Ok(keys)
+    }
+}
 
 pub struct TrackRepository {
     db: DatabaseConnection,
@@ -22,20 +274,13 @@ impl TrackRepository {
             return Err(Error::msg("Track not found"));
         }
         let track = results[0].0.clone();
-        let album =
-            album_entity::Entity::find_by_id(track.album_id.unwrap_or_default().to_string())
-                .one(&self.db)
-                .await?;
+        let album = album_entity::Entity::find_by_id(track.album_id.clone().unwrap_or_default())
+            .one(&self.db)
+            .await?;
         Ok(track_entity::Model {
             artists: results[0].1.clone(),
             album: album.unwrap(),
-            id: track.id,
-            title: track.title,
-            duration: track.duration,
-            uri: track.uri,
-            artist: track.artist,
-            track: track.track,
-            ..Default::default()
+            ..track
         })
     }
 
@@ -45,63 +290,196 @@ impl TrackRepository {
         offset: Option<u64>,
         limit: u64,
     ) -> Result<Vec<track_entity::Model>, Error> {
-        let query = match offset {
-            Some(offset) => track_entity::Entity::find().offset(offset).limit(limit),
-            None => track_entity::Entity::find().limit(limit),
-        };
-        let results = match filter {
-            Some(filter) => {
-                if filter.is_empty() {
-                    query
-                        .order_by_asc(track_entity::Column::Title)
-                        .find_with_related(artist_entity::Entity)
-                        .all(&self.db)
-                        .await?
-                } else {
-                    query
-                        .filter(track_entity::Column::Title.like(format!("%{}%", filter).as_str()))
-                        .order_by_asc(track_entity::Column::Title)
-                        .find_with_related(artist_entity::Entity)
-                        .all(&self.db)
-                        .await?
-                }
-            }
-            None => {
-                query
-                    .order_by_asc(track_entity::Column::Title)
-                    .find_with_related(artist_entity::Entity)
-                    .all(&self.db)
-                    .await?
-            }
-        };
+        self.find_all_matching(
+            filter,
+            &TrackFilter::default(),
+            &AudioFilter::default(),
+            TrackOrder::default(),
+            offset,
+            limit,
+        )
+        .await
+    }
 
-        let query = match offset {
-            Some(offset) => track_entity::Entity::find().offset(offset).limit(limit),
-            None => track_entity::Entity::find().limit(limit),
-        };
+    /// Lists the tracks whose title contains `filter`, filtered by their tags
+    /// and by the measures of the analysis pass.
+    pub async fn find_all_matching(
+        &self,
+        filter: Option<String>,
+        tags: &TrackFilter,
+        audio: &AudioFilter,
+        order: TrackOrder,
+        offset: Option<u64>,
+        limit: u64,
+    ) -> Result<Vec<track_entity::Model>, Error> {
+        let mut condition = tags.condition().add(audio.condition());
+        if let Some(filter) = filter.filter(|filter| !filter.is_empty()) {
+            condition =
+                condition.add(track_entity::Column::Title.like(format!("%{}%", filter).as_str()));
+        }
+        let mut query = order.apply(track_entity::Entity::find().filter(condition).limit(limit));
+        if let Some(offset) = offset {
+            query = query.offset(offset);
+        }
+        let tracks = query.all(&self.db).await?;
+        self.with_relations(tracks).await
+    }
 
-        let albums: Vec<(track_entity::Model, Option<album_entity::Model>)> = query
-            .order_by_asc(track_entity::Column::Title)
-            .find_also_related(album_entity::Entity)
+    /// A page of the tracks whose title contains `filter`, filtered like
+    /// `find_all_matching` and sorted by `order`. The cursors hold the value
+    /// of the sorted field, the tracks missing it share the same place as in
+    /// `find_all_matching`.
+    pub async fn find_page(
+        &self,
+        filter: Option<String>,
+        tags: &TrackFilter,
+        audio: &AudioFilter,
+        order: TrackOrder,
+        request: &PageRequest,
+    ) -> Result<Page<track_entity::Model>, Error> {
+        let mut condition = tags.condition().add(audio.condition());
+        if let Some(filter) = filter.filter(|filter| !filter.is_empty()) {
+            condition =
+                condition.add(track_entity::Column::Title.like(format!("%{}%", filter).as_str()));
+        }
+        let query = track_entity::Entity::find().filter(condition);
+        let total_count = query.clone().count(&self.db).await?;
+        let tracks = request
+            .apply_by(
+                query,
+                order.expr(),
+                order.nulls(),
+                track_entity::Column::Id,
+                order.descending,
+                |key| order.parse_key(key),
+            )?
             .all(&self.db)
             .await?;
+        let keys = order.cursor_keys(&self.db, &tracks).await?;
+        let tracks = self.with_relations(tracks).await?;
+        Ok(request.page(tracks, total_count, |track| Cursor {
+            key: keys.get(&track.id).cloned(),
+            id: track.id.clone(),
+        }))
+    }
 
-        let albums: Vec<Option<album_entity::Model>> = albums
+    /// Fills the artists and the album of the tracks, with one query each.
+    async fn with_relations(
+        &self,
+        tracks: Vec<track_entity::Model>,
+    ) -> Result<Vec<track_entity::Model>, Error> {
+        let ids: Vec<String> = tracks.iter().map(|track| track.id.clone()).collect();
+        let mut artists = self.find_artists(&ids).await?;
+        let album_ids: Vec<String> = tracks
+            .iter()
+            .filter_map(|track| track.album_id.clone())
+            .collect();
+        let albums: HashMap<String, album_entity::Model> = AlbumRepository::new(&self.db)
+            .find_by_ids(&album_ids)
+            .await?
             .into_iter()
-            .map(|(_track, album)| album.clone())
+            .map(|album| (album.id.clone(), album))
             .collect();
-        let mut albums = albums.into_iter();
 
-        Ok(results
+        Ok(tracks
             .into_iter()
-            .map(|(track, artists)| {
-                let album = albums.next().unwrap().unwrap();
-                track_entity::Model {
-                    artists,
-                    album,
-                    ..track
-                }
+            .map(|track| track_entity::Model {
+                artists: artists.remove(&track.id).unwrap_or_default(),
+                album: track
+                    .album_id
+                    .as_ref()
+                    .and_then(|album_id| albums.get(album_id))
+                    .cloned()
+                    .unwrap_or_default(),
+                ..track
             })
             .collect())
     }
+
+    /// The artists of the tracks, by track id, in a single query.
+    pub async fn find_artists(
+        &self,
+        track_ids: &[String],
+    ) -> Result<HashMap<String, Vec<artist_entity::Model>>, Error> {
+        let results = artist_tracks_entity::Entity::find()
+            .filter(artist_tracks_entity::Column::TrackId.is_in(track_ids.to_vec()))
+            .find_also_related(artist_entity::Entity)
+            .all(&self.db)
+            .await?;
+        let mut artists: HashMap<String, Vec<artist_entity::Model>> = HashMap::new();
+        for (artist_track, artist) in results {
+            if let Some(artist) = artist {
+                artists
+                    .entry(artist_track.track_id)
+                    .or_default()
+                    .push(artist);
+            }
+        }
+        Ok(artists)
+    }
+
+    /// The tracks of the albums, by album id, in disc and track order.
+    pub async fn find_by_albums(
+        &self,
+        album_ids: &[String],
+    ) -> Result<HashMap<String, Vec<track_entity::Model>>, Error> {
+        let results = track_entity::Entity::find()
+            .filter(track_entity::Column::AlbumId.is_in(album_ids.to_vec()))
+            .order_by_asc(track_entity::Column::DiscNumber)
+            .order_by_asc(track_entity::Column::Track)
+            .all(&self.db)
+            .await?;
+        let mut tracks: HashMap<String, Vec<track_entity::Model>> = HashMap::new();
+        for track in results {
+            if let Some(album_id) = track.album_id.clone() {
+                tracks.entry(album_id).or_default().push(track);
+            }
+        }
+        Ok(tracks)
+    }
+
+    pub async fn find_by_uri(&self, uri: &str) -> Result<Option<track_entity::Model>, Error> {
+        let result = track_entity::Entity::find()
+            .filter(track_entity::Column::Uri.eq(uri))
+            .one(&self.db)
+            .await?;
+        Ok(result)
+    }
+
+    /// Tracks with the same audio stream.
+    pub async fn find_by_content(
+        &self,
+        content_hash: &str,
+    ) -> Result<Vec<track_entity::Model>, Error> {
+        let results = track_entity::Entity::find()
+            .filter(track_entity::Column::ContentHash.eq(content_hash))
+            .all(&self.db)
+            .await?;
+        Ok(results)
+    }
+
+    /// Points a track to the new location of its file. Its artist links are
+    /// dropped, the scan creates them again for the new location.
+    pub async fn relink(&self, id: &str, uri: &str, content_hash: &str) -> Result<(), Error> {
+        track_entity::Entity::update_many()
+            .col_expr(track_entity::Column::Uri, Expr::value(uri))
+            .col_expr(track_entity::Column::ContentHash, Expr::value(content_hash))
+            .filter(track_entity::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        artist_tracks_entity::Entity::delete_many()
+            .filter(artist_tracks_entity::Column::TrackId.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn set_content_hash(&self, id: &str, content_hash: &str) -> Result<(), Error> {
+        track_entity::Entity::update_many()
+            .col_expr(track_entity::Column::ContentHash, Expr::value(content_hash))
+            .filter(track_entity::Column::Id.eq(id))
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
 }
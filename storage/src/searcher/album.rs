@@ -1,20 +1,25 @@
-use itertools::Itertools;
 use music_player_settings::get_application_directory;
 use music_player_types::types::Album;
 use tantivy::{
-    collector::TopDocs,
-    directory::MmapDirectory,
     doc,
-    query::{FuzzyTermQuery, PhraseQuery, TermQuery},
-    schema::{IndexRecordOption, Schema, SchemaBuilder, STORED, STRING, TEXT},
+    schema::{Schema, SchemaBuilder, INDEXED, STORED, STRING},
     Document, Index, IndexReader, ReloadPolicy, Term,
 };
 
+use super::{
+    analyzer::text_options,
+    commit_writer, open_index,
+    query::{search_page, QueryFields, SearchPage, SearchQuery},
+    rollback_writer, with_writer,
+};
+
 #[derive(Clone)]
 pub struct AlbumSearcher {
     schema: Schema,
     index: Index,
+    index_path: String,
     reader: IndexReader,
+    outdated: bool,
 }
 
 impl AlbumSearcher {
@@ -23,14 +28,13 @@ impl AlbumSearcher {
         let mut schema_builder: SchemaBuilder = Schema::builder();
 
         schema_builder.add_text_field("id", STRING | STORED);
-        schema_builder.add_text_field("title", TEXT | STORED);
-        schema_builder.add_text_field("artist", TEXT | STORED);
-        schema_builder.add_i64_field("year", STORED);
+        schema_builder.add_text_field("title", text_options() | STORED);
+        schema_builder.add_text_field("artist", text_options() | STORED);
+        schema_builder.add_i64_field("year", INDEXED | STORED);
         schema_builder.add_text_field("cover", STRING | STORED);
 
         let schema: Schema = schema_builder.build();
-        let dir = MmapDirectory::open(&index_path).unwrap();
-        let index: Index = Index::open_or_create(dir, schema.clone()).unwrap();
+        let (index, outdated) = open_index(&index_path, &schema);
         let reader = index
             .reader_builder()
             .reload_policy(ReloadPolicy::OnCommit)
@@ -40,10 +44,18 @@ impl AlbumSearcher {
         Self {
             schema: schema.clone(),
             index,
+            index_path,
             reader,
+            outdated,
         }
     }
 
+    /// Whether the index was written by an older version and replaced by an
+    /// empty one.
+    pub fn is_outdated(&self) -> bool {
+        self.outdated
+    }
+
     pub fn get_schema(&self) -> Schema {
         return self.schema.clone();
     }
@@ -52,97 +64,82 @@ impl AlbumSearcher {
         return self.index.clone();
     }
 
+    /// Replaces the document of the album with the same id, the change is
+    /// visible once committed.
     pub fn insert(&self, album: Album) -> tantivy::Result<()> {
+        let id = self.schema.get_field("id").unwrap();
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(id, &album.id));
+            writer.add_document(self.document(album))?;
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, album_id: &str) -> tantivy::Result<()> {
+        let id = self.schema.get_field("id").unwrap();
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(id, album_id));
+            Ok(())
+        })
+    }
+
+    /// Deletes all the documents of the index, the change is visible once
+    /// committed.
+    pub fn clear(&self) -> tantivy::Result<()> {
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_all_documents()?;
+            Ok(())
+        })
+    }
+
+    /// Makes the pending changes visible and closes the writer of the index.
+    pub fn commit(&self) -> tantivy::Result<()> {
+        commit_writer(&self.index_path)?;
+        self.reader.reload()
+    }
+
+    /// Drops the pending changes.
+    pub fn rollback(&self) -> tantivy::Result<()> {
+        rollback_writer(&self.index_path)
+    }
+
+    fn document(&self, album: Album) -> Document {
         let id = self.schema.get_field("id").unwrap();
         let title = self.schema.get_field("title").unwrap();
         let artist = self.schema.get_field("artist").unwrap();
         let year = self.schema.get_field("year").unwrap();
         let cover = self.schema.get_field("cover").unwrap();
 
-        // check if album already exists
-        let searcher = self.reader.searcher();
-        let term = Term::from_field_text(id, &album.id);
-        let query = TermQuery::new(term, IndexRecordOption::Basic);
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
-        if top_docs.len() > 0 {
-            return Ok(());
-        }
-
-        let doc: Document = doc!(
+        doc!(
             id => album.id.clone(),
             title => album.title.clone(),
             artist => album.artist.clone(),
             year => i64::try_from(album.year.unwrap_or(0)).unwrap(),
             cover => album.cover.unwrap_or_default().clone()
-        );
-
-        let mut writer = self.index.writer_with_num_threads(64, 192_000_000).unwrap();
-        writer.add_document(doc)?;
-        writer.commit()?;
-        Ok(())
+        )
     }
 
-    pub fn search(&self, term: &str) -> tantivy::Result<Vec<Album>> {
-        let result_by_artist = self.search_by_artist(term).unwrap_or(vec![]);
-        let result_by_title = self.search_by_title(term).unwrap_or(vec![]);
-        Ok([result_by_artist, result_by_title]
-            .concat()
-            .into_iter()
-            .unique_by(|x| x.id.clone())
-            .collect())
-    }
-
-    pub fn search_by_title(&self, s: &str) -> tantivy::Result<Vec<Album>> {
-        let searcher = self.reader.searcher();
-        let title = self.schema.get_field("title").unwrap();
-
-        let top_docs = match s.split(" ").collect::<Vec<&str>>().len() {
-            1 => {
-                let term = Term::from_field_text(title, s);
-                let query = FuzzyTermQuery::new(term, 1, true);
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-            _ => {
-                let query = PhraseQuery::new(
-                    s.split(" ")
-                        .map(|s| Term::from_field_text(title, s))
-                        .collect(),
-                );
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+        offset: usize,
+        limit: usize,
+    ) -> tantivy::Result<SearchPage<Album>> {
+        let field = |name| self.schema.get_field(name).unwrap();
+        let fields = QueryFields {
+            default: vec![(field("title"), 2.0), (field("artist"), 1.0)],
+            named: vec![
+                ("album", field("title")),
+                ("title", field("title")),
+                ("artist", field("artist")),
+                ("year", field("year")),
+            ],
         };
-
-        Ok(top_docs
-            .clone()
-            .into_iter()
-            .map(|(_, y)| Into::into(searcher.doc(y).unwrap()))
-            .collect::<Vec<Album>>())
-    }
-
-    pub fn search_by_artist(&self, s: &str) -> tantivy::Result<Vec<Album>> {
-        let searcher = self.reader.searcher();
-        let artist = self.schema.get_field("artist").unwrap();
-
-        let top_docs = match s.split(" ").collect::<Vec<&str>>().len() {
-            1 => {
-                let term = Term::from_field_text(artist, s);
-                let query = FuzzyTermQuery::new(term, 1, true);
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-            _ => {
-                let query = PhraseQuery::new(
-                    s.split(" ")
-                        .map(|s| Term::from_field_text(artist, s))
-                        .collect(),
-                );
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-        };
-
-        Ok(top_docs
-            .clone()
-            .into_iter()
-            .map(|(_, y)| Into::into(searcher.doc(y).unwrap()))
-            .collect::<Vec<Album>>())
+        search_page(
+            &self.reader,
+            query.build(&self.index, &fields),
+            offset,
+            limit,
+        )
     }
 }
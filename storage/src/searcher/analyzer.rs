@@ -0,0 +1,104 @@
+use music_player_settings::read_settings;
+use once_cell::sync::Lazy;
+use tantivy::{
+    schema::{IndexRecordOption, TextFieldIndexing, TextOptions},
+    tokenizer::{
+        AsciiFoldingFilter, Language, LowerCaser, NgramTokenizer, RemoveLongFilter,
+        SimpleTokenizer, Stemmer, TextAnalyzer,
+    },
+    Index,
+};
+
+/// Bumped whenever the analyzers change. Their names carry it and the names
+/// are part of the schemas, so an index written with another version no
+/// longer opens and is rebuilt from the database.
+const ANALYZER_VERSION: u32 = 1;
+
+/// Longest prefix indexed by the suggestions, the longer inputs are completed
+/// from their first characters.
+const MAX_PREFIX_LENGTH: usize = 30;
+
+/// Words longer than this are left out, like the default tantivy tokenizer.
+const MAX_WORD_LENGTH: usize = 40;
+
+/// The language of the `search_language` setting, its words are reduced to
+/// their stem. No stemming when it isn't set or isn't supported.
+static LANGUAGE: Lazy<Option<Language>> = Lazy::new(|| {
+    read_settings()
+        .ok()
+        .and_then(|config| config.get_string("search_language").ok())
+        .and_then(|language| parse_language(&language))
+});
+
+/// Name of the analyzer of the searchable text, it changes with the version
+/// and the stemming language.
+pub(crate) static TEXT_ANALYZER: Lazy<String> = Lazy::new(|| match *LANGUAGE {
+    Some(language) => format!("text_v{}_{:?}", ANALYZER_VERSION, language).to_lowercase(),
+    None => format!("text_v{}", ANALYZER_VERSION),
+});
+
+/// Name of the analyzer of the prefixes of the suggestions.
+pub(crate) static PREFIX_ANALYZER: Lazy<String> =
+    Lazy::new(|| format!("edge_ngram_v{}", ANALYZER_VERSION));
+
+/// Replaces `TEXT`: the words are lowercased and stripped of their accents,
+/// so that "beyonce" finds "Beyoncé".
+pub(crate) fn text_options() -> TextOptions {
+    TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(&TEXT_ANALYZER)
+            .set_index_option(IndexRecordOption::WithFreqsAndPositions),
+    )
+}
+
+/// The options of the field holding the prefixes of the suggestions.
+pub(crate) fn prefix_options() -> TextOptions {
+    TextOptions::default().set_indexing_options(
+        TextFieldIndexing::default()
+            .set_tokenizer(&PREFIX_ANALYZER)
+            .set_index_option(IndexRecordOption::WithFreqs),
+    )
+}
+
+/// Registers the analyzers on an index, they aren't saved with it.
+pub(crate) fn register_analyzers(index: &Index) {
+    let mut text = TextAnalyzer::from(SimpleTokenizer)
+        .filter(RemoveLongFilter::limit(MAX_WORD_LENGTH))
+        .filter(LowerCaser)
+        .filter(AsciiFoldingFilter);
+    if let Some(language) = *LANGUAGE {
+        text = text.filter(Stemmer::new(language));
+    }
+    index.tokenizers().register(&TEXT_ANALYZER, text);
+
+    index.tokenizers().register(
+        &PREFIX_ANALYZER,
+        TextAnalyzer::from(NgramTokenizer::prefix_only(1, MAX_PREFIX_LENGTH))
+            .filter(LowerCaser)
+            .filter(AsciiFoldingFilter),
+    );
+}
+
+fn parse_language(language: &str) -> Option<Language> {
+    match language.trim().to_lowercase().as_str() {
+        "arabic" => Some(Language::Arabic),
+        "danish" => Some(Language::Danish),
+        "dutch" => Some(Language::Dutch),
+        "english" => Some(Language::English),
+        "finnish" => Some(Language::Finnish),
+        "french" => Some(Language::French),
+        "german" => Some(Language::German),
+        "greek" => Some(Language::Greek),
+        "hungarian" => Some(Language::Hungarian),
+        "italian" => Some(Language::Italian),
+        "norwegian" => Some(Language::Norwegian),
+        "portuguese" => Some(Language::Portuguese),
+        "romanian" => Some(Language::Romanian),
+        "russian" => Some(Language::Russian),
+        "spanish" => Some(Language::Spanish),
+        "swedish" => Some(Language::Swedish),
+        "tamil" => Some(Language::Tamil),
+        "turkish" => Some(Language::Turkish),
+        _ => None,
+    }
+}
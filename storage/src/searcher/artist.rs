@@ -1,19 +1,25 @@
-use itertools::Itertools;
 use music_player_settings::get_application_directory;
 use music_player_types::types::Artist;
 use tantivy::{
-    collector::TopDocs,
-    directory::MmapDirectory,
     doc,
-    query::{FuzzyTermQuery, PhraseQuery, TermQuery},
-    schema::{IndexRecordOption, Schema, SchemaBuilder, STORED, STRING, TEXT},
+    schema::{Schema, SchemaBuilder, STORED, STRING},
     Document, Index, IndexReader, ReloadPolicy, Term,
 };
+
+use super::{
+    analyzer::text_options,
+    commit_writer, open_index,
+    query::{search_page, QueryFields, SearchPage, SearchQuery},
+    rollback_writer, with_writer,
+};
+
 #[derive(Clone)]
 pub struct ArtistSearcher {
     schema: Schema,
     index: Index,
+    index_path: String,
     reader: IndexReader,
+    outdated: bool,
 }
 
 impl ArtistSearcher {
@@ -22,11 +28,10 @@ impl ArtistSearcher {
         let mut schema_builder: SchemaBuilder = Schema::builder();
 
         schema_builder.add_text_field("id", STRING | STORED);
-        schema_builder.add_text_field("name", TEXT | STORED);
+        schema_builder.add_text_field("name", text_options() | STORED);
 
         let schema: Schema = schema_builder.build();
-        let dir = MmapDirectory::open(&index_path).unwrap();
-        let index: Index = Index::open_or_create(dir, schema.clone()).unwrap();
+        let (index, outdated) = open_index(&index_path, &schema);
         let reader = index
             .reader_builder()
             .reload_policy(ReloadPolicy::OnCommit)
@@ -36,10 +41,18 @@ impl ArtistSearcher {
         Self {
             schema: schema.clone(),
             index,
+            index_path,
             reader,
+            outdated,
         }
     }
 
+    /// Whether the index was written by an older version and replaced by an
+    /// empty one.
+    pub fn is_outdated(&self) -> bool {
+        self.outdated
+    }
+
     pub fn get_schema(&self) -> Schema {
         return self.schema.clone();
     }
@@ -48,58 +61,67 @@ impl ArtistSearcher {
         return self.index.clone();
     }
 
+    /// Replaces the document of the artist with the same id, the change is
+    /// visible once committed.
     pub fn insert(&self, artist: Artist) -> tantivy::Result<()> {
         let id = self.schema.get_field("id").unwrap();
         let name = self.schema.get_field("name").unwrap();
 
-        // Check if the artist already exists
-        let searcher = self.reader.searcher();
-        let query = TermQuery::new(
-            Term::from_field_text(id, &artist.id),
-            IndexRecordOption::Basic,
-        );
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
-        if top_docs.len() > 0 {
-            return Ok(());
-        }
-
         let doc: Document = doc!(
             id => artist.id.clone(),
             name => artist.name.clone(),
         );
-        let mut writer = self.index.writer_with_num_threads(64, 192_000_000)?;
-        writer.add_document(doc)?;
-        writer.commit()?;
-        Ok(())
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(id, &artist.id));
+            writer.add_document(doc)?;
+            Ok(())
+        })
     }
 
-    pub fn search(&self, term: &str) -> tantivy::Result<Vec<Artist>> {
-        self.search_by_name(term)
+    pub fn delete(&self, artist_id: &str) -> tantivy::Result<()> {
+        let id = self.schema.get_field("id").unwrap();
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(id, artist_id));
+            Ok(())
+        })
     }
 
-    pub fn search_by_name(&self, s: &str) -> tantivy::Result<Vec<Artist>> {
-        let searcher = self.reader.searcher();
-        let name = self.schema.get_field("name").unwrap();
+    /// Deletes all the documents of the index, the change is visible once
+    /// committed.
+    pub fn clear(&self) -> tantivy::Result<()> {
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_all_documents()?;
+            Ok(())
+        })
+    }
+
+    /// Makes the pending changes visible and closes the writer of the index.
+    pub fn commit(&self) -> tantivy::Result<()> {
+        commit_writer(&self.index_path)?;
+        self.reader.reload()
+    }
+
+    /// Drops the pending changes.
+    pub fn rollback(&self) -> tantivy::Result<()> {
+        rollback_writer(&self.index_path)
+    }
 
-        let top_docs = match s.split(" ").collect::<Vec<&str>>().len() {
-            1 => {
-                let term = Term::from_field_text(name, s);
-                let query = FuzzyTermQuery::new(term, 1, true);
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-            _ => {
-                let query = PhraseQuery::new(
-                    s.split(" ")
-                        .map(|s| Term::from_field_text(name, s))
-                        .collect(),
-                );
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+        offset: usize,
+        limit: usize,
+    ) -> tantivy::Result<SearchPage<Artist>> {
+        let name = self.schema.get_field("name").unwrap();
+        let fields = QueryFields {
+            default: vec![(name, 1.0)],
+            named: vec![("artist", name), ("name", name)],
         };
-        let results: Vec<Artist> = top_docs
-            .into_iter()
-            .map(|(_, y)| Into::into(searcher.doc(y).unwrap()))
-            .collect();
-        Ok(results.into_iter().unique_by(|x| x.id.clone()).collect())
+        search_page(
+            &self.reader,
+            query.build(&self.index, &fields),
+            offset,
+            limit,
+        )
     }
 }
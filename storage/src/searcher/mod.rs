@@ -1,14 +1,119 @@
-use music_player_types::types::{Album, Artist, SimplifiedSong, Song};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fs,
+    sync::Mutex,
+    time::Duration,
+};
+
+use anyhow::Error;
+use music_player_entity::{album as album_entity, artist as artist_entity, track as track_entity};
+use music_player_types::{
+    genre::{genre_id, split_genres},
+    types::{Album, Artist, SimplifiedSong, Song},
+};
+use once_cell::sync::Lazy;
+use sea_orm::EntityTrait;
+use tantivy::{
+    directory::MmapDirectory, schema::Schema, Index, IndexSettings, IndexWriter, TantivyError,
+};
+
+use crate::Database;
+
+use self::{
+    query::{SearchPage, SearchQuery},
+    suggestion::{Suggestion, SuggestionKind},
+};
 
 pub mod album;
+mod analyzer;
 pub mod artist;
+pub mod query;
+pub mod suggestion;
 pub mod track;
 
+/// Memory shared by the indexing threads of a writer.
+const WRITER_MEMORY_BUDGET: usize = 50_000_000;
+
+/// Number of results of `search_song`, `search_album` and `search_artist`.
+const SEARCH_LIMIT: usize = 10;
+
+/// Tantivy allows a single writer per index, the searchers of the process
+/// share the writers of the indexes they write to.
+static WRITERS: Lazy<Mutex<HashMap<String, IndexWriter>>> = Lazy::new(Default::default);
+
+/// Runs `f` with the writer of the index stored at `index_path`, created on
+/// the first write following a commit.
+pub(crate) fn with_writer<T>(
+    index_path: &str,
+    index: &Index,
+    f: impl FnOnce(&mut IndexWriter) -> tantivy::Result<T>,
+) -> tantivy::Result<T> {
+    let mut writers = WRITERS.lock().unwrap();
+    let writer = match writers.entry(index_path.to_string()) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => entry.insert(index.writer(WRITER_MEMORY_BUDGET)?),
+    };
+    f(writer)
+}
+
+/// Commits the pending changes of the index and closes its writer, which
+/// releases the lock of the directory: a `scan` run from the command line
+/// can write to the indexes while the server is running.
+pub(crate) fn commit_writer(index_path: &str) -> tantivy::Result<()> {
+    let mut writers = WRITERS.lock().unwrap();
+    if let Some(mut writer) = writers.remove(index_path) {
+        writer.commit()?;
+        writer.wait_merging_threads()?;
+    }
+    Ok(())
+}
+
+/// Drops the pending changes of the index and closes its writer.
+pub(crate) fn rollback_writer(index_path: &str) -> tantivy::Result<()> {
+    let mut writers = WRITERS.lock().unwrap();
+    if let Some(mut writer) = writers.remove(index_path) {
+        writer.rollback()?;
+    }
+    Ok(())
+}
+
+/// Opens the index stored at `index_path` and registers its analyzers. An
+/// index written with another schema, or another version of the analyzers, is
+/// replaced by an empty one, the second value tells it has to be filled again.
+pub(crate) fn open_index(index_path: &str, schema: &Schema) -> (Index, bool) {
+    let dir = MmapDirectory::open(index_path).unwrap();
+    let (index, outdated) = match Index::open_or_create(dir, schema.clone()) {
+        Ok(index) => (index, false),
+        Err(TantivyError::SchemaError(_)) => {
+            fs::remove_dir_all(index_path).unwrap();
+            fs::create_dir_all(index_path).unwrap();
+            let dir = MmapDirectory::open(index_path).unwrap();
+            let index = Index::create(dir, schema.clone(), IndexSettings::default()).unwrap();
+            (index, true)
+        }
+        Err(e) => panic!("Tantivy index couldn't be opened: {}", e),
+    };
+    analyzer::register_analyzers(&index);
+    (index, outdated)
+}
+
+/// A page of the tracks, albums and artists matching a query.
+#[derive(Debug, Clone, Default)]
+pub struct SearchResults {
+    pub tracks: SearchPage<SimplifiedSong>,
+    pub albums: SearchPage<Album>,
+    pub artists: SearchPage<Artist>,
+}
+
+/// The documents added and deleted through a searcher are only visible to
+/// the searches once `commit` is called, a scan commits once per batch of
+/// tracks instead of once per document.
 #[derive(Clone)]
 pub struct Searcher {
     pub track: track::TrackSearcher,
     pub artist: artist::ArtistSearcher,
     pub album: album::AlbumSearcher,
+    pub suggestion: suggestion::SuggestionSearcher,
 }
 
 impl Searcher {
@@ -17,30 +122,182 @@ impl Searcher {
             track: track::TrackSearcher::new(),
             artist: artist::ArtistSearcher::new(),
             album: album::AlbumSearcher::new(),
+            suggestion: suggestion::SuggestionSearcher::new(),
         }
     }
 
+    /// Whether an index was written by an older version, the indexes have to
+    /// be rebuilt from the database.
+    pub fn is_outdated(&self) -> bool {
+        self.track.is_outdated()
+            || self.artist.is_outdated()
+            || self.album.is_outdated()
+            || self.suggestion.is_outdated()
+    }
+
+    /// Adds the document of a song, replacing the one with the same id. Its
+    /// title and genres are suggested while typing.
     pub fn insert_song(&self, song: Song, id: &str) -> tantivy::Result<()> {
+        self.suggestion
+            .insert(SuggestionKind::Track, id, &song.title)?;
+        for genre in split_genres(&song.genre) {
+            self.suggestion
+                .insert(SuggestionKind::Genre, &genre_id(&genre), &genre)?;
+        }
         self.track.insert(song, id)
     }
 
+    /// Adds the document of an artist, replacing the one with the same id.
     pub fn insert_artist(&self, artist: Artist) -> tantivy::Result<()> {
+        self.suggestion
+            .insert(SuggestionKind::Artist, &artist.id, &artist.name)?;
         self.artist.insert(artist)
     }
 
+    /// Adds the document of an album, replacing the one with the same id.
     pub fn insert_album(&self, album: Album) -> tantivy::Result<()> {
+        self.suggestion
+            .insert(SuggestionKind::Album, &album.id, &album.title)?;
         self.album.insert(album)
     }
 
+    /// Deletes the document of a song and its title suggestion. The genres are
+    /// shared with other tracks, only the suggestions of the `unused_genres`
+    /// left without any track are deleted.
+    pub fn delete_song(&self, id: &str, unused_genres: &[String]) -> tantivy::Result<()> {
+        self.suggestion.delete(SuggestionKind::Track, id)?;
+        for genre_id in unused_genres {
+            self.delete_genre(genre_id)?;
+        }
+        self.track.delete(id)
+    }
+
+    /// Deletes the suggestion of a genre left without any track.
+    pub fn delete_genre(&self, id: &str) -> tantivy::Result<()> {
+        self.suggestion.delete(SuggestionKind::Genre, id)
+    }
+
+    pub fn delete_artist(&self, id: &str) -> tantivy::Result<()> {
+        self.suggestion.delete(SuggestionKind::Artist, id)?;
+        self.artist.delete(id)
+    }
+
+    pub fn delete_album(&self, id: &str) -> tantivy::Result<()> {
+        self.suggestion.delete(SuggestionKind::Album, id)?;
+        self.album.delete(id)
+    }
+
+    /// Makes the pending changes of the indexes visible to the searches.
+    pub fn commit(&self) -> tantivy::Result<()> {
+        self.track.commit()?;
+        self.artist.commit()?;
+        self.album.commit()?;
+        self.suggestion.commit()
+    }
+
+    /// Drops the pending changes of the indexes.
+    pub fn rollback(&self) -> tantivy::Result<()> {
+        self.track.rollback()?;
+        self.artist.rollback()?;
+        self.album.rollback()?;
+        self.suggestion.rollback()
+    }
+
+    /// Regenerates the indexes from the tracks, albums and artists stored in
+    /// the database, dropping the documents of the deleted ones.
+    pub async fn rebuild_from_database(&self, db: &Database) -> Result<(), Error> {
+        let artists = artist_entity::Entity::find()
+            .all(db.get_connection())
+            .await?;
+        let albums = album_entity::Entity::find()
+            .all(db.get_connection())
+            .await?;
+        let tracks = track_entity::Entity::find()
+            .find_also_related(album_entity::Entity)
+            .all(db.get_connection())
+            .await?;
+
+        // The documents are deleted by the writers shared with the other
+        // searchers, a failed rebuild must not leave the deletion pending
+        // until the next commit.
+        let rebuild = || -> tantivy::Result<()> {
+            self.track.clear()?;
+            self.artist.clear()?;
+            self.album.clear()?;
+            self.suggestion.clear()?;
+
+            for artist in artists {
+                self.insert_artist(artist.into())?;
+            }
+            for album in albums {
+                self.insert_album(album.into())?;
+            }
+            for (track, album) in tracks {
+                let album = album.unwrap_or_default();
+                let song = Song {
+                    title: track.title,
+                    artist: track.artist,
+                    album: album.title,
+                    genre: track.genre,
+                    year: track.year,
+                    duration: Duration::from_secs_f32(track.duration.unwrap_or_default()),
+                    cover: album.cover,
+                    album_artist: album.artist,
+                    ..Default::default()
+                };
+                self.insert_song(song, &track.id)?;
+            }
+            Ok(())
+        };
+        if let Err(e) = rebuild() {
+            self.rollback()?;
+            return Err(e.into());
+        }
+
+        self.commit()?;
+        Ok(())
+    }
+
+    /// Searches the tracks, albums and artists, see `SearchQuery` for the
+    /// syntax of the query. The results are ranked by relevance.
+    pub fn search(
+        &self,
+        query: &str,
+        offset: usize,
+        limit: usize,
+    ) -> tantivy::Result<SearchResults> {
+        let query = SearchQuery::parse(query);
+        Ok(SearchResults {
+            tracks: self.track.search(&query, offset, limit)?,
+            albums: self.album.search(&query, offset, limit)?,
+            artists: self.artist.search(&query, offset, limit)?,
+        })
+    }
+
+    /// Completes the beginning of the words of the titles, artists, albums
+    /// and genres.
+    pub fn suggest(&self, input: &str, limit: usize) -> tantivy::Result<Vec<Suggestion>> {
+        self.suggestion.suggest(input, limit)
+    }
+
     pub fn search_artist(&self, term: &str) -> tantivy::Result<Vec<Artist>> {
-        self.artist.search(term)
+        let page = self
+            .artist
+            .search(&SearchQuery::parse(term), 0, SEARCH_LIMIT)?;
+        Ok(page.items)
     }
 
     pub fn search_album(&self, term: &str) -> tantivy::Result<Vec<Album>> {
-        self.album.search(term)
+        let page = self
+            .album
+            .search(&SearchQuery::parse(term), 0, SEARCH_LIMIT)?;
+        Ok(page.items)
     }
 
     pub fn search_song(&self, term: &str) -> tantivy::Result<Vec<SimplifiedSong>> {
-        self.track.search(term)
+        let page = self
+            .track
+            .search(&SearchQuery::parse(term), 0, SEARCH_LIMIT)?;
+        Ok(page.items)
     }
 }
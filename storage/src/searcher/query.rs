@@ -0,0 +1,302 @@
+use std::{iter::Peekable, ops::Bound, str::Chars};
+
+use tantivy::{
+    collector::{Count, TopDocs},
+    query::{
+        AllQuery, BooleanQuery, BoostQuery, FuzzyTermQuery, Occur, PhraseQuery, Query, RangeQuery,
+        TermQuery,
+    },
+    schema::{Field, FieldType, IndexRecordOption},
+    Document, Index, IndexReader, TantivyError, Term,
+};
+
+/// Words shorter than this are matched exactly, a typo in them would match
+/// too many other words.
+const FUZZY_MIN_LENGTH: usize = 4;
+
+/// The most results a page of search results or suggestions can hold.
+pub const MAX_LIMIT: usize = 100;
+
+/// The furthest a page of search results can start, tantivy keeps the
+/// `offset + limit` best results in memory.
+pub const MAX_OFFSET: usize = 10_000;
+
+/// The field names known to at least one of the indexes, any other prefix
+/// followed by a colon is part of the text, e.g. `re:union`.
+const FIELD_NAMES: [&str; 6] = ["title", "artist", "album", "genre", "year", "name"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryValue {
+    /// A word, or the words of a quoted phrase.
+    Text(String),
+    /// `1955..1960`, either bound can be left out.
+    Range(Option<i64>, Option<i64>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueryClause {
+    /// `None` searches the default fields of the index.
+    pub field: Option<String>,
+    pub value: QueryValue,
+    pub quoted: bool,
+    /// Set by a leading `-`, the matching documents are left out.
+    pub excluded: bool,
+}
+
+/// A search query, e.g. `artist:"miles davis" year:1955..1960 genre:jazz -live`.
+/// All the clauses must match, except the excluded ones which must not.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    pub clauses: Vec<QueryClause>,
+}
+
+/// The fields of an index a query can target.
+pub(crate) struct QueryFields {
+    /// The fields searched by the clauses without a field, with their boost.
+    pub default: Vec<(Field, f32)>,
+    /// The fields by name, several names can point to the same field.
+    pub named: Vec<(&'static str, Field)>,
+}
+
+/// A page of search results and the total number of matching documents.
+#[derive(Debug, Clone, Default)]
+pub struct SearchPage<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+impl SearchQuery {
+    /// Parses a query, it never fails: a lone quote runs until the end of
+    /// the query, a field without a value is searched as a word and an
+    /// unknown field is searched as text.
+    pub fn parse(query: &str) -> Self {
+        let mut chars = query.chars().peekable();
+        let mut clauses = vec![];
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let excluded = chars.next_if_eq(&'-').is_some();
+            let mut field = None;
+            let (mut text, mut quoted) = read_value(&mut chars, true);
+            if !quoted && chars.next_if_eq(&':').is_some() {
+                let name = text.to_lowercase();
+                let (value, value_quoted) = read_value(&mut chars, false);
+                match FIELD_NAMES.contains(&name.as_str()) {
+                    true => {
+                        field = Some(name);
+                        (text, quoted) = (value, value_quoted);
+                    }
+                    false => text = format!("{}:{}", text, value),
+                }
+            }
+            if text.is_empty() {
+                match field.take() {
+                    Some(name) => text = name,
+                    None => continue,
+                }
+            }
+
+            let value = match field {
+                Some(_) if !quoted => parse_range(&text).unwrap_or(QueryValue::Text(text)),
+                _ => QueryValue::Text(text),
+            };
+            clauses.push(QueryClause {
+                field,
+                value,
+                quoted,
+                excluded,
+            });
+        }
+
+        Self { clauses }
+    }
+
+    /// Builds the tantivy query of an index. `None` when no document of the
+    /// index can match, e.g. when a clause targets a field it doesn't have.
+    pub(crate) fn build(&self, index: &Index, fields: &QueryFields) -> Option<Box<dyn Query>> {
+        let mut subqueries = vec![];
+        for clause in &self.clauses {
+            let query = match &clause.field {
+                Some(name) => fields
+                    .named
+                    .iter()
+                    .find(|(field_name, _)| field_name == name)
+                    .and_then(|(_, field)| clause_query(index, *field, clause)),
+                None => {
+                    let queries: Vec<(Occur, Box<dyn Query>)> = fields
+                        .default
+                        .iter()
+                        .filter_map(|(field, boost)| {
+                            clause_query(index, *field, clause).map(|query| {
+                                let query: Box<dyn Query> =
+                                    Box::new(BoostQuery::new(query, *boost));
+                                (Occur::Should, query)
+                            })
+                        })
+                        .collect();
+                    match queries.is_empty() {
+                        true => None,
+                        false => Some(Box::new(BooleanQuery::new(queries)) as Box<dyn Query>),
+                    }
+                }
+            };
+            match (query, clause.excluded) {
+                (Some(query), true) => subqueries.push((Occur::MustNot, query)),
+                (Some(query), false) => subqueries.push((Occur::Must, query)),
+                // Leaving out what can't be found changes nothing
+                (None, true) => {}
+                (None, false) => return None,
+            }
+        }
+
+        if self.clauses.is_empty() {
+            return None;
+        }
+        // The exclusions alone leave out documents from all of them
+        if subqueries.iter().all(|(occur, _)| *occur == Occur::MustNot) {
+            subqueries.push((Occur::Must, Box::new(AllQuery)));
+        }
+        Some(Box::new(BooleanQuery::new(subqueries)))
+    }
+}
+
+/// Runs a query ranked by BM25, `None` matches no document.
+pub(crate) fn search_page<T: From<Document>>(
+    reader: &IndexReader,
+    query: Option<Box<dyn Query>>,
+    offset: usize,
+    limit: usize,
+) -> tantivy::Result<SearchPage<T>> {
+    check_page(offset, limit)?;
+    let query = match query {
+        Some(query) => query,
+        None => {
+            return Ok(SearchPage {
+                items: vec![],
+                total: 0,
+            })
+        }
+    };
+    let searcher = reader.searcher();
+    let collector = (TopDocs::with_limit(limit).and_offset(offset), Count);
+    let (top_docs, total) = searcher.search(&query, &collector)?;
+    let items = top_docs
+        .into_iter()
+        .map(|(_, address)| searcher.doc(address).map(Into::into))
+        .collect::<tantivy::Result<Vec<T>>>()?;
+    Ok(SearchPage { items, total })
+}
+
+/// Rejects the pages out of the bounds of `MAX_LIMIT` and `MAX_OFFSET`.
+pub(crate) fn check_page(offset: usize, limit: usize) -> tantivy::Result<()> {
+    if limit == 0 || limit > MAX_LIMIT {
+        return Err(TantivyError::InvalidArgument(format!(
+            "The limit must be between 1 and {}",
+            MAX_LIMIT
+        )));
+    }
+    if offset > MAX_OFFSET {
+        return Err(TantivyError::InvalidArgument(format!(
+            "The offset must be at most {}",
+            MAX_OFFSET
+        )));
+    }
+    Ok(())
+}
+
+/// Reads a quoted phrase, or a word until a space or, for a word that may
+/// be a field name, a colon.
+fn read_value(chars: &mut Peekable<Chars>, field_name: bool) -> (String, bool) {
+    let mut value = String::new();
+    if chars.next_if_eq(&'"').is_some() {
+        for c in chars.by_ref() {
+            if c == '"' {
+                break;
+            }
+            value.push(c);
+        }
+        return (value.trim().to_string(), true);
+    }
+    while let Some(c) = chars.next_if(|c| !(c.is_whitespace() || field_name && *c == ':')) {
+        value.push(c);
+    }
+    (value, false)
+}
+
+fn parse_range(text: &str) -> Option<QueryValue> {
+    let (start, end) = text.split_once("..")?;
+    let bound = |value: &str| match value {
+        "" => Ok(None),
+        _ => value.parse::<i64>().map(Some),
+    };
+    Some(QueryValue::Range(bound(start).ok()?, bound(end).ok()?))
+}
+
+fn clause_query(index: &Index, field: Field, clause: &QueryClause) -> Option<Box<dyn Query>> {
+    let field_type = index.schema().get_field_entry(field).field_type().clone();
+    match (field_type, &clause.value) {
+        (FieldType::I64(_), QueryValue::Range(start, end)) => {
+            let bound = |value: &Option<i64>| match value {
+                Some(value) => Bound::Included(*value),
+                None => Bound::Unbounded,
+            };
+            Some(Box::new(RangeQuery::new_i64_bounds(
+                field,
+                bound(start),
+                bound(end),
+            )))
+        }
+        (FieldType::I64(_), QueryValue::Text(text)) => {
+            let value = text.parse::<i64>().ok()?;
+            Some(Box::new(TermQuery::new(
+                Term::from_field_i64(field, value),
+                IndexRecordOption::Basic,
+            )))
+        }
+        (FieldType::Str(_), QueryValue::Text(text)) => text_query(index, field, text, clause),
+        _ => None,
+    }
+}
+
+/// Matches the words of the text with the analyzer of the field, a single
+/// word also matches the words one typo away from it unless it is quoted.
+fn text_query(
+    index: &Index,
+    field: Field,
+    text: &str,
+    clause: &QueryClause,
+) -> Option<Box<dyn Query>> {
+    let analyzer = index.tokenizer_for_field(field).ok()?;
+    let mut terms = vec![];
+    analyzer
+        .token_stream(text)
+        .process(&mut |token| terms.push(Term::from_field_text(field, &token.text)));
+
+    match terms.len() {
+        0 => None,
+        1 => {
+            let term = terms.remove(0);
+            let exact: Box<dyn Query> =
+                Box::new(TermQuery::new(term.clone(), IndexRecordOption::WithFreqs));
+            let fuzzy = !clause.quoted
+                && !clause.excluded
+                && term.as_str().map_or(0, |word| word.chars().count()) >= FUZZY_MIN_LENGTH;
+            if !fuzzy {
+                return Some(exact);
+            }
+            let fuzzy: Box<dyn Query> = Box::new(BoostQuery::new(
+                Box::new(FuzzyTermQuery::new(term, 1, true)),
+                0.5,
+            ));
+            Some(Box::new(BooleanQuery::new(vec![
+                (Occur::Should, exact),
+                (Occur::Should, fuzzy),
+            ])))
+        }
+        _ => Some(Box::new(PhraseQuery::new(terms))),
+    }
+}
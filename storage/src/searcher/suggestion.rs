@@ -0,0 +1,226 @@
+use music_player_settings::get_application_directory;
+use tantivy::{
+    collector::TopDocs,
+    doc,
+    query::TermQuery,
+    schema::{IndexRecordOption, Schema, SchemaBuilder, STORED, STRING},
+    Document, Index, IndexReader, ReloadPolicy, Term,
+};
+
+use super::{
+    analyzer::prefix_options, commit_writer, open_index, query::check_page, rollback_writer,
+    with_writer,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestionKind {
+    Track,
+    Album,
+    Artist,
+    Genre,
+}
+
+impl SuggestionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuggestionKind::Track => "track",
+            SuggestionKind::Album => "album",
+            SuggestionKind::Artist => "artist",
+            SuggestionKind::Genre => "genre",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "track" => Some(SuggestionKind::Track),
+            "album" => Some(SuggestionKind::Album),
+            "artist" => Some(SuggestionKind::Artist),
+            "genre" => Some(SuggestionKind::Genre),
+            _ => None,
+        }
+    }
+}
+
+/// A title, artist, album or genre completing what is being typed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Suggestion {
+    pub kind: SuggestionKind,
+    /// The id of the track, album, artist or genre.
+    pub id: String,
+    pub text: String,
+}
+
+/// Completes the beginning of the words of the titles, artists, albums and
+/// genres: "miles d" and "dav" both suggest "Miles Davis".
+#[derive(Clone)]
+pub struct SuggestionSearcher {
+    schema: Schema,
+    index: Index,
+    index_path: String,
+    reader: IndexReader,
+    outdated: bool,
+}
+
+impl SuggestionSearcher {
+    pub fn new() -> Self {
+        let index_path = format!("{}/suggestions", get_application_directory());
+        let mut schema_builder: SchemaBuilder = Schema::builder();
+
+        schema_builder.add_text_field("key", STRING);
+        schema_builder.add_text_field("kind", STRING | STORED);
+        schema_builder.add_text_field("id", STRING | STORED);
+        schema_builder.add_text_field("text", STORED);
+        schema_builder.add_text_field("prefixes", prefix_options());
+
+        let schema: Schema = schema_builder.build();
+        let (index, outdated) = open_index(&index_path, &schema);
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::OnCommit)
+            .try_into()
+            .expect("Tantivy reader couldn't be created");
+
+        Self {
+            schema,
+            index,
+            index_path,
+            reader,
+            outdated,
+        }
+    }
+
+    /// Whether the index was written by an older version and replaced by an
+    /// empty one.
+    pub fn is_outdated(&self) -> bool {
+        self.outdated
+    }
+
+    /// Replaces the suggestion of the same kind and id, the change is visible
+    /// once committed.
+    pub fn insert(&self, kind: SuggestionKind, id: &str, text: &str) -> tantivy::Result<()> {
+        let text = normalize(text);
+        if text.is_empty() {
+            return Ok(());
+        }
+        let key = self.schema.get_field("key").unwrap();
+        let kind_field = self.schema.get_field("kind").unwrap();
+        let id_field = self.schema.get_field("id").unwrap();
+        let text_field = self.schema.get_field("text").unwrap();
+        let prefixes = self.schema.get_field("prefixes").unwrap();
+
+        let mut document: Document = doc!(
+            key => suggestion_key(kind, id),
+            kind_field => kind.as_str(),
+            id_field => id,
+            text_field => text.clone(),
+        );
+        // Every word starts a value, so that any of them can be completed
+        let mut suffix = text.as_str();
+        loop {
+            document.add_text(prefixes, suffix);
+            match suffix.split_once(' ') {
+                Some((_, rest)) => suffix = rest,
+                None => break,
+            }
+        }
+
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(key, &suggestion_key(kind, id)));
+            writer.add_document(document)?;
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, kind: SuggestionKind, id: &str) -> tantivy::Result<()> {
+        let key = self.schema.get_field("key").unwrap();
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(key, &suggestion_key(kind, id)));
+            Ok(())
+        })
+    }
+
+    /// Deletes all the documents of the index, the change is visible once
+    /// committed.
+    pub fn clear(&self) -> tantivy::Result<()> {
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_all_documents()?;
+            Ok(())
+        })
+    }
+
+    /// Makes the pending changes visible and closes the writer of the index.
+    pub fn commit(&self) -> tantivy::Result<()> {
+        commit_writer(&self.index_path)?;
+        self.reader.reload()
+    }
+
+    /// Drops the pending changes.
+    pub fn rollback(&self) -> tantivy::Result<()> {
+        rollback_writer(&self.index_path)
+    }
+
+    /// The suggestions with a word starting with the input, the shortest
+    /// ones rank first. A single term lookup, whatever the size of the
+    /// library.
+    pub fn suggest(&self, input: &str, limit: usize) -> tantivy::Result<Vec<Suggestion>> {
+        check_page(0, limit)?;
+        let prefixes = self.schema.get_field("prefixes").unwrap();
+        let kind_field = self.schema.get_field("kind").unwrap();
+        let id_field = self.schema.get_field("id").unwrap();
+        let text_field = self.schema.get_field("text").unwrap();
+
+        // The longest token of the edge n-grams is the whole input, cut at the
+        // longest prefix indexed
+        let mut prefix = None;
+        self.index
+            .tokenizer_for_field(prefixes)?
+            .token_stream(&normalize(input))
+            .process(&mut |token| prefix = Some(token.text.clone()));
+        let prefix = match prefix {
+            Some(prefix) => prefix,
+            None => return Ok(vec![]),
+        };
+
+        let searcher = self.reader.searcher();
+        let query = TermQuery::new(
+            Term::from_field_text(prefixes, &prefix),
+            IndexRecordOption::WithFreqs,
+        );
+        let top_docs = searcher.search(&query, &TopDocs::with_limit(limit))?;
+
+        let mut suggestions = vec![];
+        for (_, address) in top_docs {
+            let document = searcher.doc(address)?;
+            let text = |field| {
+                document
+                    .get_first(field)
+                    .and_then(|value| value.as_text())
+                    .unwrap_or_default()
+                    .to_string()
+            };
+            if let Some(kind) = SuggestionKind::parse(&text(kind_field)) {
+                suggestions.push(Suggestion {
+                    kind,
+                    id: text(id_field),
+                    text: text(text_field),
+                });
+            }
+        }
+        Ok(suggestions)
+    }
+}
+
+impl Default for SuggestionSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn suggestion_key(kind: SuggestionKind, id: &str) -> String {
+    format!("{}:{}", kind.as_str(), id)
+}
+
+/// Trims the text and collapses its spaces.
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
@@ -1,19 +1,25 @@
-use itertools::Itertools;
 use music_player_settings::get_application_directory;
 use music_player_types::types::{SimplifiedSong, Song};
 use tantivy::{
-    collector::TopDocs,
-    directory::MmapDirectory,
     doc,
-    query::{FuzzyTermQuery, PhraseQuery, TermQuery},
-    schema::{IndexRecordOption, Schema, SchemaBuilder, STORED, STRING, TEXT},
+    schema::{Schema, SchemaBuilder, INDEXED, STORED, STRING},
     Document, Index, IndexReader, ReloadPolicy, Term,
 };
+
+use super::{
+    analyzer::text_options,
+    commit_writer, open_index,
+    query::{search_page, QueryFields, SearchPage, SearchQuery},
+    rollback_writer, with_writer,
+};
+
 #[derive(Clone)]
 pub struct TrackSearcher {
     schema: Schema,
     index: Index,
+    index_path: String,
     reader: IndexReader,
+    outdated: bool,
 }
 
 impl TrackSearcher {
@@ -23,18 +29,18 @@ impl TrackSearcher {
         let mut schema_builder: SchemaBuilder = Schema::builder();
 
         schema_builder.add_text_field("id", STRING | STORED);
-        schema_builder.add_text_field("title", TEXT | STORED);
-        schema_builder.add_text_field("artist", TEXT | STORED);
-        schema_builder.add_text_field("album", TEXT | STORED);
-        schema_builder.add_text_field("genre", TEXT);
+        schema_builder.add_text_field("title", text_options() | STORED);
+        schema_builder.add_text_field("artist", text_options() | STORED);
+        schema_builder.add_text_field("album", text_options() | STORED);
+        schema_builder.add_text_field("genre", text_options());
         schema_builder.add_text_field("cover", STRING | STORED);
         schema_builder.add_i64_field("duration", STORED);
         schema_builder.add_text_field("artistId", STRING | STORED);
         schema_builder.add_text_field("albumId", STRING | STORED);
+        schema_builder.add_i64_field("year", INDEXED | STORED);
 
         let schema: Schema = schema_builder.build();
-        let dir = MmapDirectory::open(&index_path).unwrap();
-        let index: Index = Index::open_or_create(dir, schema.clone()).unwrap();
+        let (index, outdated) = open_index(&index_path, &schema);
         let reader = index
             .reader_builder()
             .reload_policy(ReloadPolicy::OnCommit)
@@ -44,10 +50,18 @@ impl TrackSearcher {
         Self {
             schema: schema.clone(),
             index,
+            index_path,
             reader,
+            outdated,
         }
     }
 
+    /// Whether the index was written by an older version and replaced by an
+    /// empty one.
+    pub fn is_outdated(&self) -> bool {
+        self.outdated
+    }
+
     pub fn get_schema(&self) -> Schema {
         return self.schema.clone();
     }
@@ -56,7 +70,46 @@ impl TrackSearcher {
         return self.index.clone();
     }
 
+    /// Replaces the document of the track with the same id, the change is
+    /// visible once committed.
     pub fn insert(&self, song: Song, str_id: &str) -> tantivy::Result<()> {
+        let id = self.schema.get_field("id").unwrap();
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(id, str_id));
+            writer.add_document(self.document(song, str_id))?;
+            Ok(())
+        })
+    }
+
+    pub fn delete(&self, str_id: &str) -> tantivy::Result<()> {
+        let id = self.schema.get_field("id").unwrap();
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_term(Term::from_field_text(id, str_id));
+            Ok(())
+        })
+    }
+
+    /// Deletes all the documents of the index, the change is visible once
+    /// committed.
+    pub fn clear(&self) -> tantivy::Result<()> {
+        with_writer(&self.index_path, &self.index, |writer| {
+            writer.delete_all_documents()?;
+            Ok(())
+        })
+    }
+
+    /// Makes the pending changes visible and closes the writer of the index.
+    pub fn commit(&self) -> tantivy::Result<()> {
+        commit_writer(&self.index_path)?;
+        self.reader.reload()
+    }
+
+    /// Drops the pending changes.
+    pub fn rollback(&self) -> tantivy::Result<()> {
+        rollback_writer(&self.index_path)
+    }
+
+    fn document(&self, song: Song, str_id: &str) -> Document {
         let id = self.schema.get_field("id").unwrap();
         let title = self.schema.get_field("title").unwrap();
         let artist = self.schema.get_field("artist").unwrap();
@@ -66,19 +119,11 @@ impl TrackSearcher {
         let duration = self.schema.get_field("duration").unwrap();
         let artist_id = self.schema.get_field("artistId").unwrap();
         let album_id = self.schema.get_field("albumId").unwrap();
-
-        // Check if the song is already in the index
-        let searcher = self.reader.searcher();
-        let term = Term::from_field_text(id, str_id);
-        let query = TermQuery::new(term, IndexRecordOption::Basic);
-        let top_docs = searcher.search(&query, &TopDocs::with_limit(1))?;
-        if top_docs.len() > 0 {
-            return Ok(());
-        }
+        let year = self.schema.get_field("year").unwrap();
 
         let time = song.duration.as_secs_f32() as i64;
 
-        let doc: Document = doc!(
+        let mut document = doc!(
             id => str_id,
             title => song.title.clone(),
             artist => song.artist.clone(),
@@ -89,131 +134,39 @@ impl TrackSearcher {
             artist_id => format!("{:x}", md5::compute(song.album_artist.to_owned())),
             album_id => format!("{:x}", md5::compute(song.album.to_owned()))
         );
-
-        let mut writer = self.index.writer_with_num_threads(64, 192_000_000)?;
-        writer.add_document(doc)?;
-        writer.commit()?;
-        Ok(())
-    }
-
-    pub fn search(&self, term: &str) -> tantivy::Result<Vec<SimplifiedSong>> {
-        let result_by_title = self.search_by_title(term)?;
-        let result_by_artist = self.search_by_artist(term)?;
-        let result_by_album = self.search_by_album(term)?;
-        let result_by_genre = self.search_by_genre(term)?;
-        Ok([
-            result_by_title,
-            result_by_artist,
-            result_by_album,
-            result_by_genre,
-        ]
-        .concat()
-        .into_iter()
-        .unique_by(|x| x.id.clone())
-        .collect())
-    }
-
-    pub fn search_by_title(&self, s: &str) -> tantivy::Result<Vec<SimplifiedSong>> {
-        let searcher = self.reader.searcher();
-        let title = self.schema.get_field("title").unwrap();
-
-        let top_docs = match s.split(" ").collect::<Vec<&str>>().len() {
-            1 => {
-                let term = Term::from_field_text(title, s);
-                let query = FuzzyTermQuery::new(term, 1, true);
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-            _ => {
-                let query = PhraseQuery::new(
-                    s.split(" ")
-                        .map(|s| Term::from_field_text(title, s))
-                        .collect(),
-                );
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-        };
-
-        Ok(top_docs
-            .into_iter()
-            .map(|(_, x)| Into::into(searcher.doc(x).unwrap()))
-            .collect())
-    }
-
-    pub fn search_by_artist(&self, s: &str) -> tantivy::Result<Vec<SimplifiedSong>> {
-        let searcher = self.reader.searcher();
-        let artist = self.schema.get_field("artist").unwrap();
-
-        let top_docs = match s.split(" ").collect::<Vec<&str>>().len() {
-            1 => {
-                let term = Term::from_field_text(artist, s);
-                let query = FuzzyTermQuery::new(term, 1, true);
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-            _ => {
-                let query = PhraseQuery::new(
-                    s.split(" ")
-                        .map(|s| Term::from_field_text(artist, s))
-                        .collect(),
-                );
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-        };
-
-        Ok(top_docs
-            .into_iter()
-            .map(|(_, x)| Into::into(searcher.doc(x).unwrap()))
-            .collect())
-    }
-
-    pub fn search_by_album(&self, s: &str) -> tantivy::Result<Vec<SimplifiedSong>> {
-        let searcher = self.reader.searcher();
-        let album = self.schema.get_field("album").unwrap();
-
-        let top_docs = match s.split(" ").collect::<Vec<&str>>().len() {
-            1 => {
-                let term = Term::from_field_text(album, s);
-                let query = FuzzyTermQuery::new(term, 1, true);
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-            _ => {
-                let query = PhraseQuery::new(
-                    s.split(" ")
-                        .map(|s| Term::from_field_text(album, s))
-                        .collect(),
-                );
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-        };
-
-        Ok(top_docs
-            .into_iter()
-            .map(|(_, x)| Into::into(searcher.doc(x).unwrap()))
-            .collect())
+        if let Some(value) = song.year {
+            document.add_i64(year, i64::from(value));
+        }
+        document
     }
 
-    pub fn search_by_genre(&self, s: &str) -> tantivy::Result<Vec<SimplifiedSong>> {
-        let searcher = self.reader.searcher();
-        let genre = self.schema.get_field("genre").unwrap();
-
-        let top_docs = match s.split(" ").collect::<Vec<&str>>().len() {
-            1 => {
-                let term = Term::from_field_text(genre, s);
-                let query = FuzzyTermQuery::new(term, 1, true);
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
-            _ => {
-                let query = PhraseQuery::new(
-                    s.split(" ")
-                        .map(|s| Term::from_field_text(genre, s))
-                        .collect(),
-                );
-                searcher.search(&query, &TopDocs::with_limit(10))?
-            }
+    pub fn search(
+        &self,
+        query: &SearchQuery,
+        offset: usize,
+        limit: usize,
+    ) -> tantivy::Result<SearchPage<SimplifiedSong>> {
+        let field = |name| self.schema.get_field(name).unwrap();
+        let fields = QueryFields {
+            default: vec![
+                (field("title"), 2.0),
+                (field("artist"), 1.5),
+                (field("album"), 1.0),
+                (field("genre"), 0.5),
+            ],
+            named: vec![
+                ("title", field("title")),
+                ("artist", field("artist")),
+                ("album", field("album")),
+                ("genre", field("genre")),
+                ("year", field("year")),
+            ],
         };
-
-        Ok(top_docs
-            .into_iter()
-            .map(|(_, x)| Into::into(searcher.doc(x).unwrap()))
-            .collect())
+        search_page(
+            &self.reader,
+            query.build(&self.index, &fields),
+            offset,
+            limit,
+        )
     }
 }
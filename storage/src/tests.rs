@@ -1,9 +1,43 @@
-use crate::searcher::Searcher;
+use crate::repo::{
+    album::{AlbumField, AlbumOrder, AlbumRepository},
+    artist::ArtistRepository,
+    genre::GenreRepository,
+    history::HistoryRepository,
+    page::{Page, PageRequest},
+    playlist::PlaylistRepository,
+    rating::RatingRepository,
+    smart_playlist::SmartPlaylistRepository,
+    track::{AudioFilter, TrackField, TrackFilter, TrackOrder, TrackRepository},
+};
+use crate::searcher::{
+    artist::ArtistSearcher,
+    query::{QueryClause, QueryValue, SearchQuery, MAX_LIMIT, MAX_OFFSET},
+    suggestion::SuggestionKind,
+    Searcher,
+};
 
 use super::*;
-use music_player_types::types::{Album, Artist, Song};
-use sea_orm::{ConnectionTrait, DbBackend};
-use std::{env, time::Duration};
+use music_player_entity::{
+    album as album_entity, artist as artist_entity, artist_tracks as artist_tracks_entity,
+    folder as folder_entity, genre as genre_entity, genre_tracks as genre_tracks_entity,
+    history as history_entity, playlist as playlist_entity,
+    playlist_tracks as playlist_tracks_entity, smart_playlist as smart_playlist_entity,
+    track as track_entity, track_rating as track_rating_entity,
+};
+use music_player_types::{
+    genre::genre_id,
+    smart_playlist::SmartQuery,
+    types::{Album, Artist, Song},
+};
+use sea_orm::{
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectOptions, ConnectionTrait, DbBackend,
+    EntityTrait, QueryFilter, QueryOrder, Schema as DbSchema,
+};
+use std::{env, fs, time::Duration};
+use tantivy::{
+    schema::{Schema, STORED, STRING, TEXT},
+    Index,
+};
 use tokio::time::sleep;
 
 #[tokio::test]
@@ -73,6 +107,7 @@ async fn search_album() {
         ..Default::default()
     };
     searcher.insert_album(album).unwrap();
+    searcher.commit().unwrap();
 
     sleep(Duration::from_secs(1)).await;
 
@@ -90,6 +125,7 @@ async fn search_artist() {
         ..Default::default()
     };
     searcher.insert_artist(artist).unwrap();
+    searcher.commit().unwrap();
 
     sleep(Duration::from_secs(1)).await;
 
@@ -111,9 +147,1128 @@ async fn search_track() {
     searcher
         .insert_song(song, "27234641d4f5f9e0832affa79b9f62d8")
         .unwrap();
+    searcher.commit().unwrap();
 
     sleep(Duration::from_secs(1)).await;
 
     let tracks = searcher.search_song("futsal").unwrap();
     assert_eq!(tracks.len(), 1);
 }
+
+#[test]
+fn insert_track_twice() {
+    env::set_var(
+        "MUSIC_PLAYER_APPLICATION_DIRECTORY",
+        "/tmp/search_track_twice",
+    );
+    let searcher = Searcher::new();
+    let song = Song {
+        title: "Futsal Shuffle 2020".to_owned(),
+        album: "Eternal Atake".to_owned(),
+        artist: "Lil Uzi Vert".to_owned(),
+        ..Default::default()
+    };
+
+    searcher
+        .insert_song(song.clone(), "27234641d4f5f9e0832affa79b9f62d8")
+        .unwrap();
+    searcher.commit().unwrap();
+    searcher
+        .insert_song(song, "27234641d4f5f9e0832affa79b9f62d8")
+        .unwrap();
+    searcher.commit().unwrap();
+
+    let tracks = searcher.search_song("futsal").unwrap();
+    assert_eq!(tracks.len(), 1);
+}
+
+#[test]
+fn parse_search_query() {
+    let query = SearchQuery::parse(r#"artist:"miles davis" year:1955..1960 Genre:jazz -live"#);
+    assert_eq!(
+        query.clauses,
+        vec![
+            QueryClause {
+                field: Some("artist".to_owned()),
+                value: QueryValue::Text("miles davis".to_owned()),
+                quoted: true,
+                excluded: false,
+            },
+            QueryClause {
+                field: Some("year".to_owned()),
+                value: QueryValue::Range(Some(1955), Some(1960)),
+                quoted: false,
+                excluded: false,
+            },
+            QueryClause {
+                field: Some("genre".to_owned()),
+                value: QueryValue::Text("jazz".to_owned()),
+                quoted: false,
+                excluded: false,
+            },
+            QueryClause {
+                field: None,
+                value: QueryValue::Text("live".to_owned()),
+                quoted: false,
+                excluded: true,
+            },
+        ]
+    );
+
+    let query = SearchQuery::parse("re:union year:");
+    assert_eq!(
+        query.clauses,
+        vec![
+            QueryClause {
+                field: None,
+                value: QueryValue::Text("re:union".to_owned()),
+                quoted: false,
+                excluded: false,
+            },
+            QueryClause {
+                field: None,
+                value: QueryValue::Text("year".to_owned()),
+                quoted: false,
+                excluded: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn search_with_fields() {
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", "/tmp/search_fields");
+    let searcher = Searcher::new();
+    let songs = [
+        ("So What", "Kind of Blue", 1959, "Jazz"),
+        ("So What (Live)", "Live at the Plugged Nickel", 1965, "Jazz"),
+        ("Round Midnight", "'Round About Midnight", 1957, "Jazz"),
+        ("Blue in Green (Live)", "Kind of Blue Live", 1959, "Jazz"),
+    ];
+    for (title, album, year, genre) in songs {
+        let song = Song {
+            title: title.to_owned(),
+            album: album.to_owned(),
+            artist: "Miles Davis".to_owned(),
+            album_artist: "Miles Davis".to_owned(),
+            genre: genre.to_owned(),
+            year: Some(year),
+            ..Default::default()
+        };
+        searcher
+            .insert_song(song, &format!("{:x}", md5::compute(title)))
+            .unwrap();
+    }
+    searcher.commit().unwrap();
+
+    let results = searcher
+        .search(
+            r#"artist:"miles davis" year:1955..1960 genre:jazz -live"#,
+            0,
+            10,
+        )
+        .unwrap();
+    let titles: Vec<&str> = results
+        .tracks
+        .items
+        .iter()
+        .map(|track| track.title.as_str())
+        .collect();
+    assert_eq!(results.tracks.total, 2);
+    assert!(titles.contains(&"So What"));
+    assert!(titles.contains(&"Round Midnight"));
+
+    let results = searcher.search("miles", 1, 2).unwrap();
+    assert_eq!(results.tracks.total, 4);
+    assert_eq!(results.tracks.items.len(), 2);
+
+    let results = searcher.search("composer:miles", 0, 10).unwrap();
+    assert_eq!(results.tracks.total, 0);
+
+    // An unknown field is part of the text
+    let results = searcher.search("miles:davis", 0, 10).unwrap();
+    assert_eq!(results.tracks.total, 4);
+
+    let results = searcher.search("-live", 0, 10).unwrap();
+    let titles: Vec<&str> = results
+        .tracks
+        .items
+        .iter()
+        .map(|track| track.title.as_str())
+        .collect();
+    assert_eq!(results.tracks.total, 2);
+    assert!(titles.contains(&"So What"));
+    assert!(titles.contains(&"Round Midnight"));
+
+    assert!(searcher.search("miles", 0, 0).is_err());
+    assert!(searcher.search("miles", 0, MAX_LIMIT + 1).is_err());
+    assert!(searcher.search("miles", MAX_OFFSET + 1, 10).is_err());
+    assert_eq!(
+        searcher
+            .search("miles", MAX_OFFSET, MAX_LIMIT)
+            .unwrap()
+            .tracks
+            .total,
+        4
+    );
+}
+
+#[test]
+fn suggest_while_typing() {
+    env::set_var(
+        "MUSIC_PLAYER_APPLICATION_DIRECTORY",
+        "/tmp/search_suggestions",
+    );
+    let searcher = Searcher::new();
+    let song = Song {
+        title: "So What".to_owned(),
+        album: "Kind of Blue".to_owned(),
+        artist: "Miles Davis".to_owned(),
+        album_artist: "Miles Davis".to_owned(),
+        genre: "Jazz".to_owned(),
+        ..Default::default()
+    };
+    searcher
+        .insert_song(song, "4a5ce7cf5f28ea4a1d9cd6f2e2d4a2ea")
+        .unwrap();
+    searcher
+        .insert_artist(Artist {
+            id: "c6ed4ba0e5c2e4c8a6a4a6a3a8c4c1c2".to_owned(),
+            name: "Miles Davis".to_owned(),
+            ..Default::default()
+        })
+        .unwrap();
+    searcher.commit().unwrap();
+
+    for input in ["miles d", "dav", "  MILES   DAVIS "] {
+        let suggestions = searcher.suggest(input, 10).unwrap();
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].kind, SuggestionKind::Artist);
+        assert_eq!(suggestions[0].text, "Miles Davis");
+    }
+
+    let suggestions = searcher.suggest("ja", 10).unwrap();
+    assert_eq!(suggestions.len(), 1);
+    assert_eq!(suggestions[0].kind, SuggestionKind::Genre);
+    assert_eq!(suggestions[0].id, genre_id("Jazz"));
+
+    assert_eq!(searcher.suggest("wh", 10).unwrap()[0].text, "So What");
+    assert!(searcher.suggest("davis m", 10).unwrap().is_empty());
+    assert!(searcher.suggest(" ", 10).unwrap().is_empty());
+    assert!(searcher.suggest("miles", 0).is_err());
+    assert!(searcher.suggest("miles", MAX_LIMIT + 1).is_err());
+
+    // The genre left without any track goes away with the song
+    searcher
+        .delete_song("4a5ce7cf5f28ea4a1d9cd6f2e2d4a2ea", &[genre_id("Jazz")])
+        .unwrap();
+    searcher.commit().unwrap();
+    assert!(searcher.suggest("wh", 10).unwrap().is_empty());
+    assert!(searcher.suggest("ja", 10).unwrap().is_empty());
+}
+
+#[test]
+fn search_ignores_accents_and_case() {
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", "/tmp/search_accents");
+    let searcher = Searcher::new();
+    searcher
+        .insert_artist(Artist {
+            id: "b9b2f3b3a7c9a1b6c0b5c3d4e5f60718".to_owned(),
+            name: "Beyoncé".to_owned(),
+            ..Default::default()
+        })
+        .unwrap();
+    let song = Song {
+        title: "Tsy Misy Fiainana".to_owned(),
+        artist: "Mahaleo".to_owned(),
+        album: "Malagasy Été".to_owned(),
+        ..Default::default()
+    };
+    searcher
+        .insert_song(song, "0c4b3b2a1f0e9d8c7b6a5f4e3d2c1b0a")
+        .unwrap();
+    searcher.commit().unwrap();
+
+    for query in ["beyonce", "BEYONCÉ", "Beyoncè"] {
+        let artists = searcher.search_artist(query).unwrap();
+        assert_eq!(artists.len(), 1);
+        assert_eq!(artists[0].name, "Beyoncé");
+    }
+    assert_eq!(searcher.search("album:ete", 0, 10).unwrap().tracks.total, 1);
+    assert_eq!(searcher.suggest("beyonc", 10).unwrap()[0].text, "Beyoncé");
+}
+
+#[test]
+fn release_index_lock_after_commit() {
+    let path = "/tmp/search_writer_lock";
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", path);
+    let searcher = Searcher::new();
+    let song = Song {
+        title: "Wet Dreamz".to_owned(),
+        artist: "J. Cole".to_owned(),
+        ..Default::default()
+    };
+    searcher
+        .insert_song(song, "3ac1f1651b6ef6d5f3f55b711e3bfcd1")
+        .unwrap();
+    searcher.commit().unwrap();
+
+    // Another process, e.g. a scan run from the command line, can now write
+    let index = Index::open_in_dir(format!("{}/tracks", path)).unwrap();
+    let writer = index.writer(50_000_000);
+    assert!(writer.is_ok());
+}
+
+#[test]
+fn rebuild_outdated_index() {
+    let path = "/tmp/search_outdated";
+    let _ = fs::remove_dir_all(path);
+    fs::create_dir_all(format!("{}/artists", path)).unwrap();
+
+    // An index written with the default tokenizer by an older version
+    let mut schema_builder = Schema::builder();
+    schema_builder.add_text_field("id", STRING | STORED);
+    schema_builder.add_text_field("name", TEXT | STORED);
+    Index::create_in_dir(format!("{}/artists", path), schema_builder.build()).unwrap();
+
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", path);
+    assert!(ArtistSearcher::new().is_outdated());
+    assert!(!ArtistSearcher::new().is_outdated());
+}
+
+#[tokio::test]
+async fn link_tracks_to_genres() {
+    // A single connection, each connection to an in-memory database has its own
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let db = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = DbSchema::new(DbBackend::Sqlite);
+    for statement in [
+        schema.create_table_from_entity(album_entity::Entity),
+        schema.create_table_from_entity(artist_entity::Entity),
+        schema.create_table_from_entity(artist_tracks_entity::Entity),
+        schema.create_table_from_entity(genre_entity::Entity),
+        schema.create_table_from_entity(genre_tracks_entity::Entity),
+        schema.create_table_from_entity(track_entity::Entity),
+    ] {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    let artist = Song {
+        album_artist: "OutKast".to_owned(),
+        ..Default::default()
+    };
+    artist_entity::ActiveModel::from(&artist)
+        .insert(&db)
+        .await
+        .unwrap();
+
+    let songs = [
+        ("Ms. Jackson", "Hip-Hop; Rap", "Stankonia"),
+        ("Hey Ya!", "hip hop / Funk", "Speakerboxxx"),
+        ("Roses", "Rap", "The Love Below"),
+    ];
+    for (title, genre, album) in songs {
+        let song = Song {
+            title: title.to_owned(),
+            artist: "OutKast".to_owned(),
+            album_artist: "OutKast".to_owned(),
+            album: album.to_owned(),
+            genre: genre.to_owned(),
+            uri: Some(format!("/tmp/audio/{}.mp3", title)),
+            ..Default::default()
+        };
+        album_entity::ActiveModel::from(&song)
+            .insert(&db)
+            .await
+            .unwrap();
+        track_entity::ActiveModel::from(&song)
+            .insert(&db)
+            .await
+            .unwrap();
+        GenreRepository::new(&db)
+            .link_track(&song.track_id(), &song.genre)
+            .await
+            .unwrap();
+    }
+
+    let repo = GenreRepository::new(&db);
+    let genres = repo.find_all(None, None, None).await.unwrap();
+    let names: Vec<&str> = genres.iter().map(|genre| genre.name.as_str()).collect();
+    assert_eq!(names, vec!["Funk", "Hip Hop", "Rap"]);
+
+    let hip_hop = repo.find(&genre_id("HipHop")).await.unwrap();
+    let titles: Vec<&str> = hip_hop
+        .tracks
+        .iter()
+        .map(|track| track.title.as_str())
+        .collect();
+    assert_eq!(titles, vec!["Hey Ya!", "Ms. Jackson"]);
+    let albums: Vec<&str> = hip_hop
+        .albums
+        .iter()
+        .map(|album| album.title.as_str())
+        .collect();
+    assert_eq!(albums, vec!["Speakerboxxx", "Stankonia"]);
+
+    // Retagging a track replaces its genres, the genres left empty are dropped
+    let track_id = format!("{:x}", md5::compute("/tmp/audio/Hey Ya!.mp3"));
+    repo.link_track(&track_id, "Hip-Hop").await.unwrap();
+    assert_eq!(repo.delete_unused().await.unwrap(), vec![genre_id("Funk")]);
+    let genres = repo
+        .find_all(Some("f".to_owned()), None, None)
+        .await
+        .unwrap();
+    assert!(genres.is_empty());
+    assert!(repo.find(&genre_id("Funk")).await.is_err());
+}
+
+#[tokio::test]
+async fn sort_and_filter_library() {
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let db = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = DbSchema::new(DbBackend::Sqlite);
+    for statement in [
+        schema.create_table_from_entity(album_entity::Entity),
+        schema.create_table_from_entity(artist_entity::Entity),
+        schema.create_table_from_entity(artist_tracks_entity::Entity),
+        schema.create_table_from_entity(genre_entity::Entity),
+        schema.create_table_from_entity(genre_tracks_entity::Entity),
+        schema.create_table_from_entity(history_entity::Entity),
+        schema.create_table_from_entity(track_entity::Entity),
+        schema.create_table_from_entity(track_rating_entity::Entity),
+    ] {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    let artist = Song {
+        album_artist: "OutKast".to_owned(),
+        ..Default::default()
+    };
+    artist_entity::ActiveModel::from(&artist)
+        .insert(&db)
+        .await
+        .unwrap();
+
+    let songs = [
+        ("Ms. Jackson", "Stankonia", "Hip-Hop", 2000, 320, 270, "mp3"),
+        ("Hey Ya!", "Speakerboxxx", "Funk", 2003, 256, 235, "flac"),
+        ("Roses", "The Love Below", "Rap", 2003, 128, 369, "mp3"),
+    ];
+    let mut ids = vec![];
+    for (title, album, genre, year, bitrate, duration, format) in songs {
+        let song = Song {
+            title: title.to_owned(),
+            artist: "OutKast".to_owned(),
+            album_artist: "OutKast".to_owned(),
+            album: album.to_owned(),
+            genre: genre.to_owned(),
+            year: Some(year),
+            bitrate: Some(bitrate),
+            duration: Duration::from_secs(duration),
+            uri: Some(format!("/tmp/audio/{}.{}", title, format)),
+            ..Default::default()
+        };
+        album_entity::ActiveModel::from(&song)
+            .insert(&db)
+            .await
+            .unwrap();
+        track_entity::ActiveModel::from(&song)
+            .insert(&db)
+            .await
+            .unwrap();
+        GenreRepository::new(&db)
+            .link_track(&song.track_id(), &song.genre)
+            .await
+            .unwrap();
+        ids.push(song.track_id());
+    }
+
+    // Roses played twice and Hey Ya! once, Ms. Jackson skipped three times
+    // and rated higher than Hey Ya!
+    let plays = [
+        (&ids[2], true),
+        (&ids[2], true),
+        (&ids[1], true),
+        (&ids[0], false),
+        (&ids[0], false),
+        (&ids[0], false),
+    ];
+    for (n, (track_id, completed)) in plays.into_iter().enumerate() {
+        history_entity::ActiveModel {
+            id: ActiveValue::Set(n.to_string()),
+            track_id: ActiveValue::Set(track_id.clone()),
+            started_at: ActiveValue::Set(chrono::Utc::now()),
+            listened_ms: ActiveValue::Set(1000),
+            completed: ActiveValue::Set(completed),
+            device: ActiveValue::Set(None),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    for (track_id, rating) in [(&ids[0], 5), (&ids[1], 3)] {
+        track_rating_entity::ActiveModel {
+            track_id: ActiveValue::Set(track_id.clone()),
+            rating: ActiveValue::Set(rating),
+            updated_at: ActiveValue::Set(chrono::Utc::now()),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+
+    let tracks = TrackRepository::new(&db);
+    let titles = |results: Vec<track_entity::Model>| -> Vec<String> {
+        results.into_iter().map(|track| track.title).collect()
+    };
+    let find = |tags: TrackFilter, field: TrackField, descending: bool| {
+        let tracks = &tracks;
+        async move {
+            let order = TrackOrder { field, descending };
+            tracks
+                .find_all_matching(None, &tags, &AudioFilter::default(), order, None, 10)
+                .await
+                .unwrap()
+        }
+    };
+
+    let results = find(TrackFilter::default(), TrackField::AddedAt, true).await;
+    assert!(results.iter().all(|track| track.created_at.is_some()));
+    assert_eq!(
+        titles(find(TrackFilter::default(), TrackField::Duration, false).await),
+        vec!["Hey Ya!", "Ms. Jackson", "Roses"]
+    );
+    assert_eq!(
+        titles(find(TrackFilter::default(), TrackField::PlayCount, true).await),
+        vec!["Roses", "Hey Ya!", "Ms. Jackson"]
+    );
+    assert_eq!(
+        titles(find(TrackFilter::default(), TrackField::Rating, true).await),
+        vec!["Ms. Jackson", "Hey Ya!", "Roses"]
+    );
+    let recent = TrackFilter {
+        min_year: Some(2001),
+        ..Default::default()
+    };
+    assert_eq!(
+        titles(find(recent, TrackField::Title, false).await),
+        vec!["Hey Ya!", "Roses"]
+    );
+    let flac = TrackFilter {
+        format: Some("flac".to_owned()),
+        ..Default::default()
+    };
+    assert_eq!(
+        titles(find(flac.clone(), TrackField::Title, false).await),
+        vec!["Hey Ya!"]
+    );
+    let hip_hop = TrackFilter {
+        genre: Some("hip hop".to_owned()),
+        min_bitrate: Some(200),
+        ..Default::default()
+    };
+    assert_eq!(
+        titles(find(hip_hop, TrackField::Title, false).await),
+        vec!["Ms. Jackson"]
+    );
+
+    let albums = AlbumRepository::new(&db);
+    let order = AlbumOrder {
+        field: AlbumField::Duration,
+        descending: true,
+    };
+    let results = albums
+        .find_all_matching(None, &TrackFilter::default(), order, None, None)
+        .await
+        .unwrap();
+    assert!(results.iter().all(|album| album.created_at.is_some()));
+    let names: Vec<&str> = results.iter().map(|album| album.title.as_str()).collect();
+    assert_eq!(names, vec!["The Love Below", "Stankonia", "Speakerboxxx"]);
+
+    let order = AlbumOrder {
+        field: AlbumField::PlayCount,
+        descending: true,
+    };
+    let results = albums
+        .find_all_matching(None, &TrackFilter::default(), order, None, None)
+        .await
+        .unwrap();
+    let names: Vec<&str> = results.iter().map(|album| album.title.as_str()).collect();
+    assert_eq!(names, vec!["The Love Below", "Speakerboxxx", "Stankonia"]);
+
+    let results = albums
+        .find_all_matching(None, &flac, AlbumOrder::default(), None, None)
+        .await
+        .unwrap();
+    let names: Vec<&str> = results.iter().map(|album| album.title.as_str()).collect();
+    assert_eq!(names, vec!["Speakerboxxx"]);
+}
+
+#[tokio::test]
+async fn import_ratings_once() {
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let db = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = DbSchema::new(DbBackend::Sqlite);
+    for statement in [
+        schema.create_table_from_entity(album_entity::Entity),
+        schema.create_table_from_entity(artist_entity::Entity),
+        schema.create_table_from_entity(smart_playlist_entity::Entity),
+        schema.create_table_from_entity(track_entity::Entity),
+        schema.create_table_from_entity(track_rating_entity::Entity),
+    ] {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    let album = Song {
+        artist: "OutKast".to_owned(),
+        album_artist: "OutKast".to_owned(),
+        album: "Aquemini".to_owned(),
+        ..Default::default()
+    };
+    artist_entity::ActiveModel::from(&album)
+        .insert(&db)
+        .await
+        .unwrap();
+    album_entity::ActiveModel::from(&album)
+        .insert(&db)
+        .await
+        .unwrap();
+    let mut ids = vec![];
+    for title in ["Elevators", "SpottieOttieDopaliscious"] {
+        let song = Song {
+            title: title.to_owned(),
+            uri: Some(format!("/tmp/audio/{}.mp3", title)),
+            ..album.clone()
+        };
+        track_entity::ActiveModel::from(&song)
+            .insert(&db)
+            .await
+            .unwrap();
+        ids.push(song.track_id());
+    }
+
+    let ratings = RatingRepository::new(&db);
+    ratings.import_rating(&ids[0], 4).await.unwrap();
+    assert_eq!(ratings.rating(&ids[0]).await.unwrap(), Some(4));
+    // The rating set in the player wins over the one of the file
+    ratings.set_rating(&ids[0], 2).await.unwrap();
+    ratings.import_rating(&ids[0], 4).await.unwrap();
+    assert_eq!(ratings.rating(&ids[0]).await.unwrap(), Some(2));
+
+    // A removed rating isn't imported again
+    ratings.set_rating(&ids[0], 0).await.unwrap();
+    ratings.import_rating(&ids[0], 4).await.unwrap();
+    assert_eq!(ratings.rating(&ids[0]).await.unwrap(), None);
+
+    ratings.import_rating(&ids[1], 3).await.unwrap();
+    let all = ratings.ratings(&ids).await.unwrap();
+    assert_eq!(all.len(), 1);
+    assert_eq!(all.get(&ids[1]), Some(&3));
+}
+
+#[tokio::test]
+async fn match_smart_playlist_genres() {
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let db = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = DbSchema::new(DbBackend::Sqlite);
+    for statement in [
+        schema.create_table_from_entity(album_entity::Entity),
+        schema.create_table_from_entity(artist_entity::Entity),
+        schema.create_table_from_entity(genre_entity::Entity),
+        schema.create_table_from_entity(genre_tracks_entity::Entity),
+        schema.create_table_from_entity(track_entity::Entity),
+        schema.create_table_from_entity(track_rating_entity::Entity),
+    ] {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    let album = Song {
+        artist: "Herbie Hancock".to_owned(),
+        album_artist: "Herbie Hancock".to_owned(),
+        album: "Head Hunters".to_owned(),
+        ..Default::default()
+    };
+    artist_entity::ActiveModel::from(&album)
+        .insert(&db)
+        .await
+        .unwrap();
+    album_entity::ActiveModel::from(&album)
+        .insert(&db)
+        .await
+        .unwrap();
+    let songs = [
+        ("Chameleon", "Jazz; Fusion"),
+        ("Sly", "jazz"),
+        ("Watermelon Man", "Funk"),
+    ];
+    for (title, genre) in songs {
+        let song = Song {
+            title: title.to_owned(),
+            genre: genre.to_owned(),
+            uri: Some(format!("/tmp/audio/{}.mp3", title)),
+            ..album.clone()
+        };
+        track_entity::ActiveModel::from(&song)
+            .insert(&db)
+            .await
+            .unwrap();
+        GenreRepository::new(&db)
+            .link_track(&song.track_id(), &song.genre)
+            .await
+            .unwrap();
+    }
+
+    let repo = SmartPlaylistRepository::new(&db);
+    let titles = |ids: Vec<String>| async {
+        let mut titles = vec![];
+        for id in ids {
+            let track = track_entity::Entity::find_by_id(id)
+                .one(&db)
+                .await
+                .unwrap()
+                .unwrap();
+            titles.push(track.title);
+        }
+        titles
+    };
+    // Each genre of a track with several genres is matched
+    let query = SmartQuery::parse("genre is Jazz").unwrap();
+    let ids = repo.evaluate(&query).await.unwrap();
+    assert_eq!(titles(ids).await, vec!["Chameleon", "Sly"]);
+
+    let query = SmartQuery::parse("genre contains fus").unwrap();
+    let ids = repo.evaluate(&query).await.unwrap();
+    assert_eq!(titles(ids).await, vec!["Chameleon"]);
+
+    let query = SmartQuery::parse("genre is not jazz").unwrap();
+    let ids = repo.evaluate(&query).await.unwrap();
+    assert_eq!(titles(ids).await, vec!["Watermelon Man"]);
+}
+
+#[tokio::test]
+async fn page_with_keyset_cursors() {
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let db = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = DbSchema::new(DbBackend::Sqlite);
+    for statement in [
+        schema.create_table_from_entity(album_entity::Entity),
+        schema.create_table_from_entity(artist_entity::Entity),
+        schema.create_table_from_entity(history_entity::Entity),
+        schema.create_table_from_entity(track_entity::Entity),
+    ] {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    let insert_artist = |name: &str| {
+        let song = Song {
+            album_artist: name.to_owned(),
+            ..Default::default()
+        };
+        let db = &db;
+        async move {
+            artist_entity::ActiveModel::from(&song)
+                .insert(db)
+                .await
+                .unwrap();
+        }
+    };
+    for name in ["Aaliyah", "Common", "Erykah Badu"] {
+        insert_artist(name).await;
+    }
+
+    let artists = ArtistRepository::new(&db);
+    let names = |page: &Page<artist_entity::Model>| -> Vec<String> {
+        page.items
+            .iter()
+            .map(|(_, artist)| artist.name.clone())
+            .collect()
+    };
+    let first = artists
+        .find_page(
+            None,
+            &PageRequest {
+                first: Some(2),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(&first), vec!["Aaliyah", "Common"]);
+    assert!(first.has_next_page);
+    assert!(!first.has_previous_page);
+    assert_eq!(first.total_count, 3);
+
+    // An artist added before the cursor doesn't shift the next page
+    insert_artist("Brandy").await;
+    let cursor = first.items.last().unwrap().0.clone();
+    let next = artists
+        .find_page(
+            None,
+            &PageRequest {
+                after: Some(cursor.clone()),
+                first: Some(2),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(&next), vec!["Erykah Badu"]);
+    assert!(!next.has_next_page);
+    assert!(next.has_previous_page);
+    assert_eq!(next.total_count, 4);
+
+    let previous = artists
+        .find_page(
+            None,
+            &PageRequest {
+                before: Some(cursor),
+                last: Some(1),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(names(&previous), vec!["Brandy"]);
+    assert!(previous.has_previous_page);
+    assert!(previous.has_next_page);
+
+    let song = Song {
+        title: "The Light".to_owned(),
+        artist: "Common".to_owned(),
+        album_artist: "Common".to_owned(),
+        album: "Like Water for Chocolate".to_owned(),
+        uri: Some("/tmp/audio/The Light.mp3".to_owned()),
+        ..Default::default()
+    };
+    album_entity::ActiveModel::from(&song)
+        .insert(&db)
+        .await
+        .unwrap();
+    track_entity::ActiveModel::from(&song)
+        .insert(&db)
+        .await
+        .unwrap();
+    for (id, minutes) in [("1", 10), ("2", 20), ("3", 30)] {
+        history_entity::ActiveModel {
+            id: ActiveValue::Set(id.to_owned()),
+            track_id: ActiveValue::Set(song.track_id()),
+            started_at: ActiveValue::Set(chrono::Utc::now() - chrono::Duration::minutes(minutes)),
+            listened_ms: ActiveValue::Set(1000),
+            completed: ActiveValue::Set(true),
+            device: ActiveValue::Set(None),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+    let history = HistoryRepository::new(&db);
+    let first = history
+        .find_page(&PageRequest {
+            first: Some(2),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids: Vec<&str> = first
+        .items
+        .iter()
+        .map(|(_, entry)| entry.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["1", "2"]);
+    let next = history
+        .find_page(&PageRequest {
+            after: Some(first.items[1].0.clone()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let ids: Vec<&str> = next
+        .items
+        .iter()
+        .map(|(_, entry)| entry.id.as_str())
+        .collect();
+    assert_eq!(ids, vec!["3"]);
+}
+
+/// Follows the cursors of the pages of one track, from the first page or from
+/// the last one, and checks that both ways list the same tracks.
+async fn walk_track_pages(
+    tracks: &TrackRepository,
+    field: TrackField,
+    descending: bool,
+) -> Vec<track_entity::Model> {
+    let order = TrackOrder { field, descending };
+    let mut walks = vec![];
+    for forward in [true, false] {
+        let mut results = vec![];
+        let mut cursor = None;
+        loop {
+            let request = match forward {
+                true => PageRequest {
+                    after: cursor,
+                    first: Some(1),
+                    ..Default::default()
+                },
+                false => PageRequest {
+                    before: cursor,
+                    last: Some(1),
+                    ..Default::default()
+                },
+            };
+            let page = tracks
+                .find_page(
+                    None,
+                    &TrackFilter::default(),
+                    &AudioFilter::default(),
+                    order,
+                    &request,
+                )
+                .await
+                .unwrap();
+            let more = match forward {
+                true => page.has_next_page,
+                false => page.has_previous_page,
+            };
+            let (next, track) = page.items.into_iter().next().unwrap();
+            results.push(track);
+            cursor = Some(next);
+            if !more {
+                break;
+            }
+        }
+        if !forward {
+            results.reverse();
+        }
+        walks.push(results);
+    }
+    let ids = |tracks: &[track_entity::Model]| -> Vec<String> {
+        tracks.iter().map(|track| track.id.clone()).collect()
+    };
+    assert_eq!(ids(&walks[0]), ids(&walks[1]));
+    walks.remove(0)
+}
+
+#[tokio::test]
+async fn page_tracks_by_sort_field() {
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let db = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = DbSchema::new(DbBackend::Sqlite);
+    for statement in [
+        schema.create_table_from_entity(album_entity::Entity),
+        schema.create_table_from_entity(artist_entity::Entity),
+        schema.create_table_from_entity(artist_tracks_entity::Entity),
+        schema.create_table_from_entity(history_entity::Entity),
+        schema.create_table_from_entity(track_entity::Entity),
+        schema.create_table_from_entity(track_rating_entity::Entity),
+    ] {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    // Two tracks without a year nor a scan date, one of them not analyzed
+    let now = chrono::Utc::now();
+    let songs = [
+        ("Ms. Jackson", Some(2000), Some(-9.5), Some(2)),
+        ("Hey Ya!", None, None, None),
+        ("Roses", Some(2003), Some(-7.25), Some(1)),
+        ("So Fresh, So Clean", None, Some(-8.0), None),
+    ];
+    let mut ids = vec![];
+    for (title, year, loudness, hours_ago) in songs {
+        let song = Song {
+            title: title.to_owned(),
+            artist: "OutKast".to_owned(),
+            album_artist: "OutKast".to_owned(),
+            album: "Stankonia".to_owned(),
+            year,
+            uri: Some(format!("/tmp/audio/{}.mp3", title)),
+            ..Default::default()
+        };
+        if ids.is_empty() {
+            artist_entity::ActiveModel::from(&song)
+                .insert(&db)
+                .await
+                .unwrap();
+            album_entity::ActiveModel::from(&song)
+                .insert(&db)
+                .await
+                .unwrap();
+        }
+        let mut track = track_entity::ActiveModel::from(&song);
+        track.loudness = ActiveValue::Set(loudness);
+        track.created_at =
+            ActiveValue::Set(hours_ago.map(|hours| now - chrono::Duration::hours(hours)));
+        track.insert(&db).await.unwrap();
+        ids.push(song.track_id());
+    }
+    for (track_id, rating) in [(&ids[0], 5), (&ids[2], 3)] {
+        track_rating_entity::ActiveModel {
+            track_id: ActiveValue::Set(track_id.clone()),
+            rating: ActiveValue::Set(rating),
+            updated_at: ActiveValue::Set(now),
+        }
+        .insert(&db)
+        .await
+        .unwrap();
+    }
+
+    let tracks = TrackRepository::new(&db);
+    let years = |tracks: Vec<track_entity::Model>| -> Vec<Option<u32>> {
+        tracks.into_iter().map(|track| track.year).collect()
+    };
+    assert_eq!(
+        years(walk_track_pages(&tracks, TrackField::Year, false).await),
+        vec![None, None, Some(2000), Some(2003)]
+    );
+    assert_eq!(
+        years(walk_track_pages(&tracks, TrackField::Year, true).await),
+        vec![Some(2003), Some(2000), None, None]
+    );
+
+    let titles = |tracks: Vec<track_entity::Model>| -> Vec<String> {
+        tracks.into_iter().map(|track| track.title).collect()
+    };
+    let recent = titles(walk_track_pages(&tracks, TrackField::AddedAt, true).await);
+    assert_eq!(recent[..2], ["Roses", "Ms. Jackson"]);
+    let rated = titles(walk_track_pages(&tracks, TrackField::Rating, true).await);
+    assert_eq!(rated[..2], ["Ms. Jackson", "Roses"]);
+    assert_eq!(
+        titles(walk_track_pages(&tracks, TrackField::Title, false).await),
+        vec!["Hey Ya!", "Ms. Jackson", "Roses", "So Fresh, So Clean"]
+    );
+    assert_eq!(
+        walk_track_pages(&tracks, TrackField::PlayCount, true)
+            .await
+            .len(),
+        4
+    );
+
+    // The tracks which weren't analyzed come last in both directions
+    let loudness = |tracks: Vec<track_entity::Model>| -> Vec<Option<f32>> {
+        tracks.into_iter().map(|track| track.loudness).collect()
+    };
+    assert_eq!(
+        loudness(walk_track_pages(&tracks, TrackField::Loudness, false).await),
+        vec![Some(-9.5), Some(-8.0), Some(-7.25), None]
+    );
+    assert_eq!(
+        loudness(walk_track_pages(&tracks, TrackField::Loudness, true).await),
+        vec![Some(-7.25), Some(-8.0), Some(-9.5), None]
+    );
+}
+
+#[tokio::test]
+async fn reorder_playlist_tracks() {
+    let mut opt = ConnectOptions::new("sqlite::memory:".to_owned());
+    opt.max_connections(1);
+    let db = sea_orm::Database::connect(opt).await.unwrap();
+    let schema = DbSchema::new(DbBackend::Sqlite);
+    for statement in [
+        schema.create_table_from_entity(album_entity::Entity),
+        schema.create_table_from_entity(artist_entity::Entity),
+        schema.create_table_from_entity(folder_entity::Entity),
+        schema.create_table_from_entity(playlist_entity::Entity),
+        schema.create_table_from_entity(playlist_tracks_entity::Entity),
+        schema.create_table_from_entity(track_entity::Entity),
+    ] {
+        db.execute(db.get_database_backend().build(&statement))
+            .await
+            .unwrap();
+    }
+
+    let mut ids = vec![];
+    for title in ["Nas Is Like", "N.Y. State of Mind", "The World Is Yours"] {
+        let song = Song {
+            title: title.to_owned(),
+            artist: "Nas".to_owned(),
+            album_artist: "Nas".to_owned(),
+            album: "Illmatic".to_owned(),
+            uri: Some(format!("/tmp/audio/{}.mp3", title)),
+            ..Default::default()
+        };
+        if ids.is_empty() {
+            artist_entity::ActiveModel::from(&song)
+                .insert(&db)
+                .await
+                .unwrap();
+            album_entity::ActiveModel::from(&song)
+                .insert(&db)
+                .await
+                .unwrap();
+        }
+        track_entity::ActiveModel::from(&song)
+            .insert(&db)
+            .await
+            .unwrap();
+        ids.push(song.track_id());
+    }
+    playlist_entity::ActiveModel {
+        id: ActiveValue::Set("playlist".to_owned()),
+        name: ActiveValue::Set("Queensbridge".to_owned()),
+        description: ActiveValue::Set(None),
+        folder_id: ActiveValue::Set(None),
+        created_at: ActiveValue::Set(chrono::Utc::now()),
+        file_hash: ActiveValue::Set(None),
+    }
+    .insert(&db)
+    .await
+    .unwrap();
+
+    let playlists = PlaylistRepository::new(&db);
+    let order = || async {
+        playlist_tracks_entity::Entity::find()
+            .filter(playlist_tracks_entity::Column::PlaylistId.eq("playlist"))
+            .order_by_asc(playlist_tracks_entity::Column::Position)
+            .all(&db)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|item| {
+                let index = ids.iter().position(|id| *id == item.track_id).unwrap();
+                (item.position, index)
+            })
+            .collect::<Vec<(i32, usize)>>()
+    };
+
+    let items = playlists
+        .insert_tracks("playlist", &[ids[0].clone(), ids[1].clone()], None)
+        .await
+        .unwrap();
+    assert_eq!(items[0].position, 0);
+    assert_eq!(items[1].track_id, ids[1]);
+    // The same track twice, inserted in the middle
+    let items = playlists
+        .insert_tracks("playlist", &[ids[2].clone(), ids[2].clone()], Some(1))
+        .await
+        .unwrap();
+    assert_eq!(items[0].position, 1);
+    assert_ne!(items[0].id, items[1].id);
+    assert_eq!(order().await, vec![(0, 0), (1, 2), (2, 2), (3, 1)]);
+
+    assert_eq!(playlists.move_track("playlist", 3, 0).await.unwrap(), 0);
+    assert_eq!(order().await, vec![(0, 1), (1, 0), (2, 2), (3, 2)]);
+    assert_eq!(playlists.move_track("playlist", 1, 10).await.unwrap(), 3);
+    assert_eq!(order().await, vec![(0, 1), (1, 2), (2, 2), (3, 0)]);
+    assert!(playlists.move_track("playlist", 4, 0).await.is_err());
+
+    let removed = playlists.remove_track("playlist", 1).await.unwrap();
+    assert_eq!(removed, ids[2]);
+    assert_eq!(order().await, vec![(0, 1), (1, 2), (2, 0)]);
+}
@@ -0,0 +1,66 @@
+/// Separators of the genres of a multi-valued tag, e.g. `Rock; Pop` or
+/// `Jazz/Funk`. ID3v2.4 frames separate their values with a null character.
+const GENRE_SEPARATORS: [char; 4] = [';', '/', ',', '\0'];
+
+/// Written by the scanner when a file has no genre tag.
+const MISSING_GENRE: &str = "None";
+
+/// Spellings of the same genre, by key, and the name they are shown with.
+const GENRE_ALIASES: [(&str, &str); 11] = [
+    ("hip hop", "Hip Hop"),
+    ("hiphop", "Hip Hop"),
+    ("rnb", "R&B"),
+    ("r&b", "R&B"),
+    ("r and b", "R&B"),
+    ("rhythm and blues", "R&B"),
+    ("rock n roll", "Rock & Roll"),
+    ("rock and roll", "Rock & Roll"),
+    ("rock & roll", "Rock & Roll"),
+    ("drum n bass", "Drum & Bass"),
+    ("drum and bass", "Drum & Bass"),
+];
+
+/// Identifies the spellings of a genre: lowercased, with dashes, underscores
+/// and repeated spaces turned into single spaces, then resolved through the
+/// aliases, so "Hip-Hop", "hip hop" and "HipHop" share the same key.
+pub fn genre_key(name: &str) -> String {
+    let key = name
+        .to_lowercase()
+        .replace(['-', '_', '\''], " ")
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    match GENRE_ALIASES.iter().find(|(alias, _)| *alias == key) {
+        Some((_, name)) => name.to_lowercase(),
+        None => key,
+    }
+}
+
+/// The id of a genre, the same for all its spellings.
+pub fn genre_id(name: &str) -> String {
+    format!("{:x}", md5::compute(genre_key(name)))
+}
+
+/// Splits the genre tag of a track into its genres, each one once, with the
+/// name of its alias when it has one. A missing tag has no genre.
+pub fn split_genres(value: &str) -> Vec<String> {
+    let mut genres: Vec<String> = vec![];
+    for genre in value.split(GENRE_SEPARATORS) {
+        let genre = genre.split_whitespace().collect::<Vec<&str>>().join(" ");
+        if genre.is_empty() || genre == MISSING_GENRE {
+            continue;
+        }
+        let key = genre_key(&genre);
+        let name = match GENRE_ALIASES
+            .iter()
+            .find(|(_, name)| name.to_lowercase() == key)
+        {
+            Some((_, name)) => name.to_string(),
+            None => genre,
+        };
+        if !genres.iter().any(|genre| genre_key(genre) == key) {
+            genres.push(name);
+        }
+    }
+    genres
+}
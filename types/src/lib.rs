@@ -1,4 +1,7 @@
 #[cfg(test)]
 mod tests;
 
+pub mod genre;
+pub mod rating;
+pub mod smart_playlist;
 pub mod types;
@@ -0,0 +1,118 @@
+use lofty::{ItemKey, ItemValue, Tag, TagItem, TagType};
+
+pub const MAX_RATING: u8 = 5;
+
+/// Written in the POPM frames created by the player, the frames of other
+/// players keep their email.
+const POPM_EMAIL: &str = "music-player";
+
+/// What can be marked as a favourite.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FavouriteKind {
+    Track,
+    Album,
+    Artist,
+}
+
+impl FavouriteKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FavouriteKind::Track => "track",
+            FavouriteKind::Album => "album",
+            FavouriteKind::Artist => "artist",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "track" => Some(FavouriteKind::Track),
+            "album" => Some(FavouriteKind::Album),
+            "artist" => Some(FavouriteKind::Artist),
+            _ => None,
+        }
+    }
+}
+
+/// Maps the 1-255 rating of a POPM frame to stars, with the ranges used by
+/// Windows Media Player and foobar2000. 0 means the track isn't rated.
+pub fn stars_from_popm(value: u8) -> u8 {
+    match value {
+        0 => 0,
+        1..=31 => 1,
+        32..=95 => 2,
+        96..=159 => 3,
+        160..=223 => 4,
+        _ => 5,
+    }
+}
+
+pub fn stars_to_popm(stars: u8) -> u8 {
+    [0, 1, 64, 128, 196, 255][stars.min(MAX_RATING) as usize]
+}
+
+/// FMPS_Rating holds a number between 0.0 and 1.0.
+pub fn stars_from_fmps(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * MAX_RATING as f32).round() as u8
+}
+
+pub fn stars_to_fmps(stars: u8) -> String {
+    format!("{:.1}", stars.min(MAX_RATING) as f32 / MAX_RATING as f32)
+}
+
+fn fmps_key(tag_type: TagType) -> ItemKey {
+    match tag_type {
+        TagType::VorbisComments => ItemKey::Unknown("FMPS_RATING".to_string()),
+        TagType::MP4ilst => ItemKey::Unknown("----:com.apple.iTunes:FMPS_Rating".to_string()),
+        _ => ItemKey::Unknown("FMPS_Rating".to_string()),
+    }
+}
+
+/// Reads the stars of a track from its POPM frame (ID3v2) or its
+/// FMPS_Rating item, `None` when the track isn't rated.
+pub fn read_rating(tag: &Tag) -> Option<u8> {
+    // POPM: null terminated email, rating, then an optional play counter
+    let popm = tag
+        .get_binary(&ItemKey::Popularimeter, false)
+        .and_then(|bytes| {
+            let end = bytes.iter().position(|b| *b == 0)?;
+            bytes.get(end + 1)
+        })
+        .map(|rating| stars_from_popm(*rating));
+    let stars = match popm {
+        Some(stars) => stars,
+        None => tag
+            .get_string(&fmps_key(tag.tag_type()))
+            .and_then(|value| value.trim().parse::<f32>().ok())
+            .map(stars_from_fmps)?,
+    };
+    Some(stars).filter(|stars| *stars > 0)
+}
+
+/// Writes the stars into a POPM frame for ID3v2 tags, into FMPS_Rating
+/// otherwise. 0 stars removes the rating.
+pub fn write_rating(tag: &mut Tag, stars: u8) {
+    if tag.tag_type() != TagType::ID3v2 {
+        let key = fmps_key(tag.tag_type());
+        match stars {
+            0 => tag.remove_key(&key),
+            _ => {
+                tag.insert_item_unchecked(TagItem::new(key, ItemValue::Text(stars_to_fmps(stars))))
+            }
+        }
+        return;
+    }
+
+    let mut frame = match tag.get_binary(&ItemKey::Popularimeter, false) {
+        Some(bytes) if bytes.contains(&0) => bytes.to_vec(),
+        _ => [POPM_EMAIL.as_bytes(), &[0]].concat(),
+    };
+    let position = frame.iter().position(|b| *b == 0).unwrap_or_default() + 1;
+    if frame.len() == position {
+        frame.push(0);
+    }
+    frame[position] = stars_to_popm(stars);
+    tag.insert_item(TagItem::new(
+        ItemKey::Popularimeter,
+        ItemValue::Binary(frame),
+    ));
+}
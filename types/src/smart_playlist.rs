@@ -0,0 +1,511 @@
+use std::fmt;
+
+/// The track attributes the rules of a smart playlist can test.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartField {
+    Title,
+    Artist,
+    Album,
+    AlbumArtist,
+    Genre,
+    Year,
+    /// In seconds
+    Duration,
+    /// In kbps
+    Bitrate,
+    Composer,
+    Label,
+    Bpm,
+    Tempo,
+    Loudness,
+    Key,
+    /// The extension of the file, such as `flac`
+    Format,
+    /// From 1 to 5 stars
+    Rating,
+}
+
+const FIELDS: [(SmartField, &str); 16] = [
+    (SmartField::Title, "title"),
+    (SmartField::Artist, "artist"),
+    (SmartField::Album, "album"),
+    (SmartField::AlbumArtist, "album_artist"),
+    (SmartField::Genre, "genre"),
+    (SmartField::Year, "year"),
+    (SmartField::Duration, "duration"),
+    (SmartField::Bitrate, "bitrate"),
+    (SmartField::Composer, "composer"),
+    (SmartField::Label, "label"),
+    (SmartField::Bpm, "bpm"),
+    (SmartField::Tempo, "tempo"),
+    (SmartField::Loudness, "loudness"),
+    (SmartField::Key, "key"),
+    (SmartField::Format, "format"),
+    (SmartField::Rating, "rating"),
+];
+
+impl SmartField {
+    pub fn as_str(&self) -> &'static str {
+        FIELDS
+            .iter()
+            .find(|(field, _)| field == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default()
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        FIELDS
+            .iter()
+            .find(|(_, field)| field.eq_ignore_ascii_case(name))
+            .map(|(field, _)| *field)
+    }
+
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            SmartField::Year
+                | SmartField::Duration
+                | SmartField::Bitrate
+                | SmartField::Bpm
+                | SmartField::Tempo
+                | SmartField::Loudness
+                | SmartField::Rating
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartOperator {
+    Is,
+    IsNot,
+    Contains,
+    NotContains,
+    StartsWith,
+    EndsWith,
+    Greater,
+    GreaterOrEqual,
+    Less,
+    LessOrEqual,
+}
+
+const OPERATORS: [(SmartOperator, &str); 10] = [
+    (SmartOperator::Is, "is"),
+    (SmartOperator::IsNot, "is not"),
+    (SmartOperator::Contains, "contains"),
+    (SmartOperator::NotContains, "does not contain"),
+    (SmartOperator::StartsWith, "starts with"),
+    (SmartOperator::EndsWith, "ends with"),
+    (SmartOperator::Greater, ">"),
+    (SmartOperator::GreaterOrEqual, ">="),
+    (SmartOperator::Less, "<"),
+    (SmartOperator::LessOrEqual, "<="),
+];
+
+impl SmartOperator {
+    pub fn as_str(&self) -> &'static str {
+        OPERATORS
+            .iter()
+            .find(|(operator, _)| operator == self)
+            .map(|(_, name)| *name)
+            .unwrap_or_default()
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        OPERATORS
+            .iter()
+            .find(|(_, operator)| operator.eq_ignore_ascii_case(name))
+            .map(|(operator, _)| *operator)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartCondition {
+    pub field: SmartField,
+    pub operator: SmartOperator,
+    pub value: String,
+}
+
+/// The rule tree of a smart playlist.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SmartRule {
+    All(Vec<SmartRule>),
+    Any(Vec<SmartRule>),
+    Not(Box<SmartRule>),
+    Condition(SmartCondition),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmartSort {
+    Random,
+    Field(SmartField),
+}
+
+/// A smart playlist definition, written as
+/// `genre is Jazz and year between 1955 and 1965, sorted by random, limit 100`.
+///
+/// Conditions are `field operator value`, where the operator is one of `is`,
+/// `is not`, `contains`, `does not contain`, `starts with`, `ends with`,
+/// `>`, `>=`, `<`, `<=` or `between a and b`. They are combined with `and`,
+/// `or`, `not` and parentheses. Values with spaces may be quoted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SmartQuery {
+    pub rule: SmartRule,
+    pub sort: Option<SmartSort>,
+    pub descending: bool,
+    pub limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Quoted(String),
+    Symbol(&'static str),
+}
+
+impl Token {
+    fn is_word(&self, word: &str) -> bool {
+        matches!(self, Token::Word(value) if value.eq_ignore_ascii_case(word))
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            Token::Word(value) | Token::Quoted(value) => value,
+            Token::Symbol(symbol) => symbol,
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = vec![];
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some(next) if next == c => break,
+                        Some(next) => value.push(next),
+                        None => return Err("Unterminated quoted value".to_string()),
+                    }
+                }
+                tokens.push(Token::Quoted(value));
+            }
+            '(' | ')' | ',' => {
+                chars.next();
+                tokens.push(Token::Symbol(match c {
+                    '(' => "(",
+                    ')' => ")",
+                    _ => ",",
+                }));
+            }
+            '<' | '>' | '=' | '!' => {
+                chars.next();
+                let equal = chars.next_if_eq(&'=').is_some();
+                tokens.push(Token::Symbol(match (c, equal) {
+                    ('<', false) => "<",
+                    ('<', true) => "<=",
+                    ('>', false) => ">",
+                    ('>', true) => ">=",
+                    ('=', _) => "=",
+                    ('!', true) => "!=",
+                    _ => return Err("Unexpected `!`".to_string()),
+                }));
+            }
+            _ => {
+                let mut value = String::new();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || "()<>=!,\"".contains(next) {
+                        break;
+                    }
+                    value.push(next);
+                    chars.next();
+                }
+                tokens.push(Token::Word(value));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn accept_word(&mut self, word: &str) -> bool {
+        match self.peek() {
+            Some(token) if token.is_word(word) => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn accept_symbol(&mut self, symbol: &str) -> bool {
+        match self.peek() {
+            Some(Token::Symbol(value)) if *value == symbol => {
+                self.position += 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn expect_word(&mut self, word: &str) -> Result<(), String> {
+        match self.accept_word(word) {
+            true => Ok(()),
+            false => Err(format!("Expected `{}`", word)),
+        }
+    }
+
+    fn or(&mut self) -> Result<SmartRule, String> {
+        let mut rules = vec![self.and()?];
+        while self.accept_word("or") {
+            rules.push(self.and()?);
+        }
+        Ok(match rules.len() {
+            1 => rules.remove(0),
+            _ => SmartRule::Any(rules),
+        })
+    }
+
+    fn and(&mut self) -> Result<SmartRule, String> {
+        let mut rules = vec![self.unary()?];
+        while self.accept_word("and") {
+            rules.push(self.unary()?);
+        }
+        Ok(match rules.len() {
+            1 => rules.remove(0),
+            _ => SmartRule::All(rules),
+        })
+    }
+
+    fn unary(&mut self) -> Result<SmartRule, String> {
+        if self.accept_word("not") {
+            return Ok(SmartRule::Not(Box::new(self.unary()?)));
+        }
+        if self.accept_symbol("(") {
+            let rule = self.or()?;
+            if !self.accept_symbol(")") {
+                return Err("Expected `)`".to_string());
+            }
+            return Ok(rule);
+        }
+        self.condition()
+    }
+
+    fn condition(&mut self) -> Result<SmartRule, String> {
+        let name = self.next().ok_or("Expected a field")?;
+        let field = SmartField::parse(name.text())
+            .ok_or_else(|| format!("Unknown field `{}`", name.text()))?;
+        let condition = |operator: SmartOperator, value: String| {
+            SmartRule::Condition(SmartCondition {
+                field,
+                operator,
+                value,
+            })
+        };
+
+        let operator = match self.next() {
+            Some(Token::Symbol("=")) => SmartOperator::Is,
+            Some(Token::Symbol("!=")) => SmartOperator::IsNot,
+            Some(Token::Symbol(">")) => SmartOperator::Greater,
+            Some(Token::Symbol(">=")) => SmartOperator::GreaterOrEqual,
+            Some(Token::Symbol("<")) => SmartOperator::Less,
+            Some(Token::Symbol("<=")) => SmartOperator::LessOrEqual,
+            Some(token) if token.is_word("is") => match self.accept_word("not") {
+                true => SmartOperator::IsNot,
+                false => SmartOperator::Is,
+            },
+            Some(token) if token.is_word("contains") => SmartOperator::Contains,
+            Some(token) if token.is_word("does") => {
+                self.expect_word("not")?;
+                self.expect_word("contain")?;
+                SmartOperator::NotContains
+            }
+            Some(token) if token.is_word("starts") => {
+                self.expect_word("with")?;
+                SmartOperator::StartsWith
+            }
+            Some(token) if token.is_word("ends") => {
+                self.expect_word("with")?;
+                SmartOperator::EndsWith
+            }
+            Some(token) if token.is_word("between") => {
+                let from = self.single_value()?;
+                self.expect_word("and")?;
+                let to = self.single_value()?;
+                if from.parse::<f64>().is_err() || to.parse::<f64>().is_err() {
+                    return Err(format!("`{} between` expects numbers", field.as_str()));
+                }
+                return Ok(SmartRule::All(vec![
+                    condition(SmartOperator::GreaterOrEqual, from),
+                    condition(SmartOperator::LessOrEqual, to),
+                ]));
+            }
+            _ => return Err(format!("Expected an operator after `{}`", field.as_str())),
+        };
+        let value = self.value()?;
+        if field.is_numeric() && value.parse::<f64>().is_err() {
+            return Err(format!("`{}` expects a number", field.as_str()));
+        }
+        Ok(condition(operator, value))
+    }
+
+    fn single_value(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Word(value)) | Some(Token::Quoted(value)) => Ok(value),
+            _ => Err("Expected a value".to_string()),
+        }
+    }
+
+    /// Unquoted values run until the next keyword, `artist is Miles Davis`
+    /// reads `Miles Davis`.
+    fn value(&mut self) -> Result<String, String> {
+        if let Some(Token::Quoted(value)) = self.peek() {
+            let value = value.clone();
+            self.position += 1;
+            return Ok(value);
+        }
+        let mut words = vec![];
+        while let Some(Token::Word(word)) = self.peek() {
+            if word.eq_ignore_ascii_case("and") || word.eq_ignore_ascii_case("or") {
+                break;
+            }
+            words.push(word.clone());
+            self.position += 1;
+        }
+        match words.is_empty() {
+            true => Err("Expected a value".to_string()),
+            false => Ok(words.join(" ")),
+        }
+    }
+
+    fn clause(&mut self, query: &mut SmartQuery) -> Result<(), String> {
+        if self.accept_word("sorted") || self.accept_word("sort") {
+            self.expect_word("by")?;
+            let name = self.next().ok_or("Expected a sort field")?;
+            query.sort = Some(match name.is_word("random") {
+                true => SmartSort::Random,
+                false => SmartSort::Field(
+                    SmartField::parse(name.text())
+                        .ok_or_else(|| format!("Unknown field `{}`", name.text()))?,
+                ),
+            });
+            query.descending = match self.peek() {
+                Some(token) if token.is_word("desc") || token.is_word("descending") => {
+                    self.position += 1;
+                    true
+                }
+                Some(token) if token.is_word("asc") || token.is_word("ascending") => {
+                    self.position += 1;
+                    false
+                }
+                _ => false,
+            };
+            return Ok(());
+        }
+        if self.accept_word("limit") {
+            let limit = self.next().ok_or("Expected a limit")?;
+            query.limit = Some(
+                limit
+                    .text()
+                    .parse()
+                    .map_err(|_| format!("Invalid limit `{}`", limit.text()))?,
+            );
+            return Ok(());
+        }
+        Err("Expected `sorted by` or `limit`".to_string())
+    }
+}
+
+impl SmartQuery {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut parser = Parser {
+            tokens: tokenize(input)?,
+            position: 0,
+        };
+        let mut query = SmartQuery {
+            rule: parser.or()?,
+            sort: None,
+            descending: false,
+            limit: None,
+        };
+        while parser.accept_symbol(",") {
+            parser.clause(&mut query)?;
+        }
+        match parser.peek() {
+            Some(token) => Err(format!("Unexpected `{}`", token.text())),
+            None => Ok(query),
+        }
+    }
+}
+
+impl fmt::Display for SmartRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, rules: &[SmartRule], separator: &str| {
+            for (index, rule) in rules.iter().enumerate() {
+                if index > 0 {
+                    write!(f, " {} ", separator)?;
+                }
+                match rule {
+                    SmartRule::All(_) | SmartRule::Any(_) => write!(f, "({})", rule)?,
+                    _ => write!(f, "{}", rule)?,
+                }
+            }
+            Ok(())
+        };
+        match self {
+            SmartRule::All(rules) => join(f, rules, "and"),
+            SmartRule::Any(rules) => join(f, rules, "or"),
+            SmartRule::Not(rule) => match rule.as_ref() {
+                SmartRule::Condition(_) => write!(f, "not {}", rule),
+                _ => write!(f, "not ({})", rule),
+            },
+            SmartRule::Condition(condition) => write!(
+                f,
+                "{} {} \"{}\"",
+                condition.field.as_str(),
+                condition.operator.as_str(),
+                condition.value
+            ),
+        }
+    }
+}
+
+impl fmt::Display for SmartQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.rule)?;
+        match self.sort {
+            Some(SmartSort::Random) => write!(f, ", sorted by random")?,
+            Some(SmartSort::Field(field)) => write!(
+                f,
+                ", sorted by {} {}",
+                field.as_str(),
+                if self.descending { "desc" } else { "asc" }
+            )?,
+            None => {}
+        }
+        if let Some(limit) = self.limit {
+            write!(f, ", limit {}", limit)?;
+        }
+        Ok(())
+    }
+}
@@ -1,5 +1,8 @@
-use std::time::Duration;
+use std::{path::Path, time::Duration};
 
+use super::genre::*;
+use super::rating::*;
+use super::smart_playlist::*;
 use super::types::*;
 use lofty::{Accessor, ItemKey, ItemValue, Tag, TagItem, TagType};
 use tantivy::{
@@ -194,3 +197,329 @@ fn tag_to_simplified_song() {
     assert_eq!(song.genre, "Hip-Hop");
     assert_eq!(song.album_artist, "J. Cole");
 }
+
+#[test]
+fn path_to_song() {
+    let song = Song::from_path(
+        Path::new("/music/J. Cole/2014 Forest Hills Drive/03 - Wet Dreamz.m4a"),
+        Path::new("/music"),
+    );
+
+    assert_eq!(song.title, "Wet Dreamz");
+    assert_eq!(song.artist, "J. Cole");
+    assert_eq!(song.album_artist, "J. Cole");
+    assert_eq!(song.album, "2014 Forest Hills Drive");
+    assert_eq!(song.track, Some(3));
+    assert_eq!(
+        song.uri,
+        Some("/music/J. Cole/2014 Forest Hills Drive/03 - Wet Dreamz.m4a".to_owned())
+    );
+
+    let song = Song::from_path(Path::new("/music/Untitled.mp3"), Path::new("/music"));
+
+    assert_eq!(song.title, "Untitled");
+    assert_eq!(song.artist, "None");
+    assert_eq!(song.album, "None");
+    assert_eq!(song.track, None);
+}
+
+#[test]
+fn split_artists() {
+    let separators = vec![
+        " feat. ".to_string(),
+        " ft. ".to_string(),
+        ";".to_string(),
+        "/".to_string(),
+        " & ".to_string(),
+    ];
+    assert_eq!(
+        super::types::split_artists("Kendrick Lamar feat. Drake", &separators),
+        vec!["Kendrick Lamar", "Drake"]
+    );
+    assert_eq!(
+        super::types::split_artists("A; B & C FT. D/A", &separators),
+        vec!["A", "B", "C", "D"]
+    );
+    assert_eq!(
+        super::types::split_artists("Simon&Garfunkel", &separators),
+        vec!["Simon&Garfunkel"]
+    );
+}
+
+#[test]
+fn tag_to_compilation_song() {
+    let mut tag = Tag::new(TagType::ID3v2);
+    tag.insert_item(TagItem::new(
+        ItemKey::TrackTitle,
+        ItemValue::Text("Mask Off".to_owned()),
+    ));
+    tag.insert_item(TagItem::new(
+        ItemKey::TrackArtist,
+        ItemValue::Text("Future ft. Kendrick Lamar".to_owned()),
+    ));
+    tag.insert_item(TagItem::new(
+        ItemKey::AlbumTitle,
+        ItemValue::Text("Hip-Hop Hits".to_owned()),
+    ));
+    tag.insert_item(TagItem::new(
+        ItemKey::FlagCompilation,
+        ItemValue::Text("1".to_owned()),
+    ));
+
+    let mut song = Song::from(&tag);
+    song.split_artists(&[" ft. ".to_string()]);
+
+    assert!(song.compilation);
+    assert_eq!(song.artist, "Future ft. Kendrick Lamar");
+    assert_eq!(song.album_artist, VARIOUS_ARTISTS);
+    assert_eq!(song.artists, vec!["Future", "Kendrick Lamar"]);
+    assert_eq!(
+        song.linked_artists(),
+        vec!["Future", "Kendrick Lamar", VARIOUS_ARTISTS]
+    );
+    assert!(Album::from(&tag).compilation);
+    assert_eq!(Artist::from(&tag).name, VARIOUS_ARTISTS);
+}
+
+#[test]
+fn tag_to_rich_song() {
+    let mut tag = Tag::new(TagType::VorbisComments);
+    for (key, value) in [
+        (ItemKey::TrackTitle, "Allegro con brio"),
+        (ItemKey::DiscNumber, "2"),
+        (ItemKey::DiscTotal, "5"),
+        (ItemKey::TrackTotal, "4"),
+        (ItemKey::Composer, "Ludwig van Beethoven"),
+        (ItemKey::Conductor, "Herbert von Karajan"),
+        (ItemKey::Label, "Deutsche Grammophon"),
+        (ItemKey::ISRC, "DEF056330000"),
+        (ItemKey::BPM, "107.6"),
+        (ItemKey::TrackArtistSortOrder, "Karajan, Herbert von"),
+    ] {
+        tag.insert_item(TagItem::new(key, ItemValue::Text(value.to_owned())));
+    }
+    // Unknown keys are only kept when read from a file
+    tag.push_item_unchecked(TagItem::new(
+        ItemKey::Unknown("MUSICBRAINZ_TRACKID".to_owned()),
+        ItemValue::Text("f9a6d6c4-1a5e-4f3c-9a3e-8d1a6f1b2c3d".to_owned()),
+    ));
+
+    let song = Song::from(&tag);
+
+    assert_eq!(song.disc_number, Some(2));
+    assert_eq!(song.disc_total, Some(5));
+    assert_eq!(song.track_total, Some(4));
+    assert_eq!(song.composer, Some("Ludwig van Beethoven".to_owned()));
+    assert_eq!(song.conductor, Some("Herbert von Karajan".to_owned()));
+    assert_eq!(song.label, Some("Deutsche Grammophon".to_owned()));
+    assert_eq!(song.isrc, Some("DEF056330000".to_owned()));
+    assert_eq!(song.bpm, Some(108));
+    assert_eq!(song.artist_sort, Some("Karajan, Herbert von".to_owned()));
+    assert_eq!(
+        song.musicbrainz_recording_id,
+        Some("f9a6d6c4-1a5e-4f3c-9a3e-8d1a6f1b2c3d".to_owned())
+    );
+    assert_eq!(song.musicbrainz_release_id, None);
+}
+
+#[test]
+fn musicbrainz_recording_id_from_ufid() {
+    use lofty::id3::v2::{Frame, FrameFlags, FrameValue, ID3v2Tag};
+
+    let mut id3v2 = ID3v2Tag::default();
+    id3v2.set_title("Wet Dreamz".to_owned());
+    for (owner, identifier) in [
+        ("http://www.cddb.com/id3/taginfo1.html", "3CD3N48Q24815U4"),
+        (
+            "http://musicbrainz.org",
+            "f9a6d6c4-1a5e-4f3c-9a3e-8d1a6f1b2c3d",
+        ),
+    ] {
+        let mut data = owner.as_bytes().to_vec();
+        data.push(0);
+        data.extend_from_slice(identifier.as_bytes());
+        id3v2.insert(Frame::new("UFID", FrameValue::Binary(data), FrameFlags::default()).unwrap());
+    }
+
+    let song = Song::from(&Tag::from(id3v2));
+    assert_eq!(
+        song.musicbrainz_recording_id,
+        Some("f9a6d6c4-1a5e-4f3c-9a3e-8d1a6f1b2c3d".to_owned())
+    );
+}
+
+#[test]
+fn parse_lrc() {
+    let content = "[ar:J. Cole]\n[ti:Wet Dreamz]\n[00:12.50]First line\n[00:05.00][01:02.123]Chorus\nplain line\n[00:20]";
+    let lines = super::types::parse_lrc(content);
+    assert_eq!(
+        lines,
+        vec![
+            LyricLine {
+                time_ms: Some(5000),
+                text: "Chorus".to_string()
+            },
+            LyricLine {
+                time_ms: Some(12500),
+                text: "First line".to_string()
+            },
+            LyricLine {
+                time_ms: Some(20000),
+                text: "".to_string()
+            },
+            LyricLine {
+                time_ms: Some(62123),
+                text: "Chorus".to_string()
+            },
+        ]
+    );
+    assert_eq!(current_lyric_line(&lines, 1000), None);
+    assert_eq!(current_lyric_line(&lines, 12500), Some(&lines[1]));
+    assert_eq!(current_lyric_line(&lines, 90000), Some(&lines[3]));
+    assert_eq!(format_lrc_timestamp(62123), "[01:02.12]");
+
+    let lyrics = Lyrics::new("First line\nSecond line\n", LyricsSource::Txt);
+    assert!(!lyrics.synced);
+    assert_eq!(lyrics.lines().len(), 2);
+    assert_eq!(lyrics.lines()[1].time_ms, None);
+}
+
+#[test]
+fn apply_tag_changes() {
+    let mut tag = Tag::new(TagType::ID3v2);
+    tag.set_title("Wet Dreamz".to_string());
+    tag.set_artist("J. Cole".to_string());
+    tag.set_album("2014 Forest Hills Drive".to_string());
+    tag.insert_text(ItemKey::RecordingDate, "2014-12-09".to_string());
+    tag.set_track(3);
+    tag.push_item_unchecked(TagItem::new(
+        ItemKey::Unknown("ARTISTS".to_string()),
+        ItemValue::Text("J. Cole".to_string()),
+    ));
+
+    let changes = TagChanges {
+        artist: Some("Jermaine Cole".to_string()),
+        album_artist: Some("Jermaine Cole".to_string()),
+        genre: Some("Hip-Hop".to_string()),
+        year: Some(2015),
+        disc_number: Some(1),
+        ..Default::default()
+    };
+    assert!(!changes.is_track_specific());
+    changes.apply(&mut tag);
+
+    let song = Song::from(&tag);
+    assert_eq!(song.title, "Wet Dreamz");
+    assert_eq!(song.artist, "Jermaine Cole");
+    assert_eq!(song.artists, vec!["Jermaine Cole"]);
+    assert_eq!(song.album_artist, "Jermaine Cole");
+    assert_eq!(song.genre, "Hip-Hop");
+    assert_eq!(song.year, Some(2015));
+    assert_eq!(song.track, Some(3));
+    assert_eq!(song.disc_number, Some(1));
+}
+
+#[test]
+fn parse_smart_playlist_rules() {
+    let query = SmartQuery::parse(
+        "genre is Jazz and year between 1955 and 1965, sorted by random, limit 100",
+    )
+    .unwrap();
+    let condition = |field, operator, value: &str| {
+        SmartRule::Condition(SmartCondition {
+            field,
+            operator,
+            value: value.to_string(),
+        })
+    };
+
+    assert_eq!(
+        query.rule,
+        SmartRule::All(vec![
+            condition(SmartField::Genre, SmartOperator::Is, "Jazz"),
+            SmartRule::All(vec![
+                condition(SmartField::Year, SmartOperator::GreaterOrEqual, "1955"),
+                condition(SmartField::Year, SmartOperator::LessOrEqual, "1965"),
+            ]),
+        ])
+    );
+    assert_eq!(query.sort, Some(SmartSort::Random));
+    assert_eq!(query.limit, Some(100));
+
+    let query =
+        SmartQuery::parse("(artist is \"Miles Davis\" or album contains Blue) and not format is mp3, sorted by year desc")
+            .unwrap();
+    assert_eq!(
+        query.to_string(),
+        "(artist is \"Miles Davis\" or album contains \"Blue\") and not format is \"mp3\", sorted by year desc"
+    );
+    assert_eq!(SmartQuery::parse(&query.to_string()).unwrap(), query);
+
+    assert!(SmartQuery::parse("year is nineteen").is_err());
+    assert!(SmartQuery::parse("year between 1955 and later").is_err());
+    assert!(SmartQuery::parse("title between a and b").is_err());
+    assert!(SmartQuery::parse("mood is happy").is_err());
+    assert!(SmartQuery::parse("genre is Jazz, limit").is_err());
+}
+
+#[test]
+fn read_and_write_rating_tags() {
+    assert_eq!(stars_from_popm(0), 0);
+    assert_eq!(stars_from_popm(1), 1);
+    assert_eq!(stars_from_popm(64), 2);
+    assert_eq!(stars_from_popm(128), 3);
+    assert_eq!(stars_from_popm(196), 4);
+    assert_eq!(stars_from_popm(255), 5);
+    for stars in 0..=5 {
+        assert_eq!(stars_from_popm(stars_to_popm(stars)), stars);
+        assert_eq!(
+            stars_from_fmps(stars_to_fmps(stars).parse().unwrap()),
+            stars
+        );
+    }
+
+    // POPM frame of another player, its email and play counter are kept
+    let mut tag = Tag::new(TagType::ID3v2);
+    tag.insert_item(TagItem::new(
+        ItemKey::Popularimeter,
+        ItemValue::Binary(vec![b'f', b'o', b'o', 0, 196, 0, 0, 0, 12]),
+    ));
+    assert_eq!(read_rating(&tag), Some(4));
+    write_rating(&mut tag, 2);
+    assert_eq!(
+        tag.get_binary(&ItemKey::Popularimeter, false),
+        Some(&[b'f', b'o', b'o', 0, 64, 0, 0, 0, 12][..])
+    );
+    assert_eq!(Song::from(&tag).rating, Some(2));
+
+    let mut tag = Tag::new(TagType::VorbisComments);
+    assert_eq!(read_rating(&tag), None);
+    write_rating(&mut tag, 3);
+    assert_eq!(
+        tag.get_string(&ItemKey::Unknown("FMPS_RATING".to_string())),
+        Some("0.6")
+    );
+    assert_eq!(read_rating(&tag), Some(3));
+    write_rating(&mut tag, 0);
+    assert_eq!(read_rating(&tag), None);
+}
+
+#[test]
+fn split_and_normalize_genres() {
+    assert_eq!(split_genres("Rock; Pop"), vec!["Rock", "Pop"]);
+    assert_eq!(
+        split_genres("Jazz/Funk,  Soul "),
+        vec!["Jazz", "Funk", "Soul"]
+    );
+    assert_eq!(
+        split_genres("Hip-Hop\0Rap\0hip hop"),
+        vec!["Hip Hop", "Rap"]
+    );
+    assert_eq!(split_genres("RnB / rhythm and blues"), vec!["R&B"]);
+    assert!(split_genres(" ; ").is_empty());
+    assert!(split_genres("None").is_empty());
+
+    assert_eq!(genre_key("Hip-Hop"), "hip hop");
+    assert_eq!(genre_id("HipHop"), genre_id("hip  hop"));
+    assert_ne!(genre_id("Rock"), genre_id("Pop"));
+}
@@ -1,7 +1,10 @@
-use std::time::Duration;
+use std::{path::Path, time::Duration};
 
+use chrono::{DateTime, Utc};
 use local_ip_addr::get_local_ip_address;
-use lofty::{Accessor, FileProperties, ItemKey, Tag};
+use lofty::{
+    error::ErrorKind, Accessor, FileProperties, ItemKey, ItemValue, LoftyError, Tag, TagType,
+};
 use mdns_sd::ServiceInfo;
 use music_player_discovery::{SERVICE_NAME, XBMC_SERVICE_NAME};
 use music_player_settings::{read_settings, Settings};
@@ -10,6 +13,8 @@ use tantivy::{
     Document,
 };
 use upnp_client::types::Metadata;
+
+use crate::rating::read_rating;
 use url::Url;
 
 pub const CHROMECAST_SERVICE_NAME: &str = "_googlecast._tcp.local.";
@@ -21,6 +26,10 @@ pub const XBMC_DEVICE: &str = "XBMC";
 pub const MUSIC_PLAYER_DEVICE: &str = "MusicPlayer";
 pub const UPNP_DLNA_DEVICE: &str = "UPnP/DLNA";
 
+pub const VARIOUS_ARTISTS: &str = "Various Artists";
+
+const MUSICBRAINZ_UFID_OWNER: &str = "http://musicbrainz.org";
+
 #[derive(Debug, Clone, Default)]
 pub struct Playback {
     pub current_track: Option<Track>,
@@ -57,6 +66,30 @@ pub struct Song {
     pub uri: Option<String>,
     pub cover: Option<String>,
     pub album_artist: String,
+    pub artists: Vec<String>,
+    pub compilation: bool,
+    pub disc_number: Option<u32>,
+    pub disc_total: Option<u32>,
+    pub track_total: Option<u32>,
+    pub composer: Option<String>,
+    pub conductor: Option<String>,
+    pub label: Option<String>,
+    pub isrc: Option<String>,
+    pub comment: Option<String>,
+    pub bpm: Option<u32>,
+    pub title_sort: Option<String>,
+    pub artist_sort: Option<String>,
+    pub album_sort: Option<String>,
+    pub album_artist_sort: Option<String>,
+    pub musicbrainz_recording_id: Option<String>,
+    pub musicbrainz_release_id: Option<String>,
+    pub id: Option<String>,
+    pub content_hash: Option<String>,
+    /// Slice of the file played by a track of a cue sheet, in milliseconds
+    pub start_offset: Option<u32>,
+    pub end_offset: Option<u32>,
+    /// Stars from the POPM/FMPS_Rating tags
+    pub rating: Option<u8>,
 }
 
 #[derive(Debug, Clone, Default)]
@@ -80,6 +113,17 @@ pub struct Album {
     pub artist_id: Option<String>,
     pub year: Option<u32>,
     pub cover: Option<String>,
+    pub cover_small: Option<String>,
+    pub cover_medium: Option<String>,
+    pub cover_large: Option<String>,
+    pub cover_small_webp: Option<String>,
+    pub cover_medium_webp: Option<String>,
+    pub cover_large_webp: Option<String>,
+    pub compilation: bool,
+    pub label: Option<String>,
+    pub title_sort: Option<String>,
+    pub artist_sort: Option<String>,
+    pub musicbrainz_release_id: Option<String>,
     pub tracks: Vec<Track>,
 }
 
@@ -249,6 +293,17 @@ impl From<Document> for SimplifiedSong {
 
 impl From<&Tag> for Song {
     fn from(tag: &Tag) -> Self {
+        // Multi-value ARTISTS tags are more accurate than the display artist
+        let mut artists: Vec<String> = tag
+            .get_strings(&ItemKey::Unknown("ARTISTS".to_string()))
+            .map(|x| x.to_string())
+            .collect();
+        if artists.is_empty() {
+            artists = tag
+                .get_strings(&ItemKey::TrackArtist)
+                .map(|x| x.to_string())
+                .collect();
+        }
         Self {
             title: tag.title().unwrap_or("None").to_string(),
             artist: tag.artist().unwrap_or("None").to_string(),
@@ -256,10 +311,28 @@ impl From<&Tag> for Song {
             genre: tag.genre().unwrap_or("None").to_string(),
             year: tag.year(),
             track: tag.track(),
-            album_artist: tag
-                .get_string(&ItemKey::AlbumArtist)
-                .unwrap_or(tag.artist().unwrap_or("None"))
-                .to_string(),
+            album_artist: album_artist(tag),
+            artists,
+            compilation: is_compilation(tag),
+            disc_number: tag.disk(),
+            disc_total: tag.disk_total(),
+            track_total: tag.track_total(),
+            composer: tag_string(tag, &[ItemKey::Composer]),
+            conductor: tag_string(tag, &[ItemKey::Conductor]),
+            label: tag_string(tag, &[ItemKey::Label, ItemKey::Publisher]),
+            isrc: tag_string(tag, &[ItemKey::ISRC]),
+            comment: tag_string(tag, &[ItemKey::Comment]),
+            bpm: tag_string(tag, &[ItemKey::BPM])
+                .and_then(|bpm| bpm.parse::<f32>().ok())
+                .map(|bpm| bpm.round() as u32),
+            title_sort: tag_string(tag, &[ItemKey::TrackTitleSortOrder]),
+            artist_sort: tag_string(tag, &[ItemKey::TrackArtistSortOrder]),
+            album_sort: tag_string(tag, &[ItemKey::AlbumTitleSortOrder]),
+            album_artist_sort: tag_string(tag, &[ItemKey::AlbumArtistSortOrder]),
+            musicbrainz_recording_id: tag_string(tag, &musicbrainz_keys("Track Id"))
+                .or_else(|| musicbrainz_ufid(tag)),
+            musicbrainz_release_id: tag_string(tag, &musicbrainz_keys("Album Id")),
+            rating: read_rating(tag),
             ..Default::default()
         }
     }
@@ -267,20 +340,10 @@ impl From<&Tag> for Song {
 
 impl From<&Tag> for Artist {
     fn from(tag: &Tag) -> Self {
-        let id = format!(
-            "{:x}",
-            md5::compute(
-                tag.get_string(&ItemKey::AlbumArtist)
-                    .unwrap_or(tag.artist().unwrap_or("None"))
-                    .to_string()
-            )
-        );
+        let name = album_artist(tag);
         Self {
-            id,
-            name: tag
-                .get_string(&ItemKey::AlbumArtist)
-                .unwrap_or(tag.artist().unwrap_or("None"))
-                .to_string(),
+            id: format!("{:x}", md5::compute(&name)),
+            name,
             ..Default::default()
         }
     }
@@ -292,29 +355,195 @@ impl From<&Tag> for Album {
             "{:x}",
             md5::compute(tag.album().unwrap_or("None").to_string())
         );
-        let artist_id = Some(format!(
-            "{:x}",
-            md5::compute(
-                tag.get_string(&ItemKey::AlbumArtist)
-                    .unwrap_or(tag.artist().unwrap_or("None"))
-                    .to_string()
-            )
-        ));
+        let artist = album_artist(tag);
         Self {
             id,
             title: tag.album().unwrap_or("None").to_string(),
-            artist: tag
-                .get_string(&ItemKey::AlbumArtist)
-                .unwrap_or(tag.artist().unwrap_or("None"))
-                .to_string(),
+            artist_id: Some(format!("{:x}", md5::compute(&artist))),
+            artist,
             year: tag.year(),
-            artist_id,
+            compilation: is_compilation(tag),
             ..Default::default()
         }
     }
 }
 
+fn tag_string(tag: &Tag, keys: &[ItemKey]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| tag.get_string(key))
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// MusicBrainz identifiers have no dedicated item key, each format names
+/// them differently: `MUSICBRAINZ_TRACKID` (Vorbis, APE), `MusicBrainz Track Id`
+/// (ID3v2 TXXX) or `----:com.apple.iTunes:MusicBrainz Track Id` (MP4).
+fn musicbrainz_keys(name: &str) -> [ItemKey; 3] {
+    [
+        ItemKey::Unknown(format!(
+            "MUSICBRAINZ_{}",
+            name.replace(' ', "").to_uppercase()
+        )),
+        ItemKey::Unknown(format!("MusicBrainz {}", name)),
+        ItemKey::Unknown(format!("----:com.apple.iTunes:MusicBrainz {}", name)),
+    ]
+}
+
+/// ID3v2 stores the MusicBrainz recording id in a `UFID` frame owned by
+/// `http://musicbrainz.org`, which lofty keeps as binary data: the owner and
+/// the identifier separated by a NUL byte.
+fn musicbrainz_ufid(tag: &Tag) -> Option<String> {
+    if tag.tag_type() != TagType::ID3v2 {
+        return None;
+    }
+    tag.get_items(&ItemKey::Unknown("UFID".to_owned()))
+        .find_map(|item| match item.value() {
+            ItemValue::Binary(data) => {
+                let separator = data.iter().position(|byte| *byte == 0)?;
+                let (owner, identifier) = data.split_at(separator);
+                (owner == MUSICBRAINZ_UFID_OWNER.as_bytes())
+                    .then(|| String::from_utf8_lossy(&identifier[1..]).trim().to_string())
+            }
+            _ => None,
+        })
+        .filter(|identifier| !identifier.is_empty())
+}
+
+fn is_compilation(tag: &Tag) -> bool {
+    tag.get_string(&ItemKey::FlagCompilation) == Some("1")
+}
+
+/// ALBUMARTIST groups the tracks of an album, compilations without one are
+/// filed under "Various Artists" rather than under each track artist.
+fn album_artist(tag: &Tag) -> String {
+    match tag.get_string(&ItemKey::AlbumArtist) {
+        Some(album_artist) => album_artist.to_string(),
+        None if is_compilation(tag) => VARIOUS_ARTISTS.to_string(),
+        None => tag.artist().unwrap_or("None").to_string(),
+    }
+}
+
+/// Splits a raw artist tag such as `A feat. B` or `A; B` into its artists.
+/// Separators are matched case-insensitively.
+pub fn split_artists(value: &str, separators: &[String]) -> Vec<String> {
+    let mut artists = vec![value.to_string()];
+    for separator in separators.iter().map(|x| x.to_ascii_lowercase()) {
+        if separator.is_empty() {
+            continue;
+        }
+        artists = artists
+            .iter()
+            .flat_map(|artist| {
+                let lowercase = artist.to_ascii_lowercase();
+                let mut start = 0;
+                let mut parts = vec![];
+                for (index, _) in lowercase.match_indices(&separator) {
+                    parts.push(artist[start..index].to_string());
+                    start = index + separator.len();
+                }
+                parts.push(artist[start..].to_string());
+                parts
+            })
+            .collect();
+    }
+
+    let mut result: Vec<String> = vec![];
+    for artist in artists {
+        let artist = artist
+            .trim_matches(|c: char| c.is_whitespace() || c == '(' || c == ')')
+            .to_string();
+        if !artist.is_empty() && !result.contains(&artist) {
+            result.push(artist);
+        }
+    }
+    result
+}
+
 impl Song {
+    /// A track keeps the id it got when it was first scanned, `md5(uri)`,
+    /// even once the file is moved (see `id`).
+    pub fn track_id(&self) -> String {
+        match &self.id {
+            Some(id) => id.clone(),
+            None => format!("{:x}", md5::compute(self.location())),
+        }
+    }
+
+    /// The uri of the file, followed by the start offset for the tracks of a
+    /// cue sheet which share the same file.
+    pub fn location(&self) -> String {
+        let uri = self.uri.clone().unwrap_or_default();
+        match self.start_offset {
+            Some(start_offset) => format!("{}#{}", uri, start_offset),
+            None => uri,
+        }
+    }
+
+    /// Builds a song for a file without any tag, guessing the metadata from
+    /// its location in the library: `Artist/Album/NN - Title.ext`.
+    pub fn from_path(path: &Path, music_directory: &Path) -> Self {
+        let relative = path.strip_prefix(music_directory).unwrap_or(path);
+        let mut folders: Vec<String> = relative
+            .parent()
+            .map(|parent| {
+                parent
+                    .iter()
+                    .map(|x| x.to_string_lossy().to_string())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let album = folders.pop().unwrap_or("None".to_string());
+        let artist = folders.pop().unwrap_or("None".to_string());
+
+        let stem = path
+            .file_stem()
+            .map(|x| x.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let (track, title) = parse_track_title(&stem);
+
+        Self {
+            title,
+            artist: artist.clone(),
+            album,
+            genre: "None".to_string(),
+            track,
+            album_artist: artist.clone(),
+            artists: vec![artist],
+            uri: Some(path.to_string_lossy().to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Splits every artist of the song with the configured separators.
+    pub fn split_artists(&mut self, separators: &[String]) {
+        if self.artists.is_empty() {
+            self.artists = vec![self.artist.clone()];
+        }
+        let mut artists: Vec<String> = vec![];
+        for artist in self
+            .artists
+            .iter()
+            .flat_map(|artist| split_artists(artist, separators))
+        {
+            if !artists.contains(&artist) {
+                artists.push(artist);
+            }
+        }
+        self.artists = artists;
+    }
+
+    /// Every artist credited on the song, the album artist included.
+    pub fn linked_artists(&self) -> Vec<String> {
+        let mut artists = match self.artists.is_empty() {
+            true => vec![self.artist.clone()],
+            false => self.artists.clone(),
+        };
+        if !artists.contains(&self.album_artist) {
+            artists.push(self.album_artist.clone());
+        }
+        artists
+    }
+
     pub fn with_properties(&mut self, properties: &FileProperties) -> Self {
         self.bitrate = properties.audio_bitrate();
         self.sample_rate = properties.sample_rate();
@@ -325,6 +554,252 @@ impl Song {
     }
 }
 
+/// Splits a file stem such as `03 - Title`, `03. Title` or `03 Title`
+/// into its track number and title.
+fn parse_track_title(stem: &str) -> (Option<u32>, String) {
+    let digits: String = stem.chars().take_while(|c| c.is_ascii_digit()).collect();
+    if digits.is_empty() || digits.len() > 3 {
+        return (None, stem.trim().to_string());
+    }
+    let title = stem[digits.len()..]
+        .trim_start_matches(|c: char| c.is_whitespace() || c == '-' || c == '.' || c == '_')
+        .trim()
+        .to_string();
+    if title.is_empty() {
+        return (None, stem.trim().to_string());
+    }
+    (digits.parse().ok(), title)
+}
+
+impl From<&Song> for Album {
+    fn from(song: &Song) -> Self {
+        Self {
+            id: format!("{:x}", md5::compute(song.album.as_bytes())),
+            title: song.album.clone(),
+            artist: song.album_artist.clone(),
+            artist_id: Some(format!("{:x}", md5::compute(song.album_artist.as_bytes()))),
+            year: song.year,
+            cover: song.cover.clone(),
+            compilation: song.compilation,
+            label: song.label.clone(),
+            title_sort: song.album_sort.clone(),
+            artist_sort: song.album_artist_sort.clone(),
+            musicbrainz_release_id: song.musicbrainz_release_id.clone(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanErrorKind {
+    Io,
+    UnknownFormat,
+    Corrupted,
+    Cover,
+}
+
+impl ScanErrorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ScanErrorKind::Io => "io",
+            ScanErrorKind::UnknownFormat => "unknown_format",
+            ScanErrorKind::Corrupted => "corrupted",
+            ScanErrorKind::Cover => "cover",
+        }
+    }
+}
+
+impl From<&LoftyError> for ScanErrorKind {
+    fn from(error: &LoftyError) -> Self {
+        match error.kind() {
+            ErrorKind::Io(_) => ScanErrorKind::Io,
+            ErrorKind::UnknownFormat | ErrorKind::UnsupportedTag => ScanErrorKind::UnknownFormat,
+            _ => ScanErrorKind::Corrupted,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ScanError {
+    pub path: String,
+    pub kind: ScanErrorKind,
+    pub message: String,
+}
+
+/// Tag values to write back into audio files, `None` keeps the current value.
+#[derive(Debug, Clone, Default)]
+pub struct TagChanges {
+    pub title: Option<String>,
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    pub album_artist: Option<String>,
+    pub year: Option<u32>,
+    pub genre: Option<String>,
+    pub track_number: Option<u32>,
+    pub disc_number: Option<u32>,
+    pub cover: Option<Vec<u8>>,
+}
+
+impl TagChanges {
+    /// Title and track number only make sense for a single track.
+    pub fn is_track_specific(&self) -> bool {
+        self.title.is_some() || self.track_number.is_some()
+    }
+
+    pub fn apply(&self, tag: &mut Tag) {
+        if let Some(title) = &self.title {
+            tag.set_title(title.clone());
+        }
+        if let Some(artist) = &self.artist {
+            // Drop the ARTISTS tag, it would override the new artist
+            tag.remove_key(&ItemKey::Unknown("ARTISTS".to_string()));
+            tag.set_artist(artist.clone());
+        }
+        if let Some(album) = &self.album {
+            tag.set_album(album.clone());
+        }
+        if let Some(album_artist) = &self.album_artist {
+            tag.insert_text(ItemKey::AlbumArtist, album_artist.clone());
+        }
+        if let Some(year) = self.year {
+            // lofty only rewrites an existing recording date
+            tag.remove_year();
+            if !tag.insert_text(ItemKey::Year, year.to_string()) {
+                tag.insert_text(ItemKey::RecordingDate, year.to_string());
+            }
+        }
+        if let Some(genre) = &self.genre {
+            tag.set_genre(genre.clone());
+        }
+        if let Some(track_number) = self.track_number {
+            tag.set_track(track_number);
+        }
+        if let Some(disc_number) = self.disc_number {
+            tag.set_disk(disc_number);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LyricsSource {
+    Embedded,
+    Lrc,
+    Txt,
+}
+
+impl LyricsSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LyricsSource::Embedded => "embedded",
+            LyricsSource::Lrc => "lrc",
+            LyricsSource::Txt => "txt",
+        }
+    }
+}
+
+impl From<&str> for LyricsSource {
+    fn from(source: &str) -> Self {
+        match source {
+            "lrc" => LyricsSource::Lrc,
+            "txt" => LyricsSource::Txt,
+            _ => LyricsSource::Embedded,
+        }
+    }
+}
+
+/// Lyrics of a track. Synced lyrics are kept in the LRC format, whatever
+/// their origin (SYLT frames are converted when scanning).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lyrics {
+    pub content: String,
+    pub synced: bool,
+    pub source: LyricsSource,
+}
+
+impl Lyrics {
+    pub fn new(content: &str, source: LyricsSource) -> Self {
+        Self {
+            content: content.trim().to_string(),
+            synced: !parse_lrc(content).is_empty(),
+            source,
+        }
+    }
+
+    /// Time-synced lines when available, plain lines otherwise.
+    pub fn lines(&self) -> Vec<LyricLine> {
+        if self.synced {
+            return parse_lrc(&self.content);
+        }
+        self.content
+            .lines()
+            .map(|line| LyricLine {
+                time_ms: None,
+                text: line.trim().to_string(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LyricLine {
+    pub time_ms: Option<u32>,
+    pub text: String,
+}
+
+/// Parses the `[mm:ss.xx]` timestamped lines of an LRC file, sorted by time.
+/// A line may carry several timestamps, metadata tags such as `[ar:...]`
+/// and untimed lines are ignored.
+pub fn parse_lrc(content: &str) -> Vec<LyricLine> {
+    let mut lines = Vec::new();
+    for line in content.lines() {
+        let mut rest = line.trim();
+        let mut timestamps = Vec::new();
+        while let Some(end) = rest.strip_prefix('[').and_then(|tag| tag.find(']')) {
+            match parse_lrc_timestamp(&rest[1..end + 1]) {
+                Some(time_ms) => timestamps.push(time_ms),
+                None => break,
+            }
+            rest = rest[end + 2..].trim_start();
+        }
+        for time_ms in timestamps {
+            lines.push(LyricLine {
+                time_ms: Some(time_ms),
+                text: rest.trim_end().to_string(),
+            });
+        }
+    }
+    lines.sort_by_key(|line| line.time_ms);
+    lines
+}
+
+fn parse_lrc_timestamp(timestamp: &str) -> Option<u32> {
+    let (minutes, seconds) = timestamp.split_once(':')?;
+    let (seconds, fraction) = seconds.split_once('.').unwrap_or((seconds, "0"));
+    if !fraction.chars().all(|c| c.is_ascii_digit()) || fraction.len() > 3 {
+        return None;
+    }
+    let fraction_ms = fraction.parse::<u32>().ok()? * 10u32.pow(3 - fraction.len() as u32);
+    Some(minutes.parse::<u32>().ok()? * 60_000 + seconds.parse::<u32>().ok()? * 1000 + fraction_ms)
+}
+
+/// Formats a position as an LRC timestamp, e.g. `[01:02.50]`.
+pub fn format_lrc_timestamp(time_ms: u32) -> String {
+    format!(
+        "[{:02}:{:02}.{:02}]",
+        time_ms / 60_000,
+        time_ms / 1000 % 60,
+        time_ms % 1000 / 10
+    )
+}
+
+/// Returns the synced line being sung at the given playback position.
+pub fn current_lyric_line(lines: &[LyricLine], position_ms: u32) -> Option<&LyricLine> {
+    lines
+        .iter()
+        .take_while(|line| line.time_ms.is_some_and(|time_ms| time_ms <= position_ms))
+        .last()
+}
+
 #[derive(Default, Clone)]
 pub struct Device {
     pub id: String,
@@ -536,6 +1011,18 @@ pub struct Track {
     pub artists: Vec<Artist>,
     pub album: Option<Album>,
     pub artist: String,
+    pub disc_total: Option<u32>,
+    pub track_total: Option<u32>,
+    pub composer: Option<String>,
+    pub conductor: Option<String>,
+    pub isrc: Option<String>,
+    pub comment: Option<String>,
+    pub bpm: Option<u32>,
+    pub title_sort: Option<String>,
+    pub artist_sort: Option<String>,
+    pub musicbrainz_recording_id: Option<String>,
+    pub start_offset: Option<u32>,
+    pub end_offset: Option<u32>,
 }
 
 #[derive(Default, Clone)]
@@ -544,6 +1031,8 @@ pub struct Playlist {
     pub name: String,
     pub description: Option<String>,
     pub tracks: Vec<Track>,
+    /// The rules of a smart playlist, `None` for a regular one.
+    pub rules: Option<String>,
 }
 
 #[derive(Default, Clone)]
@@ -553,6 +1042,32 @@ pub struct Folder {
     pub playlists: Vec<Playlist>,
 }
 
+/// A play of a track, from the moment it started to the moment another
+/// track replaced it or it reached its end.
+#[derive(Debug, Clone, Default)]
+pub struct HistoryEntry {
+    pub id: String,
+    pub track: Track,
+    pub started_at: DateTime<Utc>,
+    pub listened_ms: u32,
+    pub completed: bool,
+    pub device: Option<String>,
+}
+
+impl HistoryEntry {
+    pub fn skipped(&self) -> bool {
+        !self.completed
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PlayStats {
+    pub track_id: String,
+    pub play_count: u32,
+    pub skip_count: u32,
+    pub last_played: Option<DateTime<Utc>>,
+}
+
 impl Into<Metadata> for Track {
     fn into(self) -> Metadata {
         Metadata {
@@ -596,7 +1111,7 @@ impl RemoteCoverUrl for Track {
 
 impl RemoteCoverUrl for Album {
     fn with_remote_cover_url(&self, base_url: &str) -> Self {
-        let cover_url = match self.cover {
+        let cover_url = |cover: &Option<String>| match cover {
             Some(ref cover) => match cover.starts_with("http") {
                 true => Some(cover.to_owned()),
                 false => Some(format!("{}/covers/{}", base_url, cover)),
@@ -604,7 +1119,13 @@ impl RemoteCoverUrl for Album {
             None => None,
         };
         Self {
-            cover: cover_url,
+            cover: cover_url(&self.cover),
+            cover_small: cover_url(&self.cover_small),
+            cover_medium: cover_url(&self.cover_medium),
+            cover_large: cover_url(&self.cover_large),
+            cover_small_webp: cover_url(&self.cover_small_webp),
+            cover_medium_webp: cover_url(&self.cover_medium_webp),
+            cover_large_webp: cover_url(&self.cover_large_webp),
             tracks: self
                 .tracks
                 .iter()
@@ -14,6 +14,7 @@ use music_player_addons::{CurrentDevice, CurrentReceiverDevice, CurrentSourceDev
 use music_player_graphql::{
     scan_devices,
     schema::{
+        loaders::with_loaders,
         objects::{player_state::PlayerState, track::Track},
         playback::PositionMilliseconds,
         Mutation, Query, Subscription,
@@ -133,10 +134,13 @@ async fn main() {
         tracklist.clone(),
     );
     let db = Database::new().await;
-    let schema: MusicPlayerSchema = Schema::build(
-        Query::default(),
-        Mutation::default(),
-        Subscription::default(),
+    let schema: MusicPlayerSchema = with_loaders(
+        Schema::build(
+            Query::default(),
+            Mutation::default(),
+            Subscription::default(),
+        ),
+        &db,
     )
     .data(db)
     .data(cmd_tx)
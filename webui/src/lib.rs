@@ -4,9 +4,9 @@ mod tests;
 use actix_cors::Cors;
 use actix_files as fs;
 use actix_web::{
-    error::ErrorNotFound,
+    error::{ErrorBadRequest, ErrorInternalServerError, ErrorNotFound},
     guard,
-    http::header::{ContentDisposition, DispositionType, HOST},
+    http::header::{ContentDisposition, DispositionParam, DispositionType, HOST},
     web::{self, Data},
     App, Error, HttpRequest, HttpResponse, HttpServer, Responder, Result,
 };
@@ -18,18 +18,23 @@ use music_player_addons::{CurrentDevice, CurrentReceiverDevice, CurrentSourceDev
 use music_player_entity::track as track_entity;
 use music_player_graphql::{
     scan_devices,
-    schema::{Mutation, Query, Subscription},
+    schema::{loaders::with_loaders, Mutation, Query, Subscription},
     MusicPlayerSchema,
 };
 use music_player_playback::player::PlayerCommand;
+use music_player_scanner::playlist_file::{
+    decode_playlist, import_playlist, write_playlist, PlaylistEntry, PlaylistFile, PlaylistFormat,
+};
 use music_player_settings::{get_application_directory, read_settings, Settings};
-use music_player_storage::{searcher::Searcher, Database};
+use music_player_storage::{repo::playlist::PlaylistRepository, searcher::Searcher, Database};
 use music_player_tracklist::Tracklist;
 use owo_colors::OwoColorize;
 use rust_embed::RustEmbed;
 use sea_orm::EntityTrait;
+use serde::{Deserialize, Serialize};
 use std::{path::PathBuf, sync::Arc};
 use tokio::sync::{mpsc::UnboundedSender, Mutex};
+use uuid::Uuid;
 
 #[derive(RustEmbed)]
 #[folder = "musicplayer/build/"]
@@ -78,6 +83,134 @@ async fn index_file(db: Data<Database>, req: HttpRequest) -> Result<NamedFile, E
     }
 }
 
+/// Largest playlist file accepted by the import, well above the default
+/// limit of a request body.
+const MAX_PLAYLIST_FILE_SIZE: usize = 16 * 1024 * 1024;
+
+#[derive(Deserialize)]
+struct PlaylistFileQuery {
+    format: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ImportedPlaylistResponse {
+    id: String,
+    name: String,
+    tracks: usize,
+    missing: Vec<String>,
+}
+
+fn playlist_format(query: &PlaylistFileQuery) -> Result<PlaylistFormat, Error> {
+    query
+        .format
+        .as_deref()
+        .unwrap_or("m3u8")
+        .parse::<PlaylistFormat>()
+        .map_err(|e| ErrorBadRequest(e.to_string()))
+}
+
+/// Points an entry to the URL of its track on this server, so that the
+/// exported file plays from any device of the network.
+fn stream_entry(req: &HttpRequest, id: &str, entry: PlaylistEntry) -> PlaylistEntry {
+    PlaylistEntry {
+        location: format!("http://{}/tracks/{}", req.connection_info().host(), id),
+        ..entry
+    }
+}
+
+fn playlist_file_response(format: PlaylistFormat, file: &PlaylistFile, name: &str) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "{}.{}",
+                name,
+                format.extension()
+            ))],
+        })
+        .body(write_playlist(format, file))
+}
+
+async fn export_playlist(
+    db: Data<Database>,
+    req: HttpRequest,
+    query: web::Query<PlaylistFileQuery>,
+) -> Result<HttpResponse, Error> {
+    let id = req.match_info().get("id").unwrap();
+    let format = playlist_format(&query)?;
+    let playlist = PlaylistRepository::new(db.get_connection())
+        .find(id)
+        .await
+        .map_err(|e| ErrorNotFound(e.to_string()))?;
+    let file = PlaylistFile {
+        title: Some(playlist.name.clone()),
+        entries: playlist
+            .tracks
+            .iter()
+            .map(|track| stream_entry(&req, &track.id, track.into()))
+            .collect(),
+    };
+    Ok(playlist_file_response(format, &file, &playlist.name))
+}
+
+async fn export_tracklist(
+    tracklist: Data<Arc<std::sync::Mutex<Tracklist>>>,
+    req: HttpRequest,
+    query: web::Query<PlaylistFileQuery>,
+) -> Result<HttpResponse, Error> {
+    let format = playlist_format(&query)?;
+    let (previous_tracks, next_tracks) = tracklist.lock().unwrap().tracks();
+    let file = PlaylistFile {
+        title: None,
+        entries: previous_tracks
+            .iter()
+            .chain(next_tracks.iter())
+            .map(|track| stream_entry(&req, &track.id, track.into()))
+            .collect(),
+    };
+    Ok(playlist_file_response(format, &file, "tracklist"))
+}
+
+/// Creates a playlist from the playlist file sent as the body of the request,
+/// in UTF-8 or Latin-1 like the playlist files of the library.
+async fn import_playlist_file(
+    db: Data<Database>,
+    query: web::Query<PlaylistFileQuery>,
+    body: web::Bytes,
+) -> Result<HttpResponse, Error> {
+    let format = playlist_format(&query)?;
+    let file = decode_playlist(format, &body).map_err(|e| ErrorBadRequest(e.to_string()))?;
+    let name = query
+        .name
+        .clone()
+        .or_else(|| file.title.clone())
+        .unwrap_or_else(|| "Imported playlist".into());
+    let id = Uuid::new_v4().to_string();
+    let imported = import_playlist(&db, &id, &name, &file, None)
+        .await
+        .map_err(|e| ErrorInternalServerError(e.to_string()))?;
+    Ok(HttpResponse::Ok().json(ImportedPlaylistResponse {
+        id: imported.id,
+        name: imported.name,
+        tracks: imported.track_count,
+        missing: imported.missing.iter().map(ToString::to_string).collect(),
+    }))
+}
+
+/// Routes importing and exporting playlist files, registered before the
+/// pages of the playlists.
+fn playlist_files(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::resource("/playlists/import")
+            .app_data(web::PayloadConfig::new(MAX_PLAYLIST_FILE_SIZE))
+            .route(web::post().to(import_playlist_file)),
+    )
+    .route("/playlists/{id}/export", web::get().to(export_playlist))
+    .route("/tracklist/export", web::get().to(export_tracklist));
+}
+
 async fn index_ws(
     schema: web::Data<MusicPlayerSchema>,
     req: HttpRequest,
@@ -139,14 +272,18 @@ pub async fn start_webui(
     let source_device = Arc::new(Mutex::new(CurrentSourceDevice::new()));
     let receiver_device = Arc::new(Mutex::new(CurrentReceiverDevice::new()));
     let searcher = Arc::new(Mutex::new(Searcher::new()));
-    let schema = Schema::build(
-        Query::default(),
-        Mutation::default(),
-        Subscription::default(),
+    let db = Database::new().await;
+    let schema = with_loaders(
+        Schema::build(
+            Query::default(),
+            Mutation::default(),
+            Subscription::default(),
+        ),
+        &db,
     )
-    .data(Database::new().await)
+    .data(db)
     .data(cmd_tx)
-    .data(tracklist)
+    .data(tracklist.clone())
     .data(devices)
     .data(current_device)
     .data(source_device)
@@ -162,6 +299,7 @@ pub async fn start_webui(
         App::new()
             .app_data(Data::new(db.clone()))
             .app_data(Data::new(schema.clone()))
+            .app_data(Data::new(tracklist.clone()))
             .wrap(cors)
             .service(index_graphql)
             .service(index_graphiql)
@@ -179,6 +317,7 @@ pub async fn start_webui(
             .route("/artists/{_:.*}", web::get().to(index_spa))
             .route("/albums/{_:.*}", web::get().to(index_spa))
             .route("/folders/{_:.*}", web::get().to(index_spa))
+            .configure(playlist_files)
             .route("/playlists/{_:.*}", web::get().to(index_spa))
             .route("/search", web::get().to(index_spa))
             .route("/tracks/{id}", web::get().to(index_file))
@@ -1,5 +1,6 @@
+use actix_web::{test, web::Data, App};
 use futures_util::FutureExt;
-use music_player_entity::{album, artist, artist_tracks, track};
+use music_player_entity::{album, artist, artist_tracks, playlist, track};
 use music_player_playback::{
     audio_backend::{self, rodio::RodioSink},
     config::AudioFormat,
@@ -8,7 +9,7 @@ use music_player_playback::{
 use music_player_scanner::scan_directory;
 use music_player_storage::{searcher::Searcher, Database};
 use music_player_tracklist::Tracklist;
-use sea_orm::ActiveModelTrait;
+use sea_orm::{ActiveModelTrait, EntityTrait};
 use std::{env, sync::Arc, thread, time::Duration};
 use surf::{Client, Config, Url};
 use tokio::{runtime, sync::Mutex};
@@ -121,14 +122,98 @@ async fn start_webui() {
     // assert_eq!(res.status(), 200);
 }
 
+#[actix_web::test]
+async fn import_and_export_playlist_files() {
+    env::set_var("MUSIC_PLAYER_APPLICATION_DIRECTORY", "/tmp");
+    env::set_var("MUSIC_PLAYER_MUSIC_DIRECTORY", "/tmp/audio");
+    env::set_var(
+        "MUSIC_PLAYER_DATABASE_URL",
+        "sqlite:///tmp/music-player.sqlite3",
+    );
+
+    let db = Database::new().await;
+    let tracklist = Arc::new(std::sync::Mutex::new(Tracklist::new_empty()));
+    let app = test::init_service(
+        App::new()
+            .app_data(Data::new(db.clone()))
+            .app_data(Data::new(tracklist))
+            .configure(super::playlist_files),
+    )
+    .await;
+
+    // Larger than the default limit of a request body
+    let mut content = "#EXTM3U\n#PLAYLIST:Forest Hills\n\
+        /tmp/audio/03 - J. Cole - Wet Dreamz(Explicit).m4a\n"
+        .to_owned();
+    while content.len() < 512 * 1024 {
+        content.push_str("# Exported by another player\n");
+    }
+    let req = test::TestRequest::post()
+        .uri("/playlists/import?format=m3u8")
+        .set_payload(content)
+        .to_request();
+    let imported: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(imported["name"], "Forest Hills");
+    assert_eq!(imported["tracks"], 1);
+    let id = imported["id"].as_str().unwrap().to_owned();
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/playlists/{}/export?format=xspf", id))
+        .insert_header(("host", "localhost:5054"))
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 200);
+    assert_eq!(
+        res.headers().get("content-disposition").unwrap(),
+        "attachment; filename=\"Forest Hills.xspf\""
+    );
+    let body = String::from_utf8(test::read_body(res).await.to_vec()).unwrap();
+    assert!(body.contains(
+        "<location>http://localhost:5054/tracks/3ac1f1651b6ef6d5f3f55b711e3bfcd1</location>"
+    ));
+
+    let req = test::TestRequest::get()
+        .uri("/tracklist/export?format=pls")
+        .to_request();
+    let res = test::call_service(&app, req).await;
+    assert_eq!(res.status(), 200);
+    let body = test::read_body(res).await;
+    assert!(body.starts_with(b"[playlist]"));
+
+    let req = test::TestRequest::get()
+        .uri(&format!("/playlists/{}/export?format=wma", id))
+        .to_request();
+    assert_eq!(test::call_service(&app, req).await.status(), 400);
+
+    // M3U files of older players are encoded in Latin-1
+    let req = test::TestRequest::post()
+        .uri("/playlists/import?format=m3u8")
+        .set_payload(
+            &b"#EXTM3U\n#PLAYLIST:Caf\xe9\n\
+            /tmp/audio/03 - J. Cole - Wet Dreamz(Explicit).m4a\n"[..],
+        )
+        .to_request();
+    let latin1: serde_json::Value = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(latin1["name"], "Café");
+    assert_eq!(latin1["tracks"], 1);
+
+    for id in [id, latin1["id"].as_str().unwrap().to_owned()] {
+        playlist::Entity::delete_by_id(id)
+            .exec(db.get_connection())
+            .await
+            .unwrap();
+    }
+}
+
 async fn scan_music_directory(db: Database, searcher: Searcher) {
     scan_directory(
         move |song, db| {
             async move {
-                let item: artist::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
+                for item in artist::ActiveModel::from_artists(song) {
+                    match item.insert(db.get_connection()).await {
+                        Ok(_) => (),
+                        Err(_) => (),
+                    }
                 }
 
                 let item: album::ActiveModel = song.try_into().unwrap();
@@ -144,10 +229,11 @@ async fn scan_music_directory(db: Database, searcher: Searcher) {
                     Err(_) => (),
                 }
 
-                let item: artist_tracks::ActiveModel = song.try_into().unwrap();
-                match item.insert(db.get_connection()).await {
-                    Ok(_) => (),
-                    Err(_) => (),
+                for item in artist_tracks::ActiveModel::from_artists(song) {
+                    match item.insert(db.get_connection()).await {
+                        Ok(_) => (),
+                        Err(_) => (),
+                    }
                 }
             }
             .boxed()